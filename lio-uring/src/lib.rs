@@ -46,12 +46,17 @@ extern crate core;
 use core::mem::MaybeUninit;
 use core::ptr;
 use std::io::{self, IoSlice};
+use std::sync::atomic::{AtomicU32, Ordering, fence};
 use std::time::Duration;
 
 pub mod operation;
 
 mod bindings;
 
+/// Raw submission queue entry, as laid out by the kernel. See
+/// [`LioUring::push_raw`].
+pub use bindings::io_uring_sqe;
+
 /// A completed operation with result and metadata
 #[derive(Debug, Clone, Copy)]
 pub struct Completion {
@@ -60,7 +65,7 @@ pub struct Completion {
   /// Operation result (number of bytes transferred, or negative errno)
   res: i32,
   /// Completion flags providing additional context
-  pub flags: u32,
+  pub flags: CqeFlags,
 }
 
 impl Completion {
@@ -81,17 +86,67 @@ impl Completion {
 
   /// Check if more data is available (for multishot operations)
   pub fn has_more(&self) -> bool {
-    (self.flags & bindings::IORING_CQE_F_MORE) != 0
+    self.flags.is_more()
   }
 
   /// Get the buffer ID (for operations using buffer selection)
   pub fn buffer_id(&self) -> Option<u16> {
-    if (self.flags & bindings::IORING_CQE_F_BUFFER) != 0 {
-      Some((self.flags >> bindings::IORING_CQE_BUFFER_SHIFT) as u16)
+    self.flags.buffer_id()
+  }
+}
+
+/// Completion Queue Entry flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CqeFlags(u32);
+
+impl CqeFlags {
+  /// No flags set
+  pub const NONE: Self = Self(0);
+
+  /// The upper 16 bits hold the id of the selected buffer
+  pub const BUFFER: Self = Self(bindings::IORING_CQE_F_BUFFER);
+
+  /// A multishot request will post more completions
+  pub const MORE: Self = Self(bindings::IORING_CQE_F_MORE);
+
+  /// The socket had more data to read after a receive
+  pub const SOCK_NONEMPTY: Self = Self(bindings::IORING_CQE_F_SOCK_NONEMPTY);
+
+  /// This is the notification of a zero-copy send, not its result
+  pub const NOTIF: Self = Self(bindings::IORING_CQE_F_NOTIF);
+
+  /// Wraps the raw `flags` of a CQE
+  pub const fn from_bits(bits: u32) -> Self {
+    Self(bits)
+  }
+
+  /// Check if a flag is set
+  pub const fn contains(self, other: Self) -> bool {
+    (self.0 & other.0) == other.0
+  }
+
+  pub fn bits(self) -> u32 {
+    self.0
+  }
+
+  /// Check if a multishot request stays armed (`IORING_CQE_F_MORE`)
+  pub fn is_more(self) -> bool {
+    self.contains(Self::MORE)
+  }
+
+  /// Get the id of the buffer the kernel selected (`IORING_CQE_F_BUFFER`)
+  pub fn buffer_id(self) -> Option<u16> {
+    if self.contains(Self::BUFFER) {
+      Some((self.0 >> bindings::IORING_CQE_BUFFER_SHIFT) as u16)
     } else {
       None
     }
   }
+
+  /// Check if this is a zero-copy send notification (`IORING_CQE_F_NOTIF`)
+  pub fn is_notif(self) -> bool {
+    self.contains(Self::NOTIF)
+  }
 }
 
 /// Submission Queue Entry flags
@@ -163,6 +218,20 @@ impl Entry {
   pub(crate) fn into_sqe(self) -> bindings::io_uring_sqe {
     self.0
   }
+
+  /// The opcode of the operation, such as `operation::Read::CODE`.
+  pub fn opcode(&self) -> u8 {
+    self.0.opcode
+  }
+
+  /// Adds `flags` to the entry, on top of those the operation set.
+  ///
+  /// With [`SqeFlags::FIXED_FILE`] the fd of the operation is read as an
+  /// index into the fixed file table.
+  pub fn flags(mut self, flags: SqeFlags) -> Self {
+    self.0.flags |= flags.bits();
+    self
+  }
 }
 
 /// Configuration parameters for io_uring initialization
@@ -192,11 +261,121 @@ impl Params {
     self
   }
 
+  /// Whether submission queue polling is enabled.
+  pub fn is_sqpoll(&self) -> bool {
+    (self.flags & bindings::IORING_SETUP_SQPOLL) != 0
+  }
+
+  /// Disable submission queue polling, keeping the other flags.
+  pub fn without_sqpoll(mut self) -> Self {
+    self.flags &= !bindings::IORING_SETUP_SQPOLL;
+    self.sq_thread_idle = 0;
+    self
+  }
+
   /// Enable IO polling (busy-wait for completions, lower latency)
   pub fn iopoll(mut self) -> Self {
     self.flags |= bindings::IORING_SETUP_IOPOLL;
     self
   }
+
+  /// Start the ring disabled, so restrictions can be registered before
+  /// anything is submitted. See [`LioUring::register_restrictions`].
+  pub fn disabled(mut self) -> Self {
+    self.flags |= bindings::IORING_SETUP_R_DISABLED;
+    self
+  }
+}
+
+/// A rule for [`LioUring::register_restrictions`]. Anything not allowed by
+/// a rule is rejected once restrictions are registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restriction {
+  /// Allow submitting this opcode, such as `operation::Read::CODE`.
+  AllowOp(u8),
+  /// Allow `io_uring_register` calls with this opcode.
+  AllowRegisterOp(u8),
+  /// Allow submissions to set these flags.
+  AllowSqeFlags(SqeFlags),
+  /// Require every submission to set these flags.
+  RequireSqeFlags(SqeFlags),
+}
+
+impl Restriction {
+  fn into_raw(self) -> bindings::io_uring_restriction {
+    use bindings::{
+      io_uring_register_restriction_op_IORING_RESTRICTION_REGISTER_OP as REGISTER_OP,
+      io_uring_register_restriction_op_IORING_RESTRICTION_SQE_FLAGS_ALLOWED as SQE_FLAGS_ALLOWED,
+      io_uring_register_restriction_op_IORING_RESTRICTION_SQE_FLAGS_REQUIRED as SQE_FLAGS_REQUIRED,
+      io_uring_register_restriction_op_IORING_RESTRICTION_SQE_OP as SQE_OP,
+    };
+
+    let mut raw: bindings::io_uring_restriction = unsafe { std::mem::zeroed() };
+    match self {
+      Restriction::AllowOp(op) => {
+        raw.opcode = SQE_OP as u16;
+        raw.__bindgen_anon_1.sqe_op = op;
+      }
+      Restriction::AllowRegisterOp(op) => {
+        raw.opcode = REGISTER_OP as u16;
+        raw.__bindgen_anon_1.register_op = op;
+      }
+      Restriction::AllowSqeFlags(flags) => {
+        raw.opcode = SQE_FLAGS_ALLOWED as u16;
+        raw.__bindgen_anon_1.sqe_flags = flags.bits();
+      }
+      Restriction::RequireSqeFlags(flags) => {
+        raw.opcode = SQE_FLAGS_REQUIRED as u16;
+        raw.__bindgen_anon_1.sqe_flags = flags.bits();
+      }
+    }
+    raw
+  }
+}
+
+/// The opcodes the running kernel supports, queried with
+/// `IORING_REGISTER_PROBE`.
+///
+/// Lets code that runs on several kernel versions fall back to a blocking
+/// syscall when an operation isn't there.
+///
+/// ```rust,ignore
+/// use lio_uring::{LioUring, Probe, operation::Nop};
+///
+/// let ring = LioUring::new(8)?;
+/// let probe = Probe::new(&ring)?;
+/// assert!(probe.is_supported(Nop::CODE));
+/// ```
+pub struct Probe {
+  raw: ptr::NonNull<bindings::io_uring_probe>,
+}
+
+impl Probe {
+  /// Asks the kernel behind `ring` which opcodes it supports.
+  ///
+  /// # Errors
+  /// Returns an error if the kernel doesn't support probing (before 5.6).
+  pub fn new(ring: &LioUring) -> io::Result<Self> {
+    let raw = unsafe {
+      bindings::io_uring_get_probe_ring(&ring.ring as *const _ as *mut _)
+    };
+    ptr::NonNull::new(raw).map(|raw| Self { raw }).ok_or_else(|| {
+      io::Error::new(io::ErrorKind::Unsupported, "io_uring probe failed")
+    })
+  }
+
+  /// Whether the kernel supports `opcode`, such as `operation::Read::CODE`.
+  pub fn is_supported(&self, opcode: u8) -> bool {
+    unsafe {
+      bindings::io_uring_opcode_supported(self.raw.as_ptr(), opcode as i32) != 0
+    }
+  }
+}
+
+impl Drop for Probe {
+  fn drop(&mut self) {
+    unsafe { bindings::io_uring_free_probe(self.raw.as_ptr()) };
+  }
 }
 
 /// A Linux io_uring instance for high-performance async I/O.
@@ -206,6 +385,9 @@ impl Params {
 pub struct LioUring {
   ring: bindings::io_uring,
   flags: u32,
+  /// Pushed operations whose final completion hasn't been consumed yet.
+  outstanding: usize,
+  completion_backpressure: Option<usize>,
 }
 
 impl Drop for LioUring {
@@ -261,7 +443,12 @@ impl LioUring {
     let ring_init = unsafe { ring.assume_init() };
     let flags = ring_init.flags;
 
-    Ok(Self { ring: ring_init, flags })
+    Ok(Self {
+      ring: ring_init,
+      flags,
+      outstanding: 0,
+      completion_backpressure: None,
+    })
   }
 
   // ==================== Submission methods ====================
@@ -286,17 +473,27 @@ impl LioUring {
 
   /// Push an operation to the submission queue with custom flags.
   ///
+  /// An operation pushed with [`CQE_SKIP_SUCCESS`](SqeFlags::CQE_SKIP_SUCCESS)
+  /// isn't counted as [outstanding](Self::outstanding), as it may never
+  /// complete visibly.
+  ///
   /// # Safety
   /// Same requirements as `push()`.
   ///
   /// # Errors
-  /// Returns an error if the submission queue is full.
+  /// Returns an error if the submission queue is full, or if
+  /// `CQE_SKIP_SUCCESS` is set while
+  /// [completion backpressure](Self::set_completion_backpressure) is enabled.
   pub unsafe fn push_with_flags(
     &mut self,
     entry: Entry,
     user_data: u64,
     flags: SqeFlags,
   ) -> io::Result<()> {
+    let mut new = entry.into_sqe();
+    // Keep flags the operation set itself, like FIXED_FILE.
+    new.flags |= flags.bits();
+    let counted = self.check_backpressure(new.flags)?;
     let sqe = unsafe { bindings::io_uring_get_sqe(&raw mut self.ring) };
     if sqe.is_null() {
       return Err(io::Error::new(
@@ -306,14 +503,155 @@ impl LioUring {
     }
 
     unsafe {
-      (*sqe) = entry.into_sqe();
+      (*sqe) = new;
       (*sqe).user_data = user_data;
-      (*sqe).flags = flags.bits()
     }
 
+    if counted {
+      self.outstanding += 1;
+    }
     Ok(())
   }
 
+  /// Push as many of `entries` as fit in the submission queue, in order.
+  ///
+  /// Entry `i` gets `user_data_base + i` as its user data, wrapping on
+  /// overflow. Returns how many entries were pushed, the caller retries the
+  /// rest after [`submit`](Self::submit) has made room. This also stops at
+  /// the [completion backpressure](Self::set_completion_backpressure) limit.
+  ///
+  /// A slice longer than the ring's capacity is never pushed whole: at most
+  /// the capacity goes in per call, so it takes several rounds of
+  /// `push_all` and `submit`.
+  ///
+  /// # Safety
+  /// Same requirements as `push()`, for every entry that gets pushed.
+  ///
+  /// # Errors
+  /// Returns an error if not even the first entry fits. The ring is left
+  /// untouched then.
+  pub unsafe fn push_all(
+    &mut self,
+    entries: &[Entry],
+    user_data_base: u64,
+  ) -> io::Result<usize> {
+    if entries.is_empty() {
+      return Ok(0);
+    }
+    self.check_backpressure(0)?;
+
+    let mut room = self.sq_space_left();
+    if let Some(limit) = self.completion_backpressure {
+      room = room.min(limit - self.outstanding);
+    }
+    if room == 0 {
+      return Err(io::Error::new(
+        io::ErrorKind::WouldBlock,
+        "submission queue is full",
+      ));
+    }
+
+    let count = entries.len().min(room);
+    for (i, entry) in entries[..count].iter().enumerate() {
+      let entry = Entry::from_sqe(entry.0);
+      let user_data = user_data_base.wrapping_add(i as u64);
+      unsafe { self.push_with_flags(entry, user_data, SqeFlags::NONE) }?;
+    }
+    Ok(count)
+  }
+
+  /// Push a fully user-built SQE to the submission queue.
+  ///
+  /// This is an escape hatch for opcodes without an [`operation`] wrapper.
+  /// Unlike [`push_with_flags`](Self::push_with_flags), the SQE is copied as
+  /// is, including its `flags`; only `user_data` is overwritten.
+  ///
+  /// # Safety
+  /// Same requirements as `push()`. Additionally, every field of `sqe` must be
+  /// valid for its opcode on the running kernel: the kernel interprets the
+  /// entry exactly as given, so a wrong opcode, length or pointer can make it
+  /// read or write arbitrary memory of this process.
+  ///
+  /// # Errors
+  /// Returns an error if the submission queue is full, or for
+  /// `CQE_SKIP_SUCCESS` under completion backpressure, like
+  /// [`push_with_flags`](Self::push_with_flags).
+  pub unsafe fn push_raw(
+    &mut self,
+    sqe: io_uring_sqe,
+    user_data: u64,
+  ) -> io::Result<()> {
+    let counted = self.check_backpressure(sqe.flags)?;
+    let slot = unsafe { bindings::io_uring_get_sqe(&raw mut self.ring) };
+    if slot.is_null() {
+      return Err(io::Error::new(
+        io::ErrorKind::WouldBlock,
+        "submission queue is full",
+      ));
+    }
+
+    unsafe {
+      (*slot) = sqe;
+      (*slot).user_data = user_data;
+    }
+
+    if counted {
+      self.outstanding += 1;
+    }
+    Ok(())
+  }
+
+  /// Limit how many operations may be outstanding at once, counting both
+  /// those still running in the kernel and completions nobody has consumed
+  /// yet.
+  ///
+  /// Once `limit` is reached, [`push`](Self::push) and friends fail with
+  /// [`WouldBlock`](io::ErrorKind::WouldBlock) until completions are
+  /// consumed through [`wait`](Self::wait), [`wait_timeout`](Self::wait_timeout)
+  /// or [`try_wait`](Self::try_wait). This throttles a fast submitter to the
+  /// pace of the completion side, instead of letting completions pile up
+  /// until the CQ overflows. Multishot operations count once, until their
+  /// last completion.
+  ///
+  /// Operations pushed with [`CQE_SKIP_SUCCESS`](SqeFlags::CQE_SKIP_SUCCESS)
+  /// are refused while the limit is set: a successful one never completes,
+  /// a failed one does, so they can't be counted either way.
+  ///
+  /// `None`, the default, disables the limit.
+  pub fn set_completion_backpressure(&mut self, limit: Option<usize>) {
+    self.completion_backpressure = limit;
+  }
+
+  /// Number of pushed operations whose final completion hasn't been
+  /// consumed yet.
+  pub fn outstanding(&self) -> usize {
+    self.outstanding
+  }
+
+  /// Checks whether an SQE with `sqe_flags` may be pushed, and whether it
+  /// counts as outstanding.
+  fn check_backpressure(&self, sqe_flags: u8) -> io::Result<bool> {
+    let skip = sqe_flags & SqeFlags::CQE_SKIP_SUCCESS.bits() != 0;
+    match self.completion_backpressure {
+      Some(_) if skip => Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "CQE_SKIP_SUCCESS can't be used with completion backpressure",
+      )),
+      Some(limit) if self.outstanding >= limit => Err(io::Error::new(
+        io::ErrorKind::WouldBlock,
+        "completion backpressure limit reached",
+      )),
+      _ => Ok(!skip),
+    }
+  }
+
+  /// Bookkeeping for a completion the caller has consumed.
+  fn consumed(&mut self, completion: &Completion) {
+    if !completion.has_more() {
+      self.outstanding = self.outstanding.saturating_sub(1);
+    }
+  }
+
   /// Submit queued operations to the kernel.
   ///
   /// When SQPOLL is enabled, this avoids the syscall if the kernel thread
@@ -333,38 +671,98 @@ impl LioUring {
       return Ok(ret as usize);
     }
 
-    // SQPOLL path: update ktail to make entries visible to kernel
+    // SQPOLL path: publish the new tail, the kernel thread picks the
+    // entries up on its own.
     let pending = unsafe {
       let sq_tail = self.ring.sq.sqe_tail;
-      let sq_head = self.ring.sq.sqe_head;
-
-      if sq_head != sq_tail {
+      if self.ring.sq.sqe_head != sq_tail {
         self.ring.sq.sqe_head = sq_tail;
-        std::sync::atomic::fence(std::sync::atomic::Ordering::SeqCst);
-        std::ptr::write_volatile(self.ring.sq.ktail, sq_tail);
+        AtomicU32::from_ptr(self.ring.sq.ktail)
+          .store(sq_tail, Ordering::Release);
       }
-
-      sq_tail.wrapping_sub(*self.ring.sq.khead)
+      sq_tail.wrapping_sub(
+        AtomicU32::from_ptr(self.ring.sq.khead).load(Ordering::Acquire),
+      )
     };
 
     if pending == 0 {
       return Ok(0);
     }
 
-    let needs_wakeup =
-      unsafe { *self.ring.sq.kflags & bindings::IORING_SQ_NEED_WAKEUP != 0 };
+    if self.sq_needs_wakeup() {
+      self.wake_sq_thread()?;
+    }
+    Ok(pending as usize)
+  }
 
-    if needs_wakeup {
-      let ret = unsafe { bindings::io_uring_submit(&raw mut self.ring) };
-      if ret < 0 {
+  /// Submit queued operations and block until at least `want` completions
+  /// are ready, in a single `io_uring_enter(2)`.
+  ///
+  /// Saves a round trip over [`submit`](Self::submit) followed by
+  /// [`wait`](Self::wait) when the result is needed right away. The
+  /// completions are left in the queue to be consumed as usual. With `want`
+  /// set to 0 this is a plain `submit`.
+  ///
+  /// Returns the number of operations submitted. A wait interrupted by a
+  /// signal is retried; the count then only covers the last attempt, as the
+  /// entries were handed over by the first.
+  ///
+  /// # Errors
+  /// Returns an error if submission or waiting fails.
+  pub fn submit_and_wait(&mut self, want: u32) -> io::Result<usize> {
+    if want == 0 {
+      return self.submit();
+    }
+    loop {
+      let ret =
+        unsafe { bindings::io_uring_submit_and_wait(&raw mut self.ring, want) };
+      if ret >= 0 {
+        return Ok(ret as usize);
+      }
+      if -ret != libc::EINTR {
         return Err(io::Error::from_raw_os_error(-ret));
       }
-      Ok(ret as usize)
-    } else {
-      Ok(pending as usize)
     }
   }
 
+  /// Whether the SQPOLL thread went idle after `sq_thread_idle` without
+  /// work, and has to be woken up to see new submissions.
+  ///
+  /// [`submit`](Self::submit) checks this and wakes the thread when needed.
+  /// Always `false` without SQPOLL.
+  pub fn sq_needs_wakeup(&self) -> bool {
+    if !self.is_sqpoll() {
+      return false;
+    }
+    // The tail store has to be visible before the flags are read. Otherwise
+    // the thread can go idle between the two without seeing the new entries,
+    // and they stall until the next submit.
+    fence(Ordering::SeqCst);
+    let flags = unsafe {
+      AtomicU32::from_ptr(self.ring.sq.kflags).load(Ordering::Relaxed)
+    };
+    flags & bindings::IORING_SQ_NEED_WAKEUP != 0
+  }
+
+  /// Wakes the idle SQPOLL thread without submitting or waiting.
+  fn wake_sq_thread(&mut self) -> io::Result<()> {
+    let ret = unsafe {
+      libc::syscall(
+        libc::SYS_io_uring_enter,
+        self.ring.ring_fd,
+        0,
+        0,
+        bindings::IORING_ENTER_SQ_WAKEUP,
+        std::ptr::null::<libc::c_void>(),
+        0,
+      )
+    };
+    if ret < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(())
+  }
+
   /// Check if SQPOLL mode is enabled.
   pub fn is_sqpoll(&self) -> bool {
     (self.flags & bindings::IORING_SETUP_SQPOLL) != 0
@@ -397,10 +795,14 @@ impl LioUring {
     }
 
     let cqe = unsafe { &*cqe_ptr };
-    let completion =
-      Completion { user_data: cqe.user_data, res: cqe.res, flags: cqe.flags };
+    let completion = Completion {
+      user_data: cqe.user_data,
+      res: cqe.res,
+      flags: CqeFlags(cqe.flags),
+    };
 
     unsafe { bindings::io_uring_cqe_seen(&raw mut self.ring, cqe_ptr) };
+    self.consumed(&completion);
 
     Ok(completion)
   }
@@ -438,10 +840,14 @@ impl LioUring {
     }
 
     let cqe = unsafe { &*cqe_ptr };
-    let completion =
-      Completion { user_data: cqe.user_data, res: cqe.res, flags: cqe.flags };
+    let completion = Completion {
+      user_data: cqe.user_data,
+      res: cqe.res,
+      flags: CqeFlags(cqe.flags),
+    };
 
     unsafe { bindings::io_uring_cqe_seen(&raw mut self.ring, cqe_ptr) };
+    self.consumed(&completion);
 
     Ok(Some(completion))
   }
@@ -470,10 +876,14 @@ impl LioUring {
     }
 
     let cqe = unsafe { &*cqe_ptr };
-    let completion =
-      Completion { user_data: cqe.user_data, res: cqe.res, flags: cqe.flags };
+    let completion = Completion {
+      user_data: cqe.user_data,
+      res: cqe.res,
+      flags: CqeFlags(cqe.flags),
+    };
 
     unsafe { bindings::io_uring_cqe_seen(&raw mut self.ring, cqe_ptr) };
+    self.consumed(&completion);
 
     Ok(Some(completion))
   }
@@ -505,7 +915,7 @@ impl LioUring {
     Ok(Some(Completion {
       user_data: cqe.user_data,
       res: cqe.res,
-      flags: cqe.flags,
+      flags: CqeFlags(cqe.flags),
     }))
   }
 
@@ -516,6 +926,34 @@ impl LioUring {
     }
   }
 
+  /// Peek at up to `max` ready completions without removing them from the
+  /// queue, oldest first.
+  ///
+  /// Together with [`cq_ready`](Self::cq_ready) this lets a caller look at
+  /// what finished before deciding how much of it to process.
+  pub fn peek_batch(&self, max: usize) -> Vec<Completion> {
+    let mut cqes = vec![ptr::null_mut(); max];
+    let count = unsafe {
+      bindings::io_uring_peek_batch_cqe(
+        &self.ring as *const _ as *mut _,
+        cqes.as_mut_ptr(),
+        max as u32,
+      )
+    };
+
+    cqes[..count as usize]
+      .iter()
+      .map(|&cqe_ptr| {
+        let cqe = unsafe { &*cqe_ptr };
+        Completion {
+          user_data: cqe.user_data,
+          res: cqe.res,
+          flags: CqeFlags(cqe.flags),
+        }
+      })
+      .collect()
+  }
+
   // ==================== Registration methods ====================
 
   /// Register fixed buffers for zero-copy I/O.
@@ -555,6 +993,27 @@ impl LioUring {
     Ok(())
   }
 
+  /// Register fixed buffers for as long as the returned guard lives.
+  ///
+  /// Like [`register_buffers`](Self::register_buffers), but the buffers stay
+  /// borrowed until the guard is dropped, which unregisters them. The guard
+  /// derefs to the ring, so fixed operations are pushed and reaped through
+  /// it, and [`buf_index`](BufferRegistration::buf_index) gives the index to
+  /// pass to `ReadFixed`/`WriteFixed` for each slice.
+  ///
+  /// # Errors
+  /// Returns an error if registration fails, e.g. when buffers are already
+  /// registered.
+  pub fn register_buffers_scoped<'a>(
+    &'a mut self,
+    bufs: &'a [IoSlice<'a>],
+  ) -> io::Result<BufferRegistration<'a>> {
+    // The borrow of `bufs` keeps them alive and in place until the guard
+    // unregisters them.
+    unsafe { self.register_buffers(bufs) }?;
+    Ok(BufferRegistration { ring: self, len: bufs.len() })
+  }
+
   /// Unregister previously registered buffers.
   pub fn unregister_buffers(&mut self) -> io::Result<()> {
     let ret =
@@ -571,8 +1030,14 @@ impl LioUring {
   /// Pre-registers file descriptors with the kernel. Registered files can be
   /// referenced by index instead of fd, avoiding fd lookup overhead.
   ///
+  /// The table's length is fixed to `fds.len()` here. Slots are replaced with
+  /// [`register_files_update`](Self::register_files_update), but growing the
+  /// table means [`unregister_files`](Self::unregister_files) and registering
+  /// a larger one. An fd of -1 leaves its slot empty.
+  ///
   /// # Errors
-  /// Returns an error if registration fails.
+  /// Returns an error if a table is already registered or registration
+  /// fails.
   pub fn register_files(&mut self, fds: &[i32]) -> io::Result<()> {
     let ret = unsafe {
       bindings::io_uring_register_files(
@@ -588,9 +1053,32 @@ impl LioUring {
     Ok(())
   }
 
+  /// Register a fixed file table of `nr` empty slots.
+  ///
+  /// Operations that allocate a direct descriptor, like an accept with
+  /// `allocate_file_index`, take a free slot from this table.
+  ///
+  /// # Errors
+  /// Returns an error if a table is already registered or registration
+  /// fails.
+  pub fn register_files_sparse(&mut self, nr: u32) -> io::Result<()> {
+    let ret = unsafe {
+      bindings::io_uring_register_files_sparse(&raw mut self.ring, nr)
+    };
+
+    if ret < 0 {
+      return Err(io::Error::from_raw_os_error(-ret));
+    }
+    Ok(())
+  }
+
   /// Update registered files at specific indices.
   ///
   /// Replace file descriptors at the given indices. Use -1 to remove a file.
+  ///
+  /// # Errors
+  /// Returns an error if no table is registered, or if `offset + fds.len()`
+  /// runs past the length it was registered with.
   pub fn register_files_update(
     &mut self,
     offset: u32,
@@ -621,6 +1109,116 @@ impl LioUring {
     }
     Ok(())
   }
+
+  /// Lock the ring down to the operations `rules` allow.
+  ///
+  /// The ring must have been created with [`Params::disabled`], and
+  /// restrictions can only be registered once. Call
+  /// [`enable_rings`](Self::enable_rings) afterwards to start using it.
+  /// Submissions the rules don't allow complete with `-EACCES`.
+  ///
+  /// # Errors
+  /// Returns `EBADFD` if the ring isn't disabled, `EBUSY` if restrictions
+  /// were already registered.
+  pub fn register_restrictions(
+    &mut self,
+    rules: &[Restriction],
+  ) -> io::Result<()> {
+    let mut raw: Vec<bindings::io_uring_restriction> =
+      rules.iter().map(|rule| rule.into_raw()).collect();
+
+    let ret = unsafe {
+      bindings::io_uring_register_restrictions(
+        &raw mut self.ring,
+        raw.as_mut_ptr(),
+        raw.len() as u32,
+      )
+    };
+
+    if ret < 0 {
+      return Err(io::Error::from_raw_os_error(-ret));
+    }
+    Ok(())
+  }
+
+  /// Enable a ring created with [`Params::disabled`].
+  pub fn enable_rings(&mut self) -> io::Result<()> {
+    let ret = unsafe { bindings::io_uring_enable_rings(&raw mut self.ring) };
+
+    if ret < 0 {
+      return Err(io::Error::from_raw_os_error(-ret));
+    }
+    Ok(())
+  }
+}
+
+/// Fixed buffers registered by [`LioUring::register_buffers_scoped`].
+///
+/// Unregisters the buffers when dropped, so a panic between registering and
+/// unregistering doesn't leave their pages pinned. Operations still in
+/// flight keep the kernel's reference to their buffer, but, as with
+/// [`LioUring::push`], the buffers themselves must outlive them.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use lio_uring::{LioUring, operation::WriteFixed};
+/// use std::io::IoSlice;
+///
+/// let mut ring = LioUring::new(8)?;
+/// let buf = b"hello".to_vec();
+/// let slices = [IoSlice::new(&buf)];
+///
+/// let mut reg = ring.register_buffers_scoped(&slices)?;
+/// let op = WriteFixed::new(fd, buf.as_ptr(), 5, reg.buf_index(0));
+/// unsafe { reg.push(op.build(), 1) }?;
+/// reg.submit()?;
+/// reg.wait()?;
+/// ```
+pub struct BufferRegistration<'a> {
+  ring: &'a mut LioUring,
+  len: usize,
+}
+
+impl BufferRegistration<'_> {
+  /// The `buf_index` of the `i`th registered slice.
+  ///
+  /// # Panics
+  /// Panics if `i` is out of bounds.
+  pub fn buf_index(&self, i: usize) -> u16 {
+    assert!(i < self.len, "buffer {i} out of {} registered", self.len);
+    i as u16
+  }
+
+  /// Number of registered buffers.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Whether no buffers were registered.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+}
+
+impl std::ops::Deref for BufferRegistration<'_> {
+  type Target = LioUring;
+
+  fn deref(&self) -> &LioUring {
+    self.ring
+  }
+}
+
+impl std::ops::DerefMut for BufferRegistration<'_> {
+  fn deref_mut(&mut self) -> &mut LioUring {
+    self.ring
+  }
+}
+
+impl Drop for BufferRegistration<'_> {
+  fn drop(&mut self) {
+    let _ = self.ring.unregister_buffers();
+  }
 }
 
 #[cfg(test)]
@@ -633,53 +1231,54 @@ mod tests {
 
   #[test]
   fn test_completion_is_ok_positive() {
-    let c = Completion { user_data: 1, res: 0, flags: 0 };
+    let c = Completion { user_data: 1, res: 0, flags: CqeFlags::NONE };
     assert!(c.is_ok());
 
-    let c = Completion { user_data: 1, res: 100, flags: 0 };
+    let c = Completion { user_data: 1, res: 100, flags: CqeFlags::NONE };
     assert!(c.is_ok());
   }
 
   #[test]
   fn test_completion_is_ok_negative() {
-    let c = Completion { user_data: 1, res: -1, flags: 0 };
+    let c = Completion { user_data: 1, res: -1, flags: CqeFlags::NONE };
     assert!(!c.is_ok());
 
-    let c = Completion { user_data: 1, res: -libc::EBADF, flags: 0 };
+    let c =
+      Completion { user_data: 1, res: -libc::EBADF, flags: CqeFlags::NONE };
     assert!(!c.is_ok());
   }
 
   #[test]
   fn test_completion_result() {
-    let c = Completion { user_data: 1, res: 42, flags: 0 };
+    let c = Completion { user_data: 1, res: 42, flags: CqeFlags::NONE };
     assert_eq!(c.result(), 42);
 
-    let c = Completion { user_data: 1, res: -libc::EINVAL, flags: 0 };
+    let c =
+      Completion { user_data: 1, res: -libc::EINVAL, flags: CqeFlags::NONE };
     assert_eq!(c.result(), -libc::EINVAL);
   }
 
   #[test]
   fn test_completion_user_data() {
-    let c = Completion { user_data: 0xDEADBEEF, res: 0, flags: 0 };
+    let c = Completion { user_data: 0xDEADBEEF, res: 0, flags: CqeFlags::NONE };
     assert_eq!(c.user_data(), 0xDEADBEEF);
 
-    let c = Completion { user_data: u64::MAX, res: 0, flags: 0 };
+    let c = Completion { user_data: u64::MAX, res: 0, flags: CqeFlags::NONE };
     assert_eq!(c.user_data(), u64::MAX);
   }
 
   #[test]
   fn test_completion_has_more() {
-    let c = Completion { user_data: 1, res: 0, flags: 0 };
+    let c = Completion { user_data: 1, res: 0, flags: CqeFlags::NONE };
     assert!(!c.has_more());
 
-    let c =
-      Completion { user_data: 1, res: 0, flags: bindings::IORING_CQE_F_MORE };
+    let c = Completion { user_data: 1, res: 0, flags: CqeFlags::MORE };
     assert!(c.has_more());
   }
 
   #[test]
   fn test_completion_buffer_id_none() {
-    let c = Completion { user_data: 1, res: 0, flags: 0 };
+    let c = Completion { user_data: 1, res: 0, flags: CqeFlags::NONE };
     assert_eq!(c.buffer_id(), None);
   }
 
@@ -688,10 +1287,41 @@ mod tests {
     let buffer_id: u16 = 42;
     let flags = bindings::IORING_CQE_F_BUFFER
       | ((buffer_id as u32) << bindings::IORING_CQE_BUFFER_SHIFT);
-    let c = Completion { user_data: 1, res: 0, flags };
+    let c =
+      Completion { user_data: 1, res: 0, flags: CqeFlags::from_bits(flags) };
     assert_eq!(c.buffer_id(), Some(42));
   }
 
+  // ==========================================================================
+  // CqeFlags Tests (unit tests - no kernel needed)
+  // ==========================================================================
+
+  #[test]
+  fn test_cqe_flags_none() {
+    let flags = CqeFlags::NONE;
+    assert!(!flags.is_more());
+    assert!(!flags.is_notif());
+    assert_eq!(flags.buffer_id(), None);
+  }
+
+  #[test]
+  fn test_cqe_flags_from_bits() {
+    let bits = bindings::IORING_CQE_F_MORE
+      | bindings::IORING_CQE_F_BUFFER
+      | (7 << bindings::IORING_CQE_BUFFER_SHIFT);
+    let flags = CqeFlags::from_bits(bits);
+    assert!(flags.is_more());
+    assert!(!flags.is_notif());
+    assert_eq!(flags.buffer_id(), Some(7));
+    assert_eq!(flags.bits(), bits);
+  }
+
+  #[test]
+  fn test_cqe_flags_notif() {
+    assert!(CqeFlags::NOTIF.is_notif());
+    assert!(!CqeFlags::NOTIF.is_more());
+  }
+
   // ==========================================================================
   // SqeFlags Tests (unit tests - no kernel needed)
   // ==========================================================================
@@ -795,4 +1425,12 @@ mod tests {
     assert!((params.flags & bindings::IORING_SETUP_IOPOLL) != 0);
     assert_eq!(params.sq_thread_idle, 500);
   }
+
+  #[test]
+  fn test_params_without_sqpoll() {
+    let params = Params::default().sqpoll(500).iopoll().without_sqpoll();
+    assert!(!params.is_sqpoll());
+    assert!((params.flags & bindings::IORING_SETUP_IOPOLL) != 0);
+    assert_eq!(params.sq_thread_idle, 0);
+  }
 }
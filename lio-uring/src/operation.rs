@@ -683,13 +683,16 @@ opcode! {
         addr: { *mut libc::sockaddr },
         addrlen: { *mut libc::socklen_t },
         ;;
+        /// Accept into a free slot of the fixed file table instead of a new
+        /// fd. The result is then the slot index. Available since 5.19.
+        allocate_file_index: bool = false,
         flags: i32 = 0
     }
 
     pub const CODE = bindings::io_uring_op_IORING_OP_ACCEPT;
 
     pub fn build(self) -> Entry {
-        let Accept { fd, addr, addrlen, flags } = self;
+        let Accept { fd, addr, addrlen, allocate_file_index, flags } = self;
 
         let mut sqe = sqe_zeroed();
         sqe.opcode = Self::CODE;
@@ -697,6 +700,9 @@ opcode! {
         sqe.__bindgen_anon_2.addr = addr as _;
         sqe.__bindgen_anon_1.addr2 = addrlen as _;
         sqe.__bindgen_anon_3.accept_flags = flags as _;
+        if allocate_file_index {
+            sqe.__bindgen_anon_5.file_index = bindings::IORING_FILE_INDEX_ALLOC as u32;
+        }
         Entry(sqe)
     }
 }
@@ -874,6 +880,51 @@ opcode! {
     }
 }
 
+opcode! {
+    /// Close a direct descriptor, freeing its slot in the fixed file table.
+    pub struct CloseFixed {
+        file_index: { u32 },
+        ;;
+    }
+
+    pub const CODE = bindings::io_uring_op_IORING_OP_CLOSE;
+
+    pub fn build(self) -> Entry {
+        let CloseFixed { file_index } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        // The kernel takes the slot offset by one, zero means a plain fd.
+        sqe.__bindgen_anon_5.file_index = file_index + 1;
+        Entry(sqe)
+    }
+}
+
+opcode! {
+    /// Turn a direct descriptor into a regular file descriptor that can be
+    /// used with plain system calls. The result is the new fd, the slot stays
+    /// registered. Available since 6.8.
+    pub struct FixedFdInstall {
+        file_index: { u32 },
+        ;;
+        /// `IORING_FIXED_FD_NO_CLOEXEC` to leave `O_CLOEXEC` off the new fd.
+        file_flags: u32 = 0
+    }
+
+    pub const CODE = bindings::io_uring_op_IORING_OP_FIXED_FD_INSTALL;
+
+    pub fn build(self) -> Entry {
+        let FixedFdInstall { file_index, file_flags } = self;
+
+        let mut sqe = sqe_zeroed();
+        sqe.opcode = Self::CODE;
+        sqe.fd = file_index as _;
+        sqe.flags = SqeFlags::FIXED_FILE.bits();
+        sqe.__bindgen_anon_3.install_fd_flags = file_flags;
+        Entry(sqe)
+    }
+}
+
 opcode! {
     /// This command is an alternative to using
     /// [`Submitter::register_files_update`](crate::Submitter::register_files_update) which then
@@ -3135,6 +3186,7 @@ mod smoke_tests {
   smoke_test!(Bind, Bind::new(0, core::ptr::null(), 0));
   smoke_test!(Listen, Listen::new(0, 0));
   smoke_test!(FixedFdInstall, FixedFdInstall::new(0));
+  smoke_test!(CloseFixed, CloseFixed::new(0));
   smoke_test!(SendZcFixed, SendZcFixed::new(1, core::ptr::null(), 0, 0));
   smoke_test!(
     Openat2,
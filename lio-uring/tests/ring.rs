@@ -1,7 +1,9 @@
 //! Integration tests for LioUring core functionality.
 
 use lio_uring::operation::*;
-use lio_uring::{LioUring, Params, SqeFlags};
+use lio_uring::{
+  CqeFlags, LioUring, Params, Probe, Restriction, SqeFlags, io_uring_sqe,
+};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::os::fd::AsRawFd;
@@ -45,6 +47,44 @@ fn test_is_sqpoll_false_by_default() {
   assert!(!ring.is_sqpoll());
 }
 
+#[test]
+fn test_sqpoll_wakeup_after_idle() {
+  // Without CAP_SYS_NICE, SQPOLL needs Linux 5.11 or later.
+  let Ok(mut ring) = LioUring::with_params(Params::default().sqpoll(10)) else {
+    return;
+  };
+  assert!(ring.is_sqpoll());
+
+  unsafe { ring.push(Nop::new().build(), 1) }.unwrap();
+  ring.submit().unwrap();
+  let completion = ring.wait_timeout(Duration::from_secs(2)).unwrap();
+  assert_eq!(completion.expect("first nop stalled").user_data, 1);
+
+  // Well past sq_thread_idle, the thread has gone to sleep.
+  std::thread::sleep(Duration::from_millis(100));
+  assert!(ring.sq_needs_wakeup());
+
+  unsafe { ring.push(Nop::new().build(), 2) }.unwrap();
+  ring.submit().unwrap();
+  let completion = ring.wait_timeout(Duration::from_secs(2)).unwrap();
+  assert_eq!(completion.expect("submission after idle stalled").user_data, 2);
+}
+
+#[test]
+fn test_sq_needs_wakeup_false_without_sqpoll() {
+  let ring = LioUring::new(8).unwrap();
+  assert!(!ring.sq_needs_wakeup());
+}
+
+#[test]
+fn test_probe_supports_nop() {
+  let ring = LioUring::new(8).unwrap();
+  let probe = Probe::new(&ring).unwrap();
+  assert!(probe.is_supported(Nop::CODE));
+  assert!(probe.is_supported(lio_uring::operation::Read::CODE));
+  assert!(!probe.is_supported(u8::MAX));
+}
+
 // ============================================================================
 // Submission Queue Tests
 // ============================================================================
@@ -84,6 +124,65 @@ fn test_sq_full_error() {
   assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::WouldBlock);
 }
 
+#[test]
+fn test_push_all_fits() {
+  let mut ring = LioUring::new(8).unwrap();
+  let entries: Vec<_> = (0..3).map(|_| Nop::new().build()).collect();
+
+  let pushed = unsafe { ring.push_all(&entries, 100) }.unwrap();
+  assert_eq!(pushed, 3);
+  ring.submit().unwrap();
+
+  let mut user_data: Vec<_> =
+    (0..3).map(|_| ring.wait().unwrap().user_data()).collect();
+  user_data.sort_unstable();
+  assert_eq!(user_data, vec![100, 101, 102]);
+}
+
+#[test]
+fn test_push_all_longer_than_ring() {
+  let mut ring = LioUring::new(4).unwrap();
+  let capacity = ring.sq_space_left();
+  let entries: Vec<_> =
+    (0..capacity * 2 + 1).map(|_| Nop::new().build()).collect();
+
+  let mut done = 0;
+  let mut seen = Vec::new();
+  while done < entries.len() {
+    let pushed =
+      unsafe { ring.push_all(&entries[done..], done as u64) }.unwrap();
+    assert!(pushed <= capacity);
+    done += pushed;
+    ring.submit().unwrap();
+    for _ in 0..pushed {
+      seen.push(ring.wait().unwrap().user_data());
+    }
+  }
+
+  seen.sort_unstable();
+  assert_eq!(seen, (0..entries.len() as u64).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_push_all_full_ring_untouched() {
+  let mut ring = LioUring::new(1).unwrap();
+  let capacity = ring.sq_space_left();
+  for i in 0..capacity {
+    unsafe { ring.push(Nop::new().build(), i as u64) }.unwrap();
+  }
+
+  let entries = [Nop::new().build(), Nop::new().build()];
+  let err = unsafe { ring.push_all(&entries, 999) }.unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+  assert_eq!(ring.outstanding(), capacity);
+
+  ring.submit().unwrap();
+  for _ in 0..capacity {
+    assert_ne!(ring.wait().unwrap().user_data(), 999);
+  }
+  assert_eq!(ring.cq_ready(), 0);
+}
+
 #[test]
 fn test_sq_space_restored_after_submit() {
   let mut ring = LioUring::new(4).unwrap();
@@ -106,6 +205,147 @@ fn test_sq_space_restored_after_submit() {
   assert_eq!(ring.sq_space_left(), initial);
 }
 
+#[test]
+fn test_submit_and_wait_blocks_for_completions() {
+  let mut ring = LioUring::new(8).unwrap();
+  for i in 0..3 {
+    unsafe { ring.push(Nop::new().build(), i) }.unwrap();
+  }
+
+  assert_eq!(ring.submit_and_wait(3).unwrap(), 3);
+  assert!(ring.cq_ready() >= 3);
+  for _ in 0..3 {
+    assert!(ring.try_wait().unwrap().is_some());
+  }
+}
+
+#[test]
+fn test_submit_and_wait_zero_is_submit() {
+  let mut ring = LioUring::new(8).unwrap();
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  let mut buf = [0u8; 8];
+  let read =
+    lio_uring::operation::Read::new(fds[0], buf.as_mut_ptr(), buf.len() as u32)
+      .build();
+  unsafe { ring.push(read, 1) }.unwrap();
+
+  // The pipe stays empty, so waiting would hang.
+  assert_eq!(ring.submit_and_wait(0).unwrap(), 1);
+  assert_eq!(ring.cq_ready(), 0);
+
+  // Let the read finish before buf goes away.
+  unsafe { libc::close(fds[1]) };
+  assert_eq!(ring.wait().unwrap().result(), 0);
+  unsafe { libc::close(fds[0]) };
+}
+
+#[test]
+fn test_completion_backpressure_throttles_submitter() {
+  let mut ring = LioUring::new(4).unwrap();
+  let limit = 4;
+  ring.set_completion_backpressure(Some(limit));
+
+  let total = 64u64;
+  let mut pushed = 0;
+  let mut completed = 0;
+  let mut throttled = 0;
+
+  while completed < total {
+    // Fast submitter: push until refused.
+    while pushed < total {
+      let op = Nop::new().build();
+      match unsafe { ring.push(op, pushed) } {
+        Ok(()) => pushed += 1,
+        Err(err) => {
+          assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+          throttled += 1;
+          break;
+        }
+      }
+    }
+    ring.submit().unwrap();
+
+    // Completions never outgrow the limit, so the CQ can't overflow.
+    assert!(ring.outstanding() <= limit);
+    assert!(ring.cq_ready() <= limit);
+
+    // Slow consumer: one completion per round.
+    ring.wait().unwrap();
+    completed += 1;
+  }
+
+  assert!(throttled > 0);
+  assert_eq!(ring.outstanding(), 0);
+}
+
+#[test]
+fn test_completion_backpressure_disabled_by_default() {
+  let mut ring = LioUring::new(8).unwrap();
+
+  for i in 0..8 {
+    let op = Nop::new().build();
+    unsafe { ring.push(op, i) }.unwrap();
+  }
+  assert_eq!(ring.outstanding(), 8);
+
+  ring.set_completion_backpressure(Some(8));
+  let op = Nop::new().build();
+  assert!(unsafe { ring.push(op, 8) }.is_err());
+
+  ring.set_completion_backpressure(None);
+  ring.submit().unwrap();
+  for _ in 0..8 {
+    ring.wait().unwrap();
+  }
+  assert_eq!(ring.outstanding(), 0);
+}
+
+#[test]
+fn test_cqe_skip_success_not_outstanding() {
+  let mut ring = LioUring::new(8).unwrap();
+
+  let op = Nop::new().build();
+  unsafe { ring.push_with_flags(op, 1, SqeFlags::CQE_SKIP_SUCCESS) }.unwrap();
+  let op = Nop::new().build();
+  unsafe { ring.push(op, 2) }.unwrap();
+  assert_eq!(ring.outstanding(), 1);
+
+  ring.submit().unwrap();
+  assert_eq!(ring.wait().unwrap().user_data(), 2);
+  assert_eq!(ring.outstanding(), 0);
+
+  // A failure would still post a completion, uncounted.
+  ring.set_completion_backpressure(Some(8));
+  let op = Nop::new().build();
+  let err = unsafe { ring.push_with_flags(op, 3, SqeFlags::CQE_SKIP_SUCCESS) }
+    .unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+  let mut sqe: io_uring_sqe = unsafe { std::mem::zeroed() };
+  sqe.opcode = Nop::CODE;
+  sqe.fd = -1;
+  sqe.flags = SqeFlags::CQE_SKIP_SUCCESS.bits();
+  let err = unsafe { ring.push_raw(sqe, 4) }.unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+  assert_eq!(ring.sq_space_left(), 8);
+}
+
+#[test]
+fn test_push_raw_nop() {
+  let mut ring = LioUring::new(4).unwrap();
+
+  let mut sqe: io_uring_sqe = unsafe { std::mem::zeroed() };
+  sqe.opcode = Nop::CODE;
+  sqe.fd = -1;
+  unsafe { ring.push_raw(sqe, 42) }.unwrap();
+
+  ring.submit().unwrap();
+  let completion = ring.wait().unwrap();
+  assert_eq!(completion.user_data(), 42);
+  assert_eq!(completion.result(), 0);
+}
+
 // ============================================================================
 // Completion Queue Tests
 // ============================================================================
@@ -215,6 +455,69 @@ fn test_peek_does_not_consume() {
   assert!(consumed.is_some());
 }
 
+#[test]
+fn test_peek_batch_does_not_consume() {
+  let mut ring = LioUring::new(8).unwrap();
+
+  for i in 1..=3 {
+    let op = Nop::new().build();
+    unsafe { ring.push(op, i) }.unwrap();
+  }
+  ring.submit().unwrap();
+  while ring.cq_ready() < 3 {
+    std::thread::sleep(Duration::from_millis(1));
+  }
+
+  let peeked: Vec<u64> =
+    ring.peek_batch(8).iter().map(|c| c.user_data()).collect();
+  assert_eq!(peeked, [1, 2, 3]);
+  assert_eq!(ring.cq_ready(), 3);
+
+  // A smaller batch only sees the oldest ones.
+  assert_eq!(ring.peek_batch(2).len(), 2);
+
+  for i in 1..=3 {
+    assert_eq!(ring.try_wait().unwrap().unwrap().user_data(), i);
+  }
+  assert!(ring.peek_batch(8).is_empty());
+}
+
+// ============================================================================
+// Restriction Tests
+// ============================================================================
+
+#[test]
+fn test_restrictions_reject_disallowed_op() {
+  let mut ring = LioUring::with_params(
+    Params { sq_entries: 8, ..Default::default() }.disabled(),
+  )
+  .unwrap();
+  ring.register_restrictions(&[Restriction::AllowOp(Nop::CODE)]).unwrap();
+  ring.enable_rings().unwrap();
+
+  unsafe { ring.push(Nop::new().build(), 1) }.unwrap();
+  ring.submit().unwrap();
+  assert!(ring.wait().unwrap().is_ok());
+
+  let file = File::options().write(true).open("/dev/null").unwrap();
+  let data = b"denied";
+  let op = Write::new(file.as_raw_fd(), data.as_ptr(), data.len() as u32);
+  unsafe { ring.push(op.build(), 2) }.unwrap();
+  ring.submit().unwrap();
+
+  let completion = ring.wait().unwrap();
+  assert_eq!(completion.user_data(), 2);
+  assert_eq!(completion.result(), -libc::EACCES);
+}
+
+#[test]
+fn test_restrictions_need_disabled_ring() {
+  let mut ring = LioUring::new(8).unwrap();
+  let err =
+    ring.register_restrictions(&[Restriction::AllowOp(Nop::CODE)]).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::EBADFD));
+}
+
 // ============================================================================
 // Completion Struct Tests
 // ============================================================================
@@ -262,6 +565,40 @@ fn test_completion_is_ok_failure() {
   assert!(completion.result() < 0);
 }
 
+#[test]
+fn test_completion_flags_multishot_poll() {
+  let mut ring = LioUring::new(8).unwrap();
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+
+  let poll = PollAdd::new(fds[0], libc::POLLIN as u32).multi(true).build();
+  unsafe { ring.push(poll, 1) }.unwrap();
+  ring.submit().unwrap();
+
+  // Every write wakes the poll, which stays armed.
+  for _ in 0..2 {
+    assert_eq!(unsafe { libc::write(fds[1], b"x".as_ptr().cast(), 1) }, 1);
+    let completion = ring.wait().unwrap();
+    assert_eq!(completion.user_data(), 1);
+    assert!(completion.flags.is_more());
+    assert!(completion.flags.contains(CqeFlags::MORE));
+    assert_eq!(completion.flags.buffer_id(), None);
+  }
+
+  unsafe { ring.push(PollRemove::new(1).build(), 2) }.unwrap();
+  ring.submit().unwrap();
+  for _ in 0..2 {
+    let completion = ring.wait().unwrap();
+    // Neither the removed poll nor the removal posts more.
+    assert!(!completion.flags.is_more());
+  }
+
+  unsafe {
+    libc::close(fds[0]);
+    libc::close(fds[1]);
+  }
+}
+
 // ============================================================================
 // SqeFlags Tests
 // ============================================================================
@@ -373,6 +710,20 @@ fn test_register_files_update() {
   ring.unregister_files().unwrap();
 }
 
+#[test]
+fn test_register_files_update_past_end_fails() {
+  let mut ring = LioUring::new(8).unwrap();
+  let file = File::open("/dev/null").unwrap();
+
+  ring.register_files(&[-1, -1]).unwrap();
+
+  // The table stays two slots long.
+  assert!(ring.register_files_update(2, &[file.as_raw_fd()]).is_err());
+  assert!(ring.register_files_update(1, &[file.as_raw_fd()]).is_ok());
+
+  ring.unregister_files().unwrap();
+}
+
 #[test]
 fn test_unregister_files_without_register() {
   let mut ring = LioUring::new(8).unwrap();
@@ -401,6 +752,39 @@ fn test_register_buffers() {
   assert!(result.is_ok());
 }
 
+#[test]
+fn test_register_buffers_scoped() {
+  let path = "/tmp/lio_uring_test_register_buffers_scoped";
+  let file = File::create(path).unwrap();
+  let mut ring = LioUring::new(8).unwrap();
+
+  let unused = vec![0u8; 4096];
+  let buf = b"fixed write".to_vec();
+  let slices = [std::io::IoSlice::new(&unused), std::io::IoSlice::new(&buf)];
+
+  {
+    let mut reg = ring.register_buffers_scoped(&slices).unwrap();
+    assert_eq!(reg.len(), 2);
+
+    let op = WriteFixed::new(
+      file.as_raw_fd(),
+      buf.as_ptr(),
+      buf.len() as u32,
+      reg.buf_index(1),
+    );
+    unsafe { reg.push(op.build(), 1) }.unwrap();
+    reg.submit().unwrap();
+
+    let completion = reg.wait().unwrap();
+    assert_eq!(completion.result(), buf.len() as i32);
+  }
+
+  // The guard unregistered the buffers, so there's nothing left to remove.
+  assert!(ring.unregister_buffers().is_err());
+  assert_eq!(std::fs::read(path).unwrap(), b"fixed write");
+  std::fs::remove_file(path).ok();
+}
+
 #[test]
 fn test_unregister_buffers_without_register() {
   let mut ring = LioUring::new(8).unwrap();
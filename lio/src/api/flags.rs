@@ -0,0 +1,140 @@
+//! Typed flags for socket operations.
+//!
+//! [`send`](super::send) and [`recv`](super::recv) take these instead of raw
+//! `MSG_*` integers. Each type only exposes the flags that make sense for its
+//! direction, so e.g. `MSG_PEEK` can't be passed to a send.
+//!
+//! ```
+//! use lio::api::flags::SendFlags;
+//!
+//! let flags = SendFlags::NOSIGNAL | SendFlags::DONTWAIT;
+//! assert_eq!(flags.bits(), libc::MSG_NOSIGNAL | libc::MSG_DONTWAIT);
+//! ```
+
+macro_rules! msg_flags {
+  (
+    $(#[$meta:meta])*
+    pub struct $name:ident {
+      $(
+        $(#[$flag_meta:meta])*
+        const $flag:ident = $value:expr;
+      )*
+    }
+  ) => {
+    $(#[$meta])*
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct $name {
+      bits: i32,
+    }
+
+    impl $name {
+      pub const NONE: Self = Self { bits: 0 };
+      $(
+        $(#[$flag_meta])*
+        pub const $flag: Self = Self { bits: $value };
+      )*
+
+      /// Returns the raw `MSG_*` value passed to the syscall.
+      pub const fn bits(self) -> i32 {
+        self.bits
+      }
+
+      /// Creates flags from a raw `MSG_*` value without validating it.
+      ///
+      /// Meant for callers that already hold a raw integer, like FFI.
+      pub const fn from_raw(bits: i32) -> Self {
+        Self { bits }
+      }
+
+      /// Combine flags using bitwise OR
+      pub const fn or(self, other: Self) -> Self {
+        Self { bits: self.bits | other.bits }
+      }
+
+      /// Check if these flags contain all bits from another
+      pub const fn contains(self, other: Self) -> bool {
+        (self.bits & other.bits) == other.bits
+      }
+
+      pub const fn is_empty(self) -> bool {
+        self.bits == 0
+      }
+    }
+
+    impl std::ops::BitOr for $name {
+      type Output = Self;
+
+      fn bitor(self, rhs: Self) -> Self::Output {
+        self.or(rhs)
+      }
+    }
+
+    impl std::ops::BitOrAssign for $name {
+      fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.or(rhs);
+      }
+    }
+
+    impl From<$name> for i32 {
+      fn from(value: $name) -> Self {
+        value.bits
+      }
+    }
+  };
+}
+
+msg_flags! {
+  /// Flags for [`send`](super::send).
+  pub struct SendFlags {
+    /// Don't block if the operation would block (`MSG_DONTWAIT`).
+    const DONTWAIT = libc::MSG_DONTWAIT;
+    /// Don't raise `SIGPIPE` when the peer has closed the connection, return
+    /// `EPIPE` instead (`MSG_NOSIGNAL`).
+    const NOSIGNAL = libc::MSG_NOSIGNAL;
+    /// Send out-of-band data (`MSG_OOB`).
+    const OOB = libc::MSG_OOB;
+  }
+}
+
+msg_flags! {
+  /// Flags for [`recv`](super::recv).
+  pub struct RecvFlags {
+    /// Return data without removing it from the receive queue (`MSG_PEEK`).
+    const PEEK = libc::MSG_PEEK;
+    /// Block until the full buffer is filled (`MSG_WAITALL`).
+    const WAITALL = libc::MSG_WAITALL;
+    /// Don't block if the operation would block (`MSG_DONTWAIT`).
+    const DONTWAIT = libc::MSG_DONTWAIT;
+    /// Receive out-of-band data (`MSG_OOB`).
+    const OOB = libc::MSG_OOB;
+    /// Return the real length of the datagram, even if it was longer than
+    /// the buffer (`MSG_TRUNC`).
+    const TRUNC = libc::MSG_TRUNC;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_send_flags_combine() {
+    let flags = SendFlags::NOSIGNAL | SendFlags::DONTWAIT;
+    assert_eq!(flags.bits(), libc::MSG_NOSIGNAL | libc::MSG_DONTWAIT);
+    assert!(flags.contains(SendFlags::NOSIGNAL));
+    assert!(!flags.contains(SendFlags::OOB));
+  }
+
+  #[test]
+  fn test_recv_flags_combine() {
+    let mut flags = RecvFlags::PEEK;
+    flags |= RecvFlags::WAITALL;
+    assert_eq!(i32::from(flags), libc::MSG_PEEK | libc::MSG_WAITALL);
+  }
+
+  #[test]
+  fn test_default_is_empty() {
+    assert!(SendFlags::default().is_empty());
+    assert_eq!(RecvFlags::NONE.bits(), 0);
+  }
+}
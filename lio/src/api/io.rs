@@ -10,10 +10,14 @@
 //! provides various methods to consume its result:
 //!
 //! - **Async/await**: Implements `IntoFuture`, allowing direct `.await` syntax
-//! - **Blocking**: [`wait()`](Io::wait) blocks until completion
+//! - **Blocking**: [`wait()`](Io::wait) blocks until completion, and
+//!   [`blocking_timeout()`](Io::blocking_timeout) runs the driver until
+//!   completion or a deadline
 //! - **Callbacks**: [`when_done()`](Io::when_done) executes a closure on completion
 //! - **Channels**: [`send()`](Io::send) and [`send_with()`](Io::send_with)
 //!   deliver results via channels
+//! - **Joining**: [`join()`] and [`join_all()`] run several operations at once
+//!   and block until all of them complete
 //!
 //! # Architecture
 //!
@@ -21,6 +25,7 @@
 //! Io<T>
 //!   ├─> IntoFuture ──> IoFuture<T> (async/await)
 //!   ├─> wait()        (blocking)
+//!   ├─> blocking_timeout(dur)  (blocking, bounded)
 //!   ├─> when_done(F)  (callback)
 //!   ├─> send()    ──> Receiver<T>        (channel-based blocking)
 //!   └─> send_with(Sender<T>)             (custom channel)
@@ -54,14 +59,22 @@
 //! threads than where operations were initiated. This is particularly useful for
 //! delegating I/O completion handling to dedicated threads.
 
-use crate::{lio, lio::Lio, registration::Registration, typed_op::TypedOp};
+use crate::{
+  CancelHandle, CancellationToken, OpId,
+  api::{multishot::MultishotStream, ops::WithTimeout},
+  lio,
+  lio::Lio,
+  registration::Registration,
+  typed_op::{DetachSafe, MultishotOp, TypedOp},
+};
 
 use std::{
   future::Future,
+  io,
   pin::Pin,
   sync::mpsc as std_mpsc,
   task::{Context, Poll},
-  time::Duration,
+  time::{Duration, Instant},
 };
 
 /// Represents an in-progress I/O operation with multiple consumption patterns.
@@ -94,6 +107,8 @@ where
 {
   op: T,
   handle: LioHandle,
+  cancel: Option<CancellationToken>,
+  tag: Option<u64>,
 }
 
 impl<T> Io<T>
@@ -118,6 +133,69 @@ where
   {
     self.send().recv()
   }
+
+  /// Runs the driver on the current thread until the operation completes,
+  /// or gives up once `dur` has passed.
+  ///
+  /// Unlike [`wait`](Self::wait) it needs nobody else to run the event
+  /// loop, and it can't hang forever on an operation that never completes,
+  /// such as a receive from a silent peer.
+  ///
+  /// `None` means the deadline passed first. The operation is then
+  /// cancelled, with an `AsyncCancel` on io_uring, and its result, buffer
+  /// included, is dropped by the driver once the cancellation completes.
+  /// If it completes while the cancellation is handed over, its result is
+  /// still returned.
+  /// To get the buffer back on timeout, use [`timeout`](Self::timeout)
+  /// instead, which hands it back along with
+  /// [`TimedOut`](io::ErrorKind::TimedOut).
+  ///
+  /// # Panics
+  ///
+  /// Panics if running the driver fails.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  /// use std::time::Duration;
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let fd = api::resource::Resource::stdin();
+  /// match api::read(&fd, vec![0u8; 1024])
+  ///     .with_lio(&lio)
+  ///     .blocking_timeout(Duration::from_secs(5))
+  /// {
+  ///     Some((result, buf)) => println!("read {:?}", result),
+  ///     None => eprintln!("no input within 5 seconds"),
+  /// }
+  /// ```
+  pub fn blocking_timeout(mut self, dur: Duration) -> Option<T::Result>
+  where
+    T::Result: Send,
+  {
+    let deadline = Instant::now() + dur;
+    let lio = self.handle.lio();
+    let cancel = self.cancel_handle();
+    let mut receiver = self.send();
+    loop {
+      if let Some(result) = receiver.try_recv() {
+        return Some(result);
+      }
+      let left = deadline.saturating_duration_since(Instant::now());
+      if left.is_zero() {
+        cancel.cancel();
+        // Hands the cancellation to the backend right away.
+        lio
+          .run_timeout(Duration::ZERO)
+          .expect("lio error: running the driver failed");
+        // It may have completed in that last run, don't drop the result.
+        return receiver.try_recv();
+      }
+      lio.run_timeout(left).expect("lio error: running the driver failed");
+    }
+  }
+
   /// Convert the operation into a channel receiver.
   ///
   /// Returns a [`Receiver`] which receives the operation result when complete.
@@ -171,14 +249,17 @@ where
   ///     Ok(())
   /// }
   /// ```
+  ///
+  /// Any [`Sender`] works, so results can be funneled into an existing
+  /// `crossbeam` channel or a bounded [`SyncSender`](std_mpsc::SyncSender)
+  /// just the same.
   #[inline]
-  pub fn send_with(self, sender: std_mpsc::Sender<T::Result>)
+  pub fn send_with<S>(self, sender: S)
   where
+    S: Sender<T::Result> + Send + 'static,
     T::Result: Send,
   {
-    self.when_done(move |res| {
-      let _ = sender.send(res);
-    });
+    self.when_done(move |res| sender.send(res));
   }
   /// Registers a callback to be invoked when the operation completes.
   ///
@@ -217,7 +298,8 @@ where
   where
     F: FnOnce(T::Result) + Send + 'static,
   {
-    let (lio, typed_op) = self.into_lio();
+    let tag = self.tag;
+    let (lio, typed_op, cancel) = self.into_lio();
     // IMPORTANT: Box the typed_op FIRST to give it a stable heap address,
     // THEN call into_op(). The Op contains pointers into the TypedOp's data,
     // so the TypedOp must be at its final heap location before into_op() is called.
@@ -225,9 +307,127 @@ where
     // then typed_op was moved to the heap, leaving dangling pointers in the Op.
     let mut boxed = Box::new(typed_op);
     let op = boxed.into_op();
-    lio
-      .schedule(op, Registration::new_callback_boxed::<T, F>(f, boxed))
+    let timeout = boxed.timeout();
+    let after_writes = boxed.after_writes();
+    let id = lio
+      .schedule(
+        op,
+        timeout,
+        after_writes,
+        Registration::new_callback_boxed::<T, F>(f, boxed),
+      )
       .expect("lio error: lio should handle this");
+    if let Some(token) = cancel {
+      lio.attach_cancel(id, token);
+    }
+    if let Some(tag) = tag {
+      lio.attach_tag(id, tag);
+    }
+  }
+
+  /// Like [`when_done`](Self::when_done), but the callback also gets the tag
+  /// set with [`with_tag`](Self::with_tag), or `0` if none was set.
+  ///
+  /// The tag is kept apart from the id the driver tracks the operation by,
+  /// so it reaches the callback exactly as given.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// use std::sync::mpsc::channel;
+  /// use lio::{Lio, api};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let fd = api::resource::Resource::stdin();
+  /// let (tx, rx) = channel();
+  ///
+  /// for request_id in [7, 8] {
+  ///     let tx = tx.clone();
+  ///     api::read(&fd, vec![0u8; 512])
+  ///         .with_lio(&lio)
+  ///         .with_tag(request_id)
+  ///         .when_done_tagged(move |request_id, (result, buf)| {
+  ///             tx.send((request_id, result, buf)).unwrap();
+  ///         });
+  /// }
+  /// lio.run().unwrap();
+  /// let (request_id, result, buf) = rx.recv().unwrap();
+  /// ```
+  pub fn when_done_tagged<F>(self, f: F)
+  where
+    F: FnOnce(u64, T::Result) + Send + 'static,
+  {
+    let tag = self.tag.unwrap_or(0);
+    self.when_done(move |result| f(tag, result));
+  }
+}
+
+impl<T> Io<T>
+where
+  T: TypedOp,
+  T::Result: Send + 'static,
+{
+  /// Starts the operation without waiting for it, keeping the result until
+  /// it's claimed with [`Lio::await_id`](crate::Lio::await_id).
+  ///
+  /// Useful to fire an operation now and check on it later, without
+  /// holding on to a future or channel meanwhile. Fails with `ENOBUFS` if
+  /// keeping the result would exceed
+  /// [`Lio::set_pending_memory_cap`](crate::Lio::set_pending_memory_cap).
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let fd = api::resource::Resource::stdout();
+  /// let id = api::write(&fd, b"hello".to_vec()).with_lio(&lio).detach_with_id().unwrap();
+  /// let claim = lio.await_id(id);
+  /// ```
+  pub fn detach_with_id(self) -> io::Result<OpId<T::Result>> {
+    let tag = self.tag;
+    let (lio, op, cancel) = self.into_lio();
+    let (detached, id) = lio.reserve_detached::<T::Result>()?;
+    let key = id.as_u64();
+    Io { op, handle: LioHandle::Custom(lio), cancel, tag }
+      .when_done(move |result| detached.fill(key, result));
+    Ok(id)
+  }
+}
+
+impl<T> Io<T>
+where
+  T: DetachSafe,
+{
+  /// Starts the operation and forgets about it.
+  ///
+  /// The driver keeps the operation alive until it completes, then drops it
+  /// together with its result. Only available for operations where that
+  /// loses nothing, see [`DetachSafe`]. A read hands its data back, so it
+  /// can't be detached:
+  ///
+  /// ```compile_fail
+  /// use lio::{Lio, api};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let fd = api::resource::Resource::stdin();
+  /// api::read(&fd, vec![0u8; 64]).with_lio(&lio).detach();
+  /// ```
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let fd = api::resource::Resource::stdout();
+  /// api::write(&fd, b"bye".to_vec()).with_lio(&lio).detach();
+  /// api::fsync(&fd).with_lio(&lio).detach();
+  /// lio.run().unwrap();
+  /// ```
+  pub fn detach(self) {
+    self.when_done(drop);
   }
 }
 
@@ -239,6 +439,46 @@ enum LioHandle {
   Custom(Lio),
 }
 
+impl LioHandle {
+  fn lio(&self) -> Lio {
+    match self {
+      LioHandle::GloballyInstalled => lio::get_global().expect(
+        "No Lio instance available. Either call install_global(lio) or use .with_lio(&lio) before consuming the operation.",
+      ),
+      LioHandle::Custom(lio) => lio.clone(),
+    }
+  }
+}
+
+/// The sending half of a channel, for [`Io::send_with`].
+///
+/// Implemented for the senders of [`std::sync::mpsc`] and
+/// `crossbeam-channel`. Other channels, such as tokio's, can be plugged in
+/// through a newtype implementing this trait.
+pub trait Sender<T> {
+  /// Sends `value`, dropping it if the receiving half is gone.
+  fn send(&self, value: T);
+}
+
+impl<T> Sender<T> for std_mpsc::Sender<T> {
+  fn send(&self, value: T) {
+    let _ = std_mpsc::Sender::send(self, value);
+  }
+}
+
+/// Blocks the driver while the channel is full.
+impl<T> Sender<T> for std_mpsc::SyncSender<T> {
+  fn send(&self, value: T) {
+    let _ = std_mpsc::SyncSender::send(self, value);
+  }
+}
+
+impl<T> Sender<T> for crossbeam_channel::Sender<T> {
+  fn send(&self, value: T) {
+    let _ = crossbeam_channel::Sender::send(self, value);
+  }
+}
+
 /// A blocking receiver for operation results.
 ///
 /// Provides blocking and non-blocking methods to receive operation results.
@@ -355,6 +595,114 @@ impl<T> Receiver<T> {
   }
 }
 
+/// Runs two operations at once, blocking until both complete.
+///
+/// Unlike awaiting or [`wait`](Io::wait)ing one after the other, both are
+/// submitted before the driver runs, so their latencies overlap. The driver
+/// is run on the current thread until then, there's no need for anyone else
+/// to run it.
+///
+/// # Panics
+///
+/// Panics if the operations are bound to different [`Lio`] instances, or if
+/// running the driver fails.
+///
+/// # Example
+///
+/// ```no_run
+/// use lio::{Lio, api, api::io::join};
+///
+/// let lio = Lio::new(64).unwrap();
+/// let fd = api::resource::Resource::stdin();
+/// let (header, body) = join(
+///     api::read_at(&fd, vec![0u8; 64], 0).with_lio(&lio),
+///     api::read_at(&fd, vec![0u8; 4096], 64).with_lio(&lio),
+/// );
+/// ```
+pub fn join<A, B>(a: Io<A>, b: Io<B>) -> (A::Result, B::Result)
+where
+  A: TypedOp,
+  B: TypedOp,
+  A::Result: Send,
+  B::Result: Send,
+{
+  let lio = a.handle.lio();
+  assert!(lio.ptr_eq(&b.handle.lio()), "joined operations on different Lios");
+
+  let (mut a, mut b) = (a.send(), b.send());
+  let (mut out_a, mut out_b) = (None, None);
+  run_until(&lio, || {
+    if out_a.is_none() {
+      out_a = a.try_recv();
+    }
+    if out_b.is_none() {
+      out_b = b.try_recv();
+    }
+    out_a.is_some() && out_b.is_some()
+  });
+  (out_a.unwrap(), out_b.unwrap())
+}
+
+/// Runs every operation in `ios` at once, blocking until all of them
+/// complete, and returns their results in the same order.
+///
+/// See [`join`], the same applies here. Useful to fan out reads:
+///
+/// ```no_run
+/// use lio::{Lio, api, api::io::join_all};
+///
+/// let lio = Lio::new(64).unwrap();
+/// let fd = api::resource::Resource::stdin();
+/// let reads = (0..8)
+///     .map(|i| api::read_at(&fd, vec![0u8; 4096], i * 4096).with_lio(&lio))
+///     .collect();
+/// for (result, buf) in join_all(reads) {
+///     println!("{:?}", &buf[..result.unwrap() as usize]);
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if the operations are bound to different [`Lio`] instances, or if
+/// running the driver fails.
+pub fn join_all<T>(ios: Vec<Io<T>>) -> Vec<T::Result>
+where
+  T: TypedOp,
+  T::Result: Send,
+{
+  let Some(first) = ios.first() else {
+    return Vec::new();
+  };
+  let lio = first.handle.lio();
+  assert!(
+    ios.iter().all(|io| lio.ptr_eq(&io.handle.lio())),
+    "joined operations on different Lios"
+  );
+
+  let mut receivers: Vec<_> = ios.into_iter().map(Io::send).collect();
+  let mut results: Vec<_> = receivers.iter().map(|_| None).collect();
+  // Indices of the operations that haven't completed yet.
+  let mut pending: Vec<usize> = (0..receivers.len()).collect();
+  run_until(&lio, || {
+    pending.retain(|&i| match receivers[i].try_recv() {
+      Some(result) => {
+        results[i] = Some(result);
+        false
+      }
+      None => true,
+    });
+    pending.is_empty()
+  });
+  results.into_iter().map(Option::unwrap).collect()
+}
+
+/// Runs `lio` until `done` returns true.
+fn run_until(lio: &Lio, mut done: impl FnMut() -> bool) {
+  while !done() {
+    lio.run().expect("lio error: running the driver failed");
+  }
+}
+
 impl<T> Receiver<T> {
   fn get_inner(&mut self) -> Option<std_mpsc::Receiver<T>> {
     self.recv.take()
@@ -376,7 +724,7 @@ where
   /// The returned Io has no Lio instance bound. You must call
   /// `.with_lio()` before consuming the operation.
   pub fn from_op(op: T) -> Self {
-    Self { op, handle: LioHandle::GloballyInstalled }
+    Self { op, handle: LioHandle::GloballyInstalled, cancel: None, tag: None }
   }
 
   /// Binds a Lio instance to this operation.
@@ -396,17 +744,134 @@ where
   ///     .wait();
   /// ```
   pub fn with_lio(self, lio: &Lio) -> Self {
-    Io { op: self.op, handle: LioHandle::Custom(lio.clone()) }
+    Io { handle: LioHandle::Custom(lio.clone()), ..self }
   }
 
-  fn into_lio(self) -> (Lio, T) {
-    let lio = match self.handle {
-      LioHandle::GloballyInstalled => lio::get_global().expect(
-        "No Lio instance available. Either call install_global(lio) or use .with_lio(&lio) before consuming the operation.",
-      ),
-      LioHandle::Custom(lio) => lio,
+  /// Aborts the operation when `token` is cancelled.
+  ///
+  /// If it is still in flight by then, the operation completes with
+  /// `ECANCELED`. Attach the same token to many operations to cancel them
+  /// together, see [`CancellationToken`].
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{CancellationToken, Lio, api};
+  ///
+  /// let lio = Lio::new(1024).unwrap();
+  /// let token = CancellationToken::new();
+  /// let fd = api::resource::Resource::stdin();
+  /// let receiver = api::read(&fd, vec![0u8; 1024])
+  ///     .with_lio(&lio)
+  ///     .with_cancel(&token)
+  ///     .send();
+  /// token.cancel();
+  /// ```
+  pub fn with_cancel(self, token: &CancellationToken) -> Self {
+    let cancel = match self.cancel {
+      Some(prev) => token.clone().or(prev),
+      None => token.clone(),
     };
-    (lio, self.op)
+    Io { cancel: Some(cancel), ..self }
+  }
+
+  /// Returns a handle that cancels just this operation.
+  ///
+  /// If it is still in flight when [`CancelHandle::cancel`] is called, the
+  /// io_uring backend submits an `AsyncCancel` for it and the polling
+  /// backend drops its fd interest. The operation then fails with
+  /// [`ErrorKind::Interrupted`](std::io::ErrorKind::Interrupted) and hands
+  /// back its buffer, so pending reads can be torn down without leaking
+  /// them. Tokens from [`with_cancel`](Self::with_cancel) keep working
+  /// alongside the handle.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let fd = api::resource::Resource::stdin();
+  /// let mut read = api::read(&fd, vec![0u8; 64]).with_lio(&lio);
+  /// let handle = read.cancel_handle();
+  /// let receiver = read.send();
+  /// handle.cancel();
+  /// ```
+  pub fn cancel_handle(&mut self) -> CancelHandle {
+    let handle = CancelHandle::new();
+    let token = handle.token().clone();
+    self.cancel = Some(match self.cancel.take() {
+      Some(prev) => token.or(prev),
+      None => token,
+    });
+    handle
+  }
+
+  /// Attaches a caller-chosen tag to the operation, such as a request id.
+  ///
+  /// [`when_done_tagged`](Self::when_done_tagged) hands it to the callback,
+  /// and [`Lio::debug_dump`](crate::Lio::debug_dump) lists it while the
+  /// operation is in flight.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  ///
+  /// let lio = Lio::new(1024).unwrap();
+  /// let fd = api::resource::Resource::stdin();
+  /// api::read(&fd, vec![0u8; 1024])
+  ///     .with_lio(&lio)
+  ///     .with_tag(42)
+  ///     .when_done_tagged(|tag, (result, _buf)| {
+  ///         println!("request {tag}: {result:?}");
+  ///     });
+  /// ```
+  pub fn with_tag(self, tag: u64) -> Self {
+    Io { tag: Some(tag), ..self }
+  }
+
+  /// Bounds how long the operation may run.
+  ///
+  /// If `dur` passes before the operation completes, it is abandoned and
+  /// completes with [`io::ErrorKind::TimedOut`]. Operations that own a buffer
+  /// still hand it back on that path. See
+  /// [`WithTimeout`](crate::api::ops::WithTimeout).
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  /// use std::time::Duration;
+  ///
+  /// let lio = Lio::new(1024).unwrap();
+  /// let fd = api::resource::Resource::stdin();
+  /// let receiver = api::read(&fd, vec![0u8; 1024])
+  ///     .timeout(Duration::from_secs(5))
+  ///     .with_lio(&lio)
+  ///     .send();
+  /// ```
+  pub fn timeout(self, dur: Duration) -> Io<WithTimeout<T>> {
+    let Io { op, handle, cancel, tag } = self;
+    Io { op: WithTimeout::new(op, dur), handle, cancel, tag }
+  }
+
+  fn into_lio(self) -> (Lio, T, Option<CancellationToken>) {
+    (self.handle.lio(), self.op, self.cancel)
+  }
+}
+
+impl<T> Io<T>
+where
+  T: MultishotOp,
+{
+  /// Converts a multishot operation into a [`Stream`](futures_core::Stream)
+  /// of its completions.
+  ///
+  /// See [`MultishotStream`].
+  pub fn into_stream(self) -> MultishotStream<T> {
+    let (lio, op, cancel) = self.into_lio();
+    MultishotStream::new(lio, op, cancel)
   }
 }
 
@@ -418,8 +883,9 @@ where
   type IntoFuture = IoFuture<T>;
 
   fn into_future(self) -> Self::IntoFuture {
-    let (lio, op) = self.into_lio();
-    IoFuture { state: IoFutureState::Pending(op), lio }
+    let tag = self.tag;
+    let (lio, op, cancel) = self.into_lio();
+    IoFuture { state: IoFutureState::Pending(op), lio, cancel, tag }
   }
 }
 
@@ -454,6 +920,9 @@ where
 pub struct IoFuture<T> {
   state: IoFutureState<T>,
   lio: Lio,
+  /// Attached once the operation is scheduled.
+  cancel: Option<CancellationToken>,
+  tag: Option<u64>,
 }
 
 enum IoFutureState<T> {
@@ -486,10 +955,23 @@ where
         // so the TypedOp must be at its final heap location before into_op() is called.
         let mut boxed = Box::new(typed);
         let op = boxed.into_op();
+        let timeout = boxed.timeout();
+        let after_writes = boxed.after_writes();
         let id = this
           .lio
-          .schedule(op, Registration::new_waker(cx.waker().clone()))
+          .schedule(
+            op,
+            timeout,
+            after_writes,
+            Registration::new_waker(cx.waker().clone()),
+          )
           .expect("lio error: failed to schedule operation");
+        if let Some(token) = this.cancel.take() {
+          this.lio.attach_cancel(id, token);
+        }
+        if let Some(tag) = this.tag {
+          this.lio.attach_tag(id, tag);
+        }
         this.state = IoFutureState::Inflight { id, op: boxed };
         Poll::Pending
       }
@@ -639,6 +1121,44 @@ mod tests {
     assert!(poll3.is_ready());
   }
 
+  #[test]
+  fn test_io_future_wakes_latest_waker() {
+    use std::sync::{
+      Arc,
+      atomic::{AtomicUsize, Ordering},
+    };
+    use std::task::Wake;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+      fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+      }
+    }
+
+    let lio = Lio::new(64).unwrap();
+    let mut future = api::nop().with_lio(&lio).into_future();
+
+    let a = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let b = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker_a = Waker::from(a.clone());
+    let waker_b = Waker::from(b.clone());
+
+    // Submit with A, then re-poll with B like `select!` would.
+    let poll = Pin::new(&mut future).poll(&mut Context::from_waker(&waker_a));
+    assert!(poll.is_pending());
+    let poll = Pin::new(&mut future).poll(&mut Context::from_waker(&waker_b));
+    assert!(poll.is_pending());
+
+    run_until_done(&lio);
+
+    assert_eq!(a.0.load(Ordering::SeqCst), 0);
+    assert_eq!(b.0.load(Ordering::SeqCst), 1);
+    let poll = Pin::new(&mut future).poll(&mut Context::from_waker(&waker_b));
+    assert!(poll.is_ready());
+  }
+
   #[test]
   fn test_multiple_futures_can_coexist() {
     let lio = Lio::new(64).unwrap();
@@ -71,12 +71,21 @@
 //! - [`Resource`](crate::api::resource::Resource) - Reference-counted file descriptor wrapper
 //! - [`crate::buf`] - Buffer types and pooling
 
+#[cfg(unix)]
+pub mod flags;
 pub mod io;
+pub mod multishot;
 pub mod ops;
 pub mod resource;
-use crate::{api::resource::AsResource, buf::BufLike};
+use crate::{
+  api::resource::AsResource,
+  buf::{BufLike, BufStore, IoBufMut, LentBuf},
+};
 use io::Io;
-use std::{ffi::CString, net::SocketAddr, time::Duration};
+use std::{ffi::CString, time::Duration};
+
+#[cfg(unix)]
+use std::net::SocketAddr;
 
 #[cfg(unix)]
 use std::os::fd::RawFd;
@@ -91,6 +100,17 @@ doc_op! {
     }
 }
 
+doc_op! {
+    short: "Submits a user-defined operation.",
+
+    /// The backend runs `op` as described by [`RawOperation`](crate::typed_op::RawOperation).
+    /// On completion the non-negative result is returned alongside `op`, so
+    /// data the kernel wrote into it can be read back.
+    pub fn custom<O: crate::typed_op::RawOperation>(op: O) -> Io<ops::Custom<O>> {
+        Io::from_op(ops::Custom::new(op))
+    }
+}
+
 doc_op! {
     short: "Closes a raw file descriptor.",
     syscall: "close(2)",
@@ -101,6 +121,24 @@ doc_op! {
     }
 }
 
+doc_op! {
+    short: "Closes every raw file descriptor from `first` to `last`, inclusive.",
+    syscall: "close_range(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/close_range.2.html",
+
+    /// Runs on the blocking pool. `flags` takes `CLOSE_RANGE_CLOEXEC` to mark
+    /// the descriptors close-on-exec instead of closing them, and
+    /// `CLOSE_RANGE_UNSHARE`. Pass `u32::MAX` as `last` for "all above
+    /// `first`".
+    ///
+    /// Descriptors owned by a [`Resource`](resource::Resource) are closed
+    /// regardless, so only use this on descriptors nothing else tracks.
+    #[cfg(target_os = "linux")]
+    pub fn close_range(first: u32, last: u32, flags: u32) -> Io<ops::CloseRange> {
+        Io::from_op(ops::CloseRange::new(first, last, flags))
+    }
+}
+
 /// Closes a raw handle.
 ///
 /// # Parameters
@@ -137,6 +175,7 @@ doc_op! {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg(unix)]
     pub fn shutdown(res: &impl AsResource, how: i32) -> Io<ops::Shutdown> {
         Io::from_op(ops::Shutdown::new(res.as_resource().clone(), how))
     }
@@ -150,6 +189,28 @@ doc_op! {
     }
 }
 
+doc_op! {
+    short: "Waits for `duration` without blocking the event loop.",
+
+    /// The same op as [`timeout`], under the name async runtimes use. io_uring
+    /// waits with its timeout op, kqueue with an `EVFILT_TIMER` and epoll with
+    /// a `timerfd`, so no thread sleeps on it. The readiness backends count
+    /// whole milliseconds.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// async fn tick() -> std::io::Result<()> {
+    ///     lio::api::sleep(Duration::from_millis(100)).await
+    /// }
+    /// ```
+    pub fn sleep(duration: Duration) -> Io<ops::Timeout> {
+        Io::from_op(ops::Timeout::new(duration))
+    }
+}
+
 doc_op!(
   short: "Create a symlink.",
   syscall: "symlinkat(2)",
@@ -160,6 +221,207 @@ doc_op!(
   }
 );
 
+doc_op!(
+  short: "Read the target of a symlink.",
+  syscall: "readlinkat(2)",
+  doc_link: "https://man7.org/linux/man-pages/man2/readlinkat.2.html",
+
+  /// Runs on the blocking pool, there is no io_uring opcode for it. Targets
+  /// of any length are returned whole.
+  #[cfg(unix)]
+  pub fn readlinkat(dir_res: &impl AsResource, path: CString) -> Io<ops::ReadLinkAt> {
+    Io::from_op(ops::ReadLinkAt::new(dir_res.as_resource().clone(), path))
+  }
+);
+
+doc_op!(
+  short: "Resolve a path against a directory into a canonical absolute path.",
+
+  /// Like `realpath(3)`, but relative paths start at `dir_res` instead of
+  /// the working directory: `.` and `..` are collapsed and symlinks
+  /// followed, walking with `openat` from `dir_res`. The process' working
+  /// directory is never read or changed, so threads can't race on it. Runs
+  /// on the blocking pool. Every component must exist.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::ffi::CString;
+  ///
+  /// async fn resolve_at_example() -> std::io::Result<()> {
+  ///     let dir = lio::api::open_dir(CString::new("/srv/www").unwrap()).await?;
+  ///     let path = CString::new("../shared/index.html").unwrap();
+  ///     let resolved = lio::api::resolve_at(&dir, path).await?;
+  ///     println!("Resolved to {resolved:?}");
+  ///     Ok(())
+  /// }
+  /// ```
+  #[cfg(unix)]
+  pub fn resolve_at(dir_res: &impl AsResource, path: CString) -> Io<ops::ResolveAt> {
+    Io::from_op(ops::ResolveAt::new(dir_res.as_resource().clone(), path))
+  }
+);
+
+doc_op!(
+  short: "Read a whole file into memory.",
+
+  /// Combines `open(2)`, `fstat(2)`, `read(2)` and `close(2)` on the blocking
+  /// pool. The buffer is sized from `fstat`, but the file is read until EOF,
+  /// so files that grow or shrink meanwhile are handled, as are empty ones.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::ffi::CString;
+  ///
+  /// async fn read_file_example() -> std::io::Result<()> {
+  ///     let contents = lio::api::read_file(CString::new("/etc/hostname").unwrap()).await?;
+  ///     println!("{}", String::from_utf8_lossy(&contents));
+  ///     Ok(())
+  /// }
+  /// ```
+  #[cfg(unix)]
+  pub fn read_file(path: CString) -> Io<ops::ReadFile> {
+    Io::from_op(ops::ReadFile::new(path))
+  }
+);
+
+doc_op!(
+  short: "Write a whole file, replacing its contents.",
+
+  /// Combines `open(2)` with `O_CREAT | O_TRUNC`, `write(2)`, an optional
+  /// `fsync(2)` and `close(2)` on the blocking pool. With `durable` set, the
+  /// data is on storage once the operation completes.
+  ///
+  /// A crash midway can leave the file truncated or partially written, use
+  /// [`write_file_atomic`] if readers must never see that.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::ffi::CString;
+  ///
+  /// async fn write_file_example() -> std::io::Result<()> {
+  ///     let path = CString::new("/tmp/lio.conf").unwrap();
+  ///     lio::api::write_file(path, b"key = value\n".to_vec(), true).await?;
+  ///     Ok(())
+  /// }
+  /// ```
+  #[cfg(unix)]
+  pub fn write_file(path: CString, data: Vec<u8>, durable: bool) -> Io<ops::WriteFile> {
+    Io::from_op(ops::WriteFile::new(path, data, durable))
+  }
+);
+
+doc_op!(
+  short: "Atomically replace a file's contents.",
+
+  /// Writes and syncs `data` to a temporary file in the same directory, then
+  /// `rename(2)`s it over `path` and syncs the directory. Readers see either
+  /// the old contents or the new ones, even across a crash.
+  #[cfg(unix)]
+  pub fn write_file_atomic(path: CString, data: Vec<u8>) -> Io<ops::WriteFile> {
+    Io::from_op(ops::WriteFile::new_atomic(path, data))
+  }
+);
+
+/// Durably replace a file's contents, driving each step through `lio`.
+///
+/// Like [`write_file_atomic`], but instead of running the whole sequence on
+/// the blocking pool, the temporary file's writes, `fsync(2)`, the
+/// `rename(2)` over `path` and the directory `fsync(2)` are each submitted as
+/// their own operation. Only creating the temporary file uses the pool.
+///
+/// Once this resolves, `path` holds `data` and survives a crash. Readers see
+/// either the old contents or the new ones, never a partial write. On error
+/// the temporary file is removed and `path` is left as it was, unless the
+/// rename already happened and only the directory sync failed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::ffi::CString;
+///
+/// async fn save_state(lio: &lio::Lio, state: Vec<u8>) -> std::io::Result<()> {
+///     let path = CString::new("/var/lib/app/state").unwrap();
+///     lio::api::replace_file_durable(path, state, lio).await
+/// }
+/// ```
+#[cfg(unix)]
+pub async fn replace_file_durable(
+  path: CString,
+  data: Vec<u8>,
+  lio: &crate::Lio,
+) -> std::io::Result<()> {
+  ops::replace_file_durable(path, data, lio).await
+}
+
+doc_op!(
+  short: "Spawn a child process.",
+
+  /// Runs `posix_spawnp(3)` on the blocking pool and returns the child's pid
+  /// with a [`PidFd`](ops::PidFd) for it, opened with `pidfd_open(2)`. The
+  /// pidfd turns readable when the child exits, so [`poll`] can wait for it.
+  ///
+  /// `program` is looked up in `PATH` and becomes `argv[0]`, followed by
+  /// `args`. Each `(resource, target)` in `fds` is `dup2`ed onto `target` in
+  /// the child; every other lio descriptor is close-on-exec.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::ffi::CString;
+  /// use lio::api::ops::Interest;
+  ///
+  /// async fn spawn_example() -> std::io::Result<()> {
+  ///     let program = CString::new("true").unwrap();
+  ///     let (pid, pidfd) = lio::api::spawn_process(program, vec![], vec![]).await?;
+  ///     lio::api::poll(&pidfd, Interest::READABLE).await?;
+  ///     println!("{pid} exited");
+  ///     Ok(())
+  /// }
+  /// ```
+  #[cfg(target_os = "linux")]
+  pub fn spawn_process(
+    program: CString,
+    args: Vec<CString>,
+    fds: Vec<(resource::Resource, RawFd)>,
+  ) -> Io<ops::SpawnProcess> {
+    Io::from_op(ops::SpawnProcess::new(program, args, fds))
+  }
+);
+
+doc_op!(
+  short: "Wait for a child process to change state.",
+  syscall: "waitid(2)",
+  doc_link: "https://man7.org/linux/man-pages/man2/waitid.2.html",
+
+  /// `idtype` and `id` select the children, such as `P_PID` with a pid or
+  /// `P_ALL`, and `options` holds what to wait for, at least one of
+  /// `WEXITED`, `WSTOPPED` and `WCONTINUED`. Resolves to the
+  /// [`ChildStatus`](ops::ChildStatus) of the child, whose exit code is in
+  /// [`exit_code`](ops::ChildStatus::exit_code). Without `WNOWAIT` an exited
+  /// child is reaped.
+  ///
+  /// Linux 6.7 waits on the ring. Older kernels and other platforms run
+  /// the `waitid` on the blocking pool, so no `SIGCHLD` handler is needed
+  /// either way.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// async fn waitid_example(pid: libc::pid_t) -> std::io::Result<()> {
+  ///     let status = lio::api::waitid(libc::P_PID, pid as libc::id_t, libc::WEXITED).await?;
+  ///     println!("{} exited with {:?}", status.pid, status.exit_code());
+  ///     Ok(())
+  /// }
+  /// ```
+  #[cfg(unix)]
+  pub fn waitid(idtype: libc::idtype_t, id: libc::id_t, options: i32) -> Io<ops::WaitId> {
+    Io::from_op(ops::WaitId::new(idtype, id, options))
+  }
+);
+
 doc_op!(
   short: "Create a hard-link.",
   syscall: "linkat(2)",
@@ -170,6 +432,87 @@ doc_op!(
   }
 );
 
+doc_op!(
+  short: "Rename a file.",
+  syscall: "renameat(2)",
+  doc_link: "https://man7.org/linux/man-pages/man2/rename.2.html",
+
+  /// An existing `new_path` is replaced atomically.
+  pub fn renameat(old_dir_res: &impl AsResource, old_path: CString, new_dir_res: &impl AsResource, new_path: CString) -> Io<ops::RenameAt> {
+    Io::from_op(ops::RenameAt::new(old_dir_res.as_resource().clone(), old_path, new_dir_res.as_resource().clone(), new_path))
+  }
+);
+
+doc_op!(
+  short: "Rename a file, unless the target already exists.",
+  syscall: "renameat2(2)",
+  doc_link: "https://man7.org/linux/man-pages/man2/rename.2.html",
+
+  /// Paths are relative to the working directory. Checking for the target
+  /// and renaming happen atomically, so this can create lock files: write
+  /// a temporary file, then move it into place.
+  ///
+  /// Fails with [`AlreadyExists`](std::io::ErrorKind::AlreadyExists)
+  /// (`EEXIST`) if `new_path` exists. Uses `RENAME_NOREPLACE` on Linux and
+  /// `RENAME_EXCL` on Apple platforms, other platforms fail with `EINVAL`.
+  #[cfg(unix)]
+  pub fn rename_noreplace(old_path: CString, new_path: CString) -> Io<ops::RenameAt> {
+    Io::from_op(ops::RenameAt::new_noreplace(old_path, new_path))
+  }
+);
+
+doc_op!(
+  short: "Rename a file, with `renameat2` flags.",
+  syscall: "renameat2(2)",
+  doc_link: "https://man7.org/linux/man-pages/man2/rename.2.html",
+
+  /// Like [`renameat`], with `flags` passed through. With
+  /// [`RENAME_NOREPLACE`](ops::RENAME_NOREPLACE) it fails with
+  /// [`AlreadyExists`](std::io::ErrorKind::AlreadyExists) instead of
+  /// replacing `new_path`.
+  ///
+  /// Linux takes any `renameat2` flag. Apple platforms only support
+  /// `RENAME_NOREPLACE`, other platforms no flags at all, anything else fails
+  /// with `EINVAL` there.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::api::{self, ops::RENAME_NOREPLACE, resource::Resource};
+  /// use std::ffi::CString;
+  ///
+  /// async fn publish(dir: &Resource) -> std::io::Result<()> {
+  ///     let tmp = CString::new("out.tmp").unwrap();
+  ///     let dst = CString::new("out").unwrap();
+  ///     api::renameat2(dir, tmp, dir, dst, RENAME_NOREPLACE).await
+  /// }
+  /// ```
+  pub fn renameat2(old_dir_res: &impl AsResource, old_path: CString, new_dir_res: &impl AsResource, new_path: CString, flags: u32) -> Io<ops::RenameAt> {
+    Io::from_op(ops::RenameAt::with_flags(old_dir_res.as_resource().clone(), old_path, new_dir_res.as_resource().clone(), new_path, flags))
+  }
+);
+
+doc_op!(
+  short: "Create a directory.",
+  syscall: "mkdirat(2)",
+  doc_link: "https://man7.org/linux/man-pages/man2/mkdir.2.html",
+
+  /// `mode` is masked by the process umask, as usual.
+  pub fn mkdirat(dir_res: &impl AsResource, path: CString, mode: u32) -> Io<ops::MkDirAt> {
+    Io::from_op(ops::MkDirAt::new(dir_res.as_resource().clone(), path, mode))
+  }
+);
+
+doc_op!(
+  short: "Remove a file, or a directory with `AT_REMOVEDIR`.",
+  syscall: "unlinkat(2)",
+  doc_link: "https://man7.org/linux/man-pages/man2/unlink.2.html",
+
+  pub fn unlinkat(dir_res: &impl AsResource, path: CString, flags: i32) -> Io<ops::UnlinkAt> {
+    Io::from_op(ops::UnlinkAt::new(dir_res.as_resource().clone(), path, flags))
+  }
+);
+
 doc_op! {
     short: "Synchronizes file data to storage.",
     syscall: "fsync(2)",
@@ -190,6 +533,169 @@ doc_op! {
     }
 }
 
+doc_op! {
+    short: "Synchronizes file data to storage once every write to it in flight has completed.",
+    syscall: "fsync(2)",
+
+    /// The writes scheduled on `res` before this call, through the same
+    /// [`Lio`](crate::Lio), are tracked by the driver and the fsync is only
+    /// submitted after all of them complete, whether they succeed or not.
+    /// Writes scheduled afterwards don't hold it back.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::{Lio, api, api::resource::Resource};
+    ///
+    /// fn durable_append(lio: &Lio, fd: &Resource) {
+    ///     api::write(fd, b"a".to_vec()).with_lio(lio).when_done(|_| {});
+    ///     api::write(fd, b"b".to_vec()).with_lio(lio).when_done(|_| {});
+    ///     api::fsync_after_writes(fd)
+    ///         .with_lio(lio)
+    ///         .when_done(|res| res.expect("fsync failed"));
+    /// }
+    /// ```
+    pub fn fsync_after_writes(res: &impl AsResource) -> Io<ops::Fsync> {
+        Io::from_op(ops::Fsync::new_after_writes(res.as_resource().clone()))
+    }
+}
+
+doc_op! {
+    short: "Flushes a byte range of a file to storage.",
+    syscall: "sync_file_range(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/sync_file_range.2.html",
+
+    ///
+    /// Syncs `len` bytes starting at `offset`, a `len` of 0 means up to the
+    /// end of the file. Unlike [`fsync`] it doesn't flush metadata or the
+    /// rest of the file, which keeps appends to a write-ahead log from
+    /// stalling on each other.
+    ///
+    /// `flags` is a combination of:
+    ///
+    /// - `SYNC_FILE_RANGE_WAIT_BEFORE`: wait for writeback already in flight
+    ///   on the range.
+    /// - `SYNC_FILE_RANGE_WRITE`: start writeback of dirty pages in the range,
+    ///   without waiting for it.
+    /// - `SYNC_FILE_RANGE_WAIT_AFTER`: wait for the writeback to finish.
+    ///
+    /// All three together flush the range and wait for it to hit the disk.
+    /// `SYNC_FILE_RANGE_WRITE` alone only starts writeback, handy to push out
+    /// data early and make a later full sync cheaper. None of them make the
+    /// data durable on their own: file metadata, such as its size after an
+    /// append, still needs [`fsync`].
+    ///
+    /// Other platforms have no range sync, there the whole file's data is
+    /// flushed with `fdatasync` (`fsync` on macOS) and `offset`, `len` and
+    /// `flags` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # async fn example() -> std::io::Result<()> {
+    /// use lio::api;
+    /// use lio::api::resource::Resource;
+    ///
+    /// # let wal = Resource::stdin();
+    /// let flags = libc::SYNC_FILE_RANGE_WAIT_BEFORE
+    ///     | libc::SYNC_FILE_RANGE_WRITE
+    ///     | libc::SYNC_FILE_RANGE_WAIT_AFTER;
+    /// // Flush the record just appended at offset 4096.
+    /// api::sync_file_range(&wal, 4096, 512, flags).await?;
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn example() {}
+    /// # fn main() {}
+    /// ```
+    #[cfg(unix)]
+    pub fn sync_file_range(res: &impl AsResource, offset: u64, len: u32, flags: u32) -> Io<ops::SyncFileRange> {
+        Io::from_op(ops::SyncFileRange::new(res.as_resource().clone(), offset, len, flags))
+    }
+}
+
+doc_op! {
+    short: "Declares how a range of a file will be accessed.",
+    syscall: "posix_fadvise(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/posix_fadvise.2.html",
+
+    ///
+    /// `len` of 0 covers everything from `offset` to the end of the file. The
+    /// advice is only a hint, the kernel may ignore it.
+    ///
+    /// Useful values are `POSIX_FADV_SEQUENTIAL` and `POSIX_FADV_RANDOM` to
+    /// tune readahead, `POSIX_FADV_WILLNEED` to start reading the range into
+    /// the page cache, and `POSIX_FADV_DONTNEED` to evict it once you're done,
+    /// so streaming a large file doesn't push everything else out of the
+    /// cache. `POSIX_FADV_NOREUSE` and `POSIX_FADV_NORMAL` are accepted but do
+    /// little on Linux.
+    ///
+    /// Fails with [`Unsupported`](std::io::ErrorKind::Unsupported) on
+    /// platforms without `posix_fadvise`, such as macOS.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # async fn example() -> std::io::Result<()> {
+    /// use lio::api;
+    /// use lio::api::resource::Resource;
+    ///
+    /// # let file = Resource::stdin();
+    /// // Done streaming, drop the file from the page cache.
+    /// api::fadvise(&file, 0, 0, libc::POSIX_FADV_DONTNEED).await?;
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn example() {}
+    /// # fn main() {}
+    /// ```
+    #[cfg(unix)]
+    pub fn fadvise(res: &impl AsResource, offset: u64, len: i64, advice: i32) -> Io<ops::Fadvise> {
+        Io::from_op(ops::Fadvise::new(res.as_resource().clone(), offset, len, advice))
+    }
+}
+
+doc_op! {
+    short: "Allocates or deallocates disk space for a range of a file.",
+    syscall: "fallocate(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/fallocate.2.html",
+
+    ///
+    /// With a `mode` of 0 the `len` bytes starting at `offset` are reserved
+    /// up front, growing the file if the range goes past its end, so later
+    /// writes can't fail with `ENOSPC`. `FALLOC_FL_KEEP_SIZE` reserves the
+    /// space without changing the file size, and
+    /// `FALLOC_FL_PUNCH_HOLE | FALLOC_FL_KEEP_SIZE` frees the range instead.
+    ///
+    /// A `len` of 0, or one that doesn't fit an `i64`, fails with `EINVAL`.
+    /// Fails with [`Unsupported`](std::io::ErrorKind::Unsupported) outside
+    /// Linux.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # async fn example() -> std::io::Result<()> {
+    /// use lio::api;
+    /// use lio::api::resource::Resource;
+    ///
+    /// # let file = Resource::stdin();
+    /// // Reserve 1 MiB before downloading into the file.
+    /// api::fallocate(&file, 0, 1 << 20, 0).await?;
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn example() {}
+    /// # fn main() {}
+    /// ```
+    #[cfg(unix)]
+    pub fn fallocate(res: &impl AsResource, offset: u64, len: u64, mode: i32) -> Io<ops::Fallocate> {
+        Io::from_op(ops::Fallocate::new(res.as_resource().clone(), offset, len, mode))
+    }
+}
+
 doc_op! {
     short: "Writes data from buffer to file descriptor.",
     syscall: "write(2)",
@@ -216,6 +722,71 @@ doc_op! {
     }
 }
 
+doc_op! {
+    short: "Reads from a file descriptor into several buffers in order.",
+    syscall: "readv(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/readv.2.html",
+
+    /// Each buffer is filled completely before the next one, so a header and
+    /// body can be read into separate allocations without a copy. The buffers
+    /// come back in the same order, each one holding the bytes that landed in
+    /// it. Like [`writev`], lists longer than `IOV_MAX` are split into several
+    /// calls, and an empty list completes right away with 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn readv_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdin();
+    ///     let bufs = vec![Vec::with_capacity(4), Vec::with_capacity(1024)];
+    ///     let (read, bufs) = lio::api::readv(&fd, bufs).await;
+    ///     println!("Read {} bytes, header {:?}", read?, bufs[0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn readv<B>(res: &impl AsResource, bufs: Vec<B>) -> Io<ops::Readv<B>>
+    where
+        B: IoBufMut + std::marker::Send + Sync
+    {
+        Io::from_op(ops::Readv::new(res.as_resource().clone(), bufs))
+    }
+}
+
+doc_op! {
+    short: "Writes several buffers to a file descriptor in order.",
+    syscall: "writev(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/writev.2.html",
+
+    /// Lists longer than `IOV_MAX` are split into several `writev` calls, so
+    /// any number of buffers can be passed. On io_uring they are linked, so
+    /// they go out in order, and the list may be up to `IOV_MAX` times the
+    /// submission queue size before failing with `EINVAL`. The result is the
+    /// total number of bytes written, which is less than the sum of the
+    /// buffers on a short write.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn writev_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdout();
+    ///     let bufs = vec![b"Hello, ".to_vec(), b"World!".to_vec()];
+    ///     let (written, _bufs) = lio::api::writev(&fd, bufs).await;
+    ///     println!("Wrote {} bytes", written?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn writev<B>(res: &impl AsResource, bufs: Vec<B>) -> Io<ops::Writev<B>>
+    where
+        B: BufLike + std::marker::Send + Sync
+    {
+        Io::from_op(ops::Writev::new(res.as_resource().clone(), bufs))
+    }
+}
+
 doc_op! {
     short: "Writes data from buffer to file descriptor at a specific offset.",
     syscall: "pwrite(2)",
@@ -253,12 +824,43 @@ doc_op! {
 
     pub fn read<B>(res: &impl AsResource, mem: B) -> Io<ops::Read<B>>
     where
-        B: BufLike + std::marker::Send + Sync
+        B: IoBufMut + std::marker::Send + Sync
     {
         Io::from_op(ops::Read::new(res.as_resource().clone(), mem))
     }
 }
 
+doc_op! {
+    short: "Reads resource into a buffer borrowed from `pool`.",
+    syscall: "read(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/read.2.html",
+
+    ///
+    /// Returns `None` when every buffer of the pool is lent out, so a server
+    /// can apply backpressure instead of blocking on [`BufStore::get`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::buf::BufStore;
+    ///
+    /// async fn try_read_example(pool: &'static BufStore) -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdin();
+    ///     let Some(read) = lio::api::try_read(&fd, pool) else {
+    ///         // Out of buffers, try again later.
+    ///         return Ok(());
+    ///     };
+    ///     let (res, buf) = read.await;
+    ///     println!("Read {:?}", &buf.as_ref()[..res? as usize]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn try_read(res: &impl AsResource, pool: &'static BufStore) -> Option<Io<ops::Read<LentBuf<'static>>>> {
+        Some(read(res, pool.try_get()?))
+    }
+}
+
 doc_op! {
   short: "Reads resource into provided buffer with a offset.",
   syscall: "pread(2)",
@@ -266,7 +868,7 @@ doc_op! {
 
   pub fn read_at<B>(res: &impl AsResource, mem: B, offset: i64) -> Io<ops::ReadAt<B>>
   where
-      B: BufLike + std::marker::Send + Sync
+      B: IoBufMut + std::marker::Send + Sync
   {
     Io::from_op(ops::ReadAt::new(res.as_resource().clone(), mem, offset))
   }
@@ -293,49 +895,308 @@ doc_op! {
 }
 
 doc_op! {
-    short: "Creates a new socket with the specified domain, type, and protocol.",
-    syscall: "socket(2)",
+    short: "Duplicates a file descriptor.",
+    syscall: "dup(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/dup.2.html",
 
+    ///
+    /// The new descriptor has `FD_CLOEXEC` set, like [`std::fs::File::try_clone`].
     ///
     /// # Examples
     ///
     /// ```rust,no_run
-    /// async fn socket_example() -> std::io::Result<()> {
-    ///     // AF_INET (IPv4), SOCK_STREAM (TCP), protocol 0 (default)
-    ///     let sock = lio::api::socket(libc::AF_INET, libc::SOCK_STREAM, 0).await?;
-    ///     println!("Created socket: {:?}", sock);
+    /// async fn dup_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdout();
+    ///     let copy = lio::api::dup(&fd).await?;
     ///     Ok(())
     /// }
     /// ```
-    pub fn socket(domain: libc::c_int, ty: libc::c_int, proto: libc::c_int) -> Io<ops::Socket> {
-        Io::from_op(ops::Socket::new(domain, ty, proto))
+    #[cfg(unix)]
+    pub fn dup(res: &impl AsResource) -> Io<ops::Dup> {
+        Io::from_op(ops::Dup::new(res.as_resource().clone()))
     }
 }
 
 doc_op! {
-    short: "Binds a socket to a specific address.",
-    syscall: "bind(2)",
+    short: "Makes `new_res` refer to the same open file as `old_res`.",
+    syscall: "dup2(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/dup.2.html",
 
+    ///
+    /// Whatever `new_res` referred to before is closed. Unlike [`dup`], the
+    /// descriptor doesn't get `FD_CLOEXEC`, so this is the call to use when
+    /// setting up a child process' stdio.
     ///
     /// # Examples
     ///
     /// ```rust,no_run
-    /// async fn bind_example() -> std::io::Result<()> {
+    /// async fn dup2_example() -> std::io::Result<()> {
     ///     # use lio::api::resource::Resource;
-    ///     # let fd = Resource::stdin();
-    ///     let addr = "127.0.0.1:8080".parse::<std::net::SocketAddr>().unwrap();
-    ///     lio::api::bind(&fd, addr).await?;
+    ///     # let (file, target) = (Resource::stdin(), Resource::stdout());
+    ///     // From now on, writes to `target` end up in `file`.
+    ///     lio::api::dup2(&file, &target).await?;
     ///     Ok(())
     /// }
     /// ```
-    pub fn bind(resource: &impl AsResource, addr: SocketAddr) -> Io<ops::Bind> {
-        Io::from_op(ops::Bind::new(resource.as_resource().clone(), addr))
+    #[cfg(unix)]
+    pub fn dup2(old_res: &impl AsResource, new_res: &impl AsResource) -> Io<ops::Dup2> {
+        Io::from_op(ops::Dup2::new(old_res.as_resource().clone(), new_res.as_resource().clone()))
     }
 }
 
 doc_op! {
-    short: "Accepts a connection on a listening socket.",
-    syscall: "accept(2)",
+    short: "Sets the access and modification times of a file.",
+    syscall: "futimens(3)",
+    doc_link: "https://man7.org/linux/man-pages/man3/futimens.3.html",
+
+    ///
+    /// Pass [`FileTime::Now`](ops::FileTime::Now) to use the current time, or
+    /// [`FileTime::Omit`](ops::FileTime::Omit) to leave a timestamp as is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::ops::FileTime;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// async fn futimens_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdout();
+    ///     let mtime = FileTime::At(UNIX_EPOCH + Duration::from_secs(1_000_000));
+    ///     lio::api::futimens(&fd, FileTime::Omit, mtime).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn futimens(res: &impl AsResource, atime: ops::FileTime, mtime: ops::FileTime) -> Io<ops::Futimens> {
+        Io::from_op(ops::Futimens::new(res.as_resource().clone(), atime, mtime))
+    }
+}
+
+doc_op! {
+    short: "Sets the access and modification times of a file relative to a directory file descriptor.",
+    syscall: "utimensat(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/utimensat.2.html",
+
+    ///
+    /// `flags` may contain `AT_SYMLINK_NOFOLLOW` to change the times of a
+    /// symlink itself. See [`futimens`] for the meaning of each [`FileTime`](ops::FileTime).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::ops::FileTime;
+    /// use std::ffi::CString;
+    ///
+    /// async fn utimensat_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # use std::os::fd::FromRawFd;
+    ///     # let dir = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+    ///     let path = CString::new("/tmp/test.txt").unwrap();
+    ///     lio::api::utimensat(&dir, path, FileTime::Now, FileTime::Now, 0).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn utimensat(dir_res: &impl AsResource, path: CString, atime: ops::FileTime, mtime: ops::FileTime, flags: i32) -> Io<ops::UtimensAt> {
+        Io::from_op(ops::UtimensAt::new(dir_res.as_resource().clone(), path, atime, mtime, flags))
+    }
+}
+
+doc_op! {
+    short: "Queries the metadata of a file relative to a directory file descriptor.",
+    syscall: "statx(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/statx.2.html",
+
+    ///
+    /// Resolves to the file's size, modification time and mode as a
+    /// [`Metadata`](ops::Metadata). `flags` takes the `AT_*` flags of
+    /// `statx`, such as `AT_SYMLINK_NOFOLLOW`. `mask` selects the fields the
+    /// kernel must fill in (`STATX_SIZE | STATX_MTIME | STATX_TYPE | STATX_MODE`
+    /// covers [`Metadata`](ops::Metadata)); other platforms ignore it.
+    ///
+    /// io_uring runs the `statx` asynchronously. Other backends have no
+    /// asynchronous stat, so outside Linux an `fstatat` runs on the blocking
+    /// pool instead of stalling the event loop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::ffi::CString;
+    ///
+    /// async fn statx_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # use std::os::fd::FromRawFd;
+    ///     # let dir = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+    ///     let path = CString::new("/tmp/test.txt").unwrap();
+    ///     let meta = lio::api::statx(&dir, path, 0, u32::MAX).await?;
+    ///     println!("{} bytes, modified {:?}", meta.size, meta.modified);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn statx(dir_res: &impl AsResource, path: CString, flags: i32, mask: u32) -> Io<ops::Statx> {
+        Io::from_op(ops::Statx::new(dir_res.as_resource().clone(), path, flags, mask))
+    }
+}
+
+doc_op! {
+    short: "Creates a pipe, resolving to its read and write ends.",
+    syscall: "pipe2(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/pipe.2.html",
+
+    ///
+    /// `flags` takes `O_CLOEXEC` and `O_NONBLOCK`, plus `O_DIRECT` on Linux.
+    /// io_uring creates the pipe asynchronously on Linux 6.16 and later; older
+    /// kernels and the other backends call `pipe2` instead, which never
+    /// blocks for long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn pipe_example() -> std::io::Result<()> {
+    ///     let (read_end, write_end) = lio::api::pipe(libc::O_CLOEXEC).await?;
+    ///     lio::api::write(&write_end, b"hello".to_vec()).await.0?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn pipe(flags: i32) -> Io<ops::Pipe> {
+        Io::from_op(ops::Pipe::new(flags))
+    }
+}
+
+doc_op! {
+    short: "Maps a file into memory.",
+    syscall: "mmap(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/mmap.2.html",
+
+    ///
+    /// The mapping address is always chosen by the kernel, so `MAP_FIXED` is
+    /// ignored. The returned [`MmapRegion`](ops::MmapRegion) is unmapped on drop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn mmap_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdin();
+    ///     let mut region = lio::api::mmap(
+    ///         &fd,
+    ///         4096,
+    ///         libc::PROT_READ | libc::PROT_WRITE,
+    ///         libc::MAP_SHARED,
+    ///         0,
+    ///     ).await?;
+    ///     let bytes = region.as_mut_slice().expect("mapped read-write");
+    ///     bytes[..5].copy_from_slice(b"hello");
+    ///     let (result, _region) = lio::api::msync(region, libc::MS_SYNC).await;
+    ///     result?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn mmap(res: &impl AsResource, len: usize, prot: i32, flags: i32, offset: i64) -> Io<ops::Mmap> {
+        Io::from_op(ops::Mmap::new(res.as_resource().clone(), len, prot, flags, offset))
+    }
+}
+
+doc_op! {
+    short: "Flushes changes made to a memory-mapped region back to the file.",
+    syscall: "msync(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/msync.2.html",
+
+    ///
+    /// The region is handed back alongside the result.
+    #[cfg(unix)]
+    pub fn msync(region: ops::MmapRegion, flags: i32) -> Io<ops::Msync> {
+        Io::from_op(ops::Msync::new(region, flags))
+    }
+}
+
+doc_op! {
+    short: "Advises the kernel how a memory-mapped region will be accessed.",
+    syscall: "madvise(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/madvise.2.html",
+
+    ///
+    /// The advice covers the whole region, which the operation holds until it
+    /// completes and then hands back alongside the result. Taking the region
+    /// rather than a raw address keeps the range mapped for as long as the
+    /// kernel may touch it.
+    ///
+    /// Useful values are `MADV_SEQUENTIAL` and `MADV_RANDOM` to tune readahead,
+    /// `MADV_WILLNEED` to start paging the region in, and `MADV_DONTNEED` to
+    /// drop its pages. For a private mapping the latter discards any changes,
+    /// later reads see the file (or zeroes) again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn madvise_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdin();
+    ///     let region = lio::api::mmap(&fd, 4096, libc::PROT_READ, libc::MAP_SHARED, 0).await?;
+    ///     let (result, region) = lio::api::madvise(region, libc::MADV_SEQUENTIAL).await;
+    ///     result?;
+    ///     let bytes = region.as_slice().expect("mapped readable");
+    ///     let _sum: u64 = bytes.iter().map(|&b| b as u64).sum();
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn madvise(region: ops::MmapRegion, advice: i32) -> Io<ops::Madvise> {
+        Io::from_op(ops::Madvise::new(region, advice))
+    }
+}
+
+doc_op! {
+    short: "Creates a new socket with the specified domain, type, and protocol.",
+    syscall: "socket(2)",
+
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn socket_example() -> std::io::Result<()> {
+    ///     // AF_INET (IPv4), SOCK_STREAM (TCP), protocol 0 (default)
+    ///     let sock = lio::api::socket(libc::AF_INET, libc::SOCK_STREAM, 0).await?;
+    ///     println!("Created socket: {:?}", sock);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn socket(domain: libc::c_int, ty: libc::c_int, proto: libc::c_int) -> Io<ops::Socket> {
+        Io::from_op(ops::Socket::new(domain, ty, proto))
+    }
+}
+
+doc_op! {
+    short: "Binds a socket to a specific address.",
+    syscall: "bind(2)",
+
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn bind_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdin();
+    ///     let addr = "127.0.0.1:8080".parse::<std::net::SocketAddr>().unwrap();
+    ///     lio::api::bind(&fd, addr).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn bind(resource: &impl AsResource, addr: SocketAddr) -> Io<ops::Bind> {
+        Io::from_op(ops::Bind::new(resource.as_resource().clone(), addr))
+    }
+}
+
+doc_op! {
+    short: "Accepts a connection on a listening socket.",
+    syscall: "accept(2)",
 
     ///
     /// # Examples
@@ -349,6 +1210,7 @@ doc_op! {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg(unix)]
     pub fn accept(res: &impl AsResource) -> Io<ops::Accept> {
         Io::from_op(ops::Accept::new(res.as_resource().clone()))
     }
@@ -373,11 +1235,78 @@ doc_op! {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg(unix)]
     pub fn accept_unix(res: &impl AsResource) -> Io<ops::AcceptUnix> {
         Io::from_op(ops::AcceptUnix::new(res.as_resource().clone()))
     }
 }
 
+doc_op! {
+    short: "Accepts a connection straight into a fixed file slot.",
+    syscall: "accept(2)",
+
+    ///
+    /// The connection gets a [`FixedFd`](ops::FixedFd) instead of a regular
+    /// descriptor, in the fixed file table of `lio`. The returned [`Io`] is
+    /// already bound to it. Use the slot with [`recv_fixed`] and
+    /// [`send_fixed`]; dropping it or passing it to [`close_fixed`] frees it.
+    /// Only the io_uring backend supports this, others fail with `EOPNOTSUPP`.
+    #[cfg(target_os = "linux")]
+    pub fn accept_direct(res: &impl AsResource, lio: &crate::Lio) -> Io<ops::AcceptDirect> {
+        Io::from_op(ops::AcceptDirect::new(res.as_resource().clone(), lio.fixed_table())).with_lio(lio)
+    }
+}
+
+doc_op! {
+    short: "Receives data on a fixed file slot into provided buffer.",
+    syscall: "recv(2)",
+
+    /// Like [`recv`], without the fd table lookup.
+    #[cfg(target_os = "linux")]
+    pub fn recv_fixed<B>(fd: &ops::FixedFd, buf: B, flags: Option<flags::RecvFlags>) -> Io<ops::RecvFixed<B>>
+    where
+        B: IoBufMut + std::marker::Send + Sync
+    {
+        Io::from_op(ops::RecvFixed::new(fd, buf, flags.unwrap_or_default().bits()))
+    }
+}
+
+doc_op! {
+    short: "Sends data on a fixed file slot.",
+    syscall: "send(2)",
+
+    /// Like [`send`], without the fd table lookup.
+    #[cfg(target_os = "linux")]
+    pub fn send_fixed<B>(fd: &ops::FixedFd, buf: B, flags: Option<flags::SendFlags>) -> Io<ops::SendFixed<B>>
+    where
+        B: BufLike + std::marker::Send + Sync
+    {
+        Io::from_op(ops::SendFixed::new(fd, buf, flags.unwrap_or_default().bits()))
+    }
+}
+
+doc_op! {
+    short: "Installs a regular descriptor for a fixed file slot.",
+
+    /// The new descriptor refers to the same file, the slot stays taken.
+    /// Uses `IORING_OP_FIXED_FD_INSTALL`, Linux 6.8 or newer.
+    #[cfg(target_os = "linux")]
+    pub fn install_fixed(fd: &ops::FixedFd) -> Io<ops::InstallFixed> {
+        Io::from_op(ops::InstallFixed::new(fd))
+    }
+}
+
+doc_op! {
+    short: "Frees a fixed file slot.",
+    syscall: "close(2)",
+
+    /// Descriptors installed from it with [`install_fixed`] stay open.
+    #[cfg(target_os = "linux")]
+    pub fn close_fixed(fd: ops::FixedFd) -> Io<ops::CloseFixed> {
+        Io::from_op(ops::CloseFixed::new(fd))
+    }
+}
+
 doc_op! {
     short: "Marks a socket as listening for incoming connections.",
     syscall: "listen(2)",
@@ -394,6 +1323,7 @@ doc_op! {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg(unix)]
     pub fn listen(res: &impl AsResource, backlog: i32) -> Io<ops::Listen> {
         Io::from_op(ops::Listen::new(res.as_resource().clone(), backlog))
     }
@@ -418,11 +1348,75 @@ doc_op! {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg(unix)]
     pub fn connect(res: &impl AsResource, addr: SocketAddr) -> Io<ops::Connect> {
         Io::from_op(ops::Connect::new(res.as_resource().clone(), addr))
     }
 }
 
+doc_op! {
+    short: "Reads a socket option.",
+    syscall: "getsockopt(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/getsockopt.2.html",
+
+    /// Returns the raw option bytes, as long as the kernel reports them.
+    /// Runs on the blocking pool.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::resource::Resource;
+    ///
+    /// async fn rcvbuf_example(socket: Resource) -> std::io::Result<()> {
+    ///     let raw = lio::api::getsockopt(&socket, libc::SOL_SOCKET, libc::SO_RCVBUF).await?;
+    ///     let size = i32::from_ne_bytes(raw[..4].try_into().unwrap());
+    ///     println!("Receive buffer: {size} bytes");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn getsockopt(res: &impl AsResource, level: i32, name: i32) -> Io<ops::GetSockOpt> {
+        Io::from_op(ops::GetSockOpt::new(res.as_resource().clone(), level, name))
+    }
+}
+
+doc_op! {
+    short: "Sets a socket option.",
+    syscall: "setsockopt(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/setsockopt.2.html",
+
+    /// `value` holds the raw option bytes. Runs on the blocking pool.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::resource::Resource;
+    ///
+    /// async fn rcvbuf_example(socket: Resource) -> std::io::Result<()> {
+    ///     let size = 1i32 << 20;
+    ///     lio::api::setsockopt(&socket, libc::SOL_SOCKET, libc::SO_RCVBUF, size.to_ne_bytes().to_vec()).await
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn setsockopt(res: &impl AsResource, level: i32, name: i32, value: Vec<u8>) -> Io<ops::SetSockOpt> {
+        Io::from_op(ops::SetSockOpt::new(res.as_resource().clone(), level, name, value))
+    }
+}
+
+doc_op! {
+    short: "Takes the pending error of a socket (`SO_ERROR`).",
+    syscall: "getsockopt(2)",
+    doc_link: "https://man7.org/linux/man-pages/man7/socket.7.html",
+
+    /// Resolves to `Ok(())` if no error is pending, otherwise to the error,
+    /// which the kernel then clears. After a non-blocking `connect(2)` this
+    /// is how the outcome of the attempt is read.
+    #[cfg(unix)]
+    pub fn get_so_error(res: &impl AsResource) -> Io<ops::SoError> {
+        Io::from_op(ops::SoError::new(res.as_resource().clone()))
+    }
+}
+
 doc_op! {
     short: "Sends data on a connected socket.",
     syscall: "send(2)",
@@ -440,7 +1434,8 @@ doc_op! {
     ///     Ok(())
     /// }
     /// ```
-    pub fn send<B>(res: &impl AsResource, buf: B, flags: Option<i32>) -> Io<ops::Send<B>>
+    #[cfg(unix)]
+    pub fn send<B>(res: &impl AsResource, buf: B, flags: Option<flags::SendFlags>) -> Io<ops::Send<B>>
     where
         B: BufLike + std::marker::Send + Sync
     {
@@ -448,6 +1443,48 @@ doc_op! {
     }
 }
 
+doc_op! {
+    short: "Sends data on a connected socket without copying it into the kernel.",
+    syscall: "send(2)",
+    doc_link: "https://man7.org/linux/man-pages/man3/io_uring_prep_send_zc.3.html",
+
+    ///
+    /// The network stack reads straight from `buf`, which pays off for large
+    /// sends, roughly from a few KiB up. Small ones are cheaper to copy.
+    ///
+    /// The kernel may still be reading `buf` after it reported how many
+    /// bytes were sent, so the operation only completes, handing the buffer
+    /// back, once the kernel is done with it. A buffer lent from a
+    /// [`BufStore`](crate::buf::BufStore) registered with
+    /// [`register_fixed`](crate::buf::BufStore::register_fixed) is sent from
+    /// its fixed buffer slot, sparing the kernel from pinning its pages on
+    /// every send.
+    ///
+    /// Needs io_uring on Linux 6.0 or later. Elsewhere this is a plain
+    /// [`send`], copy included.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn send_zc_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let fd = Resource::stdin();
+    ///     let frame = vec![0u8; 64 * 1024];
+    ///     let (sent, frame) = lio::api::send_zc(&fd, frame, None).await;
+    ///     println!("Sent {} bytes", sent?);
+    ///     // The kernel is done with `frame`, it can be refilled.
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn send_zc<B>(res: &impl AsResource, buf: B, flags: Option<flags::SendFlags>) -> Io<ops::SendZc<B>>
+    where
+        B: BufLike + std::marker::Send + Sync
+    {
+        Io::from_op(ops::SendZc::new(res.as_resource().clone(), buf, flags))
+    }
+}
+
 doc_op! {
     short: "Receives data over a socket into provided buffer.",
     syscall: "recv(2)",
@@ -467,16 +1504,302 @@ doc_op! {
     ///     Ok(())
     /// }
     /// ```
-    pub fn recv<B>(res: &impl AsResource, buf: B, flags: Option<i32>) -> Io<ops::Recv<B>>
+    #[cfg(unix)]
+    pub fn recv<B>(res: &impl AsResource, buf: B, flags: Option<flags::RecvFlags>) -> Io<ops::Recv<B>>
     where
-        B: BufLike + std::marker::Send + Sync
+        B: IoBufMut + std::marker::Send + Sync
     {
         Io::from_op(ops::Recv::new(res.as_resource().clone(), buf, flags))
     }
 }
 
 doc_op! {
-    short: "Opens a file relative to a directory file descriptor.",
+    short: "Receives data over a socket into a buffer borrowed from `pool`.",
+    syscall: "recv(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/recv.2.html",
+
+    ///
+    /// Returns `None` when every buffer of the pool is lent out, see [`try_read`].
+    #[cfg(unix)]
+    pub fn try_recv(res: &impl AsResource, pool: &'static BufStore, flags: Option<flags::RecvFlags>) -> Option<Io<ops::Recv<LentBuf<'static>>>> {
+        Some(recv(res, pool.try_get()?, flags))
+    }
+}
+
+doc_op! {
+    short: "Sends a datagram to `addr` from an unconnected socket.",
+    syscall: "sendmsg(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/sendmsg.2.html",
+
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn send_to_example(socket: &lio::api::resource::Resource) -> std::io::Result<()> {
+    ///     let addr = "127.0.0.1:5353".parse().unwrap();
+    ///     let (bytes_sent, _buf) = lio::api::send_to(socket, b"query".to_vec(), addr).await;
+    ///     println!("Sent {} bytes", bytes_sent?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn send_to<B>(res: &impl AsResource, buf: B, addr: SocketAddr) -> Io<ops::SendTo<B>>
+    where
+        B: BufLike + std::marker::Send + Sync
+    {
+        Io::from_op(ops::SendTo::new(res.as_resource().clone(), buf, addr))
+    }
+}
+
+doc_op! {
+    short: "Receives a datagram and the address it came from.",
+    syscall: "recvmsg(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/recvmsg.2.html",
+
+    /// A datagram larger than the buffer is truncated, the rest of it is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn recv_from_example(socket: &lio::api::resource::Resource) -> std::io::Result<()> {
+    ///     let (result, buf) = lio::api::recv_from(socket, vec![0u8; 512]).await;
+    ///     let (len, from) = result?;
+    ///     println!("{} bytes from {}: {:?}", len, from, &buf[..len]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn recv_from<B>(res: &impl AsResource, buf: B) -> Io<ops::RecvFrom<B>>
+    where
+        B: IoBufMut + std::marker::Send + Sync
+    {
+        Io::from_op(ops::RecvFrom::new(res.as_resource().clone(), buf))
+    }
+}
+
+doc_op! {
+    short: "Sends a message with control data, such as file descriptors.",
+    syscall: "sendmsg(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/sendmsg.2.html",
+
+    /// `bufs` are gathered in order like [`writev`]. `addr` is the
+    /// destination for unconnected sockets, pass `None` on connected ones.
+    /// `cmsgs` travel with the data, use
+    /// [`ControlMessage::ScmRights`](ops::ControlMessage::ScmRights) on a unix
+    /// socket to hand open descriptors to another process.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::ops::ControlMessage;
+    /// use std::os::fd::AsRawFd;
+    ///
+    /// async fn pass_socket(
+    ///     unix: &lio::api::resource::Resource,
+    ///     accepted: &lio::api::resource::Resource,
+    /// ) -> std::io::Result<()> {
+    ///     let cmsgs = vec![ControlMessage::ScmRights(vec![accepted.as_raw_fd()])];
+    ///     let (result, _bufs) = lio::api::sendmsg(unix, vec![b"fd".to_vec()], None, cmsgs).await;
+    ///     result?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn sendmsg<B>(
+        res: &impl AsResource,
+        bufs: Vec<B>,
+        addr: Option<SocketAddr>,
+        cmsgs: Vec<ops::ControlMessage>,
+    ) -> Io<ops::SendMsg<B>>
+    where
+        B: BufLike + std::marker::Send + Sync
+    {
+        Io::from_op(ops::SendMsg::new(res.as_resource().clone(), bufs, addr, cmsgs))
+    }
+}
+
+doc_op! {
+    short: "Receives a message with control data, such as file descriptors.",
+    syscall: "recvmsg(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/recvmsg.2.html",
+
+    /// `bufs` are filled in order like [`readv`], and `control_len` bytes are
+    /// set aside for control data, see
+    /// [`ControlMessage::rights_space`](ops::ControlMessage::rights_space).
+    /// Control data that doesn't fit is dropped and `MSG_CTRUNC` is set in
+    /// [`ReceivedMsg::flags`](ops::ReceivedMsg::flags). Descriptors received
+    /// with [`ControlMessage::ScmRights`](ops::ControlMessage::ScmRights) are
+    /// owned by the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::ops::ControlMessage;
+    ///
+    /// async fn receive_socket(unix: &lio::api::resource::Resource) -> std::io::Result<()> {
+    ///     let control_len = ControlMessage::rights_space(1);
+    ///     let (result, _bufs) =
+    ///         lio::api::recvmsg(unix, vec![Vec::with_capacity(16)], control_len).await;
+    ///     for message in result?.control {
+    ///         if let ControlMessage::ScmRights(fds) = message {
+    ///             println!("received {fds:?}");
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn recvmsg<B>(res: &impl AsResource, bufs: Vec<B>, control_len: usize) -> Io<ops::RecvMsg<B>>
+    where
+        B: IoBufMut + std::marker::Send + Sync
+    {
+        Io::from_op(ops::RecvMsg::new(res.as_resource().clone(), bufs, control_len))
+    }
+}
+
+doc_op! {
+    short: "Sends a batch of datagrams with as few syscalls as possible.",
+    syscall: "sendmmsg(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/sendmmsg.2.html",
+
+    /// Each message is a payload and its destination. The batch runs on the
+    /// blocking pool, since io_uring has no opcode for it, and completes with
+    /// one result per message, in order: the bytes sent or why that message
+    /// failed. Platforms without `sendmmsg` fall back to one `sendmsg` per
+    /// message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn sendmmsg_example(socket: &lio::api::resource::Resource) -> std::io::Result<()> {
+    ///     let addr = "127.0.0.1:9000".parse().unwrap();
+    ///     let msgs = vec![(b"a".to_vec(), addr), (b"b".to_vec(), addr)];
+    ///     for result in lio::api::sendmmsg(socket, msgs).await? {
+    ///         println!("Sent {} bytes", result?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn sendmmsg(res: &impl AsResource, msgs: Vec<(Vec<u8>, SocketAddr)>) -> Io<ops::SendMmsg> {
+        Io::from_op(ops::SendMmsg::new(res.as_resource().clone(), msgs))
+    }
+}
+
+doc_op! {
+    short: "Receives up to `count` datagrams in one batch.",
+    syscall: "recvmmsg(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/recvmmsg.2.html",
+
+    /// Waits for the first datagram, then also takes whatever else is already
+    /// queued, up to `count`. Each datagram comes back with its sender. Like
+    /// [`sendmmsg`], this runs on the blocking pool.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn recvmmsg_example(socket: &lio::api::resource::Resource) -> std::io::Result<()> {
+    ///     for (payload, from) in lio::api::recvmmsg(socket, 32).await? {
+    ///         println!("{} bytes from {}", payload.len(), from);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn recvmmsg(res: &impl AsResource, count: usize) -> Io<ops::RecvMmsg> {
+        Io::from_op(ops::RecvMmsg::new(res.as_resource().clone(), count))
+    }
+}
+
+doc_op! {
+    short: "Waits until a resource is readable and/or writable.",
+    syscall: "poll(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/poll.2.html",
+
+    ///
+    /// No data is transferred, so this is the building block for code that
+    /// does its own I/O once the resource is ready, like a TLS state machine
+    /// driving `read_tls`/`write_tls`. The operation is one-shot: poll again
+    /// after handling the readiness.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::ops::Interest;
+    ///
+    /// async fn poll_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let socket = Resource::stdin();
+    ///     let readiness = lio::api::poll(&socket, Interest::READABLE).await?;
+    ///     if readiness.is_readable() {
+    ///         // A read won't block now.
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn poll(res: &impl AsResource, interest: ops::Interest) -> Io<ops::Poll> {
+        Io::from_op(ops::Poll::new(res.as_resource().clone(), interest))
+    }
+}
+
+doc_op! {
+    short: "Returns how many received bytes a socket has buffered and not read yet.",
+
+    ///
+    /// The asynchronous counterpart of the `SIOCINQ`/`FIONREAD` ioctl. On
+    /// io_uring it is a socket `URING_CMD`, kernels without it and other
+    /// backends do the ioctl directly, which never blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// async fn pending_bytes_example() -> std::io::Result<()> {
+    ///     # use lio::api::resource::Resource;
+    ///     # let socket = Resource::stdin();
+    ///     let pending = lio::api::pending_bytes(&socket).await?;
+    ///     println!("{pending} bytes ready to read");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn pending_bytes(res: &impl AsResource) -> Io<ops::PendingBytes> {
+        Io::from_op(ops::PendingBytes::new(res.as_resource().clone()))
+    }
+}
+
+doc_op! {
+    short: "Reports every time a resource becomes readable and/or writable.",
+    syscall: "poll(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/poll.2.html",
+
+    ///
+    /// The multishot form of [`poll`]: one submission keeps producing
+    /// readiness events. Consume it with
+    /// [`into_stream`](Io::into_stream). Dropping the stream cancels the
+    /// poll.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use lio::api::ops::Interest;
+    /// use futures_core::Stream;
+    ///
+    /// fn readable_events(
+    ///     socket: &lio::api::resource::Resource,
+    /// ) -> impl Stream<Item = std::io::Result<lio::api::ops::Readiness>> {
+    ///     lio::api::poll_multishot(socket, Interest::READABLE).into_stream()
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn poll_multishot(res: &impl AsResource, interest: ops::Interest) -> Io<ops::PollMultishot> {
+        Io::from_op(ops::PollMultishot::new(res.as_resource().clone(), interest))
+    }
+}
+
+doc_op! {
+    short: "Opens a file relative to a directory file descriptor.",
     syscall: "openat(2)",
 
     ///
@@ -500,6 +1823,34 @@ doc_op! {
     }
 }
 
+doc_op! {
+    short: "Opens a directory for use with the `*at` operations.",
+    syscall: "openat(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/openat.2.html",
+
+    /// `path` is resolved against the working directory. The returned
+    /// [`DirHandle`](ops::DirHandle) can be passed as the `dir_res` of
+    /// [`openat`], [`linkat`] and friends, and closes its descriptor on drop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::ffi::CString;
+    ///
+    /// async fn open_dir_example() -> std::io::Result<()> {
+    ///     let dir = lio::api::open_dir(CString::new("/tmp").unwrap()).await?;
+    ///     let path = CString::new("test.txt").unwrap();
+    ///     let fd = lio::api::openat(&dir, path, libc::O_RDONLY).await?;
+    ///     println!("Opened file: {:?}", fd);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn open_dir(path: CString) -> Io<ops::OpenDir> {
+        Io::from_op(ops::OpenDir::new(path))
+    }
+}
+
 doc_op! {
     short: "Copies data between file descriptors without copying to userspace (Linux only).",
     syscall: "tee(2)",
@@ -531,3 +1882,230 @@ doc_op! {
         Io::from_op(ops::Tee::new(res_in.as_resource().clone(), res_out.as_resource().clone(), size))
     }
 }
+
+doc_op! {
+    short: "Adds, modifies or removes `fd` in the epoll set `epfd` (Linux only).",
+    syscall: "epoll_ctl(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/epoll_ctl.2.html",
+
+    ///
+    /// `op` is one of `EPOLL_CTL_ADD`, `EPOLL_CTL_MOD` or `EPOLL_CTL_DEL`,
+    /// `event` is ignored for the latter. Useful to feed an epoll fd owned
+    /// by another event loop without a blocking `epoll_ctl` call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # async fn example(epfd: lio::api::resource::Resource) -> std::io::Result<()> {
+    /// use lio::api;
+    /// use lio::api::resource::Resource;
+    ///
+    /// let fd = Resource::stdin();
+    /// let event = libc::epoll_event { events: libc::EPOLLIN as u32, u64: 7 };
+    /// api::epoll_ctl(&epfd, &fd, libc::EPOLL_CTL_ADD, event).await?;
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn example() {}
+    /// # fn main() {}
+    /// ```
+    #[cfg(linux)]
+    #[cfg_attr(docsrs, doc(cfg(linux)))]
+    pub fn epoll_ctl(epfd: &impl AsResource, fd: &impl AsResource, op: i32, event: libc::epoll_event) -> Io<ops::EpollCtl> {
+        Io::from_op(ops::EpollCtl::new(epfd.as_resource().clone(), fd.as_resource().clone(), op, event))
+    }
+}
+
+doc_op! {
+    short: "Reads the extended attribute `name` of the file at `path` (Linux only).",
+    syscall: "getxattr(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/getxattr.2.html",
+
+    ///
+    /// Reads into `buf`, up to its length, and hands it back cut down to the
+    /// value along with the value's length. A `buf` too short fails with
+    /// `ERANGE`, an empty one resolves to the length without reading.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # async fn example() -> std::io::Result<()> {
+    /// use std::ffi::CString;
+    ///
+    /// let path = CString::new("/tmp/backup.tar").unwrap();
+    /// let name = CString::new("user.origin").unwrap();
+    /// let (result, value) = lio::api::getxattr(path, name, vec![0; 256]).await;
+    /// println!("{} bytes: {:?}", result?, value);
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn example() {}
+    /// # fn main() {}
+    /// ```
+    #[cfg(linux)]
+    #[cfg_attr(docsrs, doc(cfg(linux)))]
+    pub fn getxattr(path: CString, name: CString, buf: Vec<u8>) -> Io<ops::GetXattr> {
+        Io::from_op(ops::GetXattr::new(path, name, buf))
+    }
+}
+
+doc_op! {
+    short: "Sets the extended attribute `name` of the file at `path` (Linux only).",
+    syscall: "setxattr(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/setxattr.2.html",
+
+    ///
+    /// `flags` is 0, `XATTR_CREATE` or `XATTR_REPLACE`. `value` is handed
+    /// back once the attribute is set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # async fn example() -> std::io::Result<()> {
+    /// use std::ffi::CString;
+    ///
+    /// let path = CString::new("/tmp/backup.tar").unwrap();
+    /// let name = CString::new("user.origin").unwrap();
+    /// let (result, _value) = lio::api::setxattr(path, name, b"host-a".to_vec(), 0).await;
+    /// result?;
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(target_os = "linux"))]
+    /// # fn example() {}
+    /// # fn main() {}
+    /// ```
+    #[cfg(linux)]
+    #[cfg_attr(docsrs, doc(cfg(linux)))]
+    pub fn setxattr(path: CString, name: CString, value: Vec<u8>, flags: i32) -> Io<ops::SetXattr> {
+        Io::from_op(ops::SetXattr::new(path, name, value, flags))
+    }
+}
+
+doc_op! {
+    short: "Reads the extended attribute `name` of an open file (Linux only).",
+    syscall: "fgetxattr(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/fgetxattr.2.html",
+
+    ///
+    /// Like [`getxattr`], for the file behind `res`.
+    #[cfg(linux)]
+    #[cfg_attr(docsrs, doc(cfg(linux)))]
+    pub fn fgetxattr(res: &impl AsResource, name: CString, buf: Vec<u8>) -> Io<ops::FGetXattr> {
+        Io::from_op(ops::FGetXattr::new(res.as_resource().clone(), name, buf))
+    }
+}
+
+doc_op! {
+    short: "Sets the extended attribute `name` of an open file (Linux only).",
+    syscall: "fsetxattr(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/fsetxattr.2.html",
+
+    ///
+    /// Like [`setxattr`], for the file behind `res`.
+    #[cfg(linux)]
+    #[cfg_attr(docsrs, doc(cfg(linux)))]
+    pub fn fsetxattr(res: &impl AsResource, name: CString, value: Vec<u8>, flags: i32) -> Io<ops::FSetXattr> {
+        Io::from_op(ops::FSetXattr::new(res.as_resource().clone(), name, value, flags))
+    }
+}
+
+doc_op! {
+    short: "Moves data between file descriptors without copying to userspace (Linux only).",
+    syscall: "splice(2)",
+    doc_link: "https://man7.org/linux/man-pages/man2/splice.2.html",
+
+    ///
+    /// Resolves to the number of bytes moved, 0 meaning end of input. At
+    /// least one of `res_in` and `res_out` must be a pipe.
+    ///
+    /// An offset of `-1` reads or writes at the fd's current position and
+    /// advances it, which is the only valid choice for a pipe end or a
+    /// socket. Any other offset is used as-is for a file and leaves its
+    /// position alone. `flags` takes the `SPLICE_F_*` flags.
+    ///
+    /// Invalid combinations, such as two regular files, fail with `EINVAL`.
+    ///
+    /// # Examples
+    ///
+    /// Moving what arrives on a socket into a file, through a pipe:
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(target_os = "linux")]
+    /// # async fn example(
+    /// #     socket: lio::api::resource::Resource,
+    /// #     file: lio::api::resource::Resource,
+    /// # ) -> std::io::Result<()> {
+    /// use lio::api;
+    ///
+    /// let (pipe_read, pipe_write) = api::pipe(libc::O_CLOEXEC).await?;
+    /// let mut written = 0;
+    /// loop {
+    ///     let n = api::splice(&socket, -1, &pipe_write, -1, 64 * 1024, 0).await?;
+    ///     if n == 0 {
+    ///         break;
+    ///     }
+    ///     let mut left = n;
+    ///     while left > 0 {
+    ///         let moved = api::splice(&pipe_read, -1, &file, written, left as u32, 0).await?;
+    ///         left -= moved;
+    ///         written += moved as i64;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// # fn main() {}
+    /// ```
+    #[cfg(linux)]
+    #[cfg_attr(docsrs, doc(cfg(linux)))]
+    pub fn splice(res_in: &impl AsResource, off_in: i64, res_out: &impl AsResource, off_out: i64, len: u32, flags: u32) -> Io<ops::Splice> {
+        Io::from_op(ops::Splice::new(res_in.as_resource().clone(), off_in, res_out.as_resource().clone(), off_out, len, flags))
+    }
+}
+
+/// Tees up to `size` bytes from the pipe `res_in` into each pipe in `outs`,
+/// one after the other on `lio` (Linux only).
+///
+/// [`tee`] doesn't consume its input, so every output starts from the same
+/// bytes, and `res_in` still holds them afterwards. Resolves with how many
+/// bytes each output took, in the order of `outs`.
+///
+/// An output pipe with less room than `size` takes only what fits. `tee(2)`
+/// always copies from the start of the input, so the rest can't be added to
+/// that output later: consume the smallest count from `res_in` and tee the
+/// remainder in the next round. An error stops the fan-out and is returned,
+/// outputs before it keep what they were given.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")]
+/// # async fn example(
+/// #     lio: &lio::Lio,
+/// #     input: lio::api::resource::Resource,
+/// #     consumers: Vec<lio::api::resource::Resource>,
+/// # ) -> std::io::Result<()> {
+/// let teed = lio::api::tee_multi(&input, &consumers, 64 * 1024, lio).await?;
+/// let all_got = teed.iter().copied().min().unwrap_or(0);
+/// println!("Every consumer got the first {all_got} bytes");
+/// # Ok(())
+/// # }
+/// # fn main() {}
+/// ```
+#[cfg(linux)]
+#[cfg_attr(docsrs, doc(cfg(linux)))]
+pub async fn tee_multi<R: AsResource>(
+  res_in: &impl AsResource,
+  outs: &[R],
+  size: u32,
+  lio: &crate::Lio,
+) -> std::io::Result<Vec<usize>> {
+  let mut teed = Vec::with_capacity(outs.len());
+  for out in outs {
+    let out = out.as_resource().clone();
+    teed.push(tee(res_in, out, size).with_lio(lio).await? as usize);
+  }
+  Ok(teed)
+}
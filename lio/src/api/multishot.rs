@@ -0,0 +1,141 @@
+//! Streams over operations that complete more than once.
+//!
+//! A [`MultishotOp`] is submitted once and then completes repeatedly, e.g. a
+//! multishot poll reporting every readiness change. [`MultishotStream`] turns
+//! those completions into a [`Stream`], so they work with the usual stream
+//! combinators.
+//!
+//! ```no_run
+//! use lio::{Lio, api::{self, ops::Interest}};
+//!
+//! let lio = Lio::new(64).unwrap();
+//! let fd = api::resource::Resource::stdin();
+//! let events = api::poll_multishot(&fd, Interest::READABLE)
+//!     .with_lio(&lio)
+//!     .into_stream();
+//! ```
+
+use std::{
+  pin::Pin,
+  task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{
+  CancellationToken, lio::Lio, registration::Registration,
+  typed_op::MultishotOp,
+};
+
+/// A [`Stream`] of the completions of a [`MultishotOp`].
+///
+/// Created with [`Io::into_stream`](crate::api::io::Io::into_stream). The
+/// operation is submitted on the first poll. The stream ends when the
+/// operation does, which the backend may decide on its own (io_uring drops
+/// multishot requests it can't re-arm): create a new stream to keep going.
+/// Operations that opt in through [`MultishotOp::resubmit`] are submitted
+/// again instead.
+///
+/// Dropping the stream cancels the operation.
+pub struct MultishotStream<T> {
+  state: State<T>,
+  lio: Lio,
+  /// Attached every time the operation is scheduled.
+  cancel: Option<CancellationToken>,
+}
+
+enum State<T> {
+  /// Operation created but not yet submitted.
+  Pending(T),
+  /// Operation submitted. Boxed so pointers in the Op stay valid.
+  Inflight {
+    id: u64,
+    op: Box<T>,
+    /// Result of the latest completion, for [`MultishotOp::resubmit`].
+    last: Option<isize>,
+  },
+  /// The operation finished and every item was returned.
+  Done,
+}
+
+impl<T> MultishotStream<T> {
+  pub(crate) fn new(
+    lio: Lio,
+    op: T,
+    cancel: Option<CancellationToken>,
+  ) -> Self {
+    Self { state: State::Pending(op), lio, cancel }
+  }
+}
+
+impl<T> Stream for MultishotStream<T>
+where
+  T: MultishotOp + Unpin,
+{
+  type Item = T::Item;
+
+  fn poll_next(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Self::Item>> {
+    let this = &mut *self;
+
+    if let State::Pending(_) = this.state {
+      let State::Pending(op) = std::mem::replace(&mut this.state, State::Done)
+      else {
+        unreachable!()
+      };
+      this.submit(Box::new(op), cx);
+      return Poll::Pending;
+    }
+
+    let State::Inflight { id, op, last } = &mut this.state else {
+      return Poll::Ready(None);
+    };
+    match this.lio.next_shot(*id, cx.waker()) {
+      Some(Some(res)) => {
+        *last = Some(res);
+        Poll::Ready(Some(op.extract_item(res)))
+      }
+      Some(None) => {
+        let State::Inflight { op, last, .. } =
+          std::mem::replace(&mut this.state, State::Done)
+        else {
+          unreachable!()
+        };
+        if last.is_some_and(|res| op.resubmit(res)) {
+          this.submit(op, cx);
+          return Poll::Pending;
+        }
+        Poll::Ready(None)
+      }
+      None => Poll::Pending,
+    }
+  }
+}
+
+impl<T: MultishotOp> MultishotStream<T> {
+  fn submit(&mut self, mut op: Box<T>, cx: &mut Context<'_>) {
+    let id = self
+      .lio
+      .schedule(
+        op.into_op(),
+        None,
+        false,
+        Registration::new_multishot(cx.waker().clone()),
+      )
+      .expect("lio error: failed to schedule operation");
+    if let Some(token) = &self.cancel {
+      self.lio.attach_cancel(id, token.clone());
+    }
+    self.state = State::Inflight { id, op, last: None };
+  }
+}
+
+impl<T> Drop for MultishotStream<T> {
+  fn drop(&mut self) {
+    if let State::Inflight { id, .. } = self.state {
+      self.lio.abandon(id);
+    }
+  }
+}
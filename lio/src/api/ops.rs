@@ -1,53 +1,225 @@
 // Re-export items from parent module for use by operation implementations
 
+#[cfg(unix)]
 mod accept;
+#[cfg(unix)]
 mod accept_unix;
+#[cfg(unix)]
 mod bind;
+#[cfg(unix)]
+mod buffer_group;
 mod close;
+#[cfg(target_os = "linux")]
+mod close_range;
+#[cfg(unix)]
 mod connect;
+mod custom;
+#[cfg(unix)]
+mod datagram;
+#[cfg(target_os = "linux")]
+mod direct;
+#[cfg(unix)]
+mod dup;
+#[cfg(unix)]
+mod dup2;
+#[cfg(linux)]
+mod epoll_ctl;
+#[cfg(unix)]
+mod fadvise;
+#[cfg(unix)]
+mod fallocate;
 mod fsync;
+#[cfg(unix)]
+mod futex;
+#[cfg(unix)]
+mod getsockopt;
 mod linkat;
+#[cfg(unix)]
 mod listen;
+mod mkdir;
+#[cfg(unix)]
+mod mmap;
+#[cfg(unix)]
+mod mmsg;
+#[cfg(unix)]
+mod msg;
 mod nop;
+#[cfg(unix)]
+mod open_dir;
 mod openat;
+#[cfg(unix)]
+mod pending_bytes;
+#[cfg(unix)]
+mod pipe;
+#[cfg(unix)]
+mod poll;
 mod read;
 mod read_at;
+#[cfg(unix)]
+mod read_file;
+#[cfg(unix)]
+mod readlink;
+#[cfg(unix)]
+mod readv;
+#[cfg(unix)]
 mod recv;
+#[cfg(unix)]
+mod recv_append;
+#[cfg(unix)]
+mod register_buffers;
+mod rename;
+#[cfg(unix)]
+mod resolve_at;
+#[cfg(unix)]
 mod send;
+#[cfg(unix)]
+mod send_zc;
+#[cfg(unix)]
+mod setsockopt;
+#[cfg(unix)]
 mod shutdown;
+#[cfg(unix)]
 mod socket;
+#[cfg(unix)]
+mod spawn_blocking;
+#[cfg(target_os = "linux")]
+mod spawn_process;
+#[cfg(linux)]
+mod splice;
+#[cfg(unix)]
+mod statx;
 mod symlink;
+#[cfg(unix)]
+mod sync_file_range;
 mod timeout;
 
 #[cfg(linux)]
 mod tee;
 
 mod truncate;
+mod unlink;
+#[cfg(unix)]
+mod utimens;
+#[cfg(unix)]
+mod waitid;
+mod with_timeout;
 mod write;
 mod write_at;
+#[cfg(unix)]
+mod write_file;
+#[cfg(unix)]
+mod writev;
+#[cfg(linux)]
+mod xattr;
 
+#[cfg(unix)]
 pub use accept::*;
+#[cfg(unix)]
 pub use accept_unix::*;
+#[cfg(unix)]
 pub use bind::*;
+#[cfg(unix)]
+pub use buffer_group::*;
 pub use close::*;
+#[cfg(target_os = "linux")]
+pub use close_range::*;
+#[cfg(unix)]
 pub use connect::*;
+pub use custom::*;
+#[cfg(unix)]
+pub use datagram::*;
+#[cfg(target_os = "linux")]
+pub use direct::*;
+#[cfg(unix)]
+pub use dup::*;
+#[cfg(unix)]
+pub use dup2::*;
+#[cfg(linux)]
+pub use epoll_ctl::*;
+#[cfg(unix)]
+pub use fadvise::*;
+#[cfg(unix)]
+pub use fallocate::*;
 pub use fsync::*;
+#[cfg(unix)]
+pub use futex::*;
+#[cfg(unix)]
+pub use getsockopt::*;
 pub use linkat::*;
+#[cfg(unix)]
 pub use listen::*;
+pub use mkdir::*;
+#[cfg(unix)]
+pub use mmap::*;
+#[cfg(unix)]
+pub use mmsg::*;
+#[cfg(unix)]
+pub use msg::*;
 pub use nop::*;
+#[cfg(unix)]
+pub use open_dir::*;
 pub use openat::*;
+#[cfg(unix)]
+pub use pending_bytes::*;
+#[cfg(unix)]
+pub use pipe::*;
+#[cfg(unix)]
+pub use poll::*;
 pub use read::*;
 pub use read_at::*;
+#[cfg(unix)]
+pub use read_file::*;
+#[cfg(unix)]
+pub use readlink::*;
+#[cfg(unix)]
+pub use readv::*;
+#[cfg(unix)]
 pub use recv::*;
+#[cfg(unix)]
+pub use recv_append::*;
+#[cfg(unix)]
+pub use register_buffers::*;
+pub use rename::*;
+#[cfg(unix)]
+pub use resolve_at::*;
+#[cfg(unix)]
 pub use send::*;
+#[cfg(unix)]
+pub use send_zc::*;
+#[cfg(unix)]
+pub use setsockopt::*;
+#[cfg(unix)]
 pub use shutdown::*;
+#[cfg(unix)]
 pub use socket::*;
+#[cfg(unix)]
+pub use spawn_blocking::*;
+#[cfg(target_os = "linux")]
+pub use spawn_process::*;
+#[cfg(linux)]
+pub use splice::*;
+#[cfg(unix)]
+pub use statx::*;
 pub use symlink::*;
+#[cfg(unix)]
+pub use sync_file_range::*;
 pub use timeout::*;
 
 #[cfg(linux)]
 pub use tee::*;
 
 pub use truncate::*;
+pub use unlink::*;
+#[cfg(unix)]
+pub use utimens::*;
+#[cfg(unix)]
+pub use waitid::*;
+pub use with_timeout::*;
 pub use write::*;
 pub use write_at::*;
+#[cfg(unix)]
+pub use write_file::*;
+#[cfg(unix)]
+pub use writev::*;
+#[cfg(linux)]
+pub use xattr::*;
@@ -1,9 +1,11 @@
 // #[cfg(unix)]
 use std::{
+  collections::HashMap,
   io::{self, Error},
   mem::{self},
   net::SocketAddr,
-  os::fd::{FromRawFd, RawFd},
+  os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+  time::Duration,
 };
 
 use crate::{
@@ -11,6 +13,81 @@ use crate::{
   typed_op::TypedOp,
 };
 
+/// How long the accept shedding a connection waits for it, in case another
+/// thread or process took it first.
+pub(crate) const SHED_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// A descriptor a [`Lio`](crate::Lio) holds in reserve, so a listener can
+/// still clear its backlog once the process has run out of descriptors.
+///
+/// On `EMFILE`/`ENFILE` the pending connection can't be accepted, so the
+/// listener stays readable and every retry fails straight away. Like nginx,
+/// the driver then frees the spare, accepts the connection into it with an
+/// accept of its own and closes it, then takes the spare back. The caller
+/// still gets the error, but the next accept waits for a new connection
+/// instead of spinning.
+#[derive(Default)]
+pub(crate) struct SpareFd {
+  fd: Option<OwnedFd>,
+  /// Listeners of the accepts in flight, by op id.
+  accepts: HashMap<u64, Resource>,
+  /// A listener that ran out of descriptors, shed on the next run.
+  shed: Option<Resource>,
+  /// Whether the spare is freed for a shedding accept.
+  lent: bool,
+  /// Id of the shedding accept, once scheduled.
+  shedding: Option<u64>,
+}
+
+impl SpareFd {
+  /// Tracks the accept `id`, opening the spare if it isn't held already.
+  pub(crate) fn admit(&mut self, id: u64, op: &Op) {
+    let (Op::Accept { fd, .. } | Op::AcceptMultishot { fd }) = op else {
+      return;
+    };
+    if self.fd.is_none() && !self.lent {
+      self.fd = open_spare_fd().ok();
+    }
+    self.accepts.insert(id, fd.clone());
+  }
+
+  /// Notes the listener of `id` for shedding if it ran out of descriptors.
+  pub(crate) fn complete(&mut self, id: u64, res: isize) {
+    if self.shedding == Some(id) {
+      self.shedding = None;
+      self.lent = false;
+    }
+    let Some(listener) = self.accepts.remove(&id) else { return };
+    let errno = -res as i32;
+    if (errno == libc::EMFILE || errno == libc::ENFILE) && self.fd.is_some() {
+      self.shed.get_or_insert(listener);
+    }
+  }
+
+  /// Frees the spare for an accept on the listener to shed, if there is
+  /// one. [`lent_to`](Self::lent_to) names the accept once it's scheduled.
+  pub(crate) fn lend(&mut self) -> Option<Resource> {
+    let listener = self.shed.take()?;
+    self.fd = None;
+    self.lent = true;
+    Some(listener)
+  }
+
+  /// Takes the spare back once the shedding accept `id` completes, or right
+  /// away without one.
+  pub(crate) fn lent_to(&mut self, id: Option<u64>) {
+    self.shedding = id;
+    self.lent = id.is_some();
+  }
+}
+
+fn open_spare_fd() -> io::Result<OwnedFd> {
+  let fd =
+    syscall!(open(c"/dev/null".as_ptr(), libc::O_RDONLY | libc::O_CLOEXEC))?;
+  // SAFETY: open succeeded, so fd is valid and owned by nobody else.
+  Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
 // Not detach safe.
 pub struct Accept {
   res: Resource,
@@ -93,6 +170,61 @@ impl TypedOp for Accept {
   // }
 }
 
+/// Accepts connections from one submission, see
+/// [`TcpListener::accept_multishot`](crate::net::TcpListener::accept_multishot).
+#[cfg(unix)]
+pub struct AcceptMultishot {
+  res: Resource,
+}
+
+#[cfg(unix)]
+impl AcceptMultishot {
+  pub(crate) fn new(res: Resource) -> Self {
+    Self { res }
+  }
+}
+
+#[cfg(unix)]
+impl crate::typed_op::MultishotOp for AcceptMultishot {
+  type Item = io::Result<(Resource, SocketAddr)>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    Op::AcceptMultishot { fd: self.res.clone() }
+  }
+
+  fn extract_item(&self, res: isize) -> Self::Item {
+    if res < 0 {
+      return Err(Error::from_raw_os_error(-res as i32));
+    }
+    // SAFETY: result is valid fd.
+    let conn = unsafe { Resource::from_raw_fd(res as RawFd) };
+    // The multishot accept has nowhere to put addresses, ask for it.
+    // SAFETY: sockaddr_storage is plain C data, all zeroes is valid.
+    let mut addr: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    syscall!(getpeername(conn.as_raw_fd(), (&raw mut addr).cast(), &mut len))?;
+    // SAFETY: getpeername filled in the address.
+    let addr = unsafe { libc_socketaddr_into_std(&addr) }?;
+    Ok((conn, addr))
+  }
+
+  /// io_uring ends a multishot accept on the first failure, keep going
+  /// unless the listener itself is unusable or the accept was cancelled.
+  fn resubmit(&self, res: isize) -> bool {
+    res >= 0
+      || matches!(
+        (-res) as i32,
+        libc::EMFILE
+          | libc::ENFILE
+          | libc::ENOBUFS
+          | libc::ENOMEM
+          | libc::ECONNABORTED
+          | libc::EINTR
+          | libc::EAGAIN
+      )
+  }
+}
+
 // impl OperationExt for Accept {
 //   type Result = io::Result<(Resource, SocketAddr)>;
 // }
@@ -5,8 +5,7 @@
 //! domain socket addresses don't map to `std::net::SocketAddr`.
 
 use std::{
-  io::{self, Error},
-  mem,
+  io, mem,
   os::fd::{FromRawFd, RawFd},
 };
 
@@ -46,7 +45,7 @@ impl TypedOp for AcceptUnix {
 
   fn extract_result(self, res: isize) -> Self::Result {
     if res < 0 {
-      return Err(Error::from_raw_os_error(-res as i32));
+      return Err(io::Error::from_raw_os_error(-res as i32));
     }
 
     // SAFETY: result is valid fd returned by accept syscall.
@@ -0,0 +1,203 @@
+use std::{io, sync::Arc};
+
+use crate::{
+  api::resource::Resource,
+  buf::{BufferGroup, GroupMemory, SelectedBuf},
+  typed_op::{MultishotOp, TypedOp},
+};
+
+/// Set in an encoded buffer id when the kernel picked a buffer.
+const BUFFER_SELECTED: isize = 1 << 16;
+
+/// The buffer id rides above the 32 bits a CQE result can take.
+const BUFFER_ID_SHIFT: u32 = 32;
+
+/// Encodes the id of the buffer the kernel picked for a `BUFFER_SELECT` op,
+/// to be or-ed into its non-negative result.
+///
+/// Only 64-bit targets have room for it, io_uring doesn't select buffers on
+/// others.
+#[cfg(all(linux, target_pointer_width = "64"))]
+pub(crate) fn encode_buffer_id(bid: u16) -> isize {
+  (BUFFER_SELECTED | bid as isize) << BUFFER_ID_SHIFT
+}
+
+/// Provides buffers to a [`BufferGroup`], see [`BufferGroup::provide`].
+pub struct ProvideBuffers {
+  group: BufferGroup,
+  bid: u16,
+  nbufs: u16,
+}
+
+assert_op_max_size!(ProvideBuffers);
+
+impl ProvideBuffers {
+  pub(crate) fn new(group: BufferGroup, bid: u16, nbufs: u16) -> Self {
+    Self { group, bid, nbufs }
+  }
+}
+
+impl TypedOp for ProvideBuffers {
+  type Result = io::Result<BufferGroup>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let memory = self.group.memory();
+    crate::op::Op::ProvideBuffers {
+      addr: memory.buf_ptr(self.bid),
+      len: memory.buf_len() as u32,
+      nbufs: self.nbufs,
+      bgid: memory.bgid(),
+      bid: self.bid,
+      memory: memory.clone(),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res == -(libc::EOPNOTSUPP as isize) {
+      return Err(io::ErrorKind::Unsupported.into());
+    }
+    if res < 0 {
+      return Err(io::Error::from_raw_os_error((-res) as i32));
+    }
+    Ok(self.group)
+  }
+}
+
+/// Removes the buffers of a [`BufferGroup`] from the kernel, see
+/// [`BufferGroup::remove`].
+pub struct RemoveBuffers {
+  nbufs: u16,
+  bgid: u16,
+}
+
+assert_op_max_size!(RemoveBuffers, test_remove_buffers_size);
+
+impl RemoveBuffers {
+  pub(crate) fn new(nbufs: u16, bgid: u16) -> Self {
+    Self { nbufs, bgid }
+  }
+}
+
+impl TypedOp for RemoveBuffers {
+  type Result = io::Result<usize>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::RemoveBuffers { nbufs: self.nbufs, bgid: self.bgid }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res == -(libc::EOPNOTSUPP as isize) {
+      return Err(io::ErrorKind::Unsupported.into());
+    }
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(res as usize)
+    }
+  }
+}
+
+/// Receives into a buffer the kernel picks from a [`BufferGroup`], see
+/// [`BufferGroup::recv`].
+pub struct RecvSelect {
+  fd: Resource,
+  /// Keeps the group's memory alive while the kernel may write to it.
+  memory: Arc<GroupMemory>,
+  flags: i32,
+}
+
+assert_op_max_size!(RecvSelect, test_recv_select_size);
+
+impl RecvSelect {
+  pub(crate) fn new(
+    fd: Resource,
+    memory: Arc<GroupMemory>,
+    flags: i32,
+  ) -> Self {
+    Self { fd, memory, flags }
+  }
+}
+
+impl TypedOp for RecvSelect {
+  type Result = io::Result<Option<SelectedBuf>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::RecvSelect {
+      fd: self.fd.clone(),
+      len: self.memory.buf_len() as u32,
+      bgid: self.memory.bgid(),
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    selected_buf(&self.memory, res)
+  }
+}
+
+/// Reads chunk after chunk into buffers the kernel picks from a
+/// [`BufferGroup`], see [`BufferGroup::read_multi`].
+///
+/// One submission keeps producing chunks as data arrives, without a
+/// submission per read. Kernels without multishot reads (before 6.7) get
+/// a one-shot read instead, which the stream submits again after every
+/// chunk.
+///
+/// The kernel ends a multishot read once the group runs out of buffers,
+/// the last item then fails with `ENOBUFS` and the stream ends.
+pub struct ReadMulti {
+  fd: Resource,
+  /// Keeps the group's memory alive while the kernel may write to it.
+  memory: Arc<GroupMemory>,
+}
+
+impl ReadMulti {
+  pub(crate) fn new(fd: Resource, memory: Arc<GroupMemory>) -> Self {
+    Self { fd, memory }
+  }
+}
+
+impl MultishotOp for ReadMulti {
+  type Item = io::Result<Option<SelectedBuf>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::ReadSelect {
+      fd: self.fd.clone(),
+      len: self.memory.buf_len() as u32,
+      bgid: self.memory.bgid(),
+      multishot: true,
+    }
+  }
+
+  fn extract_item(&self, res: isize) -> Self::Item {
+    selected_buf(&self.memory, res)
+  }
+
+  /// Keep reading after a chunk, the end of file and errors end the
+  /// stream.
+  fn resubmit(&self, res: isize) -> bool {
+    res > 0
+  }
+}
+
+/// The buffer a `BUFFER_SELECT` op completed with, `None` if the kernel
+/// didn't pick one.
+fn selected_buf(
+  memory: &Arc<GroupMemory>,
+  res: isize,
+) -> io::Result<Option<SelectedBuf>> {
+  if res == -(libc::EOPNOTSUPP as isize) {
+    return Err(io::ErrorKind::Unsupported.into());
+  }
+  if res < 0 {
+    return Err(io::Error::from_raw_os_error((-res) as i32));
+  }
+  // Widened so the shift is valid on 32-bit targets, which never set it.
+  let res = res as i64;
+  let selected = res >> BUFFER_ID_SHIFT;
+  if selected & BUFFER_SELECTED as i64 == 0 {
+    return Ok(None);
+  }
+  let len = (res & u32::MAX as i64) as usize;
+  Ok(Some(SelectedBuf::new(memory.clone(), selected as u16, len)))
+}
@@ -5,7 +5,7 @@ use std::os::fd::RawFd;
 #[cfg(windows)]
 use std::os::windows::io::RawHandle;
 
-use crate::typed_op::TypedOp;
+use crate::typed_op::{DetachSafe, TypedOp};
 
 pub struct Close {
   #[cfg(unix)]
@@ -28,6 +28,14 @@ impl Close {
   }
 }
 
+// SAFETY: The handle is only an identifier, it is closed by whichever
+// thread runs the op.
+#[cfg(windows)]
+unsafe impl Send for Close {}
+// SAFETY: Close never dereferences the handle.
+#[cfg(windows)]
+unsafe impl Sync for Close {}
+
 assert_op_max_size!(Close);
 
 impl TypedOp for Close {
@@ -52,3 +60,5 @@ impl TypedOp for Close {
     }
   }
 }
+
+impl DetachSafe for Close {}
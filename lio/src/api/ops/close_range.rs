@@ -0,0 +1,35 @@
+use std::io;
+
+use crate::{
+  api::ops::SpawnBlocking,
+  typed_op::{DetachSafe, TypedOp},
+};
+
+/// Closes a range of descriptors on the blocking pool, see
+/// [`close_range`](crate::api::close_range).
+pub struct CloseRange(SpawnBlocking<io::Result<()>>);
+
+assert_op_max_size!(CloseRange);
+
+impl CloseRange {
+  pub(crate) fn new(first: u32, last: u32, flags: u32) -> Self {
+    Self(SpawnBlocking::new(move || {
+      syscall!(syscall(libc::SYS_close_range, first, last, flags))?;
+      Ok(())
+    }))
+  }
+}
+
+impl TypedOp for CloseRange {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+impl DetachSafe for CloseRange {}
@@ -0,0 +1,37 @@
+use std::io;
+
+use crate::{
+  BufResult,
+  op::Op,
+  typed_op::{RawOperation, TypedOp},
+};
+
+/// Submits a user-defined [`RawOperation`], see
+/// [`custom`](crate::api::custom).
+pub struct Custom<O> {
+  op: Option<O>,
+}
+
+impl<O: RawOperation> Custom<O> {
+  pub(crate) fn new(op: O) -> Self {
+    Self { op: Some(op) }
+  }
+}
+
+impl<O: RawOperation> TypedOp for Custom<O> {
+  type Result = BufResult<usize, O>;
+
+  fn into_op(&mut self) -> Op {
+    let op: &dyn RawOperation = self.op.as_ref().expect("op already taken");
+    Op::Custom { op }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let op = self.op.expect("op already taken");
+    if res < 0 {
+      (Err(io::Error::from_raw_os_error((-res) as i32)), op)
+    } else {
+      (Ok(res as usize), op)
+    }
+  }
+}
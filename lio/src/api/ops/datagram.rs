@@ -0,0 +1,144 @@
+use std::{io, mem, net::SocketAddr};
+
+use crate::{
+  BufResult,
+  api::resource::Resource,
+  buf::{BufLike, IoBufMut},
+  net_utils,
+  typed_op::{DetachSafe, TypedOp},
+};
+
+/// A `msghdr` together with the iovec and address it points to.
+///
+/// Boxed, so the pointers handed to the backend stay valid when the op
+/// moves.
+struct Header {
+  msg: libc::msghdr,
+  iov: libc::iovec,
+  addr: libc::sockaddr_storage,
+}
+
+// SAFETY: The raw pointers in msg and iov only point into the header itself
+// and into the op's buffer, both of which travel with the op.
+unsafe impl Send for Header {}
+// SAFETY: Same as Send, nothing is written through a shared reference.
+unsafe impl Sync for Header {}
+
+impl Header {
+  fn new(addr: libc::sockaddr_storage) -> Box<Self> {
+    // SAFETY: msghdr and iovec are plain C data, all zeroes is valid.
+    let (msg, iov) = unsafe { (mem::zeroed(), mem::zeroed()) };
+    Box::new(Self { msg, iov, addr })
+  }
+
+  /// Points the header at `iov` and at the first `addr_len` bytes of its
+  /// address.
+  fn prepare(
+    &mut self,
+    iov: libc::iovec,
+    addr_len: libc::socklen_t,
+  ) -> *mut libc::msghdr {
+    self.iov = iov;
+    self.msg.msg_name = (&raw mut self.addr).cast();
+    self.msg.msg_namelen = addr_len;
+    self.msg.msg_iov = &raw mut self.iov;
+    self.msg.msg_iovlen = 1;
+    &raw mut self.msg
+  }
+}
+
+/// Sends one datagram to an address, see
+/// [`send_to`](crate::api::send_to).
+pub struct SendTo<B> {
+  res: Resource,
+  buf: Option<B>,
+  header: Box<Header>,
+  addr_len: libc::socklen_t,
+}
+
+impl<B> SendTo<B> {
+  pub(crate) fn new(res: Resource, buf: B, addr: SocketAddr) -> Self {
+    let addr_len = match addr {
+      SocketAddr::V4(_) => mem::size_of::<libc::sockaddr_in>(),
+      SocketAddr::V6(_) => mem::size_of::<libc::sockaddr_in6>(),
+    };
+    Self {
+      res,
+      buf: Some(buf),
+      header: Header::new(net_utils::std_socketaddr_into_libc(addr)),
+      addr_len: addr_len as libc::socklen_t,
+    }
+  }
+}
+
+impl<B> TypedOp for SendTo<B>
+where
+  B: BufLike + Send + Sync + 'static,
+{
+  type Result = BufResult<i32, B>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let buf = self.buf.as_ref().expect("buffer not available").buf();
+    let iov =
+      libc::iovec { iov_base: buf.as_ptr() as *mut _, iov_len: buf.len() };
+    let msg = self.header.prepare(iov, self.addr_len);
+    crate::op::Op::SendMsg { fd: self.res.clone(), msg }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let buf = self.buf.expect("buffer not available");
+    if res < 0 {
+      (Err(io::Error::from_raw_os_error((-res) as i32)), buf)
+    } else {
+      (Ok(res as i32), buf.after(res as usize))
+    }
+  }
+}
+
+impl<B> DetachSafe for SendTo<B> where B: BufLike + Send + Sync + 'static {}
+
+/// Receives one datagram and its sender, see
+/// [`recv_from`](crate::api::recv_from).
+pub struct RecvFrom<B> {
+  res: Resource,
+  buf: Option<B>,
+  header: Box<Header>,
+}
+
+impl<B> RecvFrom<B> {
+  pub(crate) fn new(res: Resource, buf: B) -> Self {
+    // SAFETY: sockaddr_storage is plain C data, all zeroes is valid.
+    let addr = unsafe { mem::zeroed() };
+    Self { res, buf: Some(buf), header: Header::new(addr) }
+  }
+}
+
+impl<B> TypedOp for RecvFrom<B>
+where
+  B: IoBufMut + Send + Sync + 'static,
+{
+  type Result = BufResult<(usize, SocketAddr), B>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let buf = self.buf.as_mut().expect("buffer not available");
+    let iov = libc::iovec {
+      iov_base: buf.uninit_ptr().cast(),
+      iov_len: buf.uninit_len(),
+    };
+    let storage_len = mem::size_of::<libc::sockaddr_storage>();
+    let msg = self.header.prepare(iov, storage_len as libc::socklen_t);
+    crate::op::Op::RecvMsg { fd: self.res.clone(), msg }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let buf = self.buf.expect("buffer not available");
+    if res < 0 {
+      return (Err(io::Error::from_raw_os_error((-res) as i32)), buf);
+    }
+    // SAFETY: The kernel filled in the sender's address on success.
+    match unsafe { net_utils::libc_socketaddr_into_std(&self.header.addr) } {
+      Ok(from) => (Ok((res as usize, from)), buf.after(res as usize)),
+      Err(err) => (Err(err), buf.after(res as usize)),
+    }
+  }
+}
@@ -0,0 +1,277 @@
+//! Operations on direct descriptors, slots in the io_uring fixed file table.
+
+use std::{
+  fmt, io,
+  mem::{self, ManuallyDrop},
+  net::SocketAddr,
+  os::fd::FromRawFd,
+  sync::{Arc, Mutex},
+};
+
+use crate::{
+  BufResult,
+  api::resource::Resource,
+  buf::{BufLike, IoBufMut},
+  net_utils::libc_socketaddr_into_std,
+  op::{Op, OpBuf, RawBuf},
+  typed_op::{DetachSafe, TypedOp},
+};
+
+/// The fixed file table of one [`Lio`](crate::Lio), shared with the
+/// [`FixedFd`]s in it.
+///
+/// Dropped descriptors can't reach the driver themselves, they may be on
+/// another thread. Their slots are queued here instead, and the driver frees
+/// them with a detached [`CloseFixed`] on its next run.
+#[derive(Debug, Default)]
+pub(crate) struct FixedTable {
+  released: Mutex<Vec<u32>>,
+}
+
+impl FixedTable {
+  fn release(&self, slot: u32) {
+    self.released.lock().unwrap_or_else(|e| e.into_inner()).push(slot);
+  }
+
+  /// Slots dropped since the last call.
+  pub(crate) fn take_released(&self) -> Vec<u32> {
+    mem::take(&mut *self.released.lock().unwrap_or_else(|e| e.into_inner()))
+  }
+}
+
+/// A direct descriptor: a slot in the fixed file table of the io_uring
+/// instance that created it.
+///
+/// Operations on it skip the fd table lookup the kernel does for regular
+/// descriptors. It only means something to the [`Lio`](crate::Lio) that
+/// accepted it, operations scheduled on another one fail with `EBADF`. Only
+/// the io_uring backend supports it; other backends fail with `EOPNOTSUPP`.
+///
+/// It owns the slot: dropping it frees the slot on the driver's next run,
+/// [`close_fixed`](crate::api::close_fixed) does the same and reports the
+/// result.
+pub struct FixedFd {
+  slot: u32,
+  table: Arc<FixedTable>,
+}
+
+impl FixedFd {
+  pub(crate) fn new(slot: u32, table: Arc<FixedTable>) -> Self {
+    Self { slot, table }
+  }
+
+  /// Index of the slot in the fixed file table.
+  pub fn slot(&self) -> u32 {
+    self.slot
+  }
+
+  /// Gives up the slot without freeing it, once a close has been submitted.
+  fn disarm(self) {
+    let this = ManuallyDrop::new(self);
+    // SAFETY: this is never dropped, so the table is read out exactly once.
+    drop(unsafe { std::ptr::read(&this.table) });
+  }
+}
+
+impl fmt::Debug for FixedFd {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("FixedFd").field("slot", &self.slot).finish()
+  }
+}
+
+impl Drop for FixedFd {
+  fn drop(&mut self) {
+    self.table.release(self.slot);
+  }
+}
+
+fn check(res: isize) -> io::Result<isize> {
+  if res < 0 {
+    Err(io::Error::from_raw_os_error((-res) as i32))
+  } else {
+    Ok(res)
+  }
+}
+
+/// Accepts a connection into a fixed file slot, see
+/// [`accept_direct`](crate::api::accept_direct).
+pub struct AcceptDirect {
+  res: Resource,
+  table: Arc<FixedTable>,
+  addr: Box<libc::sockaddr_storage>,
+  len: Box<libc::socklen_t>,
+}
+
+assert_op_max_size!(AcceptDirect);
+
+impl AcceptDirect {
+  pub(crate) fn new(res: Resource, table: Arc<FixedTable>) -> Self {
+    // SAFETY: sockaddr_storage is plain integers, all zeroes is valid.
+    let addr: Box<libc::sockaddr_storage> = Box::new(unsafe { mem::zeroed() });
+    let len =
+      Box::new(mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t);
+    Self { res, table, addr, len }
+  }
+}
+
+impl TypedOp for AcceptDirect {
+  type Result = io::Result<(FixedFd, SocketAddr)>;
+
+  fn into_op(&mut self) -> Op {
+    Op::AcceptDirect {
+      fd: self.res.clone(),
+      table: self.table.clone(),
+      // Boxed, so the pointers stay valid wherever the op moves.
+      addr: &mut *self.addr as *mut _,
+      len: &mut *self.len as *mut _,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    // Owned right away, so the slot is freed if the address is bad.
+    let fd = FixedFd::new(check(res)? as u32, self.table);
+    // SAFETY: self.addr was filled by the kernel on accept.
+    let addr = unsafe { libc_socketaddr_into_std(&*self.addr as *const _) }?;
+    Ok((fd, addr))
+  }
+}
+
+/// Receives on a direct descriptor, see [`recv_fixed`](crate::api::recv_fixed).
+pub struct RecvFixed<B> {
+  slot: u32,
+  table: Arc<FixedTable>,
+  buf: Option<B>,
+  flags: i32,
+}
+
+impl<B> RecvFixed<B> {
+  pub(crate) fn new(fd: &FixedFd, buf: B, flags: i32) -> Self {
+    Self { slot: fd.slot, table: fd.table.clone(), buf: Some(buf), flags }
+  }
+}
+
+impl<B> TypedOp for RecvFixed<B>
+where
+  B: IoBufMut + Send + Sync + 'static,
+{
+  type Result = BufResult<i32, B>;
+
+  fn into_op(&mut self) -> Op {
+    let buf = self.buf.as_mut().expect("buffer not available");
+    let ptr = buf.uninit_ptr().cast();
+    let len = buf.uninit_len();
+    Op::RecvFixed {
+      slot: self.slot,
+      table: self.table.clone(),
+      flags: self.flags,
+      buffer: OpBuf::new(RawBuf { ptr, len }),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let buf = self.buf.expect("buffer not available");
+    match check(res) {
+      Ok(n) => (Ok(n as i32), buf.after(n as usize)),
+      Err(err) => (Err(err), buf),
+    }
+  }
+}
+
+/// Sends on a direct descriptor, see [`send_fixed`](crate::api::send_fixed).
+pub struct SendFixed<B> {
+  slot: u32,
+  table: Arc<FixedTable>,
+  buf: Option<B>,
+  flags: i32,
+}
+
+impl<B> SendFixed<B> {
+  pub(crate) fn new(fd: &FixedFd, buf: B, flags: i32) -> Self {
+    Self { slot: fd.slot, table: fd.table.clone(), buf: Some(buf), flags }
+  }
+}
+
+impl<B> TypedOp for SendFixed<B>
+where
+  B: BufLike + Send + Sync + 'static,
+{
+  type Result = BufResult<i32, B>;
+
+  fn into_op(&mut self) -> Op {
+    let slice = self.buf.as_ref().expect("buffer not available").buf();
+    let ptr = slice.as_ptr() as *mut u8;
+    let len = slice.len();
+    Op::SendFixed {
+      slot: self.slot,
+      table: self.table.clone(),
+      flags: self.flags,
+      buffer: OpBuf::new(RawBuf { ptr, len }),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let buf = self.buf.expect("buffer not available");
+    match check(res) {
+      Ok(n) => (Ok(n as i32), buf.after(n as usize)),
+      Err(err) => (Err(err), buf),
+    }
+  }
+}
+
+/// Installs a regular fd for a direct descriptor, see
+/// [`install_fixed`](crate::api::install_fixed).
+pub struct InstallFixed {
+  slot: u32,
+  table: Arc<FixedTable>,
+}
+
+assert_op_max_size!(InstallFixed, test_install_fixed_size);
+
+impl InstallFixed {
+  pub(crate) fn new(fd: &FixedFd) -> Self {
+    Self { slot: fd.slot, table: fd.table.clone() }
+  }
+}
+
+impl TypedOp for InstallFixed {
+  type Result = io::Result<Resource>;
+
+  fn into_op(&mut self) -> Op {
+    Op::InstallFixed { slot: self.slot, table: self.table.clone() }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let fd = check(res)? as i32;
+    // SAFETY: The kernel just installed fd and nothing else owns it.
+    Ok(unsafe { Resource::from_raw_fd(fd) })
+  }
+}
+
+/// Frees a fixed file slot, see [`close_fixed`](crate::api::close_fixed).
+pub struct CloseFixed {
+  fd: FixedFd,
+}
+
+assert_op_max_size!(CloseFixed, test_close_fixed_size);
+
+impl CloseFixed {
+  pub(crate) fn new(fd: FixedFd) -> Self {
+    Self { fd }
+  }
+}
+
+impl TypedOp for CloseFixed {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> Op {
+    Op::CloseFixed { slot: self.fd.slot, table: self.fd.table.clone() }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    // The close went out, whatever it returned the slot isn't ours anymore.
+    self.fd.disarm();
+    check(res).map(drop)
+  }
+}
+
+impl DetachSafe for CloseFixed {}
@@ -0,0 +1,38 @@
+use std::io;
+use std::os::fd::FromRawFd;
+
+use crate::api::resource::Resource;
+use crate::typed_op::TypedOp;
+
+pub struct Dup {
+  res: Resource,
+}
+
+assert_op_max_size!(Dup);
+
+impl Dup {
+  pub(crate) fn new(res: Resource) -> Self {
+    Self { res }
+  }
+
+  pub fn to_op(self) -> crate::op::Op {
+    crate::op::Op::Dup { fd: self.res }
+  }
+}
+
+impl TypedOp for Dup {
+  type Result = io::Result<Resource>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Dup { fd: self.res.clone() }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      // SAFETY: 'res' is a freshly duplicated fd we now own.
+      Ok(unsafe { Resource::from_raw_fd(res as i32) })
+    }
+  }
+}
@@ -0,0 +1,40 @@
+use std::io;
+
+use crate::api::resource::Resource;
+use crate::typed_op::TypedOp;
+
+pub struct Dup2 {
+  old_res: Resource,
+  new_res: Resource,
+}
+
+assert_op_max_size!(Dup2);
+
+impl Dup2 {
+  pub(crate) fn new(old_res: Resource, new_res: Resource) -> Self {
+    Self { old_res, new_res }
+  }
+
+  pub fn to_op(self) -> crate::op::Op {
+    crate::op::Op::Dup2 { old_fd: self.old_res, new_fd: self.new_res }
+  }
+}
+
+impl TypedOp for Dup2 {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Dup2 {
+      old_fd: self.old_res.clone(),
+      new_fd: self.new_res.clone(),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
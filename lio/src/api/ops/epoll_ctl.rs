@@ -0,0 +1,46 @@
+use std::io;
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+pub struct EpollCtl {
+  epfd: Resource,
+  fd: Resource,
+  op: i32,
+  /// Boxed, so the pointer handed to the backend stays valid when the op
+  /// moves.
+  event: Box<libc::epoll_event>,
+}
+
+assert_op_max_size!(EpollCtl);
+
+impl EpollCtl {
+  pub(crate) fn new(
+    epfd: Resource,
+    fd: Resource,
+    op: i32,
+    event: libc::epoll_event,
+  ) -> Self {
+    Self { epfd, fd, op, event: Box::new(event) }
+  }
+}
+
+impl TypedOp for EpollCtl {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::EpollCtl {
+      epfd: self.epfd.clone(),
+      fd: self.fd.clone(),
+      op: self.op,
+      event: &*self.event,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
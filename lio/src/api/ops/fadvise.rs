@@ -0,0 +1,39 @@
+use std::io;
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+pub struct Fadvise {
+  res: Resource,
+  offset: u64,
+  len: i64,
+  advice: i32,
+}
+
+assert_op_max_size!(Fadvise);
+
+impl Fadvise {
+  pub(crate) fn new(res: Resource, offset: u64, len: i64, advice: i32) -> Self {
+    Self { res, offset, len, advice }
+  }
+}
+
+impl TypedOp for Fadvise {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Fadvise {
+      fd: self.res.clone(),
+      offset: self.offset,
+      len: self.len,
+      advice: self.advice,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
@@ -0,0 +1,39 @@
+use std::io;
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+pub struct Fallocate {
+  res: Resource,
+  offset: u64,
+  len: u64,
+  mode: i32,
+}
+
+assert_op_max_size!(Fallocate);
+
+impl Fallocate {
+  pub(crate) fn new(res: Resource, offset: u64, len: u64, mode: i32) -> Self {
+    Self { res, offset, len, mode }
+  }
+}
+
+impl TypedOp for Fallocate {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Fallocate {
+      fd: self.res.clone(),
+      offset: self.offset,
+      len: self.len,
+      mode: self.mode,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
@@ -1,15 +1,21 @@
 use crate::api::resource::Resource;
 
-use crate::typed_op::TypedOp;
+use crate::typed_op::{DetachSafe, TypedOp};
 
 pub struct Fsync {
   res: Resource,
+  after_writes: bool,
 }
 assert_op_max_size!(Fsync);
 
 impl Fsync {
   pub(crate) fn new(res: Resource) -> Self {
-    Self { res }
+    Self { res, after_writes: false }
+  }
+
+  /// Held back until the writes to `res` in flight at scheduling complete.
+  pub(crate) fn new_after_writes(res: Resource) -> Self {
+    Self { res, after_writes: true }
   }
 
   pub fn to_op(self) -> crate::op::Op {
@@ -32,6 +38,10 @@ impl TypedOp for Fsync {
     }
   }
 
+  fn after_writes(&self) -> bool {
+    self.after_writes
+  }
+
   // #[cfg(unix)]
   // fn meta(&self) -> crate::operation::OpMeta {
   //   crate::operation::OpMeta::CAP_FD
@@ -51,3 +61,5 @@ impl TypedOp for Fsync {
   //   syscall!(raw fsync(self.res.as_raw_fd()))
   // }
 }
+
+impl DetachSafe for Fsync {}
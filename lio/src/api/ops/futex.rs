@@ -0,0 +1,75 @@
+use std::{io, sync::Arc};
+
+use crate::{
+  futex::{FutexWord, Parker},
+  typed_op::TypedOp,
+};
+
+/// Waits on an [`AsyncFutex`](crate::AsyncFutex), see
+/// [`AsyncFutex::wait`](crate::AsyncFutex::wait).
+pub struct FutexWait {
+  word: Arc<FutexWord>,
+  expected: u32,
+  /// Set by backends without a futex opcode, see
+  /// [`FutexWord::park`].
+  parker: Option<Parker>,
+}
+
+assert_op_max_size!(FutexWait);
+
+impl FutexWait {
+  pub(crate) fn new(word: Arc<FutexWord>, expected: u32) -> Self {
+    Self { word, expected, parker: None }
+  }
+}
+
+impl TypedOp for FutexWait {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::FutexWait {
+      word: self.word.clone(),
+      expected: self.expected,
+      parker: &raw mut self.parker,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/// Wakes waiters of an [`AsyncFutex`](crate::AsyncFutex), see
+/// [`AsyncFutex::wake`](crate::AsyncFutex::wake).
+pub struct FutexWake {
+  word: Arc<FutexWord>,
+  count: u32,
+}
+
+assert_op_max_size!(FutexWake, test_futex_wake_size);
+
+impl FutexWake {
+  pub(crate) fn new(word: Arc<FutexWord>, count: u32) -> Self {
+    Self { word, count }
+  }
+}
+
+impl TypedOp for FutexWake {
+  type Result = io::Result<usize>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::FutexWake { word: self.word.clone(), count: self.count }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(res as usize)
+    }
+  }
+}
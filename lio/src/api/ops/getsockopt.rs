@@ -0,0 +1,87 @@
+use std::{
+  io,
+  os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{
+  api::{ops::SpawnBlocking, resource::Resource},
+  typed_op::TypedOp,
+};
+
+/// Room for the largest option value read, `TCP_INFO` is a few hundred
+/// bytes.
+const MAX_OPTLEN: usize = 1024;
+
+/// Reads a socket option on the blocking pool, see
+/// [`getsockopt`](crate::api::getsockopt).
+pub struct GetSockOpt(SpawnBlocking<io::Result<Vec<u8>>>);
+
+assert_op_max_size!(GetSockOpt);
+
+impl GetSockOpt {
+  pub(crate) fn new(res: Resource, level: i32, name: i32) -> Self {
+    Self(SpawnBlocking::new(move || get(res.as_raw_fd(), level, name)))
+  }
+}
+
+impl TypedOp for GetSockOpt {
+  type Result = io::Result<Vec<u8>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+/// Takes a socket's pending error on the blocking pool, see
+/// [`get_so_error`](crate::api::get_so_error).
+pub struct SoError(SpawnBlocking<io::Result<i32>>);
+
+impl SoError {
+  pub(crate) fn new(res: Resource) -> Self {
+    Self(SpawnBlocking::new(move || so_error(res.as_raw_fd())))
+  }
+}
+
+impl TypedOp for SoError {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    match self.0.extract_result(res)?? {
+      0 => Ok(()),
+      errno => Err(io::Error::from_raw_os_error(errno)),
+    }
+  }
+}
+
+fn get(fd: RawFd, level: i32, name: i32) -> io::Result<Vec<u8>> {
+  let mut buf = vec![0u8; MAX_OPTLEN];
+  let mut len = MAX_OPTLEN as libc::socklen_t;
+  // The kernel writes at most len bytes and stores the real length in len.
+  syscall!(getsockopt(fd, level, name, buf.as_mut_ptr().cast(), &mut len))?;
+  buf.truncate(len as usize);
+  Ok(buf)
+}
+
+/// Reads and clears `SO_ERROR`, returning the errno of the last failure on
+/// `fd`, or 0.
+pub(crate) fn so_error(fd: RawFd) -> io::Result<i32> {
+  let mut errno: libc::c_int = 0;
+  let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+  // errno is a c_int, which is what SO_ERROR stores.
+  syscall!(getsockopt(
+    fd,
+    libc::SOL_SOCKET,
+    libc::SO_ERROR,
+    (&raw mut errno).cast(),
+    &mut len,
+  ))?;
+  Ok(errno)
+}
@@ -0,0 +1,37 @@
+use std::{ffi::CString, io};
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+pub struct MkDirAt {
+  dir_res: Resource,
+  path: CString,
+  mode: u32,
+}
+
+assert_op_max_size!(MkDirAt);
+
+impl MkDirAt {
+  pub(crate) fn new(dir_res: Resource, path: CString, mode: u32) -> Self {
+    Self { dir_res, path, mode }
+  }
+}
+
+impl TypedOp for MkDirAt {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::MkDirAt {
+      dir_fd: self.dir_res.clone(),
+      path: self.path.as_ptr(),
+      mode: self.mode,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
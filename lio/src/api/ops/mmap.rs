@@ -0,0 +1,214 @@
+use std::{
+  io,
+  os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{
+  BufResult,
+  api::{ops::SpawnBlocking, resource::Resource},
+  typed_op::TypedOp,
+};
+
+/// A memory-mapped region returned by [`mmap`](crate::api::mmap).
+///
+/// The region is unmapped when dropped. Its bytes are reached through
+/// [`as_slice`](Self::as_slice) and [`as_mut_slice`](Self::as_mut_slice),
+/// which check it was mapped with the protection they need.
+///
+/// Accessing a file-backed region after the file has been truncated below the
+/// mapped range raises `SIGBUS`, same as with any other mapping.
+pub struct MmapRegion {
+  ptr: *mut u8,
+  len: usize,
+  prot: i32,
+}
+
+// SAFETY: MmapRegion uniquely owns the mapping, access to it goes through
+// &self/&mut self like any other owned buffer.
+unsafe impl Send for MmapRegion {}
+// SAFETY: Shared access only hands out &[u8].
+unsafe impl Sync for MmapRegion {}
+
+impl MmapRegion {
+  /// Pointer to the start of the mapping.
+  pub fn as_ptr(&self) -> *const u8 {
+    self.ptr
+  }
+
+  /// Length of the mapping in bytes.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// The mapped bytes, or `None` if the region wasn't mapped `PROT_READ`.
+  pub fn as_slice(&self) -> Option<&[u8]> {
+    if self.prot & libc::PROT_READ == 0 {
+      return None;
+    }
+    // SAFETY: ptr/len describe a live readable mapping owned by self.
+    Some(unsafe { std::slice::from_raw_parts(self.ptr, self.len) })
+  }
+
+  /// The mapped bytes for writing, or `None` unless the region was mapped
+  /// `PROT_READ | PROT_WRITE`.
+  pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+    let rw = libc::PROT_READ | libc::PROT_WRITE;
+    if self.prot & rw != rw {
+      return None;
+    }
+    // SAFETY: ptr/len describe a live writable mapping owned exclusively by self.
+    Some(unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) })
+  }
+}
+
+impl Drop for MmapRegion {
+  fn drop(&mut self) {
+    // SAFETY: ptr/len were returned by a successful mmap and are unmapped once.
+    unsafe { libc::munmap(self.ptr.cast(), self.len) };
+  }
+}
+
+impl std::fmt::Debug for MmapRegion {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("MmapRegion")
+      .field("ptr", &self.ptr)
+      .field("len", &self.len)
+      .finish()
+  }
+}
+
+/// Maps a file on the blocking pool, see [`mmap`](crate::api::mmap).
+///
+/// Neither io_uring nor the polling backends can map memory asynchronously,
+/// and faulting in a file-backed mapping can block on I/O, so it goes through
+/// [`SpawnBlocking`].
+pub struct Mmap(SpawnBlocking<io::Result<MmapRegion>>);
+
+assert_op_max_size!(Mmap, test_mmap_size);
+
+impl Mmap {
+  pub(crate) fn new(
+    res: Resource,
+    len: usize,
+    prot: i32,
+    flags: i32,
+    offset: i64,
+  ) -> Self {
+    // MAP_FIXED could replace existing mappings, which a safe API can't allow.
+    let flags = flags & !libc::MAP_FIXED;
+    Self(SpawnBlocking::new(move || {
+      map(res.as_raw_fd(), len, prot, flags, offset)
+    }))
+  }
+}
+
+impl TypedOp for Mmap {
+  type Result = io::Result<MmapRegion>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+fn map(
+  fd: RawFd,
+  len: usize,
+  prot: i32,
+  flags: i32,
+  offset: i64,
+) -> io::Result<MmapRegion> {
+  // SAFETY: a null hint lets the kernel pick the address, MAP_FIXED is masked
+  // out so no existing mapping is replaced.
+  let ptr = unsafe {
+    libc::mmap(
+      std::ptr::null_mut(),
+      len,
+      prot,
+      flags,
+      fd,
+      offset as libc::off_t,
+    )
+  };
+  if ptr == libc::MAP_FAILED {
+    return Err(io::Error::last_os_error());
+  }
+  Ok(MmapRegion { ptr: ptr.cast(), len, prot })
+}
+
+/// Flushes a region on the blocking pool, see [`msync`](crate::api::msync).
+///
+/// `MS_SYNC` waits for the writeback, so it goes through [`SpawnBlocking`].
+pub struct Msync {
+  job: SpawnBlocking<io::Result<()>>,
+  region: MmapRegion,
+}
+
+impl Msync {
+  pub(crate) fn new(region: MmapRegion, flags: i32) -> Self {
+    // The region stays here so it can be handed back even if the job never
+    // runs. Should the op be dropped first, msync sees an unmapped range and
+    // fails with ENOMEM.
+    let (addr, len) = (region.ptr as usize, region.len);
+    let job = SpawnBlocking::new(move || {
+      // msync only looks the range up, it never dereferences it.
+      syscall!(msync(addr as *mut libc::c_void, len, flags)).map(drop)
+    });
+    Self { job, region }
+  }
+}
+
+impl TypedOp for Msync {
+  type Result = BufResult<(), MmapRegion>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.job.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let result = self.job.extract_result(res).and_then(|result| result);
+    (result, self.region)
+  }
+}
+
+pub struct Madvise {
+  region: Option<MmapRegion>,
+  advice: i32,
+}
+
+assert_op_max_size!(Madvise, test_madvise_size);
+
+impl Madvise {
+  pub(crate) fn new(region: MmapRegion, advice: i32) -> Self {
+    Self { region: Some(region), advice }
+  }
+}
+
+impl TypedOp for Madvise {
+  type Result = BufResult<(), MmapRegion>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let region = self.region.as_ref().expect("region not available");
+    crate::op::Op::Madvise {
+      addr: region.ptr,
+      len: region.len,
+      advice: self.advice,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let region = self.region.expect("region not available");
+    if res < 0 {
+      (Err(io::Error::from_raw_os_error((-res) as i32)), region)
+    } else {
+      (Ok(()), region)
+    }
+  }
+}
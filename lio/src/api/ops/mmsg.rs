@@ -0,0 +1,255 @@
+use std::{
+  io, mem,
+  net::SocketAddr,
+  os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{
+  api::{ops::SpawnBlocking, resource::Resource},
+  net_utils,
+  typed_op::TypedOp,
+};
+
+/// Largest UDP payload, each datagram [`RecvMmsg`] receives gets a buffer
+/// this large.
+const MAX_DATAGRAM: usize = 65_535;
+
+/// Received payloads, each with its sender.
+type Datagrams = Vec<(Vec<u8>, SocketAddr)>;
+
+#[cfg(target_os = "linux")]
+type MMsgHdr = libc::mmsghdr;
+
+/// Same layout as Linux's `mmsghdr`, filled one `sendmsg`/`recvmsg` at a time.
+#[cfg(not(target_os = "linux"))]
+#[repr(C)]
+struct MMsgHdr {
+  msg_hdr: libc::msghdr,
+  msg_len: libc::c_uint,
+}
+
+/// Sends a batch of datagrams on the blocking pool, see
+/// [`sendmmsg`](crate::api::sendmmsg).
+pub struct SendMmsg(SpawnBlocking<Vec<io::Result<usize>>>);
+
+assert_op_max_size!(SendMmsg);
+
+impl SendMmsg {
+  pub(crate) fn new(res: Resource, msgs: Vec<(Vec<u8>, SocketAddr)>) -> Self {
+    Self(SpawnBlocking::new(move || send_all(res.as_raw_fd(), &msgs)))
+  }
+}
+
+impl TypedOp for SendMmsg {
+  type Result = io::Result<Vec<io::Result<usize>>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)
+  }
+}
+
+/// Receives a batch of datagrams on the blocking pool, see
+/// [`recvmmsg`](crate::api::recvmmsg).
+pub struct RecvMmsg(SpawnBlocking<io::Result<Datagrams>>);
+
+impl RecvMmsg {
+  pub(crate) fn new(res: Resource, count: usize) -> Self {
+    Self(SpawnBlocking::new(move || recv_batch(res.as_raw_fd(), count)))
+  }
+}
+
+impl TypedOp for RecvMmsg {
+  type Result = io::Result<Datagrams>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+fn addr_len(addr: &SocketAddr) -> libc::socklen_t {
+  let len = match addr {
+    SocketAddr::V4(_) => mem::size_of::<libc::sockaddr_in>(),
+    SocketAddr::V6(_) => mem::size_of::<libc::sockaddr_in6>(),
+  };
+  len as libc::socklen_t
+}
+
+fn errno() -> i32 {
+  io::Error::last_os_error().raw_os_error().unwrap_or(libc::EIO)
+}
+
+/// Builds one header per iovec/address pair. Both slices must outlive the
+/// returned headers.
+fn headers(
+  iovs: &mut [libc::iovec],
+  addrs: &mut [libc::sockaddr_storage],
+  addr_lens: impl Fn(usize) -> libc::socklen_t,
+) -> Vec<MMsgHdr> {
+  iovs
+    .iter_mut()
+    .zip(addrs.iter_mut())
+    .enumerate()
+    .map(|(i, (iov, addr))| {
+      // SAFETY: mmsghdr is plain C data, all zeroes is a valid empty header.
+      let mut hdr: MMsgHdr = unsafe { mem::zeroed() };
+      hdr.msg_hdr.msg_name = (addr as *mut libc::sockaddr_storage).cast();
+      hdr.msg_hdr.msg_namelen = addr_lens(i);
+      hdr.msg_hdr.msg_iov = iov;
+      hdr.msg_hdr.msg_iovlen = 1;
+      hdr
+    })
+    .collect()
+}
+
+/// Sends every message, retrying past ones that fail so each gets a result.
+fn send_all(
+  fd: RawFd,
+  msgs: &[(Vec<u8>, SocketAddr)],
+) -> Vec<io::Result<usize>> {
+  let mut iovs: Vec<libc::iovec> = msgs
+    .iter()
+    .map(|(buf, _)| libc::iovec {
+      iov_base: buf.as_ptr() as *mut _,
+      iov_len: buf.len(),
+    })
+    .collect();
+  let mut addrs: Vec<libc::sockaddr_storage> = msgs
+    .iter()
+    .map(|(_, addr)| net_utils::std_socketaddr_into_libc(*addr))
+    .collect();
+  let mut hdrs = headers(&mut iovs, &mut addrs, |i| addr_len(&msgs[i].1));
+
+  let mut results = Vec::with_capacity(msgs.len());
+  while results.len() < hdrs.len() {
+    let start = results.len();
+    // SAFETY: Every header points into iovs/addrs, which outlive this call.
+    let ret = unsafe { send_batch(fd, &mut hdrs[start..]) };
+    if ret < 0 {
+      results.push(Err(io::Error::from_raw_os_error(-ret as i32)));
+      continue;
+    }
+    let sent = &hdrs[start..start + ret as usize];
+    results.extend(sent.iter().map(|hdr| Ok(hdr.msg_len as usize)));
+  }
+  results
+}
+
+/// Returns how many messages were sent, or `-errno` if the first one failed.
+///
+/// # Safety
+///
+/// `fd` must be valid and every header must point to live buffers.
+#[cfg(target_os = "linux")]
+unsafe fn send_batch(fd: RawFd, hdrs: &mut [MMsgHdr]) -> isize {
+  // SAFETY: Upheld by the caller.
+  let ret = unsafe {
+    libc::sendmmsg(fd, hdrs.as_mut_ptr(), hdrs.len() as libc::c_uint, 0)
+  };
+  if ret < 0 { -(errno() as isize) } else { ret as isize }
+}
+
+/// Returns how many messages were sent, or `-errno` if the first one failed.
+///
+/// # Safety
+///
+/// `fd` must be valid and every header must point to live buffers.
+#[cfg(not(target_os = "linux"))]
+unsafe fn send_batch(fd: RawFd, hdrs: &mut [MMsgHdr]) -> isize {
+  for (i, hdr) in hdrs.iter_mut().enumerate() {
+    // SAFETY: Upheld by the caller.
+    let ret = unsafe { libc::sendmsg(fd, &hdr.msg_hdr, 0) };
+    if ret < 0 {
+      return if i == 0 { -(errno() as isize) } else { i as isize };
+    }
+    hdr.msg_len = ret as libc::c_uint;
+  }
+  hdrs.len() as isize
+}
+
+/// Waits for at least one datagram, then takes whatever else is queued, up
+/// to `count`.
+fn recv_batch(fd: RawFd, count: usize) -> io::Result<Datagrams> {
+  let mut bufs: Vec<Vec<u8>> =
+    (0..count).map(|_| Vec::with_capacity(MAX_DATAGRAM)).collect();
+  let mut iovs: Vec<libc::iovec> = bufs
+    .iter_mut()
+    .map(|buf| libc::iovec {
+      iov_base: buf.as_mut_ptr().cast(),
+      iov_len: buf.capacity(),
+    })
+    .collect();
+  // SAFETY: sockaddr_storage is plain C data, all zeroes is valid.
+  let mut addrs: Vec<libc::sockaddr_storage> =
+    (0..count).map(|_| unsafe { mem::zeroed() }).collect();
+  let storage_len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+  let mut hdrs = headers(&mut iovs, &mut addrs, |_| storage_len);
+
+  // SAFETY: Every header points into bufs/addrs, which outlive this call.
+  let ret = unsafe { recv_batch_raw(fd, &mut hdrs) };
+  if ret < 0 {
+    return Err(io::Error::from_raw_os_error(-ret as i32));
+  }
+
+  let received = ret as usize;
+  bufs
+    .into_iter()
+    .zip(&hdrs)
+    .zip(&addrs)
+    .take(received)
+    .map(|((mut buf, hdr), addr)| {
+      // SAFETY: The kernel wrote msg_len bytes into the buffer.
+      unsafe { buf.set_len(hdr.msg_len as usize) };
+      // SAFETY: addr was filled in by the kernel for this datagram.
+      let addr = unsafe { net_utils::libc_socketaddr_into_std(addr) }?;
+      Ok((buf, addr))
+    })
+    .collect()
+}
+
+/// Returns how many messages were received, or `-errno`.
+///
+/// # Safety
+///
+/// `fd` must be valid and every header must point to live, writable buffers.
+#[cfg(target_os = "linux")]
+unsafe fn recv_batch_raw(fd: RawFd, hdrs: &mut [MMsgHdr]) -> isize {
+  // SAFETY: Upheld by the caller, a null timeout waits indefinitely.
+  let ret = unsafe {
+    libc::recvmmsg(
+      fd,
+      hdrs.as_mut_ptr(),
+      hdrs.len() as libc::c_uint,
+      libc::MSG_WAITFORONE as _,
+      std::ptr::null_mut(),
+    )
+  };
+  if ret < 0 { -(errno() as isize) } else { ret as isize }
+}
+
+/// Returns how many messages were received, or `-errno`.
+///
+/// # Safety
+///
+/// `fd` must be valid and every header must point to live, writable buffers.
+#[cfg(not(target_os = "linux"))]
+unsafe fn recv_batch_raw(fd: RawFd, hdrs: &mut [MMsgHdr]) -> isize {
+  for (i, hdr) in hdrs.iter_mut().enumerate() {
+    // Only the first receive may block, like MSG_WAITFORONE.
+    let flags = if i == 0 { 0 } else { libc::MSG_DONTWAIT };
+    // SAFETY: Upheld by the caller.
+    let ret = unsafe { libc::recvmsg(fd, &mut hdr.msg_hdr, flags) };
+    if ret < 0 {
+      return if i == 0 { -(errno() as isize) } else { i as isize };
+    }
+    hdr.msg_len = ret as libc::c_uint;
+  }
+  hdrs.len() as isize
+}
@@ -0,0 +1,313 @@
+use std::{io, mem, net::SocketAddr, os::fd::RawFd, ptr};
+
+use crate::{
+  BufResult,
+  api::{ops::IoVecs, resource::Resource},
+  buf::{BufLike, IoBufMut},
+  net_utils,
+  typed_op::TypedOp,
+};
+
+/// Ancillary data sent or received alongside a message, see
+/// [`sendmsg`](crate::api::sendmsg) and [`recvmsg`](crate::api::recvmsg).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ControlMessage {
+  /// File descriptors passed over a unix socket (`SCM_RIGHTS`).
+  ///
+  /// The receiver gets new descriptors for the same open files, and owns
+  /// them: it is responsible for closing them.
+  ScmRights(Vec<RawFd>),
+}
+
+impl ControlMessage {
+  /// Bytes of control buffer needed to receive `fds` descriptors in one
+  /// `SCM_RIGHTS` message, for [`recvmsg`](crate::api::recvmsg).
+  pub fn rights_space(fds: usize) -> usize {
+    // SAFETY: CMSG_SPACE only does arithmetic.
+    unsafe { libc::CMSG_SPACE((fds * mem::size_of::<RawFd>()) as u32) as usize }
+  }
+
+  fn space(&self) -> usize {
+    match self {
+      ControlMessage::ScmRights(fds) => Self::rights_space(fds.len()),
+    }
+  }
+}
+
+/// Control buffer storage, aligned for `cmsghdr`.
+type ControlBuf = Vec<u64>;
+
+fn control_buf(len: usize) -> ControlBuf {
+  vec![0u64; len.div_ceil(mem::size_of::<u64>())]
+}
+
+/// A `msghdr` together with the iovecs, address and control buffer it
+/// points to.
+///
+/// Boxed, so the pointers handed to the backend stay valid when the op
+/// moves.
+struct MsgHeader {
+  msg: libc::msghdr,
+  iovecs: IoVecs,
+  addr: libc::sockaddr_storage,
+  control: ControlBuf,
+}
+
+// SAFETY: The raw pointers in msg only point into the header itself and
+// into the op's buffers, both of which travel with the op.
+unsafe impl Send for MsgHeader {}
+// SAFETY: Same as Send, nothing is written through a shared reference.
+unsafe impl Sync for MsgHeader {}
+
+impl MsgHeader {
+  fn new(addr: libc::sockaddr_storage, control: ControlBuf) -> Box<Self> {
+    // SAFETY: msghdr is plain C data, all zeroes is valid.
+    let msg = unsafe { mem::zeroed() };
+    Box::new(Self { msg, iovecs: IoVecs(Vec::new()), addr, control })
+  }
+
+  /// Points the header at `iovecs`, at the first `addr_len` bytes of its
+  /// address and at the first `control_len` bytes of its control buffer.
+  fn prepare(
+    &mut self,
+    iovecs: Vec<libc::iovec>,
+    addr_len: libc::socklen_t,
+    control_len: usize,
+  ) -> *mut libc::msghdr {
+    self.iovecs.0 = iovecs;
+    self.msg.msg_name =
+      if addr_len == 0 { ptr::null_mut() } else { (&raw mut self.addr).cast() };
+    self.msg.msg_namelen = addr_len;
+    self.msg.msg_iov = self.iovecs.0.as_mut_ptr();
+    self.msg.msg_iovlen = self.iovecs.0.len() as _;
+    self.msg.msg_control = if control_len == 0 {
+      ptr::null_mut()
+    } else {
+      self.control.as_mut_ptr().cast()
+    };
+    self.msg.msg_controllen = control_len as _;
+    &raw mut self.msg
+  }
+
+  /// Writes `cmsgs` into the control buffer, which must hold their space.
+  fn encode(&mut self, cmsgs: &[ControlMessage], control_len: usize) {
+    // SAFETY: msghdr is plain C data, all zeroes is valid.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_control = self.control.as_mut_ptr().cast();
+    msg.msg_controllen = control_len as _;
+
+    // SAFETY: msg describes the control buffer, which has room for every
+    // message, so each header and its data stay inside it.
+    unsafe {
+      let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+      for message in cmsgs {
+        match message {
+          ControlMessage::ScmRights(fds) => {
+            let len = mem::size_of_val(fds.as_slice());
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(len as u32) as _;
+            ptr::copy_nonoverlapping(
+              fds.as_ptr().cast::<u8>(),
+              libc::CMSG_DATA(cmsg),
+              len,
+            );
+          }
+        }
+        cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+      }
+    }
+  }
+
+  /// Reads back the control messages the kernel filled in.
+  ///
+  /// Kinds other than [`ControlMessage`]'s are skipped.
+  fn decode(&self) -> Vec<ControlMessage> {
+    let mut out = Vec::new();
+    if self.msg.msg_control.is_null() {
+      return out;
+    }
+    // SAFETY: The kernel wrote msg_controllen bytes of well-formed control
+    // messages into the buffer msg points at.
+    unsafe {
+      let mut cmsg = libc::CMSG_FIRSTHDR(&self.msg);
+      while !cmsg.is_null() {
+        let header = *cmsg;
+        if header.cmsg_level == libc::SOL_SOCKET
+          && header.cmsg_type == libc::SCM_RIGHTS
+        {
+          let len = header.cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+          let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+          let fds = (0..len / mem::size_of::<RawFd>())
+            .map(|i| data.add(i).read_unaligned())
+            .collect();
+          out.push(ControlMessage::ScmRights(fds));
+        }
+        cmsg = libc::CMSG_NXTHDR(&self.msg, cmsg);
+      }
+    }
+    out
+  }
+}
+
+/// What [`recvmsg`](crate::api::recvmsg) received, besides the data itself.
+#[derive(Debug)]
+pub struct ReceivedMsg {
+  /// Bytes read into the buffers.
+  pub len: usize,
+  /// The sender's address, if the socket reported an IP one.
+  pub addr: Option<SocketAddr>,
+  /// Control messages that came with the data.
+  pub control: Vec<ControlMessage>,
+  /// Flags set on the message, such as `MSG_CTRUNC` when the control
+  /// buffer was too small and some control data was dropped.
+  pub flags: i32,
+}
+
+/// Sends a message with control data, see
+/// [`sendmsg`](crate::api::sendmsg).
+pub struct SendMsg<B> {
+  res: Resource,
+  bufs: Option<Vec<B>>,
+  header: Box<MsgHeader>,
+  addr_len: libc::socklen_t,
+  control_len: usize,
+}
+
+impl<B> SendMsg<B> {
+  pub(crate) fn new(
+    res: Resource,
+    bufs: Vec<B>,
+    addr: Option<SocketAddr>,
+    cmsgs: Vec<ControlMessage>,
+  ) -> Self {
+    let (storage, addr_len) = match addr {
+      Some(addr) => {
+        let len = match addr {
+          SocketAddr::V4(_) => mem::size_of::<libc::sockaddr_in>(),
+          SocketAddr::V6(_) => mem::size_of::<libc::sockaddr_in6>(),
+        };
+        (net_utils::std_socketaddr_into_libc(addr), len)
+      }
+      // SAFETY: sockaddr_storage is plain C data, all zeroes is valid.
+      None => (unsafe { mem::zeroed() }, 0),
+    };
+    let control_len = cmsgs.iter().map(ControlMessage::space).sum();
+    let mut header = MsgHeader::new(storage, control_buf(control_len));
+    header.encode(&cmsgs, control_len);
+    Self {
+      res,
+      bufs: Some(bufs),
+      header,
+      addr_len: addr_len as libc::socklen_t,
+      control_len,
+    }
+  }
+}
+
+impl<B> TypedOp for SendMsg<B>
+where
+  B: BufLike + Send + Sync + 'static,
+{
+  type Result = BufResult<i32, Vec<B>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let bufs = self.bufs.as_ref().expect("buffers not available");
+    let iovecs = bufs
+      .iter()
+      .map(|buf| {
+        let slice = buf.buf();
+        libc::iovec { iov_base: slice.as_ptr() as *mut _, iov_len: slice.len() }
+      })
+      .collect();
+    let msg = self.header.prepare(iovecs, self.addr_len, self.control_len);
+    crate::op::Op::SendMsg { fd: self.res.clone(), msg }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let bufs = self.bufs.expect("buffers not available");
+    if res < 0 {
+      (Err(io::Error::from_raw_os_error((-res) as i32)), bufs)
+    } else {
+      (Ok(res as i32), bufs)
+    }
+  }
+}
+
+/// Receives a message with control data, see
+/// [`recvmsg`](crate::api::recvmsg).
+pub struct RecvMsg<B> {
+  res: Resource,
+  bufs: Option<Vec<B>>,
+  header: Box<MsgHeader>,
+  control_len: usize,
+}
+
+impl<B> RecvMsg<B> {
+  pub(crate) fn new(res: Resource, bufs: Vec<B>, control_len: usize) -> Self {
+    // SAFETY: sockaddr_storage is plain C data, all zeroes is valid.
+    let addr = unsafe { mem::zeroed() };
+    Self {
+      res,
+      bufs: Some(bufs),
+      header: MsgHeader::new(addr, control_buf(control_len)),
+      control_len,
+    }
+  }
+}
+
+impl<B> TypedOp for RecvMsg<B>
+where
+  B: IoBufMut + Send + Sync + 'static,
+{
+  type Result = BufResult<ReceivedMsg, Vec<B>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let bufs = self.bufs.as_mut().expect("buffers not available");
+    let iovecs = bufs
+      .iter_mut()
+      .map(|buf| libc::iovec {
+        iov_base: buf.uninit_ptr().cast(),
+        iov_len: buf.uninit_len(),
+      })
+      .collect();
+    let storage_len = mem::size_of::<libc::sockaddr_storage>();
+    let msg = self.header.prepare(
+      iovecs,
+      storage_len as libc::socklen_t,
+      self.control_len,
+    );
+    crate::op::Op::RecvMsg { fd: self.res.clone(), msg }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let bufs = self.bufs.expect("buffers not available");
+    if res < 0 {
+      return (Err(io::Error::from_raw_os_error((-res) as i32)), bufs);
+    }
+    // Same as Readv: buffers are filled in order.
+    let mut left = res as usize;
+    let bufs = bufs
+      .into_iter()
+      .map(|buf| {
+        let filled = left.min(buf.uninit_len());
+        left -= filled;
+        buf.after(filled)
+      })
+      .collect();
+    let addr = if self.header.msg.msg_namelen == 0 {
+      None
+    } else {
+      // SAFETY: The kernel filled in msg_namelen bytes of the address.
+      unsafe { net_utils::libc_socketaddr_into_std(&self.header.addr) }.ok()
+    };
+    let received = ReceivedMsg {
+      len: res as usize,
+      addr,
+      control: self.header.decode(),
+      flags: self.header.msg.msg_flags,
+    };
+    (Ok(received), bufs)
+  }
+}
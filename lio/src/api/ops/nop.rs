@@ -1,4 +1,4 @@
-use crate::typed_op::TypedOp;
+use crate::typed_op::{DetachSafe, TypedOp};
 use std::io;
 
 pub struct Nop;
@@ -45,3 +45,5 @@ impl TypedOp for Nop {
   //   0
   // }
 }
+
+impl DetachSafe for Nop {}
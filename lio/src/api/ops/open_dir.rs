@@ -0,0 +1,64 @@
+use std::{ffi::CString, io};
+
+use crate::{
+  api::{
+    ops::OpenAt,
+    resource::{AsResource, FromResource, IntoResource, Resource},
+  },
+  typed_op::TypedOp,
+};
+
+#[cfg(target_os = "linux")]
+const DIR_FLAGS: i32 = libc::O_DIRECTORY | libc::O_PATH | libc::O_CLOEXEC;
+#[cfg(not(target_os = "linux"))]
+const DIR_FLAGS: i32 = libc::O_DIRECTORY | libc::O_RDONLY | libc::O_CLOEXEC;
+
+/// An open directory, used as the `dir_res` of `*at` operations such as
+/// [`openat`](crate::api::openat) and [`linkat`](crate::api::linkat).
+///
+/// On Linux the descriptor is opened with `O_PATH`, so it can only anchor
+/// path lookups, not be read from or synced. It is closed when the last
+/// clone is dropped.
+#[derive(Debug, Clone)]
+pub struct DirHandle(Resource);
+
+impl AsResource for DirHandle {
+  fn as_resource(&self) -> &Resource {
+    &self.0
+  }
+}
+
+impl IntoResource for DirHandle {
+  fn into_resource(self) -> Resource {
+    self.0
+  }
+}
+
+impl FromResource for DirHandle {
+  fn from_resource(resource: Resource) -> Self {
+    DirHandle(resource)
+  }
+}
+
+/// Opens a directory relative to the working directory, see
+/// [`open_dir`](crate::api::open_dir).
+pub struct OpenDir(OpenAt);
+
+impl OpenDir {
+  pub(crate) fn new(path: CString) -> Self {
+    let cwd = Resource::cwd();
+    Self(OpenAt::new(cwd, path, DIR_FLAGS))
+  }
+}
+
+impl TypedOp for OpenDir {
+  type Result = io::Result<DirHandle>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res).map(DirHandle)
+  }
+}
@@ -1,4 +1,9 @@
-use std::{ffi::CString, os::fd::FromRawFd};
+use std::ffi::CString;
+
+#[cfg(unix)]
+use std::os::fd::FromRawFd;
+#[cfg(windows)]
+use std::os::windows::io::{FromRawHandle, RawHandle};
 
 use crate::api::resource::Resource;
 use crate::typed_op::TypedOp;
@@ -41,7 +46,12 @@ impl TypedOp for OpenAt {
       Err(std::io::Error::from_raw_os_error((-res) as i32))
     } else {
       // SAFETY: 'res' is valid fd.
-      Ok(unsafe { Resource::from_raw_fd(res as i32) })
+      #[cfg(unix)]
+      let res = unsafe { Resource::from_raw_fd(res as i32) };
+      // SAFETY: 'res' is a valid handle.
+      #[cfg(windows)]
+      let res = unsafe { Resource::from_raw_handle(res as RawHandle) };
+      Ok(res)
     }
   }
 
@@ -0,0 +1,46 @@
+use std::{
+  io,
+  os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{api::resource::Resource, op::Op, typed_op::TypedOp};
+
+/// Reads how many received bytes a socket has buffered, see
+/// [`pending_bytes`](crate::api::pending_bytes).
+pub struct PendingBytes {
+  res: Resource,
+}
+
+assert_op_max_size!(PendingBytes);
+
+impl PendingBytes {
+  pub(crate) fn new(res: Resource) -> Self {
+    Self { res }
+  }
+}
+
+impl TypedOp for PendingBytes {
+  type Result = io::Result<usize>;
+
+  fn into_op(&mut self) -> Op {
+    Op::PendingBytes { fd: self.res.clone() }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    match -res as i32 {
+      // Kernels before 6.7 have no socket commands, and before 5.19 no
+      // URING_CMD at all. FIONREAD doesn't block, so ask directly.
+      libc::EOPNOTSUPP | libc::EINVAL if res < 0 => {
+        fionread(self.res.as_raw_fd())
+      }
+      errno if res < 0 => Err(io::Error::from_raw_os_error(errno)),
+      _ => Ok(res as usize),
+    }
+  }
+}
+
+fn fionread(fd: RawFd) -> io::Result<usize> {
+  let mut pending: libc::c_int = 0;
+  syscall!(ioctl(fd, libc::FIONREAD, &mut pending))?;
+  Ok(pending as usize)
+}
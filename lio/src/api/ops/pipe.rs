@@ -0,0 +1,94 @@
+use std::{
+  io,
+  os::fd::{FromRawFd, RawFd},
+};
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+/// Creates a pipe, see [`pipe`](crate::api::pipe).
+pub struct Pipe {
+  /// Boxed so the kernel can write the fds after the op moved.
+  fds: Box<[RawFd; 2]>,
+  flags: i32,
+}
+
+assert_op_max_size!(Pipe);
+
+impl Pipe {
+  pub(crate) fn new(flags: i32) -> Self {
+    Self { fds: Box::new([-1; 2]), flags }
+  }
+}
+
+impl TypedOp for Pipe {
+  /// The read end, then the write end.
+  type Result = io::Result<(Resource, Resource)>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Pipe { fds: &raw mut *self.fds, flags: self.flags }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      return Err(io::Error::from_raw_os_error((-res) as i32));
+    }
+    let [read, write] = *self.fds;
+    // SAFETY: The pipe was created, so both fds are valid and owned by
+    // nobody else.
+    unsafe { Ok((Resource::from_raw_fd(read), Resource::from_raw_fd(write))) }
+  }
+}
+
+/// Creates a pipe into `fds` on the calling thread, for backends without an
+/// asynchronous one. Returns 0 or a negated errno.
+///
+/// # Safety
+///
+/// `fds` must be valid for writing two fds.
+#[cfg(target_os = "linux")]
+pub(crate) unsafe fn pipe_blocking(fds: *mut [RawFd; 2], flags: i32) -> isize {
+  syscall!(raw pipe2(fds.cast(), flags)?);
+  0
+}
+
+/// Creates a pipe into `fds` on the calling thread, for backends without an
+/// asynchronous one. Returns 0 or a negated errno.
+///
+/// There is no `pipe2` here, so the flags are applied after the fact and
+/// only `O_CLOEXEC` and `O_NONBLOCK` are supported.
+///
+/// # Safety
+///
+/// `fds` must be valid for writing two fds.
+#[cfg(not(target_os = "linux"))]
+pub(crate) unsafe fn pipe_blocking(fds: *mut [RawFd; 2], flags: i32) -> isize {
+  if flags & !(libc::O_CLOEXEC | libc::O_NONBLOCK) != 0 {
+    return -(libc::EINVAL as isize);
+  }
+  syscall!(raw pipe(fds.cast())?);
+  // SAFETY: pipe succeeded and filled in fds.
+  let created = unsafe { *fds };
+  for fd in created {
+    let res = set_flags(fd, flags);
+    if res < 0 {
+      for fd in created {
+        // SAFETY: The fds were just created and aren't shared yet.
+        unsafe { libc::close(fd) };
+      }
+      return res;
+    }
+  }
+  0
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_flags(fd: RawFd, flags: i32) -> isize {
+  if flags & libc::O_CLOEXEC != 0 {
+    syscall!(raw fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC)?);
+  }
+  if flags & libc::O_NONBLOCK != 0 {
+    let fl = syscall!(raw fcntl(fd, libc::F_GETFL)?);
+    syscall!(raw fcntl(fd, libc::F_SETFL, fl as i32 | libc::O_NONBLOCK)?);
+  }
+  0
+}
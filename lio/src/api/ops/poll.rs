@@ -0,0 +1,150 @@
+use std::io;
+
+use crate::{
+  api::resource::Resource,
+  typed_op::{MultishotOp, TypedOp},
+};
+
+/// What [`poll`](crate::api::poll) waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interest {
+  bits: i16,
+}
+
+impl Interest {
+  /// Wait until the resource has data to read (`POLLIN`).
+  pub const READABLE: Self = Self { bits: libc::POLLIN };
+  /// Wait until the resource can be written to (`POLLOUT`).
+  pub const WRITABLE: Self = Self { bits: libc::POLLOUT };
+  pub const READ_AND_WRITE: Self =
+    Self { bits: Self::READABLE.bits | Self::WRITABLE.bits };
+
+  /// Returns the raw `POLL*` value passed to the syscall.
+  pub const fn bits(self) -> i16 {
+    self.bits
+  }
+
+  /// Combine interests using bitwise OR
+  pub const fn or(self, other: Self) -> Self {
+    Self { bits: self.bits | other.bits }
+  }
+
+  /// Check if this interest contains all bits from another
+  pub const fn contains(self, other: Self) -> bool {
+    (self.bits & other.bits) == other.bits
+  }
+}
+
+impl std::ops::BitOr for Interest {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self::Output {
+    self.or(rhs)
+  }
+}
+
+impl std::ops::BitOrAssign for Interest {
+  fn bitor_assign(&mut self, rhs: Self) {
+    *self = self.or(rhs);
+  }
+}
+
+/// Readiness reported by a completed [`poll`](crate::api::poll).
+///
+/// Errors and hangups are always reported, whether or not they were asked
+/// for, so check them before assuming the requested direction is usable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Readiness {
+  bits: i16,
+}
+
+impl Readiness {
+  /// Creates readiness from a raw `revents` value.
+  pub const fn from_raw(bits: i16) -> Self {
+    Self { bits }
+  }
+
+  /// Returns the raw `revents` value.
+  pub const fn bits(self) -> i16 {
+    self.bits
+  }
+
+  pub const fn is_readable(self) -> bool {
+    self.bits & libc::POLLIN != 0
+  }
+
+  pub const fn is_writable(self) -> bool {
+    self.bits & libc::POLLOUT != 0
+  }
+
+  /// The resource has a pending error (`POLLERR`).
+  pub const fn is_error(self) -> bool {
+    self.bits & libc::POLLERR != 0
+  }
+
+  /// The peer hung up (`POLLHUP`).
+  pub const fn is_hangup(self) -> bool {
+    self.bits & libc::POLLHUP != 0
+  }
+}
+
+pub struct Poll {
+  res: Resource,
+  interest: Interest,
+}
+
+assert_op_max_size!(Poll);
+
+impl Poll {
+  pub(crate) fn new(res: Resource, interest: Interest) -> Self {
+    Self { res, interest }
+  }
+}
+
+impl TypedOp for Poll {
+  type Result = io::Result<Readiness>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Poll { fd: self.res.clone(), events: self.interest.bits }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(Readiness::from_raw(res as i16))
+    }
+  }
+}
+
+/// Readiness events of a resource, see
+/// [`poll_multishot`](crate::api::poll_multishot).
+pub struct PollMultishot {
+  res: Resource,
+  interest: Interest,
+}
+
+impl PollMultishot {
+  pub(crate) fn new(res: Resource, interest: Interest) -> Self {
+    Self { res, interest }
+  }
+}
+
+impl MultishotOp for PollMultishot {
+  type Item = io::Result<Readiness>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::PollMultishot {
+      fd: self.res.clone(),
+      events: self.interest.bits,
+    }
+  }
+
+  fn extract_item(&self, res: isize) -> Self::Item {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(Readiness::from_raw(res as i16))
+    }
+  }
+}
@@ -1,5 +1,5 @@
 use crate::typed_op::TypedOp;
-use crate::{BufResult, api::resource::Resource, buf::BufLike};
+use crate::{BufResult, api::resource::Resource, buf::IoBufMut};
 
 pub struct Read<T>
 where
@@ -16,33 +16,38 @@ where
   /// Will return errn 22 "EINVAL" if offset < 0
   pub(crate) fn new(res: Resource, mem: T) -> Self
   where
-    T: BufLike,
+    T: IoBufMut,
   {
     Self { res, buf: Some(mem) }
   }
 
   pub fn to_op(mut self) -> crate::op::Op
   where
-    T: BufLike + 'static,
+    T: IoBufMut + 'static,
   {
     let buffer = self.buf.take().expect("buffer already taken");
-    crate::op::Op::Read { fd: self.res, buffer: crate::op::OpBuf::new(buffer) }
+    crate::op::Op::Read {
+      fd: self.res,
+      buffer: crate::op::OpBuf::new(buffer),
+      buf_index: None,
+    }
   }
 }
 
 impl<T> TypedOp for Read<T>
 where
-  T: BufLike + Send + Sync + 'static,
+  T: IoBufMut + Send + Sync + 'static,
 {
   type Result = BufResult<i32, T>;
 
   fn into_op(&mut self) -> crate::op::Op {
-    let slice = self.buf.as_mut().expect("buffer not available").buf();
-    let ptr = slice.as_ptr() as *mut u8;
-    let len = slice.len();
+    let buf = self.buf.as_mut().expect("buffer not available");
+    let ptr = buf.uninit_ptr().cast();
+    let len = buf.uninit_len();
     crate::op::Op::Read {
       fd: self.res.clone(),
       buffer: crate::op::OpBuf::new(crate::op::RawBuf { ptr, len }),
+      buf_index: buf.fixed_index(),
     }
   }
 
@@ -1,5 +1,5 @@
 use crate::{
-  BufResult, api::resource::Resource, buf::BufLike, typed_op::TypedOp,
+  BufResult, api::resource::Resource, buf::IoBufMut, typed_op::TypedOp,
 };
 
 pub struct ReadAt<T>
@@ -18,14 +18,14 @@ where
   /// Will return errn 22 "EINVAL" if offset < 0
   pub(crate) fn new(res: Resource, mem: T, offset: i64) -> Self
   where
-    T: BufLike,
+    T: IoBufMut,
   {
     Self { res, buf: Some(mem), offset }
   }
 
   pub fn to_op(mut self) -> crate::op::Op
   where
-    T: BufLike + 'static,
+    T: IoBufMut + 'static,
   {
     let buffer = self.buf.take().expect("buffer already taken");
     crate::op::Op::ReadAt {
@@ -38,14 +38,14 @@ where
 
 impl<T> TypedOp for ReadAt<T>
 where
-  T: BufLike + Send + Sync + 'static,
+  T: IoBufMut + Send + Sync + 'static,
 {
   type Result = BufResult<i32, T>;
 
   fn into_op(&mut self) -> crate::op::Op {
-    let slice = self.buf.as_mut().expect("buffer not available").buf();
-    let ptr = slice.as_ptr() as *mut u8;
-    let len = slice.len();
+    let buf = self.buf.as_mut().expect("buffer not available");
+    let ptr = buf.uninit_ptr().cast();
+    let len = buf.uninit_len();
     crate::op::Op::ReadAt {
       fd: self.res.clone(),
       offset: self.offset,
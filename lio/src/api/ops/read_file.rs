@@ -0,0 +1,72 @@
+use std::{
+  ffi::{CStr, CString},
+  io, mem,
+  os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+use crate::{api::ops::SpawnBlocking, typed_op::TypedOp};
+
+/// Read size once the stat'ed length has been reached, to detect EOF or
+/// pick up data appended since.
+const CHUNK: usize = 8 * 1024;
+
+/// Reads a whole file on the blocking pool, see
+/// [`read_file`](crate::api::read_file).
+pub struct ReadFile(SpawnBlocking<io::Result<Vec<u8>>>);
+
+assert_op_max_size!(ReadFile);
+
+impl ReadFile {
+  pub(crate) fn new(path: CString) -> Self {
+    Self(SpawnBlocking::new(move || read_file(&path)))
+  }
+}
+
+impl TypedOp for ReadFile {
+  type Result = io::Result<Vec<u8>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+/// Opens `path`, sizes the buffer from `fstat` and reads until EOF.
+///
+/// The stat'ed size is only a hint: reading continues past it if the file
+/// grew, and stops early if it shrank.
+fn read_file(path: &CStr) -> io::Result<Vec<u8>> {
+  let fd = syscall!(open(path.as_ptr(), libc::O_RDONLY | libc::O_CLOEXEC))?;
+  // SAFETY: open succeeded, so fd is valid and owned by nobody else.
+  let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+  // SAFETY: stat is plain C data, all zeroes is valid.
+  let mut stat: libc::stat = unsafe { mem::zeroed() };
+  syscall!(fstat(fd.as_raw_fd(), &mut stat))?;
+
+  let mut buf: Vec<u8> = Vec::with_capacity(stat.st_size.max(0) as usize);
+  loop {
+    if buf.len() == buf.capacity() {
+      buf.reserve(CHUNK);
+    }
+    let spare = buf.spare_capacity_mut();
+    // spare has spare.len() writable bytes.
+    let n = match syscall!(read(
+      fd.as_raw_fd(),
+      spare.as_mut_ptr().cast(),
+      spare.len()
+    )) {
+      Ok(n) => n as usize,
+      Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+      Err(err) => return Err(err),
+    };
+    if n == 0 {
+      return Ok(buf);
+    }
+    // SAFETY: read initialised the next n bytes.
+    unsafe { buf.set_len(buf.len() + n) };
+  }
+}
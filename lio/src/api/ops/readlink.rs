@@ -0,0 +1,64 @@
+use std::{
+  ffi::{CStr, CString},
+  io,
+  os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{
+  api::{ops::SpawnBlocking, resource::Resource},
+  typed_op::TypedOp,
+};
+
+/// First buffer size tried, most link targets are far shorter.
+const INITIAL_LEN: usize = 256;
+
+/// Reads a symlink's target on the blocking pool, see
+/// [`readlinkat`](crate::api::readlinkat).
+///
+/// Neither io_uring nor the polling backends have a way to run `readlinkat`
+/// asynchronously, so it goes through [`SpawnBlocking`].
+pub struct ReadLinkAt(SpawnBlocking<io::Result<CString>>);
+
+assert_op_max_size!(ReadLinkAt);
+
+impl ReadLinkAt {
+  pub(crate) fn new(dir_res: Resource, path: CString) -> Self {
+    Self(SpawnBlocking::new(move || read_link(dir_res.as_raw_fd(), &path)))
+  }
+}
+
+impl TypedOp for ReadLinkAt {
+  type Result = io::Result<CString>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+/// Calls `readlinkat`, doubling the buffer until the target fits.
+///
+/// `readlinkat` truncates silently, so a result filling the whole buffer
+/// might be cut short and is retried with a larger one.
+pub(crate) fn read_link(dir_fd: RawFd, path: &CStr) -> io::Result<CString> {
+  let mut buf: Vec<u8> = Vec::with_capacity(INITIAL_LEN);
+  loop {
+    // path is NUL-terminated and buf has capacity() writable bytes.
+    let len = syscall!(readlinkat(
+      dir_fd,
+      path.as_ptr(),
+      buf.as_mut_ptr().cast(),
+      buf.capacity()
+    ))? as usize;
+    if len < buf.capacity() {
+      // SAFETY: readlinkat wrote len bytes.
+      unsafe { buf.set_len(len) };
+      // A link target can't contain NUL bytes.
+      return Ok(CString::new(buf).expect("readlinkat returned a NUL byte"));
+    }
+    buf.reserve(buf.capacity() * 2);
+  }
+}
@@ -0,0 +1,103 @@
+use std::os::fd::RawFd;
+
+use crate::{
+  BufResult,
+  api::{
+    ops::{IOV_MAX, IoVecs},
+    resource::Resource,
+  },
+  buf::IoBufMut,
+  typed_op::TypedOp,
+};
+
+pub struct Readv<B>
+where
+  B: Send + Sync,
+{
+  res: Resource,
+  bufs: Option<Vec<B>>,
+  iovecs: IoVecs,
+}
+
+assert_op_max_size!(Readv<Vec<u8>>);
+
+impl<B> Readv<B>
+where
+  B: Send + Sync,
+{
+  pub(crate) fn new(res: Resource, bufs: Vec<B>) -> Self {
+    Self { res, bufs: Some(bufs), iovecs: IoVecs(Vec::new()) }
+  }
+}
+
+impl<B> TypedOp for Readv<B>
+where
+  B: IoBufMut + Send + Sync + 'static,
+{
+  type Result = BufResult<usize, Vec<B>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let bufs = self.bufs.as_mut().expect("buffers not available");
+    self.iovecs.0 = bufs
+      .iter_mut()
+      .map(|buf| libc::iovec {
+        iov_base: buf.uninit_ptr().cast(),
+        iov_len: buf.uninit_len(),
+      })
+      .collect();
+    crate::op::Op::Readv {
+      fd: self.res.clone(),
+      iov: self.iovecs.0.as_ptr(),
+      iovcnt: self.iovecs.0.len(),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let bufs = self.bufs.expect("buffers not available");
+    if res < 0 {
+      return (Err(std::io::Error::from_raw_os_error((-res) as i32)), bufs);
+    }
+    // The kernel fills the buffers in order, so each one got what was left
+    // of the count, up to its own size.
+    let mut left = res as usize;
+    let bufs = bufs
+      .into_iter()
+      .map(|buf| {
+        let filled = left.min(buf.uninit_len());
+        left -= filled;
+        buf.after(filled)
+      })
+      .collect();
+    (Ok(res as usize), bufs)
+  }
+}
+
+/// Reads into `iov` with as many `readv(2)` calls as `IOV_MAX` requires.
+///
+/// Stops early on a short read, like a single `readv` would, and returns the
+/// total read. An error after some bytes came in is dropped in favour of that
+/// count, the next read will report it again. An empty `iov` reads nothing
+/// and returns 0 without a syscall.
+///
+/// # Safety
+///
+/// `fd` must be valid and every iovec must point to writable memory.
+pub(crate) unsafe fn readv_chunked(fd: RawFd, iov: &[libc::iovec]) -> isize {
+  let mut total = 0isize;
+  for chunk in iov.chunks(IOV_MAX) {
+    // SAFETY: Upheld by the caller, and the chunk is at most IOV_MAX long.
+    let ret =
+      unsafe { libc::readv(fd, chunk.as_ptr(), chunk.len() as libc::c_int) };
+    if ret < 0 {
+      let errno =
+        std::io::Error::last_os_error().raw_os_error().unwrap_or(libc::EIO);
+      return if total > 0 { total } else { -(errno as isize) };
+    }
+    total += ret as isize;
+    let wanted: usize = chunk.iter().map(|v| v.iov_len).sum();
+    if (ret as usize) < wanted {
+      break;
+    }
+  }
+  total
+}
@@ -1,5 +1,6 @@
 use crate::{
-  BufResult, api::resource::Resource, buf::BufLike, typed_op::TypedOp,
+  BufResult, api::flags::RecvFlags, api::resource::Resource, buf::IoBufMut,
+  typed_op::TypedOp,
 };
 
 pub struct Recv<T>
@@ -15,37 +16,39 @@ impl<T> Recv<T>
 where
   T: Send + Sync,
 {
-  pub(crate) fn new(res: Resource, buf: T, flags: Option<i32>) -> Self {
-    Self { res, buf: Some(buf), flags: flags.unwrap_or(0) }
+  pub(crate) fn new(res: Resource, buf: T, flags: Option<RecvFlags>) -> Self {
+    Self { res, buf: Some(buf), flags: flags.unwrap_or_default().bits() }
   }
 
   pub fn to_op(mut self) -> crate::op::Op
   where
-    T: BufLike + 'static,
+    T: IoBufMut + 'static,
   {
     let buffer = self.buf.take().expect("buffer already taken");
     crate::op::Op::Recv {
       fd: self.res,
       flags: self.flags,
       buffer: crate::op::OpBuf::new(buffer),
+      buf_index: None,
     }
   }
 }
 
 impl<T> TypedOp for Recv<T>
 where
-  T: BufLike + Send + Sync + 'static,
+  T: IoBufMut + Send + Sync + 'static,
 {
   type Result = BufResult<i32, T>;
 
   fn into_op(&mut self) -> crate::op::Op {
-    let slice = self.buf.as_mut().expect("buffer not available").buf();
-    let ptr = slice.as_ptr() as *mut u8;
-    let len = slice.len();
+    let buf = self.buf.as_mut().expect("buffer not available");
+    let ptr = buf.uninit_ptr().cast();
+    let len = buf.uninit_len();
     crate::op::Op::Recv {
       fd: self.res.clone(),
       flags: self.flags,
       buffer: crate::op::OpBuf::new(crate::op::RawBuf { ptr, len }),
+      buf_index: buf.fixed_index(),
     }
   }
 
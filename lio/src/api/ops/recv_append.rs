@@ -0,0 +1,60 @@
+use crate::{
+  BufResult,
+  api::resource::Resource,
+  op::{Op, OpBuf, RawBuf},
+  typed_op::TypedOp,
+};
+
+/// Receives into the spare capacity of a `Vec` after its first `at` bytes,
+/// see [`Socket::recv_append`](crate::net::Socket::recv_append).
+pub struct RecvAppend {
+  res: Resource,
+  buf: Option<Vec<u8>>,
+  at: usize,
+}
+
+assert_op_max_size!(RecvAppend);
+
+impl RecvAppend {
+  /// Panics if `at` is past the capacity of `buf`.
+  pub(crate) fn new(res: Resource, buf: Vec<u8>, at: usize) -> Self {
+    assert!(
+      at <= buf.capacity(),
+      "recv_append offset {at} is past the buffer capacity {}",
+      buf.capacity()
+    );
+    Self { res, buf: Some(buf), at }
+  }
+}
+
+impl TypedOp for RecvAppend {
+  type Result = BufResult<usize, Vec<u8>>;
+
+  fn into_op(&mut self) -> Op {
+    let buf = self.buf.as_mut().expect("buffer not available");
+    let len = buf.capacity() - self.at;
+    // SAFETY: at <= capacity was checked in new, so the pointer stays within
+    // (or one past the end of) the allocation.
+    let ptr = unsafe { buf.as_mut_ptr().add(self.at) };
+    Op::Recv {
+      fd: self.res.clone(),
+      flags: 0,
+      buffer: OpBuf::new(RawBuf { ptr, len }),
+      buf_index: None,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let mut buf = self.buf.expect("buffer not available");
+    if res < 0 {
+      // On error, return buffer unchanged
+      (Err(std::io::Error::from_raw_os_error((-res) as i32)), buf)
+    } else {
+      let filled = self.at + res as usize;
+      // SAFETY: The first `at` bytes were filled by the caller and the next
+      // `res` by the kernel.
+      unsafe { buf.set_len(filled) };
+      (Ok(filled), buf)
+    }
+  }
+}
@@ -0,0 +1,48 @@
+use std::{
+  io,
+  sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+  },
+};
+
+use crate::{api::ops::IoVecs, typed_op::TypedOp};
+
+/// Registers a [`BufStore`](crate::buf::BufStore)'s buffers as the ring's
+/// fixed buffers, see [`BufStore::register_fixed`](crate::buf::BufStore::register_fixed).
+pub struct RegisterBuffers {
+  iovecs: IoVecs,
+  /// The store's count of registered buffers, set on success.
+  registered: Arc<AtomicUsize>,
+}
+
+impl RegisterBuffers {
+  pub(crate) fn new(
+    iovecs: Vec<libc::iovec>,
+    registered: Arc<AtomicUsize>,
+  ) -> Self {
+    Self { iovecs: IoVecs(iovecs), registered }
+  }
+}
+
+impl TypedOp for RegisterBuffers {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::RegisterBuffers {
+      iov: self.iovecs.0.as_ptr(),
+      count: self.iovecs.0.len(),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res == -(libc::EOPNOTSUPP as isize) {
+      return Err(io::ErrorKind::Unsupported.into());
+    }
+    if res < 0 {
+      return Err(io::Error::from_raw_os_error((-res) as i32));
+    }
+    self.registered.store(self.iovecs.0.len(), Ordering::Release);
+    Ok(())
+  }
+}
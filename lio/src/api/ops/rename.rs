@@ -0,0 +1,71 @@
+use std::{ffi::CString, io};
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+/// [`renameat2`](crate::api::renameat2) flag: fail with `EEXIST` instead of
+/// replacing an existing target.
+///
+/// Same value as Linux's `RENAME_NOREPLACE`, translated to `RENAME_EXCL` on
+/// Apple platforms.
+pub const RENAME_NOREPLACE: u32 = 1;
+
+pub struct RenameAt {
+  old_dir_res: Resource,
+  old_path: CString,
+  new_dir_res: Resource,
+  new_path: CString,
+  flags: u32,
+}
+
+assert_op_max_size!(RenameAt);
+
+impl RenameAt {
+  pub(crate) fn new(
+    old_dir_res: Resource,
+    old_path: CString,
+    new_dir_res: Resource,
+    new_path: CString,
+  ) -> Self {
+    Self::with_flags(old_dir_res, old_path, new_dir_res, new_path, 0)
+  }
+
+  pub(crate) fn with_flags(
+    old_dir_res: Resource,
+    old_path: CString,
+    new_dir_res: Resource,
+    new_path: CString,
+    flags: u32,
+  ) -> Self {
+    Self { old_dir_res, old_path, new_dir_res, new_path, flags }
+  }
+
+  /// Renames relative to the working directory, failing if `new_path`
+  /// already exists. See [`rename_noreplace`](crate::api::rename_noreplace).
+  #[cfg(unix)]
+  pub(crate) fn new_noreplace(old_path: CString, new_path: CString) -> Self {
+    let cwd = Resource::cwd();
+    Self::with_flags(cwd.clone(), old_path, cwd, new_path, RENAME_NOREPLACE)
+  }
+}
+
+impl TypedOp for RenameAt {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::RenameAt {
+      old_dir_fd: self.old_dir_res.clone(),
+      old_path: self.old_path.as_ptr(),
+      new_dir_fd: self.new_dir_res.clone(),
+      new_path: self.new_path.as_ptr(),
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
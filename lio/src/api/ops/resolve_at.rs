@@ -0,0 +1,195 @@
+use std::{
+  collections::VecDeque,
+  ffi::{CStr, CString},
+  io,
+  mem::MaybeUninit,
+  os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+};
+
+use super::readlink::read_link;
+use crate::{
+  api::{ops::SpawnBlocking, resource::Resource},
+  typed_op::TypedOp,
+};
+
+/// Symlinks followed before giving up with `ELOOP`, Linux's limit.
+const MAX_LINKS: usize = 40;
+
+/// Directories passed through only anchor lookups, so they don't need to be
+/// readable where `O_PATH` exists.
+#[cfg(target_os = "linux")]
+const WALK_FLAGS: i32 = libc::O_DIRECTORY | libc::O_PATH | libc::O_CLOEXEC;
+#[cfg(not(target_os = "linux"))]
+const WALK_FLAGS: i32 = libc::O_DIRECTORY | libc::O_RDONLY | libc::O_CLOEXEC;
+
+/// Canonicalizes a path against a directory on the blocking pool, see
+/// [`resolve_at`](crate::api::resolve_at).
+pub struct ResolveAt(SpawnBlocking<io::Result<CString>>);
+
+assert_op_max_size!(ResolveAt);
+
+impl ResolveAt {
+  pub(crate) fn new(dir_res: Resource, path: CString) -> Self {
+    Self(SpawnBlocking::new(move || resolve(dir_res.as_raw_fd(), &path)))
+  }
+}
+
+impl TypedOp for ResolveAt {
+  type Result = io::Result<CString>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+/// Walks `path` one component at a time from `dir_fd`, following symlinks,
+/// like `realpath` does from the working directory.
+///
+/// The current directory is always held open, so `..` and relative link
+/// targets resolve against it with `openat` and the process' working
+/// directory is never involved.
+fn resolve(dir_fd: RawFd, path: &CStr) -> io::Result<CString> {
+  let mut cur = open_dir_at(dir_fd, c".")?;
+  let mut resolved = dir_path(&cur)?;
+  if path.to_bytes().starts_with(b"/") {
+    cur = open_dir_at(dir_fd, c"/")?;
+    resolved.clear();
+  }
+  let mut pending = components(path.to_bytes());
+  let mut links = 0;
+
+  while let Some(name) = pending.pop_front() {
+    if name == b".." {
+      cur = open_dir_at(cur.as_raw_fd(), c"..")?;
+      resolved.pop();
+      continue;
+    }
+    let name = CString::new(name).expect("path components contain no NUL");
+    let st = stat_at(cur.as_raw_fd(), &name)?;
+    if st.st_mode & libc::S_IFMT == libc::S_IFLNK {
+      links += 1;
+      if links > MAX_LINKS {
+        return Err(io::Error::from_raw_os_error(libc::ELOOP));
+      }
+      let target = read_link(cur.as_raw_fd(), &name)?;
+      if target.to_bytes().starts_with(b"/") {
+        cur = open_dir_at(cur.as_raw_fd(), c"/")?;
+        resolved.clear();
+      }
+      for component in components(target.to_bytes()).into_iter().rev() {
+        pending.push_front(component);
+      }
+    } else {
+      // Anything but the last component has to be a directory.
+      if !pending.is_empty() {
+        cur = open_dir_at(cur.as_raw_fd(), &name)?;
+      }
+      resolved.push(name.into_bytes());
+    }
+  }
+
+  let mut out = Vec::new();
+  for name in &resolved {
+    out.push(b'/');
+    out.extend_from_slice(name);
+  }
+  if out.is_empty() {
+    out.push(b'/');
+  }
+  Ok(CString::new(out).expect("path components contain no NUL"))
+}
+
+/// Splits a path into its components, dropping empty ones and `.`.
+fn components(path: &[u8]) -> VecDeque<Vec<u8>> {
+  path
+    .split(|&b| b == b'/')
+    .filter(|name| !name.is_empty() && *name != b".")
+    .map(<[u8]>::to_vec)
+    .collect()
+}
+
+fn open_dir_at(dir_fd: RawFd, name: &CStr) -> io::Result<OwnedFd> {
+  let fd = syscall!(openat(dir_fd, name.as_ptr(), WALK_FLAGS))?;
+  // SAFETY: openat succeeded, so fd is valid and owned by nobody else.
+  Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn stat_at(dir_fd: RawFd, name: &CStr) -> io::Result<libc::stat> {
+  let mut st = MaybeUninit::<libc::stat>::uninit();
+  syscall!(fstatat(
+    dir_fd,
+    name.as_ptr(),
+    st.as_mut_ptr(),
+    libc::AT_SYMLINK_NOFOLLOW
+  ))?;
+  // SAFETY: fstatat succeeded and filled st.
+  Ok(unsafe { st.assume_init() })
+}
+
+fn same_file(a: &libc::stat, b: &libc::stat) -> bool {
+  a.st_dev == b.st_dev && a.st_ino == b.st_ino
+}
+
+/// Absolute path of the directory `dir` as components, found by walking
+/// `..` up to the root and looking each directory up in its parent.
+fn dir_path(dir: &OwnedFd) -> io::Result<Vec<Vec<u8>>> {
+  let mut names = Vec::new();
+  let mut cur = open_dir_at(dir.as_raw_fd(), c".")?;
+  loop {
+    let st = stat_at(cur.as_raw_fd(), c".")?;
+    let parent = open_dir_at(cur.as_raw_fd(), c"..")?;
+    // The root is its own parent.
+    if same_file(&st, &stat_at(parent.as_raw_fd(), c".")?) {
+      names.reverse();
+      return Ok(names);
+    }
+    names.push(entry_for(&parent, &st)?);
+    cur = parent;
+  }
+}
+
+/// Name of the entry in `parent` that is the file `st` describes.
+fn entry_for(parent: &OwnedFd, st: &libc::stat) -> io::Result<Vec<u8>> {
+  // Listing needs a readable descriptor, which fdopendir then takes over.
+  let fd = syscall!(openat(
+    parent.as_raw_fd(),
+    c".".as_ptr(),
+    libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC
+  ))?;
+  // SAFETY: fd is an open directory descriptor nobody else owns.
+  let dir = unsafe { libc::fdopendir(fd) };
+  if dir.is_null() {
+    let err = io::Error::last_os_error();
+    let _ = syscall!(close(fd));
+    return Err(err);
+  }
+
+  let mut found = None;
+  loop {
+    // SAFETY: dir is a valid directory stream until closedir below.
+    let entry = unsafe { libc::readdir(dir) };
+    if entry.is_null() {
+      break;
+    }
+    // SAFETY: readdir returned a valid entry with a NUL-terminated name.
+    let name = unsafe { CStr::from_ptr((*entry).d_name.as_ptr()) };
+    if name == c"." || name == c".." {
+      continue;
+    }
+    // Mount points only match through stat, not through d_ino.
+    if let Ok(entry_st) = stat_at(parent.as_raw_fd(), name)
+      && same_file(&entry_st, st)
+    {
+      found = Some(name.to_bytes().to_vec());
+      break;
+    }
+  }
+  // SAFETY: dir came from fdopendir and isn't used after this.
+  unsafe { libc::closedir(dir) };
+
+  found.ok_or_else(|| io::Error::from_raw_os_error(libc::ENOENT))
+}
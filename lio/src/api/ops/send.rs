@@ -1,5 +1,9 @@
 use crate::{
-  BufResult, api::resource::Resource, buf::BufLike, typed_op::TypedOp,
+  BufResult,
+  api::flags::SendFlags,
+  api::resource::Resource,
+  buf::BufLike,
+  typed_op::{DetachSafe, TypedOp},
 };
 
 pub struct Send<B>
@@ -15,8 +19,8 @@ impl<B> Send<B>
 where
   B: std::marker::Send + std::marker::Sync,
 {
-  pub(crate) fn new(res: Resource, buf: B, flags: Option<i32>) -> Self {
-    Self { res, buf: Some(buf), flags: flags.unwrap_or(0) }
+  pub(crate) fn new(res: Resource, buf: B, flags: Option<SendFlags>) -> Self {
+    Self { res, buf: Some(buf), flags: flags.unwrap_or_default().bits() }
   }
 
   pub fn to_op(mut self) -> crate::op::Op
@@ -89,3 +93,8 @@ where
   //   syscall!(raw send(self.res.as_raw_fd(), ptr as *mut _, len, self.flags))
   // }
 }
+
+impl<B> DetachSafe for Send<B> where
+  B: BufLike + std::marker::Send + std::marker::Sync + 'static
+{
+}
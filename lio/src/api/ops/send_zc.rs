@@ -0,0 +1,60 @@
+use crate::{
+  BufResult,
+  api::flags::SendFlags,
+  api::resource::Resource,
+  buf::BufLike,
+  typed_op::{DetachSafe, TypedOp},
+};
+
+/// Zero-copy send, see [`send_zc`](crate::api::send_zc).
+pub struct SendZc<B>
+where
+  B: std::marker::Send + std::marker::Sync,
+{
+  res: Resource,
+  buf: Option<B>,
+  flags: i32,
+}
+
+impl<B> SendZc<B>
+where
+  B: std::marker::Send + std::marker::Sync,
+{
+  pub(crate) fn new(res: Resource, buf: B, flags: Option<SendFlags>) -> Self {
+    Self { res, buf: Some(buf), flags: flags.unwrap_or_default().bits() }
+  }
+}
+
+impl<B> TypedOp for SendZc<B>
+where
+  B: BufLike + std::marker::Send + std::marker::Sync + 'static,
+{
+  type Result = BufResult<i32, B>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let buf = self.buf.as_ref().expect("buffer not available");
+    let slice = buf.buf();
+    let ptr = slice.as_ptr() as *mut u8;
+    let len = slice.len();
+    crate::op::Op::SendZc {
+      fd: self.res.clone(),
+      flags: self.flags,
+      buffer: crate::op::OpBuf::new(crate::op::RawBuf { ptr, len }),
+      buf_index: buf.fixed_index(),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let buf = self.buf.expect("buffer not available");
+    if res < 0 {
+      (Err(std::io::Error::from_raw_os_error((-res) as i32)), buf)
+    } else {
+      (Ok(res as i32), buf.after(res as usize))
+    }
+  }
+}
+
+impl<B> DetachSafe for SendZc<B> where
+  B: BufLike + std::marker::Send + std::marker::Sync + 'static
+{
+}
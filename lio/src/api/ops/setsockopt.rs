@@ -0,0 +1,54 @@
+use std::{
+  io,
+  os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{
+  api::{ops::SpawnBlocking, resource::Resource},
+  typed_op::TypedOp,
+};
+
+/// Sets a socket option on the blocking pool, see
+/// [`setsockopt`](crate::api::setsockopt).
+pub struct SetSockOpt(SpawnBlocking<io::Result<()>>);
+
+assert_op_max_size!(SetSockOpt);
+
+impl SetSockOpt {
+  pub(crate) fn new(
+    res: Resource,
+    level: i32,
+    name: i32,
+    value: Vec<u8>,
+  ) -> Self {
+    Self(SpawnBlocking::new(move || set(res.as_raw_fd(), level, name, &value)))
+  }
+
+  /// Sets an option that takes a `c_int`, like the boolean ones.
+  pub(crate) fn int(res: Resource, level: i32, name: i32, value: i32) -> Self {
+    Self::new(res, level, name, (value as libc::c_int).to_ne_bytes().to_vec())
+  }
+}
+
+impl TypedOp for SetSockOpt {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+fn set(fd: RawFd, level: i32, name: i32, value: &[u8]) -> io::Result<()> {
+  syscall!(setsockopt(
+    fd,
+    level,
+    name,
+    value.as_ptr().cast(),
+    value.len() as libc::socklen_t,
+  ))?;
+  Ok(())
+}
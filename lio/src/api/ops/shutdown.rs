@@ -1,5 +1,5 @@
 use crate::api::resource::Resource;
-use crate::typed_op::TypedOp;
+use crate::typed_op::{DetachSafe, TypedOp};
 
 pub struct Shutdown {
   res: Resource,
@@ -44,3 +44,5 @@ impl TypedOp for Shutdown {
   //   unsafe { libc::shutdown(self.res.as_raw_fd(), self.how) as isize }
   // }
 }
+
+impl DetachSafe for Shutdown {}
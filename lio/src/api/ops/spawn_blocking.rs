@@ -0,0 +1,90 @@
+use std::{
+  io,
+  os::fd::{FromRawFd, OwnedFd},
+  panic::{self, AssertUnwindSafe},
+  sync::{Arc, Mutex},
+  thread,
+};
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+type Slot<T> = Arc<Mutex<Option<thread::Result<T>>>>;
+
+/// A closure running on the blocking pool, see [`spawn_blocking`](crate::spawn_blocking).
+///
+/// The pool thread stores the closure's output and closes the write end of a
+/// pipe. The driver polls the read end, which reports the hangup, so
+/// completion goes through the same path as any other I/O operation.
+pub struct SpawnBlocking<T> {
+  /// Read end of the completion pipe, or why it couldn't be created.
+  done: Result<Resource, i32>,
+  output: Slot<T>,
+}
+
+assert_op_max_size!(SpawnBlocking<u64>);
+
+impl<T> SpawnBlocking<T>
+where
+  T: Send + 'static,
+{
+  pub(crate) fn new<F>(f: F) -> Self
+  where
+    F: FnOnce() -> T + Send + 'static,
+  {
+    let output: Slot<T> = Arc::new(Mutex::new(None));
+    let done = match pipe() {
+      Ok((read, write)) => {
+        let slot = output.clone();
+        crate::worker::execute(Box::new(move || {
+          let result = panic::catch_unwind(AssertUnwindSafe(f));
+          *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+          // Closing the write end completes the poll on the driver.
+          drop(write);
+        }));
+        Ok(read)
+      }
+      Err(err) => Err(err.raw_os_error().unwrap_or(libc::EIO)),
+    };
+    Self { done, output }
+  }
+}
+
+fn pipe() -> io::Result<(Resource, OwnedFd)> {
+  let mut fds = [0; 2];
+  syscall!(pipe(fds.as_mut_ptr()))?;
+  // SAFETY: pipe succeeded, so both fds are valid and owned by nobody else.
+  let (read, write) =
+    unsafe { (Resource::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+  for fd in fds {
+    syscall!(fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+  }
+  Ok((read, write))
+}
+
+impl<T> TypedOp for SpawnBlocking<T>
+where
+  T: Send + Sync + 'static,
+{
+  type Result = io::Result<T>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    match &self.done {
+      Ok(fd) => crate::op::Op::Poll { fd: fd.clone(), events: libc::POLLIN },
+      Err(_) => crate::op::Op::Nop,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if let Err(errno) = self.done {
+      return Err(io::Error::from_raw_os_error(errno));
+    }
+    if res < 0 {
+      return Err(io::Error::from_raw_os_error((-res) as i32));
+    }
+    let output = self.output.lock().unwrap_or_else(|e| e.into_inner()).take();
+    match output.expect("lio: blocking pool closed the pipe without a result") {
+      Ok(value) => Ok(value),
+      Err(payload) => panic::resume_unwind(payload),
+    }
+  }
+}
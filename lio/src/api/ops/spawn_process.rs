@@ -0,0 +1,170 @@
+use std::{
+  ffi::CString,
+  io,
+  mem::MaybeUninit,
+  os::fd::{AsRawFd, FromRawFd, RawFd},
+  ptr,
+};
+
+use crate::{
+  api::{
+    ops::SpawnBlocking,
+    resource::{AsResource, FromResource, IntoResource, Resource},
+  },
+  typed_op::TypedOp,
+};
+
+unsafe extern "C" {
+  static environ: *const *mut libc::c_char;
+}
+
+/// Process id of a spawned child.
+pub type Pid = libc::pid_t;
+
+/// A pidfd referring to a child process, see
+/// [`spawn_process`](crate::api::spawn_process).
+///
+/// It becomes readable once the child exits, so [`poll`](crate::api::poll)
+/// waits for the exit without blocking a thread; `waitid(P_PIDFD, ..)` then
+/// reaps it.
+#[derive(Debug, Clone)]
+pub struct PidFd(Resource);
+
+impl AsResource for PidFd {
+  fn as_resource(&self) -> &Resource {
+    &self.0
+  }
+}
+
+impl IntoResource for PidFd {
+  fn into_resource(self) -> Resource {
+    self.0
+  }
+}
+
+impl FromResource for PidFd {
+  fn from_resource(resource: Resource) -> Self {
+    PidFd(resource)
+  }
+}
+
+/// Spawns a process on the blocking pool, see
+/// [`spawn_process`](crate::api::spawn_process).
+pub struct SpawnProcess(SpawnBlocking<io::Result<(Pid, PidFd)>>);
+
+assert_op_max_size!(SpawnProcess);
+
+impl SpawnProcess {
+  pub(crate) fn new(
+    program: CString,
+    args: Vec<CString>,
+    fds: Vec<(Resource, RawFd)>,
+  ) -> Self {
+    Self(SpawnBlocking::new(move || spawn(&program, &args, &fds)))
+  }
+}
+
+impl TypedOp for SpawnProcess {
+  type Result = io::Result<(Pid, PidFd)>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+/// Turns a `posix_spawn*` return value, which is the errno itself, into a
+/// result.
+fn check(ret: libc::c_int) -> io::Result<()> {
+  if ret == 0 { Ok(()) } else { Err(io::Error::from_raw_os_error(ret)) }
+}
+
+/// File actions that are destroyed on drop.
+struct FileActions(libc::posix_spawn_file_actions_t);
+
+impl FileActions {
+  fn new() -> io::Result<Self> {
+    let mut actions = MaybeUninit::uninit();
+    // SAFETY: actions is writable, init fills it in.
+    check(unsafe {
+      libc::posix_spawn_file_actions_init(actions.as_mut_ptr())
+    })?;
+    // SAFETY: init succeeded.
+    Ok(Self(unsafe { actions.assume_init() }))
+  }
+
+  fn dup2(&mut self, fd: RawFd, target: RawFd) -> io::Result<()> {
+    // SAFETY: self.0 was initialised by posix_spawn_file_actions_init.
+    check(unsafe {
+      libc::posix_spawn_file_actions_adddup2(&mut self.0, fd, target)
+    })
+  }
+}
+
+impl Drop for FileActions {
+  fn drop(&mut self) {
+    // SAFETY: self.0 was initialised and is destroyed only here.
+    unsafe { libc::posix_spawn_file_actions_destroy(&mut self.0) };
+  }
+}
+
+/// Spawns `program` with `posix_spawnp` and opens a pidfd for it.
+///
+/// Every `(fd, target)` pair is `dup2`ed in the child, which clears
+/// `FD_CLOEXEC` on `target`. All other descriptors lio opens are
+/// close-on-exec, so they don't leak into the child.
+fn spawn(
+  program: &CString,
+  args: &[CString],
+  fds: &[(Resource, RawFd)],
+) -> io::Result<(Pid, PidFd)> {
+  let mut actions = FileActions::new()?;
+  for (res, target) in fds {
+    actions.dup2(res.as_raw_fd(), *target)?;
+  }
+
+  let mut argv: Vec<*mut libc::c_char> = Vec::with_capacity(args.len() + 2);
+  argv.push(program.as_ptr().cast_mut());
+  argv.extend(args.iter().map(|arg| arg.as_ptr().cast_mut()));
+  argv.push(ptr::null_mut());
+
+  let mut pid: Pid = 0;
+  // SAFETY: program and argv are NUL-terminated and outlive the call, argv is
+  // NULL-terminated and environ is the process environment.
+  check(unsafe {
+    libc::posix_spawnp(
+      &mut pid,
+      program.as_ptr(),
+      &actions.0,
+      ptr::null(),
+      argv.as_ptr(),
+      environ,
+    )
+  })?;
+
+  // The child stays a zombie until reaped, so this can't race its exit.
+  let fd = match syscall!(syscall(libc::SYS_pidfd_open, pid, 0)) {
+    Ok(fd) => fd,
+    Err(err) => {
+      reap(pid);
+      return Err(err);
+    }
+  };
+  // SAFETY: pidfd_open succeeded, so fd is valid and owned by nobody else.
+  Ok((pid, PidFd(unsafe { Resource::from_raw_fd(fd) })))
+}
+
+/// Kills and reaps a child whose pid never reaches the caller, so it
+/// doesn't keep running or linger as a zombie.
+fn reap(pid: Pid) {
+  let _ = syscall!(kill(pid, libc::SIGKILL));
+  // A null status is allowed, nobody looks at it.
+  while let Err(err) = syscall!(waitpid(pid, ptr::null_mut(), 0)) {
+    if err.kind() != io::ErrorKind::Interrupted {
+      break;
+    }
+  }
+}
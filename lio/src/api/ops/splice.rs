@@ -0,0 +1,53 @@
+use std::io;
+
+use crate::api::resource::Resource;
+use crate::typed_op::TypedOp;
+
+/// Moves data between two fds without copying through userspace, see
+/// [`splice`](crate::api::splice).
+pub struct Splice {
+  res_in: Resource,
+  off_in: i64,
+  res_out: Resource,
+  off_out: i64,
+  len: u32,
+  flags: u32,
+}
+
+assert_op_max_size!(Splice);
+
+impl Splice {
+  pub(crate) fn new(
+    res_in: Resource,
+    off_in: i64,
+    res_out: Resource,
+    off_out: i64,
+    len: u32,
+    flags: u32,
+  ) -> Self {
+    Self { res_in, off_in, res_out, off_out, len, flags }
+  }
+}
+
+impl TypedOp for Splice {
+  type Result = io::Result<i32>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Splice {
+      fd_in: self.res_in.clone(),
+      off_in: self.off_in,
+      fd_out: self.res_out.clone(),
+      off_out: self.off_out,
+      len: self.len,
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(res as i32)
+    }
+  }
+}
@@ -0,0 +1,156 @@
+use std::{
+  ffi::CString,
+  io,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(not(target_os = "linux"))]
+use std::{ffi::CStr, mem::MaybeUninit, os::fd::AsRawFd, os::fd::RawFd};
+
+#[cfg(not(target_os = "linux"))]
+use crate::api::ops::SpawnBlocking;
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+/// File metadata returned by [`statx`](crate::api::statx).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+  /// Size in bytes.
+  pub size: u64,
+  /// Last modification time.
+  pub modified: SystemTime,
+  /// File type and permission bits, as in `st_mode`.
+  pub mode: u32,
+}
+
+impl Metadata {
+  pub fn is_dir(&self) -> bool {
+    self.mode & libc::S_IFMT as u32 == libc::S_IFDIR as u32
+  }
+
+  pub fn is_file(&self) -> bool {
+    self.mode & libc::S_IFMT as u32 == libc::S_IFREG as u32
+  }
+}
+
+fn system_time(secs: i64, nanos: u32) -> SystemTime {
+  // Nanoseconds always count forward, also before the epoch.
+  let since = Duration::from_nanos(nanos as u64);
+  if secs >= 0 {
+    UNIX_EPOCH + Duration::from_secs(secs as u64) + since
+  } else {
+    UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs()) + since
+  }
+}
+
+/// Queries file metadata, see [`statx`](crate::api::statx).
+///
+/// On Linux this is a `statx` into a boxed buffer. Elsewhere there is no
+/// asynchronous stat, so `fstatat` runs on the blocking pool instead.
+#[cfg(target_os = "linux")]
+pub struct Statx {
+  dir_res: Resource,
+  path: CString,
+  flags: i32,
+  mask: u32,
+  /// Boxed so the pointer handed to the backend stays valid when the op
+  /// moves.
+  buf: Box<libc::statx>,
+}
+
+/// Queries file metadata, see [`statx`](crate::api::statx).
+///
+/// On Linux this is a `statx` into a boxed buffer. Elsewhere there is no
+/// asynchronous stat, so `fstatat` runs on the blocking pool instead.
+#[cfg(not(target_os = "linux"))]
+pub struct Statx(SpawnBlocking<io::Result<Metadata>>);
+
+assert_op_max_size!(Statx);
+
+impl Statx {
+  #[cfg(target_os = "linux")]
+  pub(crate) fn new(
+    dir_res: Resource,
+    path: CString,
+    flags: i32,
+    mask: u32,
+  ) -> Self {
+    // SAFETY: statx is plain C data, all zeroes is valid.
+    let buf = Box::new(unsafe { std::mem::zeroed() });
+    Self { dir_res, path, flags, mask, buf }
+  }
+
+  #[cfg(not(target_os = "linux"))]
+  pub(crate) fn new(
+    dir_res: Resource,
+    path: CString,
+    flags: i32,
+    _mask: u32,
+  ) -> Self {
+    Self(SpawnBlocking::new(move || stat_at(dir_res.as_raw_fd(), &path, flags)))
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn stat_at(dir_fd: RawFd, path: &CStr, flags: i32) -> io::Result<Metadata> {
+  let mut st = MaybeUninit::<libc::stat>::uninit();
+  syscall!(fstatat(dir_fd, path.as_ptr(), st.as_mut_ptr(), flags))?;
+  // SAFETY: fstatat succeeded and filled st.
+  let st = unsafe { st.assume_init() };
+  Ok(Metadata {
+    size: st.st_size as u64,
+    modified: system_time(st.st_mtime, st.st_mtime_nsec as u32),
+    mode: st.st_mode as u32,
+  })
+}
+
+impl TypedOp for Statx {
+  type Result = io::Result<Metadata>;
+
+  #[cfg(target_os = "linux")]
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Statx {
+      dir_fd: self.dir_res.clone(),
+      path: self.path.as_ptr(),
+      flags: self.flags,
+      mask: self.mask,
+      buf: &raw mut *self.buf,
+    }
+  }
+
+  #[cfg(not(target_os = "linux"))]
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  #[cfg(target_os = "linux")]
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      return Err(io::Error::from_raw_os_error((-res) as i32));
+    }
+    let stx = &*self.buf;
+    Ok(Metadata {
+      size: stx.stx_size,
+      modified: system_time(stx.stx_mtime.tv_sec, stx.stx_mtime.tv_nsec),
+      mode: stx.stx_mode as u32,
+    })
+  }
+
+  #[cfg(not(target_os = "linux"))]
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_system_time_before_epoch() {
+    let time = system_time(-2, 250);
+    assert_eq!(
+      UNIX_EPOCH.duration_since(time).unwrap(),
+      Duration::new(1, 999_999_750)
+    );
+  }
+}
@@ -0,0 +1,39 @@
+use std::io;
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+pub struct SyncFileRange {
+  res: Resource,
+  offset: u64,
+  len: u32,
+  flags: u32,
+}
+
+assert_op_max_size!(SyncFileRange);
+
+impl SyncFileRange {
+  pub(crate) fn new(res: Resource, offset: u64, len: u32, flags: u32) -> Self {
+    Self { res, offset, len, flags }
+  }
+}
+
+impl TypedOp for SyncFileRange {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::SyncFileRange {
+      fd: self.res.clone(),
+      offset: self.offset,
+      len: self.len,
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
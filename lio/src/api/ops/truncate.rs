@@ -1,5 +1,5 @@
 use crate::api::resource::Resource;
-use crate::typed_op::TypedOp;
+use crate::typed_op::{DetachSafe, TypedOp};
 
 pub struct Truncate {
   res: Resource,
@@ -53,3 +53,5 @@ impl TypedOp for Truncate {
   //   syscall!(raw ftruncate(self.res.as_raw_fd(), self.size as i64))
   // }
 }
+
+impl DetachSafe for Truncate {}
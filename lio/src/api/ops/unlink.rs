@@ -0,0 +1,37 @@
+use std::{ffi::CString, io};
+
+use crate::{api::resource::Resource, typed_op::TypedOp};
+
+pub struct UnlinkAt {
+  dir_res: Resource,
+  path: CString,
+  flags: i32,
+}
+
+assert_op_max_size!(UnlinkAt);
+
+impl UnlinkAt {
+  pub(crate) fn new(dir_res: Resource, path: CString, flags: i32) -> Self {
+    Self { dir_res, path, flags }
+  }
+}
+
+impl TypedOp for UnlinkAt {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::UnlinkAt {
+      dir_fd: self.dir_res.clone(),
+      path: self.path.as_ptr(),
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
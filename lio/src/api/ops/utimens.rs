@@ -0,0 +1,144 @@
+use std::{
+  ffi::CString,
+  io,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+  api::resource::Resource,
+  typed_op::{DetachSafe, TypedOp},
+};
+
+/// A timestamp passed to [`futimens`](crate::api::futimens) and
+/// [`utimensat`](crate::api::utimensat).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileTime {
+  /// Set the timestamp to the current time (`UTIME_NOW`).
+  Now,
+  /// Leave the timestamp unchanged (`UTIME_OMIT`).
+  Omit,
+  /// Set the timestamp to the given time.
+  At(SystemTime),
+}
+
+impl FileTime {
+  fn to_timespec(self) -> libc::timespec {
+    let (tv_sec, tv_nsec) = match self {
+      FileTime::Now => (0, libc::UTIME_NOW),
+      FileTime::Omit => (0, libc::UTIME_OMIT),
+      FileTime::At(time) => match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos() as i64),
+        Err(before) => {
+          // tv_nsec must stay in 0..1e9, so borrow a second for the fraction.
+          let d = before.duration();
+          let (secs, nanos) = (d.as_secs() as i64, d.subsec_nanos() as i64);
+          if nanos == 0 {
+            (-secs, 0)
+          } else {
+            (-secs - 1, 1_000_000_000 - nanos)
+          }
+        }
+      },
+    };
+    libc::timespec {
+      tv_sec: tv_sec as libc::time_t,
+      tv_nsec: tv_nsec as libc::c_long,
+    }
+  }
+}
+
+fn to_times(atime: FileTime, mtime: FileTime) -> [libc::timespec; 2] {
+  [atime.to_timespec(), mtime.to_timespec()]
+}
+
+pub struct Futimens {
+  res: Resource,
+  times: [libc::timespec; 2],
+}
+
+assert_op_max_size!(Futimens);
+
+impl Futimens {
+  pub(crate) fn new(res: Resource, atime: FileTime, mtime: FileTime) -> Self {
+    Self { res, times: to_times(atime, mtime) }
+  }
+}
+
+impl TypedOp for Futimens {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::Futimens { fd: self.res.clone(), times: self.times.as_ptr() }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+impl DetachSafe for Futimens {}
+
+pub struct UtimensAt {
+  dir_res: Resource,
+  pathname: CString,
+  times: [libc::timespec; 2],
+  flags: i32,
+}
+
+impl UtimensAt {
+  pub(crate) fn new(
+    dir_res: Resource,
+    pathname: CString,
+    atime: FileTime,
+    mtime: FileTime,
+    flags: i32,
+  ) -> Self {
+    Self { dir_res, pathname, times: to_times(atime, mtime), flags }
+  }
+}
+
+impl TypedOp for UtimensAt {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::UtimensAt {
+      dir_fd: self.dir_res.clone(),
+      path: self.pathname.as_ptr(),
+      times: self.times.as_ptr(),
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      Err(io::Error::from_raw_os_error((-res) as i32))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+impl DetachSafe for UtimensAt {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  #[test]
+  fn test_file_time_before_epoch() {
+    let ts = FileTime::At(UNIX_EPOCH - Duration::new(1, 250)).to_timespec();
+    assert_eq!(ts.tv_sec, -2);
+    assert_eq!(ts.tv_nsec, 999_999_750);
+  }
+
+  #[test]
+  fn test_file_time_special_values() {
+    assert_eq!(FileTime::Now.to_timespec().tv_nsec, libc::UTIME_NOW);
+    assert_eq!(FileTime::Omit.to_timespec().tv_nsec, libc::UTIME_OMIT);
+  }
+}
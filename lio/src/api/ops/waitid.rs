@@ -0,0 +1,117 @@
+use std::{cell::UnsafeCell, io, sync::Arc};
+
+use crate::{api::ops::SpawnBlocking, op::Op, typed_op::TypedOp};
+
+/// How a child changed state, returned by [`waitid`](crate::api::waitid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChildStatus {
+  /// Pid of the child, 0 if `WNOHANG` was given and no child changed state.
+  pub pid: libc::pid_t,
+  /// What happened to the child, one of the `CLD_*` codes.
+  pub code: i32,
+  /// The exit status for `CLD_EXITED`, otherwise the signal involved.
+  pub status: i32,
+}
+
+impl ChildStatus {
+  /// Returns the exit code if the child exited normally.
+  pub fn exit_code(&self) -> Option<i32> {
+    (self.code == libc::CLD_EXITED).then_some(self.status)
+  }
+}
+
+/// The `siginfo_t` a [`WaitId`] is written to.
+///
+/// Shared with the pool thread of [`wait_on_pool`](Self::wait_on_pool), so
+/// it stays alive even if the op is dropped before the child changes state.
+pub struct WaitInfo(UnsafeCell<libc::siginfo_t>);
+
+// SAFETY: Only one writer, the kernel or a pool thread, touches the siginfo
+// before the op completes, and it's only read after that.
+unsafe impl Send for WaitInfo {}
+// SAFETY: Same as Send.
+unsafe impl Sync for WaitInfo {}
+
+impl WaitInfo {
+  pub(crate) fn as_ptr(&self) -> *mut libc::siginfo_t {
+    self.0.get()
+  }
+
+  /// Runs the `waitid` on a blocking pool thread for backends without an
+  /// asynchronous one, returning the op that completes once it returns.
+  ///
+  /// A failure is stored as `si_errno` with a zero `si_signo`, which a
+  /// successful `waitid` never leaves behind.
+  pub(crate) fn wait_on_pool(
+    info: &Arc<Self>,
+    idtype: libc::idtype_t,
+    id: libc::id_t,
+    options: i32,
+  ) -> Op {
+    let info = info.clone();
+    let mut job = SpawnBlocking::new(move || {
+      // SAFETY: info is a valid siginfo_t, and nothing else accesses it
+      // until the job completes.
+      unsafe {
+        let ptr = info.as_ptr();
+        if libc::waitid(idtype, id, ptr, options) < 0 {
+          (*ptr).si_signo = 0;
+          (*ptr).si_errno =
+            io::Error::last_os_error().raw_os_error().unwrap_or(libc::EIO);
+        }
+      }
+    });
+    TypedOp::into_op(&mut job)
+  }
+}
+
+/// Waits for a child to change state, see [`waitid`](crate::api::waitid).
+pub struct WaitId {
+  idtype: libc::idtype_t,
+  id: libc::id_t,
+  options: i32,
+  info: Arc<WaitInfo>,
+}
+
+assert_op_max_size!(WaitId);
+
+impl WaitId {
+  pub(crate) fn new(
+    idtype: libc::idtype_t,
+    id: libc::id_t,
+    options: i32,
+  ) -> Self {
+    // SAFETY: siginfo_t is plain C data, all zeroes is valid.
+    let info =
+      Arc::new(WaitInfo(UnsafeCell::new(unsafe { std::mem::zeroed() })));
+    Self { idtype, id, options, info }
+  }
+}
+
+impl TypedOp for WaitId {
+  type Result = io::Result<ChildStatus>;
+
+  fn into_op(&mut self) -> Op {
+    Op::WaitId {
+      idtype: self.idtype,
+      id: self.id,
+      options: self.options,
+      info: self.info.clone(),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res < 0 {
+      return Err(io::Error::from_raw_os_error((-res) as i32));
+    }
+    // SAFETY: The op completed, so whoever wrote the siginfo is done with it.
+    let info = unsafe { &*self.info.as_ptr() };
+    if info.si_signo == 0 && info.si_errno != 0 {
+      return Err(io::Error::from_raw_os_error(info.si_errno));
+    }
+    // SAFETY: waitid fills in the child fields of the siginfo, or leaves it
+    // zeroed if no child changed state.
+    let (pid, status) = unsafe { (info.si_pid(), info.si_status()) };
+    Ok(ChildStatus { pid, code: info.si_code, status })
+  }
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use crate::typed_op::TypedOp;
+
+/// Runs `O` with a deadline, see [`Socket::recv_timeout`](crate::net::Socket::recv_timeout).
+///
+/// The backend enforces the deadline alongside the operation itself: a linked
+/// timeout on io_uring, a timer on the polling backends. If it passes first,
+/// `O` completes with `ETIMEDOUT`, which surfaces as
+/// [`io::ErrorKind::TimedOut`](std::io::ErrorKind::TimedOut). Only the
+/// operation is abandoned, its resource stays open.
+pub struct WithTimeout<O> {
+  op: O,
+  timeout: Duration,
+}
+
+impl<O> WithTimeout<O> {
+  pub(crate) fn new(op: O, timeout: Duration) -> Self {
+    Self { op, timeout }
+  }
+}
+
+impl<O> TypedOp for WithTimeout<O>
+where
+  O: TypedOp,
+{
+  type Result = O::Result;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.op.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.op.extract_result(res)
+  }
+
+  fn timeout(&self) -> Option<Duration> {
+    Some(self.timeout)
+  }
+
+  fn after_writes(&self) -> bool {
+    self.op.after_writes()
+  }
+}
@@ -1,5 +1,8 @@
 use crate::{
-  BufResult, api::resource::Resource, buf::BufLike, typed_op::TypedOp,
+  BufResult,
+  api::resource::Resource,
+  buf::BufLike,
+  typed_op::{DetachSafe, TypedOp},
 };
 
 pub struct Write<B>
@@ -88,3 +91,5 @@ where
   //   syscall!(raw write(fd, ptr.cast::<libc::c_void>() as *const _, len))
   // }
 }
+
+impl<B> DetachSafe for Write<B> where B: BufLike + Send + Sync + 'static {}
@@ -1,5 +1,8 @@
 use crate::{
-  BufResult, api::resource::Resource, buf::BufLike, typed_op::TypedOp,
+  BufResult,
+  api::resource::Resource,
+  buf::BufLike,
+  typed_op::{DetachSafe, TypedOp},
 };
 
 pub struct WriteAt<B>
@@ -99,3 +102,5 @@ where
   //   ))
   // }
 }
+
+impl<B> DetachSafe for WriteAt<B> where B: BufLike + Send + Sync + 'static {}
@@ -0,0 +1,170 @@
+use std::{
+  ffi::{CStr, CString},
+  io,
+  os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd},
+  process,
+  sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+  Lio,
+  api::{self, ops::SpawnBlocking, resource::Resource},
+  typed_op::TypedOp,
+};
+
+/// Writes a whole file on the blocking pool, see
+/// [`write_file`](crate::api::write_file) and
+/// [`write_file_atomic`](crate::api::write_file_atomic).
+pub struct WriteFile(SpawnBlocking<io::Result<()>>);
+
+assert_op_max_size!(WriteFile);
+
+impl WriteFile {
+  pub(crate) fn new(path: CString, data: Vec<u8>, durable: bool) -> Self {
+    Self(SpawnBlocking::new(move || write_file(&path, &data, durable)))
+  }
+
+  pub(crate) fn new_atomic(path: CString, data: Vec<u8>) -> Self {
+    Self(SpawnBlocking::new(move || write_file_atomic(&path, &data)))
+  }
+}
+
+impl TypedOp for WriteFile {
+  type Result = io::Result<()>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+fn open(path: &CStr, flags: i32) -> io::Result<OwnedFd> {
+  let fd = syscall!(open(path.as_ptr(), flags | libc::O_CLOEXEC, 0o666))?;
+  // SAFETY: open succeeded, so fd is valid and owned by nobody else.
+  Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn write_file(path: &CStr, data: &[u8], durable: bool) -> io::Result<()> {
+  let fd = open(path, libc::O_CREAT | libc::O_TRUNC | libc::O_WRONLY)?;
+  write_all(&fd, data)?;
+  if durable {
+    syscall!(fsync(fd.as_raw_fd()))?;
+  }
+  Ok(())
+}
+
+/// Writes and syncs a temporary file next to `path`, then renames it over
+/// `path`, so readers see either the old contents or the new, never a mix.
+fn write_file_atomic(path: &CStr, data: &[u8]) -> io::Result<()> {
+  let (tmp, fd) = create_tmp(path)?;
+  let written = write_all(&fd, data)
+    .and_then(|()| syscall!(fsync(fd.as_raw_fd())))
+    .and_then(|_| syscall!(rename(tmp.as_ptr(), path.as_ptr())));
+  drop(fd);
+  if let Err(err) = written {
+    let _ = syscall!(unlink(tmp.as_ptr()));
+    return Err(err);
+  }
+
+  // Sync the directory too, or the rename itself may not survive a crash.
+  let dir = open(&parent(path), libc::O_RDONLY | libc::O_DIRECTORY)?;
+  syscall!(fsync(dir.as_raw_fd()))?;
+  Ok(())
+}
+
+/// Writes `data` to a temporary file next to `path`, syncs it, renames it
+/// over `path` and syncs the directory, see
+/// [`replace_file_durable`](crate::api::replace_file_durable).
+///
+/// Only creating the temporary file goes through the blocking pool, as
+/// `IORING_OP_OPENAT` can't be given a mode.
+pub(crate) async fn replace_file_durable(
+  path: CString,
+  mut data: Vec<u8>,
+  lio: &Lio,
+) -> io::Result<()> {
+  let create = path.clone();
+  let (tmp, file) =
+    crate::spawn_blocking(move || create_tmp(&create)).with_lio(lio).await??;
+  // SAFETY: open succeeded, the resource takes over the only owner of fd.
+  let file = unsafe { Resource::from_raw_fd(file.into_raw_fd()) };
+  let cwd = Resource::cwd();
+
+  let replaced = async {
+    let mut offset = 0;
+    while !data.is_empty() {
+      let (written, buf) =
+        api::write_at(&file, data, offset).with_lio(lio).await;
+      data = buf;
+      match written {
+        Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+        Ok(n) => {
+          data.drain(..n as usize);
+          offset += n as i64;
+        }
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+        Err(err) => return Err(err),
+      }
+    }
+    api::fsync(&file).with_lio(lio).await?;
+    api::renameat(&cwd, tmp.clone(), &cwd, path.clone()).with_lio(lio).await
+  }
+  .await;
+  drop(file);
+  if let Err(err) = replaced {
+    let _ = syscall!(unlink(tmp.as_ptr()));
+    return Err(err);
+  }
+
+  // Sync the directory too, or the rename itself may not survive a crash.
+  let dir =
+    api::openat(&cwd, parent(&path), libc::O_RDONLY | libc::O_DIRECTORY)
+      .with_lio(lio)
+      .await?;
+  api::fsync(&dir).with_lio(lio).await
+}
+
+/// Creates the temporary file [`write_file_atomic`] and
+/// [`replace_file_durable`] write before renaming over `path`.
+///
+/// `O_EXCL` makes sure a concurrent writer, or a file left behind by a crash,
+/// is never truncated or shared; a taken name is retried with the next one.
+fn create_tmp(path: &CStr) -> io::Result<(CString, OwnedFd)> {
+  static NEXT: AtomicU64 = AtomicU64::new(0);
+  loop {
+    let mut tmp = path.to_bytes().to_vec();
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    tmp.extend_from_slice(format!(".tmp.{}.{n}", process::id()).as_bytes());
+    let tmp = CString::new(tmp).expect("path had no NUL bytes");
+    match open(&tmp, libc::O_CREAT | libc::O_EXCL | libc::O_WRONLY) {
+      Ok(fd) => return Ok((tmp, fd)),
+      Err(err) if err.raw_os_error() == Some(libc::EEXIST) => {}
+      Err(err) => return Err(err),
+    }
+  }
+}
+
+fn parent(path: &CStr) -> CString {
+  let bytes = path.to_bytes();
+  let dir = match bytes.iter().rposition(|&b| b == b'/') {
+    Some(0) => &b"/"[..],
+    Some(i) => &bytes[..i],
+    None => &b"."[..],
+  };
+  CString::new(dir).expect("path had no NUL bytes")
+}
+
+fn write_all(fd: &OwnedFd, mut data: &[u8]) -> io::Result<()> {
+  while !data.is_empty() {
+    // data has data.len() readable bytes.
+    match syscall!(write(fd.as_raw_fd(), data.as_ptr().cast(), data.len())) {
+      Ok(n) => data = &data[n as usize..],
+      Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+      Err(err) => return Err(err),
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,105 @@
+use std::os::fd::RawFd;
+
+use crate::{
+  BufResult,
+  api::resource::Resource,
+  buf::BufLike,
+  typed_op::{DetachSafe, TypedOp},
+};
+
+/// Most buffers a single `writev(2)` accepts.
+pub(crate) const IOV_MAX: usize = libc::IOV_MAX as usize;
+
+pub(crate) struct IoVecs(pub(crate) Vec<libc::iovec>);
+
+// SAFETY: The iovecs only point into buffers the same op keeps alive, which
+// are Send + Sync.
+unsafe impl Send for IoVecs {}
+// SAFETY: A shared IoVecs only reads the iovec structs, which are plain
+// data, never the memory they point to. That is written by the kernel only
+// while the driver holds the op and nothing else uses it, and otherwise
+// reached through the op's own buffers, which are Sync.
+unsafe impl Sync for IoVecs {}
+
+pub struct Writev<B>
+where
+  B: Send + Sync,
+{
+  res: Resource,
+  bufs: Option<Vec<B>>,
+  iovecs: IoVecs,
+}
+
+assert_op_max_size!(Writev<Vec<u8>>);
+
+impl<B> Writev<B>
+where
+  B: Send + Sync,
+{
+  pub(crate) fn new(res: Resource, bufs: Vec<B>) -> Self {
+    Self { res, bufs: Some(bufs), iovecs: IoVecs(Vec::new()) }
+  }
+}
+
+impl<B> TypedOp for Writev<B>
+where
+  B: BufLike + Send + Sync + 'static,
+{
+  type Result = BufResult<usize, Vec<B>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    let bufs = self.bufs.as_ref().expect("buffers not available");
+    self.iovecs.0 = bufs
+      .iter()
+      .map(|buf| {
+        let slice = buf.buf();
+        libc::iovec { iov_base: slice.as_ptr() as *mut _, iov_len: slice.len() }
+      })
+      .collect();
+    crate::op::Op::Writev {
+      fd: self.res.clone(),
+      iov: self.iovecs.0.as_ptr(),
+      iovcnt: self.iovecs.0.len(),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let bufs = self.bufs.expect("buffers not available");
+    if res < 0 {
+      (Err(std::io::Error::from_raw_os_error((-res) as i32)), bufs)
+    } else {
+      (Ok(res as usize), bufs)
+    }
+  }
+}
+
+impl<B> DetachSafe for Writev<B> where B: BufLike + Send + Sync + 'static {}
+
+/// Writes `iov` with as many `writev(2)` calls as `IOV_MAX` requires.
+///
+/// Stops early on a short write, like a single `writev` would, and returns
+/// the total written. An error after some bytes went out is dropped in favour
+/// of that count, the next write will report it again.
+///
+/// # Safety
+///
+/// `fd` must be valid and every iovec must point to readable memory.
+pub(crate) unsafe fn writev_chunked(fd: RawFd, iov: &[libc::iovec]) -> isize {
+  let mut total = 0isize;
+  for chunk in iov.chunks(IOV_MAX) {
+    // SAFETY: Upheld by the caller, and the chunk is at most IOV_MAX long.
+    let ret =
+      unsafe { libc::writev(fd, chunk.as_ptr(), chunk.len() as libc::c_int) };
+    if ret < 0 {
+      let errno =
+        std::io::Error::last_os_error().raw_os_error().unwrap_or(libc::EIO);
+      return if total > 0 { total } else { -(errno as isize) };
+    }
+    total += ret as isize;
+    let wanted: usize = chunk.iter().map(|v| v.iov_len).sum();
+    if (ret as usize) < wanted {
+      break;
+    }
+  }
+  total
+}
@@ -0,0 +1,171 @@
+use std::{ffi::CString, io};
+
+use crate::{BufResult, api::resource::Resource, typed_op::TypedOp};
+
+/// Completes a get with the value length, the buffer cut down to it.
+fn get_result(res: isize, mut value: Vec<u8>) -> BufResult<usize, Vec<u8>> {
+  if res < 0 {
+    return (Err(io::Error::from_raw_os_error((-res) as i32)), value);
+  }
+  value.truncate(res as usize);
+  (Ok(res as usize), value)
+}
+
+fn set_result(res: isize, value: Vec<u8>) -> BufResult<(), Vec<u8>> {
+  if res < 0 {
+    (Err(io::Error::from_raw_os_error((-res) as i32)), value)
+  } else {
+    (Ok(()), value)
+  }
+}
+
+/// Values are at most 64 KiB, longer ones fail with `E2BIG` all the same.
+fn value_len(value: &[u8]) -> u32 {
+  u32::try_from(value.len()).unwrap_or(u32::MAX)
+}
+
+/// Reads an extended attribute of a path, see
+/// [`getxattr`](crate::api::getxattr).
+pub struct GetXattr {
+  path: CString,
+  name: CString,
+  value: Vec<u8>,
+}
+
+assert_op_max_size!(GetXattr);
+
+impl GetXattr {
+  pub(crate) fn new(path: CString, name: CString, value: Vec<u8>) -> Self {
+    Self { path, name, value }
+  }
+}
+
+impl TypedOp for GetXattr {
+  type Result = BufResult<usize, Vec<u8>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::GetXattr {
+      path: self.path.as_ptr(),
+      name: self.name.as_ptr(),
+      value: self.value.as_mut_ptr().cast(),
+      len: value_len(&self.value),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    get_result(res, self.value)
+  }
+}
+
+/// Sets an extended attribute of a path, see
+/// [`setxattr`](crate::api::setxattr).
+pub struct SetXattr {
+  path: CString,
+  name: CString,
+  value: Vec<u8>,
+  flags: i32,
+}
+
+assert_op_max_size!(SetXattr, test_set_xattr_size);
+
+impl SetXattr {
+  pub(crate) fn new(
+    path: CString,
+    name: CString,
+    value: Vec<u8>,
+    flags: i32,
+  ) -> Self {
+    Self { path, name, value, flags }
+  }
+}
+
+impl TypedOp for SetXattr {
+  type Result = BufResult<(), Vec<u8>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::SetXattr {
+      path: self.path.as_ptr(),
+      name: self.name.as_ptr(),
+      value: self.value.as_ptr().cast(),
+      len: value_len(&self.value),
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    set_result(res, self.value)
+  }
+}
+
+/// Reads an extended attribute of an open file, see
+/// [`fgetxattr`](crate::api::fgetxattr).
+pub struct FGetXattr {
+  res: Resource,
+  name: CString,
+  value: Vec<u8>,
+}
+
+assert_op_max_size!(FGetXattr, test_fget_xattr_size);
+
+impl FGetXattr {
+  pub(crate) fn new(res: Resource, name: CString, value: Vec<u8>) -> Self {
+    Self { res, name, value }
+  }
+}
+
+impl TypedOp for FGetXattr {
+  type Result = BufResult<usize, Vec<u8>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::FGetXattr {
+      fd: self.res.clone(),
+      name: self.name.as_ptr(),
+      value: self.value.as_mut_ptr().cast(),
+      len: value_len(&self.value),
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    get_result(res, self.value)
+  }
+}
+
+/// Sets an extended attribute of an open file, see
+/// [`fsetxattr`](crate::api::fsetxattr).
+pub struct FSetXattr {
+  res: Resource,
+  name: CString,
+  value: Vec<u8>,
+  flags: i32,
+}
+
+assert_op_max_size!(FSetXattr, test_fset_xattr_size);
+
+impl FSetXattr {
+  pub(crate) fn new(
+    res: Resource,
+    name: CString,
+    value: Vec<u8>,
+    flags: i32,
+  ) -> Self {
+    Self { res, name, value, flags }
+  }
+}
+
+impl TypedOp for FSetXattr {
+  type Result = BufResult<(), Vec<u8>>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    crate::op::Op::FSetXattr {
+      fd: self.res.clone(),
+      name: self.name.as_ptr(),
+      value: self.value.as_ptr().cast(),
+      len: value_len(&self.value),
+      flags: self.flags,
+    }
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    set_result(res, self.value)
+  }
+}
@@ -57,6 +57,17 @@
 //! }
 //! ```
 //!
+//! Or from an [`OwnedFd`](std::os::fd::OwnedFd), without `unsafe`:
+//!
+//! ```rust
+//! use std::{fs::File, os::fd::OwnedFd};
+//! use lio::api::resource::Resource;
+//!
+//! fn from_file(file: File) -> Resource {
+//!     Resource::from(OwnedFd::from(file))
+//! }
+//! ```
+//!
 //! Or from raw file descriptors (Unix):
 //!
 //! ```rust
@@ -71,6 +82,9 @@
 
 use std::sync::Arc;
 
+#[cfg(windows)]
+use std::os::windows::io::RawHandle;
+
 macro_rules! impl_native_convervions {
   ($nice:ident) => {
     #[cfg(unix)]
@@ -91,6 +105,7 @@ macro_rules! impl_native_convervions {
     #[cfg(windows)]
     impl std::os::windows::io::AsHandle for $nice {
       fn as_handle(&self) -> std::os::windows::io::BorrowedHandle<'_> {
+        // SAFETY: Owned guarrantees it's valid.
         unsafe {
           std::os::windows::io::BorrowedHandle::borrow_raw(self.0.inner)
         }
@@ -140,7 +155,11 @@ impl Drop for Owned {
   /// unimplemented and will panic if enabled.
   fn drop(&mut self) {
     // if self.should_close.load(Ordering::Acquire) {
+    #[cfg(unix)]
     let _ = syscall!(close(self.inner));
+    // SAFETY: Owned is the last reference to the handle.
+    #[cfg(windows)]
+    let _ = unsafe { windows_sys::Win32::Foundation::CloseHandle(self.inner) };
     // let op = api::close(UniqueResource(Owned {
     //   inner: self.inner,
     //   should_close: AtomicBool::new(true),
@@ -152,6 +171,14 @@ impl Drop for Owned {
   }
 }
 
+// SAFETY: A HANDLE is an opaque kernel object identifier, usable from any
+// thread like a file descriptor.
+#[cfg(windows)]
+unsafe impl Send for Owned {}
+// SAFETY: Owned never mutates the handle.
+#[cfg(windows)]
+unsafe impl Sync for Owned {}
+
 /// A reference-counted, platform-independent wrapper around OS I/O resources.
 ///
 /// See the [module documentation](self) for usage examples and details.
@@ -281,6 +308,26 @@ impl std::os::windows::io::FromRawHandle for Resource {
   }
 }
 
+#[cfg(unix)]
+impl From<std::os::fd::OwnedFd> for Resource {
+  fn from(fd: std::os::fd::OwnedFd) -> Self {
+    use std::os::fd::{FromRawFd, IntoRawFd};
+    // SAFETY: The fd comes from an OwnedFd, so it's open and nobody else
+    // closes it.
+    unsafe { Resource::from_raw_fd(fd.into_raw_fd()) }
+  }
+}
+
+#[cfg(windows)]
+impl From<std::os::windows::io::OwnedHandle> for Resource {
+  fn from(handle: std::os::windows::io::OwnedHandle) -> Self {
+    use std::os::windows::io::{FromRawHandle, IntoRawHandle};
+    // SAFETY: The handle comes from an OwnedHandle, so it's open and nobody
+    // else closes it.
+    unsafe { Resource::from_raw_handle(handle.into_raw_handle()) }
+  }
+}
+
 impl Resource {
   /// Returns a `Resource` for standard output (stdout).
   ///
@@ -323,7 +370,8 @@ impl Resource {
     let handle = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) };
     assert!(handle != INVALID_HANDLE_VALUE, "Failed to get stdout handle");
 
-    let mut dup_handle: HANDLE = 0;
+    let mut dup_handle: HANDLE = std::ptr::null_mut();
+    // SAFETY: Returns a pseudo handle, always valid.
     let current_process = unsafe { GetCurrentProcess() };
 
     // SAFETY: All handles are valid, dup_handle is a valid out pointer
@@ -342,9 +390,7 @@ impl Resource {
 
     // SAFETY: dup_handle is valid, just returned from DuplicateHandle
     unsafe {
-      <Self as std::os::windows::io::FromRawHandle>::from_raw_handle(
-        dup_handle as *mut std::ffi::c_void,
-      )
+      <Self as std::os::windows::io::FromRawHandle>::from_raw_handle(dup_handle)
     }
   }
 
@@ -387,7 +433,8 @@ impl Resource {
     let handle = unsafe { GetStdHandle(STD_ERROR_HANDLE) };
     assert!(handle != INVALID_HANDLE_VALUE, "Failed to get stderr handle");
 
-    let mut dup_handle: HANDLE = 0;
+    let mut dup_handle: HANDLE = std::ptr::null_mut();
+    // SAFETY: Returns a pseudo handle, always valid.
     let current_process = unsafe { GetCurrentProcess() };
 
     // SAFETY: All handles are valid, dup_handle is a valid out pointer
@@ -406,12 +453,19 @@ impl Resource {
 
     // SAFETY: dup_handle is valid, just returned from DuplicateHandle
     unsafe {
-      <Self as std::os::windows::io::FromRawHandle>::from_raw_handle(
-        dup_handle as *mut std::ffi::c_void,
-      )
+      <Self as std::os::windows::io::FromRawHandle>::from_raw_handle(dup_handle)
     }
   }
 
+  /// A `Resource` for `AT_FDCWD`, making `*at` operations resolve relative
+  /// paths against the working directory.
+  #[cfg(unix)]
+  pub(crate) fn cwd() -> Self {
+    // SAFETY: AT_FDCWD is not a real descriptor, closing it once the last
+    // clone is dropped fails with EBADF, which is ignored.
+    unsafe { <Self as std::os::fd::FromRawFd>::from_raw_fd(libc::AT_FDCWD) }
+  }
+
   /// Returns a `Resource` for standard input (stdin).
   ///
   /// This creates a duplicate of the stdin file descriptor, so the returned
@@ -451,7 +505,8 @@ impl Resource {
     let handle = unsafe { GetStdHandle(STD_INPUT_HANDLE) };
     assert!(handle != INVALID_HANDLE_VALUE, "Failed to get stdin handle");
 
-    let mut dup_handle: HANDLE = 0;
+    let mut dup_handle: HANDLE = std::ptr::null_mut();
+    // SAFETY: Returns a pseudo handle, always valid.
     let current_process = unsafe { GetCurrentProcess() };
 
     // SAFETY: All handles are valid, dup_handle is a valid out pointer
@@ -470,9 +525,7 @@ impl Resource {
 
     // SAFETY: dup_handle is valid, just returned from DuplicateHandle
     unsafe {
-      <Self as std::os::windows::io::FromRawHandle>::from_raw_handle(
-        dup_handle as *mut std::ffi::c_void,
-      )
+      <Self as std::os::windows::io::FromRawHandle>::from_raw_handle(dup_handle)
     }
   }
 
@@ -543,6 +596,22 @@ impl std::fmt::Debug for Resource {
   }
 }
 
+/// Resources are equal when they wrap the same file descriptor/handle, even if
+/// they aren't clones of each other.
+impl PartialEq for Resource {
+  fn eq(&self, other: &Self) -> bool {
+    self.0.inner == other.0.inner
+  }
+}
+
+impl Eq for Resource {}
+
+impl std::hash::Hash for Resource {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.0.inner.hash(state);
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -607,4 +676,46 @@ mod tests {
     // stdout2 should still be valid
     assert_eq!(stdout2.count(), 1);
   }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_eq_and_hash_by_fd() {
+    use std::hash::{BuildHasher, RandomState};
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    let stdout = Resource::stdout();
+    // SAFETY: Wraps a live fd, forgotten below so it isn't closed twice.
+    let same_fd = unsafe { Resource::from_raw_fd(stdout.as_raw_fd()) };
+    let other = Resource::stdout();
+
+    let hasher = RandomState::new();
+    assert_eq!(stdout, same_fd);
+    assert_eq!(hasher.hash_one(&stdout), hasher.hash_one(&same_fd));
+    assert_eq!(stdout, stdout.clone());
+    assert_ne!(stdout, other);
+
+    std::mem::forget(same_fd);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_from_owned_fd_closes_with_last_clone() {
+    use std::{io::Read, os::fd::OwnedFd};
+
+    let (mut reader, writer) = std::io::pipe().unwrap();
+    let resource = Resource::from(OwnedFd::from(writer));
+    let clone = resource.clone();
+
+    drop(resource);
+    // SAFETY: clone keeps the write end open.
+    let written =
+      unsafe { libc::write(clone.0.inner, b"x".as_ptr().cast(), 1) };
+    assert_eq!(written, 1);
+
+    // Closing the last write end lets the reader see EOF.
+    drop(clone);
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).unwrap();
+    assert_eq!(out, b"x");
+  }
 }
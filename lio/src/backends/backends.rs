@@ -105,6 +105,9 @@ pub struct OpCompleted {
   /// - `>= 0` on success (the return value, e.g., bytes transferred)
   /// - `< 0` on error (negative errno value)
   pub(crate) result: isize,
+
+  /// Whether a multishot operation will complete again.
+  pub(crate) more: bool,
 }
 
 impl OpCompleted {
@@ -115,7 +118,13 @@ impl OpCompleted {
   /// - `op_id`: The unique ID of the operation
   /// - `result`: The operation result (non-negative for success, negative errno for error)
   pub fn new(op_id: u64, result: isize) -> Self {
-    Self { op_id, result }
+    Self { op_id, result, more: false }
+  }
+
+  /// Creates an intermediate result of a multishot operation, which stays
+  /// in flight.
+  pub fn new_more(op_id: u64, result: isize) -> Self {
+    Self { op_id, result, more: true }
   }
 }
 
@@ -166,6 +175,48 @@ pub trait IoBackend {
   /// - [`SubmitErr::Full`]: Submission queue is full (call flush first)
   fn push(&mut self, id: u64, op: Op) -> io::Result<()>;
 
+  /// Like [`push`](Self::push), but the operation completes with
+  /// `-ETIMEDOUT` if it hasn't finished within `timeout`.
+  ///
+  /// Operations that complete during the push itself ignore the timeout.
+  ///
+  /// # Errors
+  ///
+  /// Same as [`push`](Self::push). Backends without timeout support return
+  /// [`io::ErrorKind::Unsupported`].
+  fn push_with_timeout(
+    &mut self,
+    id: u64,
+    op: Op,
+    timeout: Duration,
+  ) -> io::Result<()> {
+    let _ = (id, op, timeout);
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+  }
+
+  /// Stops the in-flight operation `id`.
+  ///
+  /// The operation still completes one last time: with `-ECANCELED`, or with
+  /// its real result if it finished first. Cancelling an operation that
+  /// already completed does nothing.
+  ///
+  /// # Errors
+  ///
+  /// Backends without cancellation support return
+  /// [`io::ErrorKind::Unsupported`].
+  fn cancel(&mut self, id: u64) -> io::Result<()> {
+    let _ = id;
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+  }
+
+  /// Returns `true` if [`push`](Self::push) would fail right now because the
+  /// submission queue has no free slots.
+  ///
+  /// Backends without a bounded submission queue never report being full.
+  fn is_full(&self) -> bool {
+    false
+  }
+
   /// Flushes all queued operations to the kernel.
   ///
   /// This submits all operations queued via [`push`](Self::push) in a single syscall.
@@ -1,28 +1,121 @@
 //! `lio`-provided [`IoBackend`] impl for `io_uring`.
 
 use lio_uring::{
-  Entry, LioUring,
+  Completion, Entry, LioUring, Params, Probe, SqeFlags,
   operation::{
-    self, Accept, Bind, Close, Connect, Fsync, Ftruncate, LinkAt, Listen,
-    OpenAt, Read, Recv, Send, Shutdown, Socket, SymlinkAt, Tee, Timeout, Write,
+    self, Accept, AcceptMulti, AsyncCancel, Bind, Close, CloseFixed, Connect,
+    EpollCtl, FGetXattr, FSetXattr, Fadvise, Fallocate, FixedFdInstall, Fsync,
+    Ftruncate, FutexWait, FutexWake, GetXattr, LinkAt, LinkTimeout, Listen,
+    Madvise, MkDirAt, OpenAt, Pipe, PollAdd, ProvideBuffers, Read, ReadFixed,
+    ReadMulti, Readv, Recv, RecvMsg, RemoveBuffers, RenameAt, Send, SendMsg,
+    SendZc, SetXattr, Shutdown, Socket, Splice, Statx, SymlinkAt,
+    SyncFileRange, Tee, Timeout, UnlinkAt, UringCmd16, WaitId, Write, Writev,
   },
 };
 
 use crate::{
-  backends::{IoBackend, OpCompleted},
+  api::ops::{
+    IOV_MAX, SpawnBlocking, WaitInfo, encode_buffer_id, pipe_blocking,
+  },
+  backends::{IoBackend, OpCompleted, pollingv2::Poller},
+  futex::FutexWord,
   op::{Op, RawBuf},
+  typed_op::TypedOp,
 };
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
-use std::os::fd::AsRawFd;
+use std::os::fd::{AsRawFd, RawFd};
 use std::time::Duration;
 
+/// `user_data` of linked timeout SQEs, their completions are dropped.
+const LINK_TIMEOUT_KEY: u64 = u64::MAX - 1;
+
+/// `user_data` of cancel SQEs, their completions are dropped.
+const CANCEL_KEY: u64 = u64::MAX - 2;
+
+/// `cmd_op` of the socket `URING_CMD` reading the receive queue length.
+const SOCKET_URING_OP_SIOCINQ: u32 = 0;
+
+/// `futex2(2)` flags of [`AsyncFutex`](crate::AsyncFutex) words. Unlike the
+/// `futex(2)` interface, the size is explicit: `FUTEX2_SIZE_U32`, and
+/// `FUTEX2_PRIVATE` (the same bit as `FUTEX_PRIVATE_FLAG`) since the word
+/// is never shared with another process.
+const FUTEX2_FLAGS: u32 = 0x02 | 128;
+
+/// `FUTEX_BITSET_MATCH_ANY`, truncated to the 32 futex bits.
+const FUTEX_MATCH_ANY: u64 = u32::MAX as u64;
+
+/// Slots in the fixed file table, registered with the first op using it.
+const FIXED_FILES: u32 = 4096;
+
+fn op_completed(cqe: &Completion) -> OpCompleted {
+  let mut result = cqe.result() as isize;
+  // BUFFER_SELECT ops only learn which buffer the kernel picked from the
+  // flags, so it travels along in the result.
+  #[cfg(target_pointer_width = "64")]
+  if let Some(bid) = cqe.buffer_id()
+    && result >= 0
+  {
+    result |= encode_buffer_id(bid);
+  }
+  if cqe.has_more() {
+    OpCompleted::new_more(cqe.user_data(), result)
+  } else {
+    OpCompleted::new(cqe.user_data(), result)
+  }
+}
+
+/// Completes ops that need no syscall right away, without the ring.
+///
+/// Returns `None` if the op should be submitted to the ring instead.
+fn run_without_ring(op: &Op) -> Option<isize> {
+  match op {
+    // The result has no room for the selected buffer's id.
+    #[cfg(not(target_pointer_width = "64"))]
+    Op::ProvideBuffers { .. }
+    | Op::RecvSelect { .. }
+    | Op::ReadSelect { .. } => Some(-(libc::EOPNOTSUPP as isize)),
+    // An empty list completes right away with 0, as on the polling backends.
+    Op::Readv { iovcnt: 0, .. } => Some(0),
+    _ => None,
+  }
+}
+
+/// Whether `op` can't go to the ring and runs as its blocking syscall on
+/// the pool, see [`PooledOp`].
+///
+/// That is the ops without an io_uring opcode, so they don't block the
+/// loop thread. io_uring also rejects more than `IOV_MAX` iovecs, so longer
+/// reads are split up by `readv_chunked` there. Longer writes are split
+/// into linked SQEs instead, see `push_writev_chain`.
+fn runs_on_pool(op: &Op) -> bool {
+  match op {
+    Op::Dup { .. }
+    | Op::Dup2 { .. }
+    | Op::Futimens { .. }
+    | Op::UtimensAt { .. } => true,
+    Op::Readv { iovcnt, .. } => *iovcnt > IOV_MAX,
+    _ => false,
+  }
+}
+
 fn create_io_uring_entry(op: &Op) -> Entry {
   match op {
     Op::Nop => operation::Nop::new().build(),
-    Op::Read { fd, buffer } => {
+    Op::Custom { op } => {
+      // SAFETY: op points into the boxed Custom TypedOp, which outlives the op.
+      let op = unsafe { &**op };
+      op.create_entry()
+    }
+    Op::Read { fd, buffer, buf_index } => {
       // SAFETY: OpBuf stores RawBuf set by into_op
       let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
-      Read::new(fd.as_raw_fd(), ptr, len as u32).build()
+      match buf_index {
+        Some(index) => {
+          ReadFixed::new(fd.as_raw_fd(), ptr, len as u32, *index).build()
+        }
+        None => Read::new(fd.as_raw_fd(), ptr, len as u32).build(),
+      }
     }
     Op::Write { fd, buffer } => {
       // SAFETY: OpBuf stores (ptr, len) tuple set by into_op
@@ -44,15 +137,80 @@ fn create_io_uring_entry(op: &Op) -> Entry {
       let (ptr, len) = unsafe { buffer.peek::<(*const u8, usize)>() };
       Send::new(fd.as_raw_fd(), ptr, len as u32).flags(*flags).build()
     }
-    Op::Recv { fd, flags, buffer } => {
+    Op::SendZc { fd, flags, buffer, buf_index } => {
       // SAFETY: OpBuf stores RawBuf set by into_op
       let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
-      Recv::new(fd.as_raw_fd(), ptr, len as u32).flags(*flags).build()
+      SendZc::new(fd.as_raw_fd(), ptr, len as u32)
+        .buf_index(*buf_index)
+        .flags(*flags)
+        .build()
+    }
+    Op::Recv { fd, flags, buffer, buf_index } => {
+      // SAFETY: OpBuf stores RawBuf set by into_op
+      let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
+      match buf_index {
+        // There is no fixed buffer recv, but without flags a read is the
+        // same thing.
+        Some(index) if *flags == 0 => {
+          ReadFixed::new(fd.as_raw_fd(), ptr, len as u32, *index).build()
+        }
+        _ => Recv::new(fd.as_raw_fd(), ptr, len as u32).flags(*flags).build(),
+      }
     }
+    Op::RecvSelect { fd, len, bgid, flags } => {
+      Recv::new(fd.as_raw_fd(), std::ptr::null_mut(), *len)
+        .flags(*flags)
+        .buf_group(*bgid)
+        .build()
+        .flags(SqeFlags::BUFFER_SELECT)
+    }
+    // Both read at, and advance, the file position.
+    Op::ReadSelect { fd, len, bgid, multishot: true } => {
+      ReadMulti::new(fd.as_raw_fd(), *len, *bgid).offset(u64::MAX).build()
+    }
+    Op::ReadSelect { fd, len, bgid, multishot: false } => {
+      Read::new(fd.as_raw_fd(), std::ptr::null_mut(), *len)
+        .offset(u64::MAX)
+        .buf_group(*bgid)
+        .build()
+        .flags(SqeFlags::BUFFER_SELECT)
+    }
+    Op::ProvideBuffers { addr, len, nbufs, bgid, bid, .. } => {
+      ProvideBuffers::new(*addr, *len as i32, *nbufs, *bgid, *bid).build()
+    }
+    Op::RemoveBuffers { nbufs, bgid } => {
+      RemoveBuffers::new(*nbufs, *bgid).build()
+    }
+    Op::SendMsg { fd, msg } => SendMsg::new(fd.as_raw_fd(), *msg).build(),
+    Op::RecvMsg { fd, msg } => RecvMsg::new(fd.as_raw_fd(), *msg).build(),
     Op::Accept { fd, addr, len } => {
       // Cast sockaddr_storage* to sockaddr*
       Accept::new(fd.as_raw_fd(), (*addr) as *mut libc::sockaddr, *len).build()
     }
+    Op::AcceptMultishot { fd } => AcceptMulti::new(fd.as_raw_fd()).build(),
+    Op::AcceptDirect { fd, addr, len, .. } => {
+      Accept::new(fd.as_raw_fd(), (*addr) as *mut libc::sockaddr, *len)
+        .allocate_file_index(true)
+        .build()
+    }
+    Op::RecvFixed { slot, flags, buffer, .. } => {
+      // SAFETY: OpBuf stores RawBuf set by into_op
+      let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
+      Recv::new(*slot as i32, ptr, len as u32)
+        .flags(*flags)
+        .build()
+        .flags(SqeFlags::FIXED_FILE)
+    }
+    Op::SendFixed { slot, flags, buffer, .. } => {
+      // SAFETY: OpBuf stores RawBuf set by into_op
+      let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
+      Send::new(*slot as i32, ptr, len as u32)
+        .flags(*flags)
+        .build()
+        .flags(SqeFlags::FIXED_FILE)
+    }
+    Op::InstallFixed { slot, .. } => FixedFdInstall::new(*slot).build(),
+    Op::CloseFixed { slot, .. } => CloseFixed::new(*slot).build(),
     Op::Connect { fd, addr, len, .. } => {
       Connect::new(fd.as_raw_fd(), (*addr) as *const libc::sockaddr, *len)
         .build()
@@ -66,6 +224,25 @@ fn create_io_uring_entry(op: &Op) -> Entry {
     Op::Socket { domain, ty, proto } => {
       Socket::new(*domain, *ty, *proto).build()
     }
+    Op::Poll { fd, events } => {
+      PollAdd::new(fd.as_raw_fd(), *events as u16 as u32).build()
+    }
+    Op::PollMultishot { fd, events } => {
+      PollAdd::new(fd.as_raw_fd(), *events as u16 as u32).multi(true).build()
+    }
+    Op::PendingBytes { fd } => {
+      UringCmd16::new(fd.as_raw_fd(), SOCKET_URING_OP_SIOCINQ).build()
+    }
+    Op::Readv { fd, iov, iovcnt } => {
+      Readv::new(fd.as_raw_fd(), *iov, *iovcnt as u32)
+        .offset(-1i64 as u64)
+        .build()
+    }
+    Op::Writev { fd, iov, iovcnt } => {
+      Writev::new(fd.as_raw_fd(), *iov, *iovcnt as u32)
+        .offset(-1i64 as u64)
+        .build()
+    }
     Op::OpenAt { dir_fd, path, flags } => {
       OpenAt::new(dir_fd.as_raw_fd(), *path).flags(*flags).build()
     }
@@ -75,6 +252,23 @@ fn create_io_uring_entry(op: &Op) -> Entry {
     // actually truncate in some tests. Needs investigation - might be SQE
     // setup issue or kernel-specific behavior.
     Op::Truncate { fd, size } => Ftruncate::new(fd.as_raw_fd(), *size).build(),
+    Op::Fadvise { fd, offset, len, advice } => {
+      Fadvise::new(fd.as_raw_fd(), *len as libc::off_t, *advice)
+        .offset(*offset)
+        .build()
+    }
+    Op::Fallocate { fd, offset, len, mode } => {
+      Fallocate::new(fd.as_raw_fd(), *len).offset(*offset).mode(*mode).build()
+    }
+    Op::SyncFileRange { fd, offset, len, flags } => {
+      SyncFileRange::new(fd.as_raw_fd(), *len)
+        .offset(*offset)
+        .flags(*flags)
+        .build()
+    }
+    Op::Madvise { addr, len, advice } => {
+      Madvise::new(addr.cast(), *len as libc::off_t, *advice).build()
+    }
     Op::LinkAt { old_dir_fd, old_path, new_dir_fd, new_path } => LinkAt::new(
       old_dir_fd.as_raw_fd(),
       *old_path,
@@ -82,18 +276,109 @@ fn create_io_uring_entry(op: &Op) -> Entry {
       *new_path,
     )
     .build(),
+    Op::FutexWait { word, expected, .. } => FutexWait::new(
+      word.as_ptr(),
+      *expected as u64,
+      FUTEX_MATCH_ANY,
+      FUTEX2_FLAGS,
+    )
+    .build(),
+    Op::FutexWake { word, count } => FutexWake::new(
+      word.as_ptr(),
+      *count as u64,
+      FUTEX_MATCH_ANY,
+      FUTEX2_FLAGS,
+    )
+    .build(),
+    Op::Pipe { fds, flags } => {
+      Pipe::new(fds.cast()).flags(*flags as u32).build()
+    }
+    Op::WaitId { idtype, id, options, info } => {
+      WaitId::new(*idtype, *id, *options).infop(info.as_ptr()).build()
+    }
+    Op::Statx { dir_fd, path, flags, mask, buf } => {
+      Statx::new(dir_fd.as_raw_fd(), *path, *buf)
+        .flags(*flags)
+        .mask(*mask)
+        .build()
+    }
     Op::SymlinkAt { target, linkpath, dir_fd } => {
       SymlinkAt::new(dir_fd.as_raw_fd(), *target, *linkpath).build()
     }
+    Op::RenameAt { old_dir_fd, old_path, new_dir_fd, new_path, flags } => {
+      RenameAt::new(
+        old_dir_fd.as_raw_fd(),
+        *old_path,
+        new_dir_fd.as_raw_fd(),
+        *new_path,
+      )
+      .flags(*flags)
+      .build()
+    }
+    Op::MkDirAt { dir_fd, path, mode } => {
+      MkDirAt::new(dir_fd.as_raw_fd(), *path).mode(*mode).build()
+    }
+    Op::UnlinkAt { dir_fd, path, flags } => {
+      UnlinkAt::new(dir_fd.as_raw_fd(), *path).flags(*flags).build()
+    }
     #[cfg(target_os = "linux")]
+    Op::Splice { fd_in, off_in, fd_out, off_out, len, flags } => Splice::new(
+      fd_in.as_raw_fd(),
+      *off_in,
+      fd_out.as_raw_fd(),
+      *off_out,
+      *len,
+    )
+    .flags(*flags)
+    .build(),
+    Op::EpollCtl { epfd, fd, op, event } => {
+      EpollCtl::new(epfd.as_raw_fd(), fd.as_raw_fd(), *op, *event).build()
+    }
     Op::Tee { fd_in, fd_out, size } => {
       Tee::new(fd_in.as_raw_fd(), fd_out.as_raw_fd(), *size).build()
     }
+    Op::GetXattr { path, name, value, len } => {
+      GetXattr::new(*name, *value, *path, *len).build()
+    }
+    Op::SetXattr { path, name, value, len, flags } => {
+      SetXattr::new(*name, *value, *path, *len).flags(*flags).build()
+    }
+    Op::FGetXattr { fd, name, value, len } => {
+      FGetXattr::new(fd.as_raw_fd(), *name, *value, *len).build()
+    }
+    Op::FSetXattr { fd, name, value, len, flags } => {
+      FSetXattr::new(fd.as_raw_fd(), *name, *value, *len).flags(*flags).build()
+    }
     Op::Timeout { timespec, .. } => {
       // __kernel_timespec has same layout as libc::timespec
       // timespec is already a pointer to data in the boxed TypedOp
       Timeout::new(*timespec as *const _).build()
     }
+    Op::Dup { .. }
+    | Op::Dup2 { .. }
+    | Op::Futimens { .. }
+    | Op::UtimensAt { .. } => unreachable!("run on the pool by runs_on_pool"),
+    Op::RegisterBuffers { .. } => unreachable!("handled by register_buffers"),
+  }
+}
+
+/// Bitmap of the opcodes a kernel supports, taken from a [`Probe`].
+///
+/// Unlike the probe itself it can be sent along with the backend.
+#[derive(Clone, Copy)]
+struct Opcodes([u64; 4]);
+
+impl Opcodes {
+  fn from_probe(probe: &Probe) -> Self {
+    let mut bits = [0; 4];
+    for code in (0..=u8::MAX).filter(|&code| probe.is_supported(code)) {
+      bits[code as usize / 64] |= 1 << (code % 64);
+    }
+    Self(bits)
+  }
+
+  fn contains(&self, code: u8) -> bool {
+    self.0[code as usize / 64] & (1 << (code % 64)) != 0
   }
 }
 
@@ -102,6 +387,11 @@ fn create_io_uring_entry(op: &Op) -> Entry {
 /// This is the highest-performance backend, using Linux's io_uring interface
 /// for truly asynchronous I/O with minimal syscall overhead.
 ///
+/// The opcodes of the kernel are probed in [`init`](IoBackend::init). Ops
+/// it lacks run as their blocking syscall instead of failing with `EINVAL`:
+/// ones that return right away, like `bind`, on the calling thread, the
+/// rest on the blocking pool, see [`PooledOp`].
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -116,8 +406,97 @@ fn create_io_uring_entry(op: &Op) -> Entry {
 #[derive(Default)]
 pub struct IoUring {
   ring: Option<LioUring>,
+  /// Setup parameters from [`with_params`](Self::with_params).
+  params: Option<Params>,
+  /// Completions of ops that ran without the ring, returned on the next wait.
+  immediate: Vec<OpCompleted>,
   /// Reusable buffer for completed operations (avoids allocation per poll/wait).
   completed: Vec<OpCompleted>,
+  /// Timespecs of linked timeouts, by the id of the op they guard. The
+  /// kernel may read them until the op completes.
+  timeouts: HashMap<u64, Box<libc::timespec>>,
+  /// Ops with a linked timeout that were cancelled through
+  /// [`cancel`](IoBackend::cancel), their `-ECANCELED` isn't a timeout.
+  cancelled: HashSet<u64>,
+  /// Whether the fixed file table is registered.
+  fixed_files: bool,
+  /// Opcodes the kernel supports, `None` if it is too old to be probed
+  /// (before 5.6).
+  opcodes: Option<Opcodes>,
+  /// Zero-copy sends in flight, with their byte count once it is known.
+  /// They complete twice, the second time when the kernel lets go of the
+  /// buffer, and only that one is passed on.
+  zc_sends: HashMap<u64, Option<isize>>,
+  /// Vectored writes longer than `IOV_MAX` in flight, by op id.
+  writev_chains: HashMap<u64, WritevChain>,
+  /// Ops running on the blocking pool, by op id. The ring polls their job,
+  /// which holds the result.
+  pooled: HashMap<u64, SpawnBlocking<isize>>,
+}
+
+/// An op the kernel has no opcode for, sent to the blocking pool to run as
+/// its blocking syscall, the same way `waitid` is in
+/// [`fallback`](IoUring::fallback).
+struct PooledOp(Op);
+
+// SAFETY: The pointers in the op point into its TypedOp, which the driver
+// keeps alive until the op completes. A pooled op only completes once its
+// job has returned, and it can't be cancelled or time out before that.
+unsafe impl std::marker::Send for PooledOp {}
+
+impl PooledOp {
+  fn run(self) -> isize {
+    Poller::run_op_blocking(self.0)
+  }
+}
+
+/// Whether `op` returns right away when run as its blocking syscall, so it
+/// may run on the loop thread.
+fn is_instant(op: &Op) -> bool {
+  matches!(
+    op,
+    Op::Nop
+      | Op::Bind { .. }
+      | Op::Listen { .. }
+      | Op::Shutdown { .. }
+      | Op::Socket { .. }
+      | Op::Fadvise { .. }
+      | Op::Madvise { .. }
+      | Op::EpollCtl { .. }
+      | Op::FutexWake { .. }
+  )
+}
+
+/// A vectored write split into linked `Writev`s of at most `IOV_MAX`
+/// iovecs, which all complete under the id of the op.
+struct WritevChain {
+  /// Bytes asked for by the chunks that haven't completed, in order.
+  wanted: VecDeque<usize>,
+  /// The total so far, reported like `writev_chunked` does.
+  result: isize,
+  /// Set once a chunk failed or fell short, the kernel cancels the rest.
+  done: bool,
+}
+
+impl WritevChain {
+  /// Counts the completion of the next chunk, returning the result of the
+  /// op once every chunk completed.
+  fn complete(&mut self, res: isize) -> Option<isize> {
+    let wanted = self.wanted.pop_front().unwrap_or(0);
+    if !self.done {
+      if res < 0 {
+        // An error after some bytes went out is dropped in favour of them.
+        if self.result == 0 {
+          self.result = res;
+        }
+        self.done = true;
+      } else {
+        self.result += res;
+        self.done = (res as usize) < wanted;
+      }
+    }
+    self.wanted.is_empty().then_some(self.result)
+  }
 }
 
 impl IoUring {
@@ -128,11 +507,219 @@ impl IoUring {
     Self::default()
   }
 
+  /// Create a new uninitialized io_uring backend that sets up its ring with
+  /// `params`, such as [`Params::sqpoll`].
+  ///
+  /// `params.sq_entries` is replaced by the capacity given to
+  /// [`init`](IoBackend::init).
+  ///
+  /// With SQPOLL a kernel thread picks up submissions from the queue, so
+  /// submitting takes no `io_uring_enter` unless the thread went idle. Before
+  /// Linux 5.11 the kernel only grants it with `CAP_SYS_NICE` (or
+  /// `CAP_SYS_ADMIN`). If setting it up fails, `init` falls back to a ring
+  /// without it, see [`is_sqpoll`](Self::is_sqpoll).
+  pub fn with_params(params: Params) -> Self {
+    Self { params: Some(params), ..Self::default() }
+  }
+
+  /// Whether the ring runs with a SQPOLL thread.
+  pub fn is_sqpoll(&self) -> bool {
+    self.ring.as_ref().is_some_and(LioUring::is_sqpoll)
+  }
+
+  /// Whether the kernel supports `code`. Kernels too old to probe are too
+  /// old for any of the opcodes this is asked about.
+  fn has_op(&self, code: u8) -> bool {
+    self.opcodes.is_some_and(|opcodes| opcodes.contains(code))
+  }
+
+  /// Whether the kernel is known to lack the opcode of `entry`, in which
+  /// case its op runs as the blocking syscall instead.
+  fn lacks_op(&self, entry: &Entry) -> bool {
+    self.opcodes.is_some_and(|opcodes| !opcodes.contains(entry.opcode()))
+  }
+
+  /// Pushes a vectored write of more than `IOV_MAX` iovecs as linked
+  /// `Writev`s, so the chunks go out in order without blocking the loop.
+  /// With a `timeout`, a linked timeout follows every chunk.
+  ///
+  /// Links can't span submissions, so a chain that doesn't fit in the
+  /// submission queue completes with `EINVAL`.
+  fn push_writev_chain(
+    &mut self,
+    id: u64,
+    fd: RawFd,
+    iov: *const libc::iovec,
+    iovcnt: usize,
+    timeout: Option<&libc::timespec>,
+  ) -> io::Result<()> {
+    let chunks = iovcnt.div_ceil(IOV_MAX);
+    let needed = if timeout.is_some() { chunks * 2 } else { chunks };
+    if self.ring().sq_space_left() < needed {
+      self.ring().submit()?;
+    }
+    if self.ring().sq_space_left() < needed {
+      self.immediate.push(OpCompleted::new(id, -(libc::EINVAL as isize)));
+      return Ok(());
+    }
+
+    // SAFETY: iov points to iovcnt iovecs owned by the TypedOp.
+    let iov = unsafe { std::slice::from_raw_parts(iov, iovcnt) };
+    let mut wanted = VecDeque::with_capacity(chunks);
+    for (i, chunk) in iov.chunks(IOV_MAX).enumerate() {
+      let last = i + 1 == chunks;
+      wanted.push_back(chunk.iter().map(|v| v.iov_len).sum());
+      let entry = Writev::new(fd, chunk.as_ptr(), chunk.len() as u32)
+        .offset(-1i64 as u64)
+        .build();
+      // SAFETY: The chunk points into the iovecs of the TypedOp, which
+      // outlives the op, and fd stays open until it completes. The timespec
+      // is kept in self.timeouts until then too.
+      unsafe {
+        match timeout {
+          Some(timespec) => {
+            let link =
+              LinkTimeout::new(timespec as *const _ as *const _).build();
+            self.ring().push_with_flags(entry, id, SqeFlags::IO_LINK)?;
+            if last {
+              self.ring().push(link, LINK_TIMEOUT_KEY)?;
+            } else {
+              self.ring().push_with_flags(
+                link,
+                LINK_TIMEOUT_KEY,
+                SqeFlags::IO_LINK,
+              )?;
+            }
+          }
+          None if last => self.ring().push(entry, id)?,
+          None => self.ring().push_with_flags(entry, id, SqeFlags::IO_LINK)?,
+        }
+      }
+    }
+    let chain = WritevChain { wanted, result: 0, done: false };
+    self.writev_chains.insert(id, chain);
+    Ok(())
+  }
+
   #[inline]
   fn ring(&mut self) -> &mut LioUring {
     self.ring.as_mut().expect("IoUring not initialized - call init() first")
   }
 
+  /// Registers the fixed file table the first time an op needs it.
+  ///
+  /// Returns the result to complete the op with if that fails.
+  fn register_fixed_files(&mut self, op: &Op) -> Option<isize> {
+    let uses_table = matches!(
+      op,
+      Op::AcceptDirect { .. }
+        | Op::RecvFixed { .. }
+        | Op::SendFixed { .. }
+        | Op::InstallFixed { .. }
+        | Op::CloseFixed { .. }
+    );
+    if !uses_table || self.fixed_files {
+      return None;
+    }
+    match self.ring().register_files_sparse(FIXED_FILES) {
+      Ok(()) => {
+        self.fixed_files = true;
+        None
+      }
+      Err(err) => Some(-(err.raw_os_error().unwrap_or(libc::EIO) as isize)),
+    }
+  }
+
+  /// Creates the pipe of an [`Op::Pipe`] with `pipe2` when the kernel has no
+  /// pipe opcode.
+  ///
+  /// Returns `None` for any other op, or if the ring can run it.
+  fn pipe_without_ring(&self, op: &Op) -> Option<isize> {
+    match op {
+      // SAFETY: fds points to two fds in the boxed Pipe TypedOp.
+      Op::Pipe { fds, flags } if !self.has_op(Pipe::CODE) => {
+        Some(unsafe { pipe_blocking(*fds, *flags) })
+      }
+      _ => None,
+    }
+  }
+
+  /// Emulates the futex ops on kernels without them, see
+  /// [`FutexWord::park`], turns zero-copy sends into plain ones and
+  /// multishot reads into one-shot ones where they are missing, and moves
+  /// `waitid` to the blocking pool without its opcode.
+  ///
+  /// Returns the op to submit in their place, or the result to complete
+  /// them with right away as the error.
+  fn fallback(&self, op: Op) -> Result<Op, isize> {
+    match op {
+      Op::SendZc { fd, flags, buffer, .. } if !self.has_op(SendZc::CODE) => {
+        Ok(Op::Send { fd, flags, buffer })
+      }
+      Op::FutexWait { word, expected, parker }
+        if !self.has_op(FutexWait::CODE) =>
+      {
+        // SAFETY: parker points into the boxed op, which outlives the poll.
+        unsafe { FutexWord::park(&word, expected, parker) }
+      }
+      Op::FutexWake { word, count } if !self.has_op(FutexWake::CODE) => {
+        Err(word.wake_parked(count) as isize)
+      }
+      Op::WaitId { idtype, id, options, info }
+        if !self.has_op(WaitId::CODE) =>
+      {
+        Ok(WaitInfo::wait_on_pool(&info, idtype, id, options))
+      }
+      // The stream submits it again after each chunk, see
+      // [`ReadMulti`](crate::api::ops::ReadMulti).
+      Op::ReadSelect { fd, len, bgid, multishot: true }
+        if !self.has_op(ReadMulti::CODE) =>
+      {
+        Ok(Op::ReadSelect { fd, len, bgid, multishot: false })
+      }
+      op => Ok(op),
+    }
+  }
+
+  /// Starts `op` on the blocking pool, returning the poll to submit under
+  /// its id in its place. The completion of the poll is replaced with the
+  /// result of the syscall in [`wait_timeout`](IoBackend::wait_timeout).
+  fn run_on_pool(&mut self, id: u64, op: Op) -> Op {
+    let op = PooledOp(op);
+    let mut job = SpawnBlocking::new(move || op.run());
+    let poll = job.into_op();
+    self.pooled.insert(id, job);
+    poll
+  }
+
+  /// Registers the fixed buffer table of an [`Op::RegisterBuffers`].
+  ///
+  /// Returns `None` for any other op.
+  fn register_buffers(&mut self, op: &Op) -> Option<isize> {
+    let Op::RegisterBuffers { iov, count } = op else {
+      return None;
+    };
+    // SAFETY: iov points to count iovecs owned by the TypedOp, each one
+    // describing a buffer of the BufStore that stays alive and in place.
+    let result = unsafe {
+      let slices: Vec<io::IoSlice<'_>> =
+        std::slice::from_raw_parts(*iov, *count)
+          .iter()
+          .map(|v| {
+            io::IoSlice::new(std::slice::from_raw_parts(
+              v.iov_base.cast::<u8>(),
+              v.iov_len,
+            ))
+          })
+          .collect();
+      self.ring().register_buffers(&slices)
+    };
+    Some(match result {
+      Ok(()) => 0,
+      Err(err) => -(err.raw_os_error().unwrap_or(libc::EIO) as isize),
+    })
+  }
+
   /// Poll for completions with optional timeout.
   ///
   /// - `timeout = None`: Block indefinitely
@@ -143,6 +730,11 @@ impl IoUring {
     timeout: Option<Duration>,
   ) -> io::Result<&[OpCompleted]> {
     self.completed.clear();
+    self.completed.append(&mut self.immediate);
+
+    // Don't block on the ring when there already is something to return.
+    let timeout =
+      if self.completed.is_empty() { timeout } else { Some(Duration::ZERO) };
 
     let ring = self.ring.as_mut().expect("IoUring not initialized");
 
@@ -150,31 +742,24 @@ impl IoUring {
       None => {
         // Block indefinitely for first completion
         let first = ring.wait()?;
-        self
-          .completed
-          .push(OpCompleted::new(first.user_data(), first.result() as isize));
+        self.completed.push(op_completed(&first));
       }
       Some(d) if d.is_zero() => {
         // Non-blocking: check if anything is ready
         match ring.try_wait()? {
           Some(op) => {
-            self
-              .completed
-              .push(OpCompleted::new(op.user_data(), op.result() as isize));
+            self.completed.push(op_completed(&op));
           }
-          None => return Ok(&[]),
+          None => return Ok(&self.completed),
         }
       }
       Some(d) => {
         // Wait with timeout
         match ring.wait_timeout(d)? {
           Some(first) => {
-            self.completed.push(OpCompleted::new(
-              first.user_data(),
-              first.result() as isize,
-            ));
+            self.completed.push(op_completed(&first));
           }
-          None => return Ok(&[]), // Timeout expired
+          None => return Ok(&self.completed), // Timeout expired
         }
       }
     }
@@ -182,9 +767,7 @@ impl IoUring {
     // Drain any additional completions (non-blocking)
     let ring = self.ring.as_mut().expect("IoUring not initialized");
     while let Ok(Some(op)) = ring.try_wait() {
-      self
-        .completed
-        .push(OpCompleted::new(op.user_data(), op.result() as isize));
+      self.completed.push(op_completed(&op));
     }
 
     Ok(&self.completed)
@@ -193,27 +776,179 @@ impl IoUring {
 
 impl IoBackend for IoUring {
   fn init(&mut self, cap: usize) -> io::Result<()> {
-    let ring = LioUring::new(cap as u32)?;
+    let ring = match self.params.clone() {
+      None => LioUring::new(cap as u32)?,
+      Some(params) => {
+        let params = Params { sq_entries: cap as u32, ..params };
+        match LioUring::with_params(params.clone()) {
+          Ok(ring) => ring,
+          // Typically EPERM, without the privileges older kernels want.
+          Err(_) if params.is_sqpoll() => {
+            LioUring::with_params(params.without_sqpoll())?
+          }
+          Err(err) => return Err(err),
+        }
+      }
+    };
+    self.opcodes =
+      Probe::new(&ring).ok().map(|probe| Opcodes::from_probe(&probe));
     self.ring = Some(ring);
+    self.fixed_files = false;
     // Pre-allocate completions buffer (reasonable batch size)
     self.completed = Vec::with_capacity(cap.min(256));
+    self.immediate = Vec::with_capacity(64);
     Ok(())
   }
 
   fn push(&mut self, id: u64, op: Op) -> io::Result<()> {
+    let op = match self.fallback(op) {
+      Ok(op) => op,
+      Err(result) => {
+        self.immediate.push(OpCompleted::new(id, result));
+        return Ok(());
+      }
+    };
+    if let Some(result) = run_without_ring(&op)
+      .or_else(|| self.register_fixed_files(&op))
+      .or_else(|| self.register_buffers(&op))
+      .or_else(|| self.pipe_without_ring(&op))
+    {
+      self.immediate.push(OpCompleted::new(id, result));
+      return Ok(());
+    }
+    if runs_on_pool(&op) {
+      let op = self.run_on_pool(id, op);
+      return self.push(id, op);
+    }
+    if let Op::Writev { fd, iov, iovcnt } = &op
+      && *iovcnt > IOV_MAX
+    {
+      return self.push_writev_chain(id, fd.as_raw_fd(), *iov, *iovcnt, None);
+    }
+
     let entry = create_io_uring_entry(&op);
+    if self.lacks_op(&entry) {
+      if !is_instant(&op) {
+        let op = self.run_on_pool(id, op);
+        return self.push(id, op);
+      }
+      let result = Poller::run_op_blocking(op);
+      self.immediate.push(OpCompleted::new(id, result));
+      return Ok(());
+    }
 
     // Push to submission queue without syscall
     // SAFETY: entry is a valid SQE created from op, id is used as user_data
     unsafe { self.ring().push(entry, id) }.map_err(|_| {
       io::Error::new(io::ErrorKind::WouldBlock, "submission queue full")
     })?;
+    if matches!(op, Op::SendZc { .. }) {
+      self.zc_sends.insert(id, None);
+    }
+
+    Ok(())
+  }
+
+  fn push_with_timeout(
+    &mut self,
+    id: u64,
+    op: Op,
+    timeout: Duration,
+  ) -> io::Result<()> {
+    let op = match self.fallback(op) {
+      Ok(op) => op,
+      Err(result) => {
+        self.immediate.push(OpCompleted::new(id, result));
+        return Ok(());
+      }
+    };
+    if let Some(result) = run_without_ring(&op)
+      .or_else(|| self.register_fixed_files(&op))
+      .or_else(|| self.register_buffers(&op))
+      .or_else(|| self.pipe_without_ring(&op))
+    {
+      self.immediate.push(OpCompleted::new(id, result));
+      return Ok(());
+    }
+    // A blocking syscall can't be interrupted, so the timeout is dropped.
+    if runs_on_pool(&op) {
+      let op = self.run_on_pool(id, op);
+      return self.push(id, op);
+    }
+
+    let timespec = Box::new(libc::timespec {
+      tv_sec: timeout.as_secs() as libc::time_t,
+      tv_nsec: timeout.subsec_nanos() as libc::c_long,
+    });
+    if let Op::Writev { fd, iov, iovcnt } = &op
+      && *iovcnt > IOV_MAX
+    {
+      let fd = fd.as_raw_fd();
+      self.push_writev_chain(id, fd, *iov, *iovcnt, Some(&timespec))?;
+      if self.writev_chains.contains_key(&id) {
+        self.timeouts.insert(id, timespec);
+      }
+      return Ok(());
+    }
+
+    let entry = create_io_uring_entry(&op);
+    if self.lacks_op(&entry) {
+      // A blocking syscall can't be interrupted, so the timeout is dropped.
+      if !is_instant(&op) {
+        let op = self.run_on_pool(id, op);
+        return self.push(id, op);
+      }
+      let result = Poller::run_op_blocking(op);
+      self.immediate.push(OpCompleted::new(id, result));
+      return Ok(());
+    }
+
+    // The op and its timeout must land in the same submission.
+    if self.ring().sq_space_left() < 2 {
+      self.ring().submit()?;
+    }
+
+    // __kernel_timespec has same layout as libc::timespec
+    let link = LinkTimeout::new(&*timespec as *const _ as *const _).build();
+
+    // SAFETY: entry is a valid SQE created from op, id is used as user_data.
+    // The timespec is boxed and kept in self.timeouts until the op completes.
+    unsafe {
+      self.ring().push_with_flags(entry, id, SqeFlags::IO_LINK)?;
+      self.ring().push(link, LINK_TIMEOUT_KEY)?;
+    }
+    self.timeouts.insert(id, timespec);
+    if matches!(op, Op::SendZc { .. }) {
+      self.zc_sends.insert(id, None);
+    }
 
     Ok(())
   }
 
+  fn cancel(&mut self, id: u64) -> io::Result<()> {
+    if self.is_full() {
+      self.ring().submit()?;
+    }
+    // The syscall of a pooled op can't be interrupted, and its TypedOp has
+    // to outlive it. It completes once the syscall returns.
+    if self.pooled.contains_key(&id) {
+      return Ok(());
+    }
+    if self.timeouts.contains_key(&id) {
+      self.cancelled.insert(id);
+    }
+    let entry = AsyncCancel::new(id).build();
+    // SAFETY: A cancel SQE only refers to the target by its user_data.
+    unsafe { self.ring().push(entry, CANCEL_KEY) }
+  }
+
+  fn is_full(&self) -> bool {
+    self.ring.as_ref().is_some_and(|ring| ring.sq_space_left() == 0)
+  }
+
   fn flush(&mut self) -> io::Result<usize> {
-    // Submit all queued operations with a single syscall
+    // Submit all queued operations with a single syscall. With SQPOLL this
+    // only publishes them to the kernel thread, waking it if it went idle.
     let submitted = self.ring().submit()?;
     Ok(submitted)
   }
@@ -222,7 +957,57 @@ impl IoBackend for IoUring {
     &mut self,
     timeout: Option<Duration>,
   ) -> io::Result<&[OpCompleted]> {
-    self.poll_inner(timeout)
+    self.poll_inner(timeout)?;
+
+    // An op cancelled by its linked timeout timed out, unless it was
+    // cancelled on purpose.
+    let timeouts = &mut self.timeouts;
+    let cancelled = &mut self.cancelled;
+    let zc_sends = &mut self.zc_sends;
+    let writev_chains = &mut self.writev_chains;
+    let pooled = &mut self.pooled;
+    self.completed.retain_mut(|completed| {
+      if completed.op_id == LINK_TIMEOUT_KEY || completed.op_id == CANCEL_KEY {
+        return false;
+      }
+      if let Some(job) = pooled.remove(&completed.op_id) {
+        completed.result = match job.extract_result(completed.result) {
+          Ok(result) => result,
+          Err(err) => -(err.raw_os_error().unwrap_or(libc::EIO) as isize),
+        };
+      }
+      // The chunks of a long writev complete one by one, the last one
+      // completes the op with the total.
+      if let Some(chain) = writev_chains.get_mut(&completed.op_id) {
+        let Some(result) = chain.complete(completed.result) else {
+          return false;
+        };
+        writev_chains.remove(&completed.op_id);
+        completed.result = result;
+      }
+      // A zero-copy send first reports its byte count, flagged with more to
+      // come while the kernel still holds on to the buffer. The notification
+      // that it let go completes the op with that count.
+      if let Some(sent) = zc_sends.get_mut(&completed.op_id) {
+        if completed.more {
+          *sent = Some(completed.result);
+          return false;
+        }
+        if let Some(sent) = sent.take() {
+          completed.result = sent;
+        }
+        zc_sends.remove(&completed.op_id);
+      }
+      if timeouts.remove(&completed.op_id).is_some()
+        && !cancelled.remove(&completed.op_id)
+        && completed.result == -(libc::ECANCELED as isize)
+      {
+        completed.result = -(libc::ETIMEDOUT as isize);
+      }
+      true
+    });
+
+    Ok(&self.completed)
   }
 }
 
@@ -235,4 +1020,152 @@ mod tests {
     let mut backend = IoUring::new();
     backend.init(64).unwrap();
   }
+
+  #[test]
+  fn test_init_with_sqpoll() {
+    let mut backend = IoUring::with_params(Params::default().sqpoll(100));
+    // Falls back to a plain ring where SQPOLL isn't allowed, either way
+    // submissions go through.
+    backend.init(64).unwrap();
+    backend.push(1, Op::Nop).unwrap();
+    backend.flush().unwrap();
+    let completed = backend.wait_timeout(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].op_id, 1);
+  }
+
+  #[test]
+  fn test_writev_chain_result() {
+    let chain = |wanted: &[usize]| WritevChain {
+      wanted: wanted.iter().copied().collect(),
+      result: 0,
+      done: false,
+    };
+    let canceled = -(libc::ECANCELED as isize);
+
+    let mut full = chain(&[10, 4]);
+    assert_eq!(full.complete(10), None);
+    assert_eq!(full.complete(4), Some(14));
+
+    // A short chunk breaks the link, the rest is cancelled.
+    let mut short = chain(&[10, 4, 4]);
+    assert_eq!(short.complete(6), None);
+    assert_eq!(short.complete(canceled), None);
+    assert_eq!(short.complete(canceled), Some(6));
+
+    let mut failed = chain(&[10, 4]);
+    assert_eq!(failed.complete(-(libc::EPIPE as isize)), None);
+    assert_eq!(failed.complete(canceled), Some(-(libc::EPIPE as isize)));
+  }
+
+  #[test]
+  fn test_lacking_opcode_runs_on_pool() {
+    use crate::{api::resource::Resource, op::OpBuf};
+    use std::os::fd::FromRawFd;
+
+    let mut backend = IoUring::new();
+    backend.init(64).unwrap();
+    // Pretend the kernel has no IORING_OP_RECV.
+    let Some(opcodes) = &mut backend.opcodes else { return };
+    opcodes.0[Recv::CODE as usize / 64] &= !(1 << (Recv::CODE % 64));
+
+    let mut fds = [0; 2];
+    // SAFETY: fds has room for the two descriptors.
+    let ret = unsafe {
+      libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, fds.as_mut_ptr())
+    };
+    assert_eq!(ret, 0);
+    // SAFETY: socketpair just created both fds, nothing else owns them.
+    let (a, b) =
+      unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) };
+
+    let mut buf = [0u8; 8];
+    let raw = RawBuf { ptr: buf.as_mut_ptr(), len: buf.len() };
+    let op =
+      Op::Recv { fd: a, flags: 0, buffer: OpBuf::new(raw), buf_index: None };
+    backend.push(1, op).unwrap();
+    backend.flush().unwrap();
+    // The recv blocks a pool thread, not this one.
+    let completed = backend.wait_timeout(Some(Duration::from_millis(20)));
+    assert!(completed.unwrap().is_empty());
+
+    // SAFETY: b is open, the source is two readable bytes.
+    let sent = unsafe { libc::write(b.as_raw_fd(), b"hi".as_ptr().cast(), 2) };
+    assert_eq!(sent, 2);
+    let completed = backend.wait_timeout(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].op_id, 1);
+    assert_eq!(completed[0].result, 2);
+    assert_eq!(&buf[..2], b"hi");
+  }
+
+  #[test]
+  fn test_long_readv_runs_on_pool() {
+    use crate::api::resource::Resource;
+    use std::os::fd::FromRawFd;
+
+    let mut backend = IoUring::new();
+    backend.init(64).unwrap();
+
+    let mut fds = [0; 2];
+    // SAFETY: fds has room for the two descriptors.
+    let ret = unsafe {
+      libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, fds.as_mut_ptr())
+    };
+    assert_eq!(ret, 0);
+    // SAFETY: socketpair just created both fds, nothing else owns them.
+    let (a, b) =
+      unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) };
+
+    let mut bufs = vec![[0u8; 1]; IOV_MAX + 1];
+    let iov: Vec<libc::iovec> = bufs
+      .iter_mut()
+      .map(|buf| libc::iovec { iov_base: buf.as_mut_ptr().cast(), iov_len: 1 })
+      .collect();
+    let op = Op::Readv { fd: a, iov: iov.as_ptr(), iovcnt: iov.len() };
+    backend.push(1, op).unwrap();
+    backend.flush().unwrap();
+    // Nothing to read yet, the readv blocks a pool thread, not this one.
+    let completed = backend.wait_timeout(Some(Duration::from_millis(20)));
+    assert!(completed.unwrap().is_empty());
+
+    // SAFETY: b is open, the source is two readable bytes.
+    let sent = unsafe { libc::write(b.as_raw_fd(), b"hi".as_ptr().cast(), 2) };
+    assert_eq!(sent, 2);
+    let completed = backend.wait_timeout(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].op_id, 1);
+    assert_eq!(completed[0].result, 2);
+    assert_eq!(&bufs[..2], &[[b'h'], [b'i']]);
+  }
+
+  #[test]
+  fn test_dup_runs_on_pool() {
+    use crate::api::resource::Resource;
+    use std::os::fd::FromRawFd;
+
+    let mut backend = IoUring::new();
+    backend.init(64).unwrap();
+
+    backend.push(1, Op::Dup { fd: Resource::stdin() }).unwrap();
+    backend.flush().unwrap();
+    let completed = backend.wait_timeout(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].op_id, 1);
+    assert!(completed[0].result >= 0);
+    // SAFETY: The dup just created the fd, nothing else owns it.
+    drop(unsafe { Resource::from_raw_fd(completed[0].result as i32) });
+  }
+
+  #[test]
+  fn test_opcodes_bitmap() {
+    let mut bits = [0; 4];
+    bits[1] = 1 << 2;
+    bits[3] = 1 << 63;
+    let opcodes = Opcodes(bits);
+    assert!(opcodes.contains(66));
+    assert!(opcodes.contains(u8::MAX));
+    assert!(!opcodes.contains(2));
+    assert!(!opcodes.contains(65));
+  }
 }
@@ -6,56 +6,68 @@
 //! # Architecture
 //!
 //! IOCP is a completion-based model (like io_uring), not readiness-based (like epoll).
-//! Operations are submitted with an OVERLAPPED structure and complete asynchronously.
+//! Operations are submitted with an OVERLAPPED structure and complete asynchronously,
+//! completions are drained in batches with `GetQueuedCompletionStatusEx`.
+//!
+//! A handle has to be associated with the port before its overlapped
+//! operations complete there. That happens once per handle, on its first
+//! read or write. Handles that can't be associated (consoles, handles opened
+//! without `FILE_FLAG_OVERLAPPED`) are read and written blocking instead.
 //!
 //! # Operation Categories
 //!
-//! - **Native IOCP**: Read, Write, ReadAt, WriteAt, Send, Recv, Accept, Connect
+//! - **Native IOCP**: Read, Write, ReadAt, WriteAt
 //!   - Use OVERLAPPED for async completion
-//! - **Blocking**: Socket, Bind, Listen, Close, Fsync, Truncate, Shutdown, OpenAt, LinkAt, SymlinkAt, Nop
-//!   - Execute synchronously in push(), complete immediately
+//! - **Blocking**: Close, Fsync, Truncate, OpenAt, Nop, Custom
+//!   - Execute synchronously in push(), complete on the next wait
 //! - **Timer**: Timeout
 //!   - Use CreateTimerQueueTimer, post to IOCP on expiry
+//!
+//! Every other op completes with `ERROR_NOT_SUPPORTED`.
 
 use std::collections::{HashMap, HashSet};
+use std::ffi::c_void;
 use std::io;
-use std::os::windows::io::{AsRawHandle, RawHandle};
+use std::os::windows::io::AsRawHandle;
 use std::ptr;
 use std::time::Duration;
 
 use windows_sys::Win32::Foundation::{
-  CloseHandle, ERROR_IO_PENDING, FALSE, GetLastError, HANDLE,
-  INVALID_HANDLE_VALUE, WAIT_TIMEOUT,
+  CloseHandle, ERROR_BROKEN_PIPE, ERROR_HANDLE_EOF, ERROR_IO_PENDING,
+  ERROR_NOT_SUPPORTED, ERROR_OPERATION_ABORTED, FALSE, GENERIC_READ,
+  GENERIC_WRITE, GetLastError, HANDLE, INVALID_HANDLE_VALUE,
+  RtlNtStatusToDosError, WAIT_TIMEOUT,
 };
 use windows_sys::Win32::Networking::WinSock::{
-  AF_INET, AF_INET6, INVALID_SOCKET, SD_BOTH, SD_RECEIVE, SD_SEND,
-  SO_UPDATE_ACCEPT_CONTEXT, SO_UPDATE_CONNECT_CONTEXT, SOCK_DGRAM, SOCK_STREAM,
-  SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6, SOCKADDR_STORAGE, SOCKET, SOCKET_ERROR,
-  SOL_SOCKET, WSA_IO_PENDING, WSABUF, WSADATA, WSAGetLastError, WSARecv,
-  WSASend, WSASocketW, WSAStartup, bind, closesocket, connect, getaddrinfo,
-  listen, setsockopt, shutdown, socket,
+  SOCKET, WSAGetLastError, closesocket,
 };
 use windows_sys::Win32::Storage::FileSystem::{
-  CreateFileW, FILE_BEGIN, FlushFileBuffers, OPEN_EXISTING, ReadFile,
-  SetEndOfFile, SetFilePointerEx, WriteFile,
+  CREATE_ALWAYS, CREATE_NEW, CreateFileA, FILE_APPEND_DATA, FILE_BEGIN,
+  FILE_FLAG_OVERLAPPED, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
+  FlushFileBuffers, OPEN_ALWAYS, OPEN_EXISTING, ReadFile, SetEndOfFile,
+  SetFilePointerEx, TRUNCATE_EXISTING, WriteFile,
 };
 use windows_sys::Win32::System::IO::{
-  CreateIoCompletionPort, GetQueuedCompletionStatus, OVERLAPPED,
-  PostQueuedCompletionStatus,
+  CancelIoEx, CreateIoCompletionPort, GetQueuedCompletionStatusEx, OVERLAPPED,
+  OVERLAPPED_ENTRY, PostQueuedCompletionStatus,
 };
 use windows_sys::Win32::System::Threading::{
-  CreateTimerQueueTimer, DeleteTimerQueueTimer, WT_EXECUTEONLYONCE,
+  CreateTimerQueueTimer, DeleteTimerQueueTimer, INFINITE, WT_EXECUTEONLYONCE,
 };
 
 use crate::backends::{IoBackend, OpCompleted};
-use crate::op::{Op, OpBuf, RawBuf};
+use crate::op::{Op, RawBuf};
 
-/// Marker value for wake-up notifications (not a real operation).
+/// Completion key of wake-up notifications (not a real operation).
 const NOTIFY_KEY: usize = usize::MAX;
 
-/// Marker value for timer completions.
+/// Completion key of timer expiries, the op id is passed as the OVERLAPPED
+/// pointer.
 const TIMER_KEY: usize = usize::MAX - 1;
 
+/// Completion key of associated handles.
+const IO_KEY: usize = 0;
+
 /// State for an in-flight IOCP operation.
 ///
 /// This is heap-allocated to ensure the OVERLAPPED pointer remains stable
@@ -69,26 +81,25 @@ struct IocpOpState {
 }
 
 impl IocpOpState {
-  fn new(op_id: u64) -> Box<Self> {
-    Box::new(Self { overlapped: unsafe { std::mem::zeroed() }, op_id })
-  }
-
-  /// Get the OVERLAPPED pointer for this state.
-  fn overlapped_ptr(&mut self) -> *mut OVERLAPPED {
-    &mut self.overlapped as *mut _
+  fn new(op_id: u64, offset: Option<i64>) -> Box<Self> {
+    // SAFETY: OVERLAPPED is plain data, all zeroes is its documented initial
+    // state.
+    let mut overlapped: OVERLAPPED = unsafe { std::mem::zeroed() };
+    if let Some(offset) = offset {
+      overlapped.Anonymous.Anonymous.Offset = offset as u64 as u32;
+      overlapped.Anonymous.Anonymous.OffsetHigh =
+        ((offset as u64) >> 32) as u32;
+    }
+    Box::new(Self { overlapped, op_id })
   }
 }
 
-/// Immediate completion for operations that don't use IOCP.
-struct ImmediateCompletion {
-  op_id: u64,
-  result: isize,
-}
-
-/// Timer state for Timeout operations.
-struct TimerState {
-  timer_handle: HANDLE,
-  op_id: u64,
+/// An overlapped operation the kernel still owns.
+struct InFlight {
+  /// Keeps the OVERLAPPED the kernel writes into alive.
+  state: Box<IocpOpState>,
+  /// Keeps the handle and the buffer alive.
+  op: Op,
 }
 
 /// Windows I/O Completion Ports backend.
@@ -96,6 +107,11 @@ struct TimerState {
 /// IOCP is the native asynchronous I/O mechanism on Windows, providing
 /// high-performance I/O multiplexing through kernel-managed completion ports.
 ///
+/// Handles opened for overlapped I/O have no file position, so
+/// [`read`](crate::api::read) and [`write`](crate::api::write) on files
+/// always start at offset 0. Use [`read_at`](crate::api::read_at) and
+/// [`write_at`](crate::api::write_at) on files.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -109,779 +125,432 @@ struct TimerState {
 /// ```
 #[derive(Default)]
 pub struct Iocp {
-  /// Handle to the I/O completion port
-  port: Option<HANDLE>,
+  /// Handle to the I/O completion port, null until `init`.
+  port: HANDLE,
 
   /// Handles that have been associated with the completion port.
-  /// We track these to avoid double-association.
-  associated_handles: HashSet<isize>,
+  /// A handle can only be associated once, and stays associated until it
+  /// is closed.
+  associated: HashSet<usize>,
 
-  /// Mapping from OVERLAPPED pointer address to operation state.
-  /// Used to identify which operation completed and clean up.
-  active_ops: HashMap<usize, Box<IocpOpState>>,
+  /// Overlapped operations by id.
+  in_flight: HashMap<u64, InFlight>,
 
-  /// Stored operations for buffer ownership (op_id -> Op).
-  /// We keep the Op alive until completion to maintain buffer validity.
-  stored_ops: HashMap<u64, Op>,
+  /// Timer queue timers by the id of their Timeout op.
+  timers: HashMap<u64, HANDLE>,
 
-  /// Active timers (op_id -> timer state).
-  active_timers: HashMap<u64, TimerState>,
+  /// Results of operations that completed in push().
+  /// These are drained during wait.
+  immediate: Vec<OpCompleted>,
 
-  /// Results from immediate/blocking operations.
-  /// These are drained during poll/wait.
-  immediate: Vec<ImmediateCompletion>,
+  /// Reusable buffer for dequeued completion packets.
+  entries: Vec<OVERLAPPED_ENTRY>,
 
   /// Reusable buffer for completed operations.
   completed: Vec<OpCompleted>,
-
-  /// WSA initialization flag
-  wsa_initialized: bool,
 }
 
+// SAFETY: The port and timer handles are kernel object identifiers, usable
+// from any thread. In-flight ops are only touched through &mut self.
+unsafe impl Send for Iocp {}
+
 impl Iocp {
   /// Creates a new uninitialized IOCP backend.
   ///
-  /// Call [`init`](Self::init) before using.
+  /// Call [`init`](IoBackend::init) before using.
   pub fn new() -> Self {
     Self::default()
   }
 
-  /// Returns the completion port handle, panicking if not initialized.
+  /// Wakes up a blocked [`wait_timeout`](IoBackend::wait_timeout) call.
+  pub fn notify(&self) -> io::Result<()> {
+    // SAFETY: port is a valid completion port after init.
+    let success = unsafe {
+      PostQueuedCompletionStatus(self.port, 0, NOTIFY_KEY, ptr::null())
+    };
+    if success == FALSE { Err(io::Error::last_os_error()) } else { Ok(()) }
+  }
+
+  /// Convert a Windows error code to lio convention (negative error code).
   #[inline]
-  fn port(&self) -> HANDLE {
-    self.port.expect("Iocp not initialized - call init() first")
+  fn error_result(error: u32) -> isize {
+    -(error as isize)
   }
 
-  /// Ensure WSA is initialized (for socket operations).
-  fn ensure_wsa(&mut self) -> io::Result<()> {
-    if !self.wsa_initialized {
-      let mut wsa_data: WSADATA = unsafe { std::mem::zeroed() };
-      let result = unsafe { WSAStartup(0x0202, &mut wsa_data) };
-      if result != 0 {
-        return Err(io::Error::from_raw_os_error(result));
-      }
-      self.wsa_initialized = true;
-    }
-    Ok(())
+  #[inline]
+  fn last_error() -> isize {
+    // SAFETY: Reads the calling thread's last error.
+    Self::error_result(unsafe { GetLastError() })
   }
 
   /// Associates a handle with the completion port if not already associated.
-  fn ensure_associated(&mut self, handle: HANDLE) -> io::Result<()> {
-    let handle_val = handle as isize;
-
-    if self.associated_handles.contains(&handle_val) {
-      return Ok(());
+  fn associate(&mut self, handle: HANDLE) -> bool {
+    if self.associated.contains(&(handle as usize)) {
+      return true;
     }
-
-    // Associate the handle with the completion port.
-    // We use 0 as the completion key since we identify operations via OVERLAPPED pointer.
-    let result = unsafe {
-      CreateIoCompletionPort(
-        handle,
-        self.port(),
-        0, // Completion key (we use OVERLAPPED pointer instead)
-        0, // Number of concurrent threads (0 = system default)
-      )
-    };
-
-    if result == 0 {
-      return Err(io::Error::last_os_error());
+    // SAFETY: handle is kept open by the op's Resource, port is valid.
+    let port = unsafe { CreateIoCompletionPort(handle, self.port, IO_KEY, 0) };
+    if port.is_null() {
+      return false;
     }
-
-    self.associated_handles.insert(handle_val);
-    Ok(())
-  }
-
-  /// Convert a Windows error code to lio convention (negative errno).
-  #[inline]
-  fn error_result(error: u32) -> isize {
-    -(error as isize)
+    self.associated.insert(handle as usize);
+    true
   }
 
   /// Run a blocking operation and return the result.
   fn run_blocking(op: &Op) -> isize {
     match op {
-      Op::Socket { domain, ty, proto } => {
-        let sock = unsafe { socket(*domain, *ty, *proto) };
-        if sock == INVALID_SOCKET {
-          Self::error_result(unsafe { WSAGetLastError() } as u32)
-        } else {
-          sock as isize
-        }
-      }
-
-      Op::Bind { fd, addr } => {
-        let handle = fd.as_raw_handle() as SOCKET;
-        let (addr_ptr, addr_len) = sockaddr_storage_to_ptr(addr);
-        let result = unsafe { bind(handle, addr_ptr, addr_len) };
-        if result == SOCKET_ERROR {
-          Self::error_result(unsafe { WSAGetLastError() } as u32)
-        } else {
-          0
-        }
-      }
-
-      Op::Listen { fd, backlog } => {
-        let handle = fd.as_raw_handle() as SOCKET;
-        let result = unsafe { listen(handle, *backlog) };
-        if result == SOCKET_ERROR {
-          Self::error_result(unsafe { WSAGetLastError() } as u32)
+      Op::Read { fd, buffer, .. } | Op::ReadAt { fd, buffer, .. } => {
+        let mut state = IocpOpState::new(0, Self::offset(op));
+        // SAFETY: ErasedBuffer stores RawBuf set by into_op.
+        let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
+        let mut read = 0u32;
+        // SAFETY: The handle is open, ptr/len are valid per Op invariants and
+        // the handle is synchronous, so the OVERLAPPED only carries the offset.
+        let ok = unsafe {
+          ReadFile(
+            fd.as_raw_handle(),
+            ptr.cast(),
+            len.min(u32::MAX as usize) as u32,
+            &mut read,
+            &mut state.overlapped,
+          )
+        };
+        if ok == FALSE {
+          Self::eof_or(Self::last_error())
         } else {
-          0
+          read as isize
         }
       }
-
-      Op::Shutdown { fd, how } => {
-        let handle = fd.as_raw_handle() as SOCKET;
-        // Map libc shutdown constants to Windows
-        let win_how = match *how {
-          0 => SD_RECEIVE, // SHUT_RD
-          1 => SD_SEND,    // SHUT_WR
-          _ => SD_BOTH,    // SHUT_RDWR
+      Op::Write { fd, buffer } | Op::WriteAt { fd, buffer, .. } => {
+        let mut state = IocpOpState::new(0, Self::offset(op));
+        // SAFETY: ErasedBuffer stores RawBuf set by into_op.
+        let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
+        let mut written = 0u32;
+        // SAFETY: Same as the read above.
+        let ok = unsafe {
+          WriteFile(
+            fd.as_raw_handle(),
+            ptr.cast_const().cast(),
+            len.min(u32::MAX as usize) as u32,
+            &mut written,
+            &mut state.overlapped,
+          )
         };
-        let result = unsafe { shutdown(handle, win_how) };
-        if result == SOCKET_ERROR {
-          Self::error_result(unsafe { WSAGetLastError() } as u32)
-        } else {
-          0
-        }
+        if ok == FALSE { Self::last_error() } else { written as isize }
       }
 
       Op::Close { handle, is_socket } => {
-        let result = if *is_socket {
-          unsafe { closesocket(*handle as SOCKET) }
-        } else {
-          let r = unsafe { CloseHandle(*handle as HANDLE) };
-          if r == 0 { -1 } else { 0 }
-        };
-        if result != 0 {
-          if *is_socket {
-            Self::error_result(unsafe { WSAGetLastError() } as u32)
-          } else {
-            Self::error_result(unsafe { GetLastError() })
+        if *is_socket {
+          // SAFETY: The caller hands over ownership of the socket.
+          if unsafe { closesocket(*handle as SOCKET) } != 0 {
+            // SAFETY: Reads the calling thread's last socket error.
+            return Self::error_result(unsafe { WSAGetLastError() } as u32);
           }
-        } else {
-          0
+        // SAFETY: The caller hands over ownership of the handle.
+        } else if unsafe { CloseHandle(*handle) } == FALSE {
+          return Self::last_error();
         }
+        0
       }
 
       Op::Fsync { fd } => {
-        let handle = fd.as_raw_handle() as HANDLE;
-        let result = unsafe { FlushFileBuffers(handle) };
-        if result == 0 {
-          Self::error_result(unsafe { GetLastError() })
-        } else {
-          0
+        // SAFETY: The handle is kept open by the op's Resource.
+        if unsafe { FlushFileBuffers(fd.as_raw_handle()) } == FALSE {
+          return Self::last_error();
         }
+        0
       }
 
       Op::Truncate { fd, size } => {
-        let handle = fd.as_raw_handle() as HANDLE;
-        // Move file pointer to the desired position
-        let mut new_pos: i64 = 0;
-        let result = unsafe {
-          SetFilePointerEx(handle, *size as i64, &mut new_pos, FILE_BEGIN)
+        let handle = fd.as_raw_handle();
+        // SAFETY: The handle is kept open by the op's Resource.
+        let ok = unsafe {
+          SetFilePointerEx(handle, *size as i64, ptr::null_mut(), FILE_BEGIN)
         };
-        if result == 0 {
-          return Self::error_result(unsafe { GetLastError() });
-        }
-        // Set end of file at current position
-        let result = unsafe { SetEndOfFile(handle) };
-        if result == 0 {
-          Self::error_result(unsafe { GetLastError() })
-        } else {
-          0
+        // SAFETY: Same as above, the end of file moves to the new position.
+        if ok == FALSE || unsafe { SetEndOfFile(handle) } == FALSE {
+          return Self::last_error();
         }
+        0
       }
 
+      // Windows has no directory-relative open, paths resolve against the
+      // working directory.
       Op::OpenAt { dir_fd: _, path, flags } => {
-        // Windows doesn't have openat() - we require absolute paths
-        // For now, just use CreateFileW with the path
-        // Note: This is a simplified implementation
-        let _ = flags; // TODO: Map flags properly
+        let (access, disposition) = open_flags(*flags);
+        // SAFETY: path points into the CString owned by the OpenAt op.
         let handle = unsafe {
-          CreateFileW(
-            *path as *const u16,
-            0x80000000 | 0x40000000, // GENERIC_READ | GENERIC_WRITE
-            0x00000001 | 0x00000002, // FILE_SHARE_READ | FILE_SHARE_WRITE
+          CreateFileA(
+            path.cast(),
+            access,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
             ptr::null(),
-            OPEN_EXISTING,
-            0x80, // FILE_ATTRIBUTE_NORMAL
-            0,
+            disposition,
+            FILE_FLAG_OVERLAPPED,
+            ptr::null_mut(),
           )
         };
         if handle == INVALID_HANDLE_VALUE {
-          Self::error_result(unsafe { GetLastError() })
+          Self::last_error()
         } else {
           handle as isize
         }
       }
 
-      Op::LinkAt { .. } => {
-        // Windows doesn't support linkat directly
-        // Would need CreateHardLinkW but lacks dir_fd semantics
-        Self::error_result(windows_sys::Win32::Foundation::ERROR_NOT_SUPPORTED)
-      }
-
-      Op::SymlinkAt { .. } => {
-        // Windows symlinks require elevated privileges
-        // Would need CreateSymbolicLinkW
-        Self::error_result(windows_sys::Win32::Foundation::ERROR_NOT_SUPPORTED)
-      }
-
       Op::Nop => 0,
 
-      // These should not be called via run_blocking
-      _ => {
-        Self::error_result(windows_sys::Win32::Foundation::ERROR_NOT_SUPPORTED)
+      Op::Custom { op } => {
+        // SAFETY: op points into the boxed Custom TypedOp, which outlives the op.
+        let op = unsafe { &**op };
+        op.run_blocking()
       }
-    }
-  }
-
-  /// Start a read operation using ReadFile with OVERLAPPED.
-  fn start_read(&mut self, id: u64, op: Op) -> io::Result<()> {
-    let (fd, buffer) = match &op {
-      Op::Read { fd, buffer } => (fd.as_raw_handle(), buffer),
-      Op::ReadAt { fd, offset, buffer } => {
-        // For ReadAt, we need to set the offset in OVERLAPPED
-        let handle = fd.as_raw_handle() as HANDLE;
-        self.ensure_associated(handle)?;
-
-        let mut state = IocpOpState::new(id);
-        state.overlapped.Anonymous.Anonymous.Offset =
-          (*offset as u64 & 0xFFFFFFFF) as u32;
-        state.overlapped.Anonymous.Anonymous.OffsetHigh =
-          ((*offset as u64) >> 32) as u32;
 
-        let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
-        let overlapped_ptr = state.overlapped_ptr();
-        let mut bytes_read: u32 = 0;
-
-        let result = unsafe {
-          ReadFile(
-            handle,
-            ptr as *mut _,
-            len as u32,
-            &mut bytes_read,
-            overlapped_ptr,
-          )
-        };
-
-        return self.handle_async_result(result, id, state, op);
-      }
-      _ => unreachable!(),
-    };
-
-    let handle = fd as HANDLE;
-    self.ensure_associated(handle)?;
-
-    let mut state = IocpOpState::new(id);
-    let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
-    let overlapped_ptr = state.overlapped_ptr();
-    let mut bytes_read: u32 = 0;
-
-    let result = unsafe {
-      ReadFile(
-        handle,
-        ptr as *mut _,
-        len as u32,
-        &mut bytes_read,
-        overlapped_ptr,
-      )
-    };
-
-    self.handle_async_result(result, id, state, op)
-  }
-
-  /// Start a write operation using WriteFile with OVERLAPPED.
-  fn start_write(&mut self, id: u64, op: Op) -> io::Result<()> {
-    let (fd, buffer, offset) = match &op {
-      Op::Write { fd, buffer } => (fd.as_raw_handle(), buffer, None),
-      Op::WriteAt { fd, offset, buffer } => {
-        (fd.as_raw_handle(), buffer, Some(*offset))
-      }
-      _ => unreachable!(),
-    };
-
-    let handle = fd as HANDLE;
-    self.ensure_associated(handle)?;
-
-    let mut state = IocpOpState::new(id);
-
-    // Set offset if this is WriteAt
-    if let Some(off) = offset {
-      state.overlapped.Anonymous.Anonymous.Offset =
-        (off as u64 & 0xFFFFFFFF) as u32;
-      state.overlapped.Anonymous.Anonymous.OffsetHigh =
-        ((off as u64) >> 32) as u32;
+      _ => Self::error_result(ERROR_NOT_SUPPORTED),
     }
-
-    let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
-    let overlapped_ptr = state.overlapped_ptr();
-    let mut bytes_written: u32 = 0;
-
-    let result = unsafe {
-      WriteFile(
-        handle,
-        ptr as *const _,
-        len as u32,
-        &mut bytes_written,
-        overlapped_ptr,
-      )
-    };
-
-    self.handle_async_result(result, id, state, op)
   }
 
-  /// Start a WSASend operation.
-  fn start_wsa_send(&mut self, id: u64, op: Op) -> io::Result<()> {
-    let (fd, buffer, flags) = match &op {
-      Op::Send { fd, buffer, flags } => (fd.as_raw_handle(), buffer, *flags),
-      _ => unreachable!(),
-    };
-
-    let socket = fd as SOCKET;
-    // Note: Sockets should already be associated when created
-    // but ensure association anyway
-    self.ensure_associated(fd as HANDLE)?;
-
-    let mut state = IocpOpState::new(id);
-    let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
-
-    let mut wsa_buf = WSABUF { len: len as u32, buf: ptr as *mut _ };
-    let mut bytes_sent: u32 = 0;
-    let overlapped_ptr = state.overlapped_ptr();
-
-    let result = unsafe {
-      WSASend(
-        socket,
-        &mut wsa_buf,
-        1,
-        &mut bytes_sent,
-        flags as u32,
-        overlapped_ptr,
-        None,
-      )
-    };
-
-    self.handle_wsa_result(result, id, state, op)
+  fn offset(op: &Op) -> Option<i64> {
+    match op {
+      Op::ReadAt { offset, .. } | Op::WriteAt { offset, .. } => Some(*offset),
+      _ => None,
+    }
   }
 
-  /// Start a WSARecv operation.
-  fn start_wsa_recv(&mut self, id: u64, op: Op) -> io::Result<()> {
-    let (fd, buffer, flags) = match &op {
-      Op::Recv { fd, buffer, flags } => (fd.as_raw_handle(), buffer, *flags),
-      _ => unreachable!(),
-    };
-
-    let socket = fd as SOCKET;
-    self.ensure_associated(fd as HANDLE)?;
-
-    let mut state = IocpOpState::new(id);
-    let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
-
-    let mut wsa_buf = WSABUF { len: len as u32, buf: ptr as *mut _ };
-    let mut bytes_received: u32 = 0;
-    let mut recv_flags: u32 = flags as u32;
-    let overlapped_ptr = state.overlapped_ptr();
-
-    let result = unsafe {
-      WSARecv(
-        socket,
-        &mut wsa_buf,
-        1,
-        &mut bytes_received,
-        &mut recv_flags,
-        overlapped_ptr,
-        None,
-      )
-    };
-
-    self.handle_wsa_result(result, id, state, op)
+  /// Reads past the end of a file or pipe fail, lio reports them as 0 bytes.
+  fn eof_or(result: isize) -> isize {
+    if result == Self::error_result(ERROR_HANDLE_EOF)
+      || result == Self::error_result(ERROR_BROKEN_PIPE)
+    {
+      0
+    } else {
+      result
+    }
   }
 
-  /// Start an accept operation.
-  ///
-  /// Note: AcceptEx requires loading the function pointer via WSAIoctl.
-  /// For simplicity, this implementation uses a blocking accept for now.
-  fn start_accept(&mut self, id: u64, op: Op) -> io::Result<()> {
-    let (fd, addr, len) = match &op {
-      Op::Accept { fd, addr, len } => (fd.as_raw_handle(), *addr, *len),
+  /// Start a read or write on an associated handle.
+  fn start_io(&mut self, id: u64, op: Op) {
+    let (handle, buffer, is_read) = match &op {
+      Op::Read { fd, buffer, .. } | Op::ReadAt { fd, buffer, .. } => {
+        (fd.as_raw_handle(), buffer, true)
+      }
+      Op::Write { fd, buffer } | Op::WriteAt { fd, buffer, .. } => {
+        (fd.as_raw_handle(), buffer, false)
+      }
       _ => unreachable!(),
     };
 
-    let socket = fd as SOCKET;
+    if !self.associate(handle) {
+      let result = Self::run_blocking(&op);
+      self.immediate.push(OpCompleted::new(id, result));
+      return;
+    }
 
-    // Use blocking accept for now (AcceptEx requires more setup)
-    let result = unsafe {
-      windows_sys::Win32::Networking::WinSock::accept(
-        socket,
-        addr as *mut SOCKADDR,
-        len as *mut i32,
-      )
-    };
+    let mut state = IocpOpState::new(id, Self::offset(&op));
+    // SAFETY: ErasedBuffer stores RawBuf set by into_op.
+    let RawBuf { ptr, len } = unsafe { buffer.peek::<RawBuf>() };
+    let len = len.min(u32::MAX as usize) as u32;
 
-    let completion_result = if result == INVALID_SOCKET {
-      Self::error_result(unsafe { WSAGetLastError() } as u32)
+    // The byte count is reported by the completion packet.
+    let ok = if is_read {
+      // SAFETY: The handle is associated and open, ptr/len stay valid and
+      // state stays at the same address until the completion is dequeued.
+      unsafe {
+        ReadFile(
+          handle,
+          ptr.cast(),
+          len,
+          ptr::null_mut(),
+          &mut state.overlapped,
+        )
+      }
     } else {
-      result as isize
-    };
-
-    self
-      .immediate
-      .push(ImmediateCompletion { op_id: id, result: completion_result });
-
-    Ok(())
-  }
-
-  /// Start a connect operation.
-  ///
-  /// Note: ConnectEx requires loading the function pointer and pre-binding.
-  /// For simplicity, this implementation uses blocking connect for now.
-  fn start_connect(&mut self, id: u64, op: Op) -> io::Result<()> {
-    let (fd, addr, len, _connect_called) = match &op {
-      Op::Connect { fd, addr, len, connect_called } => {
-        (fd.as_raw_handle(), addr, *len, *connect_called)
+      // SAFETY: Same as above.
+      unsafe {
+        WriteFile(
+          handle,
+          ptr.cast_const().cast(),
+          len,
+          ptr::null_mut(),
+          &mut state.overlapped,
+        )
       }
-      _ => unreachable!(),
     };
 
-    let socket = fd as SOCKET;
-    let (addr_ptr, _) = sockaddr_storage_to_ptr(addr);
-
-    let result = unsafe { connect(socket, addr_ptr, len as i32) };
-
-    let completion_result = if result == SOCKET_ERROR {
-      let error = unsafe { WSAGetLastError() };
-      // WSAEWOULDBLOCK means connect is in progress for non-blocking sockets
-      if error == windows_sys::Win32::Networking::WinSock::WSAEWOULDBLOCK as i32
-      {
-        // For true async, we'd need to poll for completion
-        // For now, return in-progress as error
-        Self::error_result(error as u32)
-      } else {
-        Self::error_result(error as u32)
-      }
+    // A packet is queued both when the call finishes right away and when it
+    // is pending.
+    // SAFETY: Reads the calling thread's last error.
+    if ok != FALSE || unsafe { GetLastError() } == ERROR_IO_PENDING {
+      self.in_flight.insert(id, InFlight { state, op });
     } else {
-      0
-    };
-
-    self
-      .immediate
-      .push(ImmediateCompletion { op_id: id, result: completion_result });
-
-    Ok(())
+      let result = Self::last_error();
+      let result = if is_read { Self::eof_or(result) } else { result };
+      self.immediate.push(OpCompleted::new(id, result));
+    }
   }
 
   /// Start a timer operation using the timer queue.
-  fn start_timer(&mut self, id: u64, op: Op) -> io::Result<()> {
-    let duration = match &op {
-      Op::Timeout { duration, .. } => *duration,
-      _ => unreachable!(),
-    };
-
-    let due_time = duration.as_millis() as u32;
-    let mut timer_handle: HANDLE = 0;
-
-    // Create a timer that posts to our IOCP on expiry
-    let port = self.port();
-
-    // We need to pass the op_id to the callback
-    // Store the id in a box that will be passed as context
-    let context =
-      Box::into_raw(Box::new(TimerCallbackContext { port, op_id: id }));
-
+  fn start_timer(&mut self, id: u64, duration: Duration) -> io::Result<()> {
+    let due_time = duration.as_millis().min(INFINITE as u128 - 1) as u32;
+    let context = Box::into_raw(Box::new(TimerCallbackContext {
+      port: self.port,
+      op_id: id,
+    }));
+
+    let mut timer: HANDLE = ptr::null_mut();
+    // SAFETY: context stays alive until the callback frees it, the default
+    // timer queue is used.
     let result = unsafe {
       CreateTimerQueueTimer(
-        &mut timer_handle,
-        0, // Use default timer queue
+        &mut timer,
+        ptr::null_mut(),
         Some(timer_callback),
-        context as *const _,
+        context.cast_const().cast(),
         due_time,
-        0, // No repeat
+        0,
         WT_EXECUTEONLYONCE,
       )
     };
 
-    if result == 0 {
-      // Clean up context on failure
-      let _ = unsafe { Box::from_raw(context) };
+    if result == FALSE {
+      // SAFETY: The timer wasn't created, context is still owned here.
+      drop(unsafe { Box::from_raw(context) });
       return Err(io::Error::last_os_error());
     }
 
-    self.active_timers.insert(id, TimerState { timer_handle, op_id: id });
-    self.stored_ops.insert(id, op);
-
+    self.timers.insert(id, timer);
     Ok(())
   }
 
-  /// Handle the result of an async Windows file I/O operation.
-  fn handle_async_result(
-    &mut self,
-    result: i32,
-    id: u64,
-    state: Box<IocpOpState>,
-    op: Op,
-  ) -> io::Result<()> {
-    let error = unsafe { GetLastError() };
-
-    if result != 0 {
-      // Operation completed synchronously
-      // The completion will still be posted to the IOCP
-      let addr = &state.overlapped as *const _ as usize;
-      self.active_ops.insert(addr, state);
-      self.stored_ops.insert(id, op);
-      Ok(())
-    } else if error == ERROR_IO_PENDING {
-      // Operation is pending asynchronously
-      let addr = &state.overlapped as *const _ as usize;
-      self.active_ops.insert(addr, state);
-      self.stored_ops.insert(id, op);
-      Ok(())
-    } else {
-      // Error - complete immediately
-      self.immediate.push(ImmediateCompletion {
-        op_id: id,
-        result: Self::error_result(error),
-      });
-      Ok(())
-    }
-  }
-
-  /// Handle the result of an async WSA operation.
-  fn handle_wsa_result(
-    &mut self,
-    result: i32,
-    id: u64,
-    state: Box<IocpOpState>,
-    op: Op,
-  ) -> io::Result<()> {
-    if result == 0 {
-      // Operation completed synchronously or is pending
-      let addr = &state.overlapped as *const _ as usize;
-      self.active_ops.insert(addr, state);
-      self.stored_ops.insert(id, op);
-      Ok(())
-    } else {
-      let error = unsafe { WSAGetLastError() };
-      if error == WSA_IO_PENDING as i32 {
-        // Operation is pending
-        let addr = &state.overlapped as *const _ as usize;
-        self.active_ops.insert(addr, state);
-        self.stored_ops.insert(id, op);
-        Ok(())
-      } else {
-        // Error - complete immediately
-        self.immediate.push(ImmediateCompletion {
-          op_id: id,
-          result: Self::error_result(error as u32),
-        });
-        Ok(())
-      }
-    }
-  }
-
-  /// Poll for completions with optional timeout.
-  fn poll_inner(
-    &mut self,
-    timeout_duration: Option<Duration>,
-  ) -> io::Result<&[OpCompleted]> {
-    self.completed.clear();
-
-    // First, drain any immediate completions
-    for result in self.immediate.drain(..) {
-      self.completed.push(OpCompleted::new(result.op_id, result.result));
-    }
-
-    // Calculate timeout for GetQueuedCompletionStatus
-    let timeout = match timeout_duration {
-      None => u32::MAX, // INFINITE
-      Some(d) => d.as_millis().min(u32::MAX as u128 - 1) as u32,
-    };
-
-    let is_blocking = timeout_duration != Some(Duration::ZERO);
-
-    loop {
-      let mut bytes_transferred: u32 = 0;
-      let mut completion_key: usize = 0;
-      let mut overlapped_ptr: *mut OVERLAPPED = ptr::null_mut();
-
-      let success = unsafe {
-        GetQueuedCompletionStatus(
-          self.port(),
-          &mut bytes_transferred,
-          &mut completion_key,
-          &mut overlapped_ptr,
-          if self.completed.is_empty() { timeout } else { 0 },
-        )
-      };
-
-      // Check for timeout or error with no completion
-      if success == FALSE && overlapped_ptr.is_null() {
-        let error = unsafe { GetLastError() };
-        if error == WAIT_TIMEOUT {
-          break;
-        }
-        return Err(io::Error::from_raw_os_error(error as i32));
-      }
-
-      // Handle wake-up notifications
-      if completion_key == NOTIFY_KEY {
-        if is_blocking && !self.completed.is_empty() {
-          break;
-        }
-        continue;
-      }
-
-      // Handle timer completions
-      if completion_key == TIMER_KEY {
-        let op_id = bytes_transferred as u64;
-        if let Some(timer_state) = self.active_timers.remove(&op_id) {
-          // Delete the timer
+  /// Turns a dequeued packet into a completion.
+  fn complete(&mut self, entry: &OVERLAPPED_ENTRY) {
+    match entry.lpCompletionKey {
+      NOTIFY_KEY => {}
+      TIMER_KEY => {
+        let id = entry.lpOverlapped as usize as u64;
+        // A cancelled timer may still have posted its expiry.
+        if let Some(timer) = self.timers.remove(&id) {
+          // SAFETY: timer was created by start_timer. The one-shot callback has
+          // run, so this doesn't wait.
           unsafe {
-            DeleteTimerQueueTimer(0, timer_state.timer_handle, 0);
-          }
-          // Remove the stored op
-          self.stored_ops.remove(&op_id);
-          self.completed.push(OpCompleted::new(op_id, 0));
+            DeleteTimerQueueTimer(ptr::null_mut(), timer, ptr::null_mut())
+          };
+          self.completed.push(OpCompleted::new(id, 0));
         }
-        continue;
       }
-
-      // Look up the operation from the OVERLAPPED pointer
-      let overlapped_addr = overlapped_ptr as usize;
-      if let Some(state) = self.active_ops.remove(&overlapped_addr) {
-        let op_id = state.op_id;
-
-        // Remove stored op (releases buffer ownership)
-        self.stored_ops.remove(&op_id);
-
-        let result = if success != FALSE {
-          bytes_transferred as isize
+      _ => {
+        // SAFETY: Packets with IO_KEY carry the OVERLAPPED of an in-flight
+        // IocpOpState, which is its first field.
+        let id = unsafe { (*entry.lpOverlapped.cast::<IocpOpState>()).op_id };
+        let Some(InFlight { state, op }) = self.in_flight.remove(&id) else {
+          return;
+        };
+        // Internal holds the NTSTATUS of the finished request.
+        let status = state.overlapped.Internal as i32;
+        let result = if status == 0 {
+          entry.dwNumberOfBytesTransferred as isize
         } else {
-          let error = unsafe { GetLastError() };
-          Self::error_result(error)
+          // SAFETY: Pure conversion between status codes.
+          let result =
+            Self::error_result(unsafe { RtlNtStatusToDosError(status) });
+          if matches!(op, Op::Read { .. } | Op::ReadAt { .. }) {
+            Self::eof_or(result)
+          } else {
+            result
+          }
         };
-
-        self.completed.push(OpCompleted::new(op_id, result));
-      }
-
-      // For blocking wait, try to get more completions without blocking
-      if is_blocking && !self.completed.is_empty() {
-        break;
-      }
-
-      // For non-blocking poll, only process one then check for more
-      if !is_blocking {
-        break;
+        self.completed.push(OpCompleted::new(id, result));
       }
     }
+  }
 
-    // Drain any additional ready completions
-    if !self.completed.is_empty() {
-      loop {
-        let mut bytes_transferred: u32 = 0;
-        let mut completion_key: usize = 0;
-        let mut overlapped_ptr: *mut OVERLAPPED = ptr::null_mut();
-
-        let success = unsafe {
-          GetQueuedCompletionStatus(
-            self.port(),
-            &mut bytes_transferred,
-            &mut completion_key,
-            &mut overlapped_ptr,
-            0, // Non-blocking
-          )
-        };
-
-        if success == FALSE && overlapped_ptr.is_null() {
-          break;
-        }
-
-        if completion_key == NOTIFY_KEY {
-          continue;
-        }
-
-        if completion_key == TIMER_KEY {
-          let op_id = bytes_transferred as u64;
-          if let Some(timer_state) = self.active_timers.remove(&op_id) {
-            unsafe {
-              DeleteTimerQueueTimer(0, timer_state.timer_handle, 0);
-            }
-            self.stored_ops.remove(&op_id);
-            self.completed.push(OpCompleted::new(op_id, 0));
-          }
-          continue;
-        }
-
-        let overlapped_addr = overlapped_ptr as usize;
-        if let Some(state) = self.active_ops.remove(&overlapped_addr) {
-          let op_id = state.op_id;
-          self.stored_ops.remove(&op_id);
-
-          let result = if success != FALSE {
-            bytes_transferred as isize
-          } else {
-            let error = unsafe { GetLastError() };
-            Self::error_result(error)
-          };
-
-          self.completed.push(OpCompleted::new(op_id, result));
-        }
+  /// Dequeues up to `entries.len()` packets, waiting at most `ms`.
+  fn dequeue(&mut self, ms: u32) -> io::Result<()> {
+    let mut removed = 0u32;
+    // SAFETY: entries is valid for entries.len() writes, port is valid.
+    let ok = unsafe {
+      GetQueuedCompletionStatusEx(
+        self.port,
+        self.entries.as_mut_ptr(),
+        self.entries.len() as u32,
+        &mut removed,
+        ms,
+        FALSE,
+      )
+    };
+    if ok == FALSE {
+      // SAFETY: Reads the calling thread's last error.
+      let error = unsafe { GetLastError() };
+      if error == WAIT_TIMEOUT {
+        return Ok(());
       }
+      return Err(io::Error::from_raw_os_error(error as i32));
     }
-
-    Ok(&self.completed)
+    for i in 0..removed as usize {
+      let entry = self.entries[i];
+      self.complete(&entry);
+    }
+    Ok(())
   }
 }
 
 impl Drop for Iocp {
   fn drop(&mut self) {
-    // Clean up any active timers
-    for (_, timer_state) in self.active_timers.drain() {
-      unsafe {
-        DeleteTimerQueueTimer(
-          0,
-          timer_state.timer_handle,
-          INVALID_HANDLE_VALUE,
-        );
-      }
+    if self.port.is_null() {
+      return;
     }
 
-    // Close the completion port
-    if let Some(port) = self.port {
+    for (_, timer) in self.timers.drain() {
+      // SAFETY: timer was created by start_timer, INVALID_HANDLE_VALUE waits
+      // for a running callback so the port outlives it.
       unsafe {
-        CloseHandle(port);
+        DeleteTimerQueueTimer(ptr::null_mut(), timer, INVALID_HANDLE_VALUE)
+      };
+    }
+
+    // The kernel writes into in-flight buffers until their packet is
+    // dequeued, so cancel them and wait for every packet.
+    for InFlight { state, op } in self.in_flight.values() {
+      if let Some(fd) = op.resource() {
+        // SAFETY: The handle is kept open by the op, the OVERLAPPED is ours.
+        unsafe { CancelIoEx(fd.as_raw_handle(), &state.overlapped) };
       }
     }
+    if self.entries.is_empty() {
+      // SAFETY: OVERLAPPED_ENTRY is plain data.
+      self.entries.push(unsafe { std::mem::zeroed() });
+    }
+    while !self.in_flight.is_empty() {
+      if self.dequeue(INFINITE).is_err() {
+        // Leak what's left rather than free memory the kernel still uses.
+        std::mem::forget(std::mem::take(&mut self.in_flight));
+        break;
+      }
+    }
+
+    // SAFETY: The port is ours and nothing is queued on it anymore.
+    unsafe { CloseHandle(self.port) };
   }
 }
 
 impl IoBackend for Iocp {
   fn init(&mut self, cap: usize) -> io::Result<()> {
-    // Initialize WSA
-    self.ensure_wsa()?;
-
-    // Create the I/O completion port
+    // SAFETY: Creates a new port, no handle is associated.
     let port = unsafe {
-      CreateIoCompletionPort(
-        INVALID_HANDLE_VALUE,
-        0, // No existing port
-        0, // Completion key (unused for creation)
-        0, // Concurrent threads (0 = system decides)
-      )
+      CreateIoCompletionPort(INVALID_HANDLE_VALUE, ptr::null_mut(), 0, 0)
     };
-
-    if port == 0 {
+    if port.is_null() {
       return Err(io::Error::last_os_error());
     }
 
-    self.port = Some(port);
-    self.associated_handles = HashSet::with_capacity(cap);
-    self.active_ops = HashMap::with_capacity(cap);
-    self.stored_ops = HashMap::with_capacity(cap);
-    self.active_timers = HashMap::with_capacity(16);
+    self.port = port;
+    self.associated = HashSet::with_capacity(cap);
+    self.in_flight = HashMap::with_capacity(cap);
+    self.timers = HashMap::with_capacity(16);
     self.immediate = Vec::with_capacity(64);
+    // SAFETY: OVERLAPPED_ENTRY is plain data, entries are only read after the
+    // kernel filled them.
+    self.entries = vec![unsafe { std::mem::zeroed() }; cap.clamp(1, 256)];
     self.completed = Vec::with_capacity(cap.min(256));
 
     Ok(())
@@ -889,32 +558,44 @@ impl IoBackend for Iocp {
 
   fn push(&mut self, id: u64, op: Op) -> io::Result<()> {
     match &op {
-      // Native IOCP operations
-      Op::Read { .. } | Op::ReadAt { .. } => self.start_read(id, op),
-      Op::Write { .. } | Op::WriteAt { .. } => self.start_write(id, op),
-      Op::Send { .. } => self.start_wsa_send(id, op),
-      Op::Recv { .. } => self.start_wsa_recv(id, op),
-      Op::Accept { .. } => self.start_accept(id, op),
-      Op::Connect { .. } => self.start_connect(id, op),
-      Op::Timeout { .. } => self.start_timer(id, op),
-
-      // Blocking operations
-      Op::Socket { .. }
-      | Op::Bind { .. }
-      | Op::Listen { .. }
-      | Op::Close { .. }
-      | Op::Fsync { .. }
-      | Op::Truncate { .. }
-      | Op::Shutdown { .. }
-      | Op::OpenAt { .. }
-      | Op::LinkAt { .. }
-      | Op::SymlinkAt { .. }
-      | Op::Nop => {
+      Op::Read { .. }
+      | Op::ReadAt { .. }
+      | Op::Write { .. }
+      | Op::WriteAt { .. } => self.start_io(id, op),
+      Op::Timeout { duration } => self.start_timer(id, *duration)?,
+      Op::Close { handle, .. } => {
+        // The handle value may be reused by the next open.
+        self.associated.remove(&(*handle as usize));
         let result = Self::run_blocking(&op);
-        self.immediate.push(ImmediateCompletion { op_id: id, result });
-        Ok(())
+        self.immediate.push(OpCompleted::new(id, result));
+      }
+      _ => {
+        let result = Self::run_blocking(&op);
+        self.immediate.push(OpCompleted::new(id, result));
       }
     }
+    Ok(())
+  }
+
+  fn cancel(&mut self, id: u64) -> io::Result<()> {
+    if let Some(timer) = self.timers.remove(&id) {
+      // SAFETY: timer was created by start_timer, INVALID_HANDLE_VALUE waits
+      // for a running callback.
+      unsafe {
+        DeleteTimerQueueTimer(ptr::null_mut(), timer, INVALID_HANDLE_VALUE)
+      };
+      self.immediate.push(OpCompleted::new(
+        id,
+        Self::error_result(ERROR_OPERATION_ABORTED),
+      ));
+    } else if let Some(InFlight { state, op }) = self.in_flight.get(&id) {
+      // The aborted request still posts its packet.
+      if let Some(fd) = op.resource() {
+        // SAFETY: The handle is kept open by the op, the OVERLAPPED is ours.
+        unsafe { CancelIoEx(fd.as_raw_handle(), &state.overlapped) };
+      }
+    }
+    Ok(())
   }
 
   fn flush(&mut self) -> io::Result<usize> {
@@ -926,18 +607,17 @@ impl IoBackend for Iocp {
     &mut self,
     timeout: Option<Duration>,
   ) -> io::Result<&[OpCompleted]> {
-    self.poll_inner(timeout)
-  }
-}
+    self.completed.clear();
+    self.completed.append(&mut self.immediate);
 
-/// Wake up a blocked wait() call.
-impl Iocp {
-  pub fn notify(&self) -> io::Result<()> {
-    let success = unsafe {
-      PostQueuedCompletionStatus(self.port(), 0, NOTIFY_KEY, ptr::null_mut())
+    let ms = match timeout {
+      _ if !self.completed.is_empty() => 0,
+      None => INFINITE,
+      Some(d) => d.as_millis().min(INFINITE as u128 - 1) as u32,
     };
+    self.dequeue(ms)?;
 
-    if success == FALSE { Err(io::Error::last_os_error()) } else { Ok(()) }
+    Ok(&self.completed)
   }
 }
 
@@ -945,47 +625,52 @@ impl Iocp {
 // Helper types and functions
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// Maps `open(2)` flags onto `CreateFileA`'s access and creation disposition.
+fn open_flags(flags: i32) -> (u32, u32) {
+  let access = match flags & (libc::O_RDONLY | libc::O_WRONLY | libc::O_RDWR) {
+    libc::O_WRONLY => GENERIC_WRITE,
+    libc::O_RDWR => GENERIC_READ | GENERIC_WRITE,
+    _ => GENERIC_READ,
+  };
+  let access = if flags & libc::O_APPEND != 0 {
+    access | FILE_APPEND_DATA
+  } else {
+    access
+  };
+
+  let creat = flags & libc::O_CREAT != 0;
+  let disposition =
+    match (creat, flags & libc::O_EXCL != 0, flags & libc::O_TRUNC != 0) {
+      (true, true, _) => CREATE_NEW,
+      (true, false, true) => CREATE_ALWAYS,
+      (true, false, false) => OPEN_ALWAYS,
+      (false, _, true) => TRUNCATE_EXISTING,
+      (false, _, false) => OPEN_EXISTING,
+    };
+  (access, disposition)
+}
+
 /// Context passed to timer callback.
-#[repr(C)]
 struct TimerCallbackContext {
   port: HANDLE,
   op_id: u64,
 }
 
 /// Timer callback function - posts completion to IOCP.
-unsafe extern "system" fn timer_callback(
-  context: *mut std::ffi::c_void,
-  _timer_or_wait_fired: u8,
-) {
-  let ctx = &*(context as *const TimerCallbackContext);
-
-  // Post completion to the IOCP
-  // Use TIMER_KEY as completion key and op_id as bytes_transferred
-  PostQueuedCompletionStatus(
-    ctx.port,
-    ctx.op_id as u32,
-    TIMER_KEY,
-    ptr::null_mut(),
-  );
-
-  // Clean up the context
-  let _ = Box::from_raw(context as *mut TimerCallbackContext);
-}
-
-/// Convert sockaddr_storage to pointer and length for Windows APIs.
-fn sockaddr_storage_to_ptr(
-  addr: &libc::sockaddr_storage,
-) -> (*const SOCKADDR, i32) {
-  // Determine the actual address length based on family
-  let len = if addr.ss_family == AF_INET as u16 {
-    std::mem::size_of::<SOCKADDR_IN>() as i32
-  } else if addr.ss_family == AF_INET6 as u16 {
-    std::mem::size_of::<SOCKADDR_IN6>() as i32
-  } else {
-    std::mem::size_of::<SOCKADDR_STORAGE>() as i32
+unsafe extern "system" fn timer_callback(context: *mut c_void, _fired: u8) {
+  // SAFETY: context was created by start_timer and the one-shot callback is
+  // its only user.
+  let ctx = unsafe { Box::from_raw(context.cast::<TimerCallbackContext>()) };
+
+  // SAFETY: The port outlives its timers, see Drop.
+  unsafe {
+    PostQueuedCompletionStatus(
+      ctx.port,
+      0,
+      TIMER_KEY,
+      ctx.op_id as usize as *const OVERLAPPED,
+    )
   };
-
-  (addr as *const _ as *const SOCKADDR, len)
 }
 
 #[cfg(test)]
@@ -1019,4 +704,20 @@ mod tests {
     assert_eq!(completions[0].op_id, 1);
     assert_eq!(completions[0].result, 0);
   }
+
+  #[test]
+  fn test_timeout() {
+    let mut backend = Iocp::new();
+    backend.init(64).unwrap();
+
+    backend
+      .push(7, Op::Timeout { duration: Duration::from_millis(10) })
+      .unwrap();
+
+    let completions =
+      backend.wait_timeout(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].op_id, 7);
+    assert_eq!(completions[0].result, 0);
+  }
 }
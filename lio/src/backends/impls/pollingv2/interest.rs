@@ -13,6 +13,10 @@ impl Interest {
   pub const READ: Self = Self { bits: 1 << 0 };
   pub const WRITE: Self = Self { bits: 1 << 1 };
   pub const TIMER: Self = Self { bits: 1 << 2 };
+  /// The peer hung up (`EPOLLHUP`/`EPOLLRDHUP`, kqueue `EV_EOF`).
+  pub const HANGUP: Self = Self { bits: 1 << 3 };
+  /// An error is pending on the fd (`EPOLLERR`, kqueue `EV_ERROR`).
+  pub const ERROR: Self = Self { bits: 1 << 4 };
   pub const READ_AND_WRITE: Self =
     Self { bits: Self::READ.bits | Self::WRITE.bits };
 
@@ -28,6 +32,14 @@ impl Interest {
     self.bits & Self::TIMER.bits != 0
   }
 
+  pub const fn is_hangup(self) -> bool {
+    self.bits & Self::HANGUP.bits != 0
+  }
+
+  pub const fn is_error(self) -> bool {
+    self.bits & Self::ERROR.bits != 0
+  }
+
   pub const fn is_none(self) -> bool {
     self.bits == 0
   }
@@ -24,7 +24,7 @@ use core::slice;
 use std::collections::HashMap;
 use std::io;
 use std::os::fd::RawFd;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Get the current errno value.
 #[inline]
@@ -54,6 +54,86 @@ fn syscall_result_ssize(ret: libc::ssize_t) -> isize {
   if ret < 0 { -(get_errno() as isize) } else { ret }
 }
 
+/// `renameat`, refusing to replace an existing `new_path` if `noreplace` is
+/// set. Platforms without an atomic way to do that fail with `EINVAL`.
+///
+/// # Safety
+///
+/// Both paths must be valid C strings.
+unsafe fn renameat(
+  old_dir: RawFd,
+  old_path: *const libc::c_char,
+  new_dir: RawFd,
+  new_path: *const libc::c_char,
+  flags: u32,
+) -> isize {
+  #[cfg(target_os = "linux")]
+  {
+    // SAFETY: Upheld by the caller. Called through syscall(2) because musl
+    // lacks a renameat2 wrapper.
+    let ret = unsafe {
+      libc::syscall(
+        libc::SYS_renameat2,
+        old_dir,
+        old_path,
+        new_dir,
+        new_path,
+        flags,
+      )
+    };
+    syscall_result(ret as libc::c_int)
+  }
+  #[cfg(target_vendor = "apple")]
+  {
+    if flags & !crate::api::ops::RENAME_NOREPLACE != 0 {
+      return -(libc::EINVAL as isize);
+    }
+    let flags = if flags != 0 { libc::RENAME_EXCL } else { 0 };
+    // SAFETY: Upheld by the caller.
+    syscall_result(unsafe {
+      libc::renameatx_np(old_dir, old_path, new_dir, new_path, flags)
+    })
+  }
+  #[cfg(not(any(target_os = "linux", target_vendor = "apple")))]
+  {
+    if flags != 0 {
+      return -(libc::EINVAL as isize);
+    }
+    // SAFETY: Upheld by the caller.
+    syscall_result(unsafe {
+      libc::renameat(old_dir, old_path, new_dir, new_path)
+    })
+  }
+}
+
+/// `splice`, taking io_uring's `-1` for "no offset" instead of a null
+/// pointer.
+#[cfg(target_os = "linux")]
+fn splice(
+  fd_in: RawFd,
+  mut off_in: i64,
+  fd_out: RawFd,
+  mut off_out: i64,
+  len: u32,
+  flags: u32,
+) -> isize {
+  fn offset(off: &mut i64) -> *mut libc::loff_t {
+    if *off == -1 { std::ptr::null_mut() } else { off }
+  }
+  // SAFETY: The offsets are null or point to locals that outlive the call.
+  syscall_result_ssize(unsafe {
+    libc::splice(
+      fd_in,
+      offset(&mut off_in),
+      fd_out,
+      offset(&mut off_out),
+      len as libc::size_t,
+      flags,
+    )
+  })
+}
+
+use crate::api::ops::so_error;
 use crate::backends::pollingv2::interest::Interest;
 use crate::backends::{IoBackend, OpCompleted};
 // use crate::operation::Operation;
@@ -261,6 +341,9 @@ pub struct Poller {
   events: Events,
   /// Immediate completions (operations that completed without polling)
   immediate: Vec<ImmediateCompletion>,
+  /// Deadlines of ops pushed with a timeout. Entries of ops that already
+  /// completed are dropped on the next expiry check.
+  deadlines: Vec<(Instant, u64)>,
 
   /// Reusing the completed allocation.
   completed: Vec<OpCompleted>,
@@ -282,6 +365,45 @@ impl Poller {
     self.fd_map.as_mut().expect("Poller not initialized - call init() first")
   }
 
+  /// Drops a pending op and stops polling for it.
+  ///
+  /// Returns whether the op was still pending.
+  fn unregister(&mut self, id: u64) -> io::Result<bool> {
+    let Some(op) = self.op_map.remove(&id) else {
+      return Ok(false);
+    };
+    if let Some(fd) = self.fd_map().remove(&id) {
+      if matches!(op, crate::op::Op::Timeout { .. }) {
+        self.sys().delete_timer(id)?;
+      } else {
+        self.sys().delete(fd)?;
+      }
+    }
+    Ok(true)
+  }
+
+  /// Completes ops whose deadline passed with `-ETIMEDOUT` and stops
+  /// polling their fds.
+  fn expire_deadlines(&mut self) -> io::Result<()> {
+    let now = Instant::now();
+    let mut i = 0;
+    while i < self.deadlines.len() {
+      let (at, id) = self.deadlines[i];
+      if !self.op_map.contains_key(&id) {
+        self.deadlines.swap_remove(i);
+        continue;
+      }
+      if at > now {
+        i += 1;
+        continue;
+      }
+      self.deadlines.swap_remove(i);
+      self.unregister(id)?;
+      self.completed.push(OpCompleted::new(id, -(libc::ETIMEDOUT as isize)));
+    }
+    Ok(())
+  }
+
   /// Run an op by reference using peek (no ownership transfer of buffers).
   /// Used in wait_timeout so the op can be put back in op_map on EAGAIN.
   fn run_op_on_event(op: &crate::op::Op) -> isize {
@@ -289,7 +411,7 @@ impl Poller {
     use std::os::fd::AsRawFd;
 
     match op {
-      Op::Read { fd, buffer } => {
+      Op::Read { fd, buffer, .. } => {
         let fd = fd.as_raw_fd();
         // SAFETY: ErasedBuffer stores RawBuf set by into_op
         let crate::op::RawBuf { ptr, len } =
@@ -322,7 +444,8 @@ impl Poller {
           libc::pwrite(fd, ptr as *const _, len, *offset)
         })
       }
-      Op::Send { fd, flags, buffer } => {
+      // No zero-copy here, the buffer is copied like for a plain send.
+      Op::Send { fd, flags, buffer } | Op::SendZc { fd, flags, buffer, .. } => {
         let fd = fd.as_raw_fd();
         // SAFETY: ErasedBuffer stores (ptr, len) tuple set by into_op.
         let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
@@ -331,7 +454,7 @@ impl Poller {
           libc::send(fd, ptr as *const _, len, *flags)
         })
       }
-      Op::Recv { fd, flags, buffer } => {
+      Op::Recv { fd, flags, buffer, .. } => {
         let fd = fd.as_raw_fd();
         // SAFETY: ErasedBuffer stores (ptr, len) tuple set by into_op.
         let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
@@ -340,40 +463,80 @@ impl Poller {
           libc::recv(fd, ptr as *mut _, len, *flags)
         })
       }
+      // SAFETY: fd is valid (from AsRawFd), msg points into the boxed TypedOp.
+      Op::SendMsg { fd, msg } => unsafe {
+        syscall_result_ssize(libc::sendmsg(fd.as_raw_fd(), *msg, 0))
+      },
+      // SAFETY: fd is valid (from AsRawFd), msg points into the boxed TypedOp.
+      Op::RecvMsg { fd, msg } => unsafe {
+        syscall_result_ssize(libc::recvmsg(fd.as_raw_fd(), *msg, 0))
+      },
       // SAFETY: fd is valid (from AsRawFd), addr/len are valid pointers from Op.
       Op::Accept { fd, addr, len } => unsafe {
         syscall_result(libc::accept(fd.as_raw_fd(), *addr as *mut _, *len))
       },
+      // SAFETY: fd is valid (from AsRawFd), no address is requested.
+      Op::AcceptMultishot { fd } => unsafe {
+        syscall_result(libc::accept(
+          fd.as_raw_fd(),
+          std::ptr::null_mut(),
+          std::ptr::null_mut(),
+        ))
+      },
       Op::Timeout { .. } => 0,
-      Op::Connect { fd, addr, len, connect_called } => {
-        let fd = fd.as_raw_fd();
-        // SAFETY: fd is valid (from AsRawFd), addr is valid pointer from TypedOp.
-        unsafe {
-          let ret = libc::connect(fd, *addr as *const libc::sockaddr, *len);
-          if ret < 0 {
-            let err = get_errno();
-            if err == libc::EINPROGRESS {
-              return -(err as isize);
-            }
-            // EISCONN means success only when already in the EINPROGRESS flow
-            if err == libc::EISCONN && *connect_called {
-              return 0;
-            }
-            return -(err as isize);
-          }
-          ret as isize
+      Op::Poll { fd, events } | Op::PollMultishot { fd, events } => {
+        let mut pfd =
+          libc::pollfd { fd: fd.as_raw_fd(), events: *events, revents: 0 };
+        // SAFETY: pfd is a valid pollfd, a zero timeout never blocks.
+        let ret = unsafe { libc::poll(&mut pfd, 1, 0) };
+        match ret {
+          // Spurious wakeup, re-arm.
+          0 => -(libc::EAGAIN as isize),
+          _ if ret < 0 => -(get_errno() as isize),
+          _ => pfd.revents as isize,
         }
       }
+      // Writable after EINPROGRESS: the attempt finished and SO_ERROR holds
+      // its outcome. Calling connect again reports it differently per
+      // platform.
+      Op::Connect { fd, .. } => match so_error(fd.as_raw_fd()) {
+        Ok(errno) => -(errno as isize),
+        Err(err) => -(err.raw_os_error().unwrap_or(libc::EIO) as isize),
+      },
+      #[cfg(target_os = "linux")]
+      // SAFETY: fd_in/fd_out are valid (from AsRawFd), size is a valid length.
+      Op::Tee { fd_in, fd_out, size } => unsafe {
+        // The pipe was reported ready, but another reader may have drained it
+        // since; never block the event loop in that case.
+        syscall_result_ssize(libc::tee(
+          fd_in.as_raw_fd(),
+          fd_out.as_raw_fd(),
+          *size as libc::size_t,
+          libc::SPLICE_F_NONBLOCK,
+        ))
+      },
+      #[cfg(target_os = "linux")]
+      Op::Splice { fd_in, off_in, fd_out, off_out, len, flags } => splice(
+        fd_in.as_raw_fd(),
+        *off_in,
+        fd_out.as_raw_fd(),
+        *off_out,
+        *len,
+        // Same as tee, the pipe may have been drained since it was ready.
+        *flags | libc::SPLICE_F_NONBLOCK,
+      ),
       _ => panic!("run_op_on_event called for non-event op"),
     }
   }
 
-  fn run_op_blocking(op: crate::op::Op) -> isize {
+  /// Runs `op` as its blocking syscall. The io_uring backend uses it too,
+  /// for opcodes the kernel lacks.
+  pub(crate) fn run_op_blocking(op: crate::op::Op) -> isize {
     use crate::op::Op;
     use std::os::fd::AsRawFd;
 
     match op {
-      Op::Read { fd, buffer } => {
+      Op::Read { fd, buffer, .. } => {
         let fd = fd.as_raw_fd();
         // SAFETY: ErasedBuffer stores (ptr, len) tuple set by into_op.
         let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
@@ -405,7 +568,8 @@ impl Poller {
           libc::pwrite(fd, ptr as *const _, len, offset)
         })
       }
-      Op::Send { fd, flags, buffer } => {
+      // No zero-copy here, the buffer is copied like for a plain send.
+      Op::Send { fd, flags, buffer } | Op::SendZc { fd, flags, buffer, .. } => {
         let fd = fd.as_raw_fd();
         // SAFETY: ErasedBuffer stores (ptr, len) tuple set by into_op.
         let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
@@ -414,7 +578,7 @@ impl Poller {
           libc::send(fd, ptr as *const _, len, flags)
         })
       }
-      Op::Recv { fd, flags, buffer } => {
+      Op::Recv { fd, flags, buffer, .. } => {
         let fd = fd.as_raw_fd();
         // SAFETY: ErasedBuffer stores (ptr, len) tuple set by into_op.
         let (ptr, len) = unsafe { buffer.peek::<(*mut u8, usize)>() };
@@ -423,10 +587,40 @@ impl Poller {
           libc::recv(fd, ptr as *mut _, len, flags)
         })
       }
+      // SAFETY: fd is valid (from AsRawFd), msg points into the boxed TypedOp.
+      Op::SendMsg { fd, msg } => unsafe {
+        syscall_result_ssize(libc::sendmsg(fd.as_raw_fd(), msg, 0))
+      },
+      // SAFETY: fd is valid (from AsRawFd), msg points into the boxed TypedOp.
+      Op::RecvMsg { fd, msg } => unsafe {
+        syscall_result_ssize(libc::recvmsg(fd.as_raw_fd(), msg, 0))
+      },
+      // SAFETY: fd is valid (from AsRawFd), iov points to iovcnt iovecs owned by the TypedOp.
+      Op::Readv { fd, iov, iovcnt } => unsafe {
+        crate::api::ops::readv_chunked(
+          fd.as_raw_fd(),
+          std::slice::from_raw_parts(iov, iovcnt),
+        )
+      },
+      // SAFETY: fd is valid (from AsRawFd), iov points to iovcnt iovecs owned by the TypedOp.
+      Op::Writev { fd, iov, iovcnt } => unsafe {
+        crate::api::ops::writev_chunked(
+          fd.as_raw_fd(),
+          std::slice::from_raw_parts(iov, iovcnt),
+        )
+      },
       // SAFETY: fd is valid (from AsRawFd), addr/len are valid pointers from Op.
       Op::Accept { fd, addr, len } => unsafe {
         syscall_result(libc::accept(fd.as_raw_fd(), addr as *mut _, len))
       },
+      // SAFETY: fd is valid (from AsRawFd), no address is requested.
+      Op::AcceptMultishot { fd } => unsafe {
+        syscall_result(libc::accept(
+          fd.as_raw_fd(),
+          std::ptr::null_mut(),
+          std::ptr::null_mut(),
+        ))
+      },
       Op::Connect { fd, addr, len, connect_called } => {
         let fd = fd.as_raw_fd();
         // SAFETY: fd is valid (from AsRawFd), addr is valid pointer from TypedOp.
@@ -493,6 +687,70 @@ impl Poller {
       Op::SymlinkAt { target, linkpath, dir_fd } => unsafe {
         syscall_result(libc::symlinkat(target, dir_fd.as_raw_fd(), linkpath))
       },
+      Op::RenameAt { old_dir_fd, old_path, new_dir_fd, new_path, flags } => {
+        let (old_dir, new_dir) =
+          (old_dir_fd.as_raw_fd(), new_dir_fd.as_raw_fd());
+        // SAFETY: paths are valid C strings from Op.
+        unsafe { renameat(old_dir, old_path, new_dir, new_path, flags) }
+      }
+      // SAFETY: dir_fd is valid (from AsRawFd), path is a valid C string from Op.
+      Op::MkDirAt { dir_fd, path, mode } => unsafe {
+        syscall_result(libc::mkdirat(
+          dir_fd.as_raw_fd(),
+          path,
+          mode as libc::mode_t,
+        ))
+      },
+      // SAFETY: dir_fd is valid (from AsRawFd), path is a valid C string from Op.
+      Op::UnlinkAt { dir_fd, path, flags } => unsafe {
+        syscall_result(libc::unlinkat(dir_fd.as_raw_fd(), path, flags))
+      },
+      #[cfg(target_os = "linux")]
+      // SAFETY: epfd/fd are valid (from AsRawFd), event points into the boxed
+      // TypedOp.
+      Op::EpollCtl { epfd, fd, op, event } => unsafe {
+        syscall_result(libc::epoll_ctl(
+          epfd.as_raw_fd(),
+          op,
+          fd.as_raw_fd(),
+          event as *mut libc::epoll_event,
+        ))
+      },
+      #[cfg(target_os = "linux")]
+      // SAFETY: path and name are valid C strings, value points to len
+      // writable bytes, all owned by the TypedOp.
+      Op::GetXattr { path, name, value, len } => unsafe {
+        syscall_result_ssize(libc::getxattr(path, name, value, len as usize))
+      },
+      #[cfg(target_os = "linux")]
+      // SAFETY: path and name are valid C strings, value points to len
+      // bytes, all owned by the TypedOp.
+      Op::SetXattr { path, name, value, len, flags } => unsafe {
+        syscall_result(libc::setxattr(path, name, value, len as usize, flags))
+      },
+      #[cfg(target_os = "linux")]
+      // SAFETY: fd is valid (from AsRawFd), name is a valid C string and
+      // value points to len writable bytes, owned by the TypedOp.
+      Op::FGetXattr { fd, name, value, len } => unsafe {
+        syscall_result_ssize(libc::fgetxattr(
+          fd.as_raw_fd(),
+          name,
+          value,
+          len as usize,
+        ))
+      },
+      #[cfg(target_os = "linux")]
+      // SAFETY: fd is valid (from AsRawFd), name is a valid C string and
+      // value points to len bytes, owned by the TypedOp.
+      Op::FSetXattr { fd, name, value, len, flags } => unsafe {
+        syscall_result(libc::fsetxattr(
+          fd.as_raw_fd(),
+          name,
+          value,
+          len as usize,
+          flags,
+        ))
+      },
       #[cfg(target_os = "linux")]
       // SAFETY: fd_in/fd_out are valid (from AsRawFd), size is a valid length.
       Op::Tee { fd_in, fd_out, size } => unsafe {
@@ -503,11 +761,132 @@ impl Poller {
           0,
         ))
       },
+      #[cfg(target_os = "linux")]
+      Op::Splice { fd_in, off_in, fd_out, off_out, len, flags } => splice(
+        fd_in.as_raw_fd(),
+        off_in,
+        fd_out.as_raw_fd(),
+        off_out,
+        len,
+        flags,
+      ),
+      // SAFETY: fd is valid (from AsRawFd).
+      Op::Dup { fd } => unsafe {
+        syscall_result(libc::fcntl(fd.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0))
+      },
+      // SAFETY: both fds are valid (from AsRawFd).
+      Op::Dup2 { old_fd, new_fd } => unsafe {
+        syscall_result(libc::dup2(old_fd.as_raw_fd(), new_fd.as_raw_fd()))
+      },
+      Op::FutexWake { word, count } => word.wake_parked(count) as isize,
+      Op::FutexWait { .. } => unreachable!("parked by push"),
+      Op::WaitId { .. } => unreachable!("moved to the pool by push"),
+      // SAFETY: fds points to two fds in the boxed Pipe TypedOp.
+      Op::Pipe { fds, flags } => unsafe {
+        crate::api::ops::pipe_blocking(fds, flags)
+      },
+      // SAFETY: fd is valid (from AsRawFd), times points to two timespecs in the TypedOp.
+      Op::Futimens { fd, times } => unsafe {
+        syscall_result(libc::futimens(fd.as_raw_fd(), times))
+      },
+      // SAFETY: dir_fd is valid (from AsRawFd), path is a valid C string and
+      // times points to two timespecs, both owned by the TypedOp.
+      Op::UtimensAt { dir_fd, path, times, flags } => unsafe {
+        syscall_result(libc::utimensat(dir_fd.as_raw_fd(), path, times, flags))
+      },
+      #[cfg(target_os = "linux")]
+      // SAFETY: dir_fd is valid (from AsRawFd), path is a valid C string and
+      // buf points to a statx, both owned by the TypedOp.
+      Op::Statx { dir_fd, path, flags, mask, buf } => unsafe {
+        syscall_result(libc::statx(dir_fd.as_raw_fd(), path, flags, mask, buf))
+      },
+      // SAFETY: addr/len describe a mapping owned by the Madvise TypedOp.
+      Op::Madvise { addr, len, advice } => unsafe {
+        syscall_result(libc::madvise(addr.cast(), len, advice))
+      },
+      #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+      Op::Fadvise { fd, offset, len, advice } => {
+        // SAFETY: fd is valid (from AsRawFd).
+        let ret = unsafe {
+          libc::posix_fadvise(
+            fd.as_raw_fd(),
+            offset as libc::off_t,
+            len as libc::off_t,
+            advice,
+          )
+        };
+        // posix_fadvise returns the error number instead of setting errno.
+        -(ret as isize)
+      }
+      #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+      Op::Fadvise { .. } => -(libc::EOPNOTSUPP as isize),
+      #[cfg(target_os = "linux")]
+      // SAFETY: fd is valid (from AsRawFd).
+      Op::Fallocate { fd, offset, len, mode } => unsafe {
+        syscall_result(libc::fallocate(
+          fd.as_raw_fd(),
+          mode,
+          offset as libc::off_t,
+          len as libc::off_t,
+        ))
+      },
+      #[cfg(not(target_os = "linux"))]
+      Op::Fallocate { .. } => -(libc::EOPNOTSUPP as isize),
+      #[cfg(target_os = "linux")]
+      // SAFETY: fd is valid (from AsRawFd).
+      Op::SyncFileRange { fd, offset, len, flags } => unsafe {
+        syscall_result(libc::sync_file_range(
+          fd.as_raw_fd(),
+          offset as libc::off64_t,
+          len as libc::off64_t,
+          flags,
+        ))
+      },
+      // Without sync_file_range the whole file's data is flushed.
+      #[cfg(apple)]
+      // SAFETY: fd is valid (from AsRawFd).
+      Op::SyncFileRange { fd, .. } => unsafe {
+        syscall_result(libc::fsync(fd.as_raw_fd()))
+      },
+      #[cfg(all(not(target_os = "linux"), not(apple)))]
+      // SAFETY: fd is valid (from AsRawFd).
+      Op::SyncFileRange { fd, .. } => unsafe {
+        syscall_result(libc::fdatasync(fd.as_raw_fd()))
+      },
+      Op::Poll { fd, events } | Op::PollMultishot { fd, events } => {
+        let mut pfd = libc::pollfd { fd: fd.as_raw_fd(), events, revents: 0 };
+        // SAFETY: pfd is a valid pollfd for a single fd.
+        let ret = unsafe { libc::poll(&mut pfd, 1, -1) };
+        if ret < 0 { -(get_errno() as isize) } else { pfd.revents as isize }
+      }
+      Op::PendingBytes { fd } => {
+        let mut pending: libc::c_int = 0;
+        // SAFETY: fd is valid (from AsRawFd), FIONREAD writes one c_int.
+        let ret =
+          unsafe { libc::ioctl(fd.as_raw_fd(), libc::FIONREAD, &mut pending) };
+        if ret < 0 { -(get_errno() as isize) } else { pending as isize }
+      }
       Op::Timeout { duration, .. } => {
         std::thread::sleep(duration);
         0
       }
       Op::Nop => 0,
+      #[cfg(target_os = "linux")]
+      Op::AcceptDirect { .. }
+      | Op::RecvFixed { .. }
+      | Op::SendFixed { .. }
+      | Op::InstallFixed { .. }
+      | Op::CloseFixed { .. } => -(libc::EOPNOTSUPP as isize),
+      Op::RegisterBuffers { .. }
+      | Op::ProvideBuffers { .. }
+      | Op::RemoveBuffers { .. }
+      | Op::RecvSelect { .. }
+      | Op::ReadSelect { .. } => -(libc::EOPNOTSUPP as isize),
+      Op::Custom { op } => {
+        // SAFETY: op points into the boxed Custom TypedOp, which outlives the op.
+        let op = unsafe { &*op };
+        op.run_blocking()
+      }
     }
   }
 }
@@ -532,22 +911,85 @@ impl IoBackend for Poller {
       Op::ReadAt { .. }
       | Op::WriteAt { .. }
       | Op::Read { .. }
-      | Op::Write { .. } => {
+      | Op::Write { .. }
+      | Op::Readv { .. } => {
         let result = Poller::run_op_blocking(op);
         self.immediate.push(ImmediateCompletion { id, result });
         return Ok(());
       }
-      Op::Send { fd, .. } => Some((fd.as_raw_fd(), Interest::WRITE)),
+      // Also how sockets send vectored, which must wait for room.
+      Op::Send { fd, .. } | Op::SendZc { fd, .. } | Op::Writev { fd, .. } => {
+        Some((fd.as_raw_fd(), Interest::WRITE))
+      }
       Op::Recv { fd, .. } => Some((fd.as_raw_fd(), Interest::READ)),
-      Op::Accept { fd, .. } => Some((fd.as_raw_fd(), Interest::READ)),
+      Op::SendMsg { fd, .. } => Some((fd.as_raw_fd(), Interest::WRITE)),
+      Op::RecvMsg { fd, .. } => Some((fd.as_raw_fd(), Interest::READ)),
+      Op::Accept { fd, .. } | Op::AcceptMultishot { fd } => {
+        Some((fd.as_raw_fd(), Interest::READ))
+      }
+      Op::Poll { fd, events } | Op::PollMultishot { fd, events } => {
+        let mut interest = Interest::NONE;
+        if events & libc::POLLIN != 0 {
+          interest |= Interest::READ;
+        }
+        if events & libc::POLLOUT != 0 {
+          interest |= Interest::WRITE;
+        }
+        Some((fd.as_raw_fd(), interest))
+      }
       Op::Connect { .. } => None,
+      // There is no futex here, the waiter polls a pipe instead.
+      Op::FutexWait { word, expected, parker } => {
+        // SAFETY: parker points into the boxed op, which outlives the poll.
+        match unsafe { crate::futex::FutexWord::park(word, *expected, *parker) }
+        {
+          Ok(parked) => return self.push(id, parked),
+          Err(result) => {
+            self.immediate.push(ImmediateCompletion { id, result });
+            return Ok(());
+          }
+        }
+      }
+      // Nor is there a waitid, a pool thread waits for the child.
+      Op::WaitId { idtype, id: child, options, info } => {
+        let waited = crate::api::ops::WaitInfo::wait_on_pool(
+          info, *idtype, *child, *options,
+        );
+        return self.push(id, waited);
+      }
       Op::Bind { .. }
       | Op::Listen { .. }
       | Op::Shutdown { .. }
       | Op::OpenAt { .. }
       | Op::Close { .. }
       | Op::Fsync { .. }
-      | Op::Truncate { .. } => {
+      | Op::Truncate { .. }
+      | Op::Fadvise { .. }
+      | Op::Fallocate { .. }
+      | Op::SyncFileRange { .. }
+      | Op::Dup { .. }
+      | Op::Dup2 { .. }
+      | Op::Futimens { .. }
+      | Op::UtimensAt { .. }
+      | Op::Pipe { .. }
+      | Op::FutexWake { .. }
+      | Op::Madvise { .. }
+      | Op::PendingBytes { .. } => {
+        let result = Poller::run_op_blocking(op);
+        self.immediate.push(ImmediateCompletion { id, result });
+        return Ok(());
+      }
+      #[cfg(target_os = "linux")]
+      Op::EpollCtl { .. } => {
+        let result = Poller::run_op_blocking(op);
+        self.immediate.push(ImmediateCompletion { id, result });
+        return Ok(());
+      }
+      #[cfg(target_os = "linux")]
+      Op::GetXattr { .. }
+      | Op::SetXattr { .. }
+      | Op::FGetXattr { .. }
+      | Op::FSetXattr { .. } => {
         let result = Poller::run_op_blocking(op);
         self.immediate.push(ImmediateCompletion { id, result });
         return Ok(());
@@ -556,14 +998,56 @@ impl IoBackend for Poller {
       Op::Tee { fd_in, .. } => {
         Some((fd_in.as_raw_fd(), Interest::READ_AND_WRITE))
       }
+      // Regular files can't be registered but are always ready, so try right
+      // away and only wait if the socket or pipe side isn't.
+      #[cfg(target_os = "linux")]
+      Op::Splice { fd_in, .. } => {
+        let result = Poller::run_op_on_event(&op);
+        if result != -(libc::EAGAIN as isize) {
+          self.immediate.push(ImmediateCompletion { id, result });
+          return Ok(());
+        }
+        Some((fd_in.as_raw_fd(), Interest::READ))
+      }
       Op::Timeout { .. } => None,
-      Op::Nop => {
+      // Fixed files are an io_uring feature, these fail right away.
+      #[cfg(target_os = "linux")]
+      Op::AcceptDirect { .. }
+      | Op::RecvFixed { .. }
+      | Op::SendFixed { .. }
+      | Op::InstallFixed { .. }
+      | Op::CloseFixed { .. } => {
+        let result = Poller::run_op_blocking(op);
+        self.immediate.push(ImmediateCompletion { id, result });
+        return Ok(());
+      }
+      #[cfg(target_os = "linux")]
+      Op::Statx { .. } => {
+        let result = Poller::run_op_blocking(op);
+        self.immediate.push(ImmediateCompletion { id, result });
+        return Ok(());
+      }
+      // So are registered buffers, ops using them run as plain reads.
+      Op::RegisterBuffers { .. }
+      | Op::ProvideBuffers { .. }
+      | Op::RemoveBuffers { .. }
+      | Op::RecvSelect { .. }
+      | Op::ReadSelect { .. } => {
+        let result = Poller::run_op_blocking(op);
+        self.immediate.push(ImmediateCompletion { id, result });
+        return Ok(());
+      }
+      Op::Nop | Op::Custom { .. } => {
         let result = Poller::run_op_blocking(op);
         self.immediate.push(ImmediateCompletion { id, result });
         return Ok(());
       }
       Op::Socket { .. } => None,
-      Op::LinkAt { .. } | Op::SymlinkAt { .. } => {
+      Op::LinkAt { .. }
+      | Op::SymlinkAt { .. }
+      | Op::RenameAt { .. }
+      | Op::MkDirAt { .. }
+      | Op::UnlinkAt { .. } => {
         let result = Poller::run_op_blocking(op);
         self.immediate.push(ImmediateCompletion { id, result });
         return Ok(());
@@ -580,9 +1064,22 @@ impl IoBackend for Poller {
         self.fd_map().remove(&id);
         let op = self.op_map.remove(&id).unwrap();
         let errno = e.raw_os_error().unwrap_or(libc::EIO);
-        // Try the operation anyway - it will fail with a proper error
-        let result = Poller::run_op_blocking(op);
-        let final_result = if result < 0 { result } else { -(errno as isize) };
+        // Try the operation anyway - it will fail with a proper error.
+        // epoll refuses regular files with EPERM, they are always ready.
+        let result = match op {
+          // Anything else could block the loop thread until it's ready.
+          Op::Poll { .. } | Op::PollMultishot { .. }
+            if errno != libc::EPERM =>
+          {
+            -(errno as isize)
+          }
+          op => Poller::run_op_blocking(op),
+        };
+        let final_result = if result < 0 || errno == libc::EPERM {
+          result
+        } else {
+          -(errno as isize)
+        };
         self.immediate.push(ImmediateCompletion { id, result: final_result });
         return Ok(());
       }
@@ -603,7 +1100,7 @@ impl IoBackend for Poller {
           let duration_ms = duration.as_millis() as RawFd;
           self.fd_map().insert(id, duration_ms);
           self.sys().add(duration_ms, id, Interest::TIMER)?;
-          // op stays in op_map; kqueue fires when duration elapses
+          // op stays in op_map; the timer fires when duration elapses
         }
         Op::Socket { .. } => {
           let result =
@@ -617,6 +1114,31 @@ impl IoBackend for Poller {
     Ok(())
   }
 
+  fn push_with_timeout(
+    &mut self,
+    id: u64,
+    op: crate::op::Op,
+    timeout: Duration,
+  ) -> io::Result<()> {
+    self.push(id, op)?;
+    // Only ops waiting on readiness can time out, the rest already ran.
+    if self.op_map.contains_key(&id) {
+      self.deadlines.push((Instant::now() + timeout, id));
+    }
+    Ok(())
+  }
+
+  fn cancel(&mut self, id: u64) -> io::Result<()> {
+    if !self.unregister(id)? {
+      // Already completed.
+      return Ok(());
+    }
+    self
+      .immediate
+      .push(ImmediateCompletion { id, result: -(libc::ECANCELED as isize) });
+    Ok(())
+  }
+
   fn flush(&mut self) -> io::Result<usize> {
     // For epoll/kqueue, operations are registered immediately in push()
     // since each registration is a separate syscall anyway.
@@ -646,6 +1168,15 @@ impl IoBackend for Poller {
     // SAFETY: as_raw_buf() provides mutable access to the entire capacity of the events buffer
     let events = unsafe { self.events.as_raw_buf() };
 
+    // Wake up in time for the nearest deadline.
+    let timeout = match self.deadlines.iter().map(|(at, _)| *at).min() {
+      Some(at) => {
+        let left = at.saturating_duration_since(Instant::now());
+        Some(timeout.map_or(left, |t| t.min(left)))
+      }
+      None => timeout,
+    };
+
     let items_written = match self.sys.as_ref().unwrap().wait(events, timeout) {
       Ok(n) => n,
       Err(e) => {
@@ -699,6 +1230,21 @@ impl IoBackend for Poller {
         }
       }
 
+      // Multishot ops stay armed: a poll reports every event, an accept
+      // every connection.
+      if result >= 0
+        && matches!(
+          op,
+          crate::op::Op::PollMultishot { .. }
+            | crate::op::Op::AcceptMultishot { .. }
+        )
+      {
+        self.op_map.insert(operation_id, op);
+        self.sys().modify(entry_fd, operation_id, event.interest)?;
+        self.completed.push(OpCompleted::new_more(operation_id, result));
+        continue;
+      }
+
       // Operation completed (success or error other than would-block)
       // Clean up - use delete_timer for timer events, delete for fd-based events
       if event.interest.is_timer() {
@@ -712,6 +1258,8 @@ impl IoBackend for Poller {
       self.completed.push(OpCompleted::new(operation_id, result));
     }
 
+    self.expire_deadlines()?;
+
     Ok(self.completed.as_ref())
   }
 }
@@ -1,5 +1,7 @@
 use super::super::{Interest, ReadinessPoll};
 use super::NOTIFY_KEY;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 use std::os::fd::{AsFd, BorrowedFd};
@@ -7,6 +9,9 @@ use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
 use std::time::Duration;
 use std::{io, ptr};
 
+/// Set in the epoll data of timers, so their events report [`Interest::TIMER`].
+const TIMER_BIT: u64 = 1 << 63;
+
 /// Wrapper around an epoll file descriptor
 ///
 /// This type is intentionally `!Send` to ensure it's only used from a single thread.
@@ -22,6 +27,9 @@ pub struct OsPoller {
   #[cfg(not(target_os = "redox"))]
   timer_fd: Option<OwnedFd>,
 
+  /// Timerfds of the timers added with [`Interest::TIMER`], by key.
+  timers: RefCell<HashMap<u64, OwnedFd>>,
+
   /// Marker to make this type `!Send`
   _not_send: PhantomData<*const ()>,
 }
@@ -53,6 +61,7 @@ impl OsPoller {
 
       #[cfg(not(target_os = "redox"))]
       timer_fd: Some(timer_fd),
+      timers: RefCell::new(HashMap::new()),
 
       _not_send: PhantomData,
     };
@@ -70,6 +79,45 @@ impl OsPoller {
 
     Ok(epoll)
   }
+
+  /// Arms a one-shot timerfd firing after `duration_ms`, reported as `key`.
+  #[cfg(not(target_os = "redox"))]
+  fn add_timer(&self, duration_ms: RawFd, key: u64) -> io::Result<()> {
+    let fd = syscall!(timerfd_create(
+      libc::CLOCK_MONOTONIC,
+      libc::TFD_NONBLOCK | libc::TFD_CLOEXEC
+    ))?;
+    // SAFETY: fd is valid, just returned successfully from timerfd_create
+    let timer = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let ms = duration_ms.max(0) as i64;
+    // SAFETY: All-zeros is a valid representation of itimerspec
+    let mut value: libc::itimerspec =
+      unsafe { MaybeUninit::zeroed().assume_init() };
+    value.it_value.tv_sec = (ms / 1000) as _;
+    // A zero it_value disarms the timer, so zero waits fire right away instead.
+    value.it_value.tv_nsec = ((ms % 1000) * 1_000_000).max(1) as _;
+    syscall!(timerfd_settime(fd, 0, &value, ptr::null_mut()))?;
+
+    let mut event = libc::epoll_event {
+      events: (libc::EPOLLIN | libc::EPOLLONESHOT) as u32,
+      u64: key | TIMER_BIT,
+    };
+    syscall!(epoll_ctl(
+      self.epoll_fd.as_raw_fd(),
+      libc::EPOLL_CTL_ADD,
+      fd,
+      &mut event as *mut libc::epoll_event,
+    ))?;
+
+    self.timers.borrow_mut().insert(key, timer);
+    Ok(())
+  }
+
+  #[cfg(target_os = "redox")]
+  fn add_timer(&self, _duration_ms: RawFd, _key: u64) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+  }
 }
 impl Drop for OsPoller {
   fn drop(&mut self) {
@@ -85,6 +133,11 @@ impl ReadinessPoll for OsPoller {
   type NativeEvent = libc::epoll_event;
 
   fn add(&self, fd: RawFd, key: u64, interest: Interest) -> io::Result<()> {
+    // For timers, fd contains the duration in milliseconds
+    if interest.is_timer() {
+      return self.add_timer(fd, key);
+    }
+
     let mut events = 0u32;
 
     if interest.is_readable() {
@@ -93,10 +146,14 @@ impl ReadinessPoll for OsPoller {
     if interest.is_writable() {
       events |= libc::EPOLLOUT as u32;
     }
+    if interest.is_hangup() {
+      events |= libc::EPOLLRDHUP as u32;
+    }
 
     // Use EPOLLONESHOT for consistency with kqueue's EV_ONESHOT behavior
     events |= libc::EPOLLONESHOT as u32;
-    // Note: EPOLLHUP and EPOLLERR are always reported by the kernel regardless of registration
+    // Note: EPOLLHUP and EPOLLERR are always reported by the kernel regardless
+    // of registration, so ERROR interest needs no extra flag.
 
     let mut event = libc::epoll_event { events, u64: key as u64 };
 
@@ -121,10 +178,14 @@ impl ReadinessPoll for OsPoller {
     if interest.is_writable() {
       events |= libc::EPOLLOUT as u32;
     }
+    if interest.is_hangup() {
+      events |= libc::EPOLLRDHUP as u32;
+    }
 
     // Use EPOLLONESHOT for consistency with kqueue's EV_ONESHOT behavior
     events |= libc::EPOLLONESHOT as u32;
-    // Note: EPOLLHUP and EPOLLERR are always reported by the kernel regardless of registration
+    // Note: EPOLLHUP and EPOLLERR are always reported by the kernel regardless
+    // of registration, so ERROR interest needs no extra flag.
 
     let mut event = libc::epoll_event { events, u64: key as u64 };
 
@@ -154,10 +215,12 @@ impl ReadinessPoll for OsPoller {
     }
   }
 
-  fn delete_timer(&self, _key: u64) -> io::Result<()> {
-    // epoll doesn't use this - timers on Linux use timerfd which is a regular fd
-    // and gets deleted via delete() instead
-    Err(io::Error::from_raw_os_error(libc::ENOENT))
+  fn delete_timer(&self, key: u64) -> io::Result<()> {
+    // Closing the timerfd also removes it from the epoll set.
+    match self.timers.borrow_mut().remove(&key) {
+      Some(_) => Ok(()),
+      None => Err(io::Error::from_raw_os_error(libc::ENOENT)),
+    }
   }
 
   /// Returns [`libc::EINVAL`] if events.is_empty()
@@ -259,29 +322,40 @@ impl ReadinessPoll for OsPoller {
   }
 
   fn event_key(event: &Self::NativeEvent) -> u64 {
-    event.u64
+    event.u64 & !TIMER_BIT
   }
 
   fn event_interest(event: &Self::NativeEvent) -> Interest {
+    if event.u64 & TIMER_BIT != 0 {
+      return Interest::TIMER;
+    }
+
     // epoll can return both read and write in a single event
     let readable = (event.events & libc::EPOLLIN as u32) != 0;
     let writable = (event.events & libc::EPOLLOUT as u32) != 0;
 
-    // EPOLLHUP, EPOLLERR, and EPOLLRDHUP should be treated as making the fd readable
-    // so that operations can be attempted and will return appropriate errors
     let is_hup = (event.events & libc::EPOLLHUP as u32) != 0;
     let is_err = (event.events & libc::EPOLLERR as u32) != 0;
     let is_rdhup = (event.events & libc::EPOLLRDHUP as u32) != 0;
 
-    let error_or_hup = is_hup || is_err || is_rdhup;
-    let effective_readable = readable || error_or_hup;
+    let mut interest = Interest::NONE;
+    if writable {
+      interest |= Interest::WRITE;
+    }
+    if is_hup || is_rdhup {
+      interest |= Interest::HANGUP;
+    }
+    if is_err {
+      interest |= Interest::ERROR;
+    }
 
-    match (effective_readable, writable) {
-      (true, true) => Interest::READ_AND_WRITE,
-      (true, false) => Interest::READ,
-      (false, true) => Interest::WRITE,
-      (false, false) => Interest::READ, // Fallback, shouldn't happen
+    // Hangups and errors still mark the fd readable so that operations can
+    // be attempted and will return appropriate errors
+    if readable || is_hup || is_err || is_rdhup || interest.is_none() {
+      interest |= Interest::READ;
     }
+
+    interest
   }
 }
 
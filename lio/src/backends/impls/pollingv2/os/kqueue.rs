@@ -127,7 +127,9 @@ impl OsPoller {
     let mut changes: [libc::kevent; 2] = unsafe { std::mem::zeroed() };
     let mut n = 0;
 
-    if interest.is_readable() {
+    // kqueue has no dedicated hangup/error filter: EOF and errors are
+    // reported as flags on the read filter.
+    if interest.is_readable() || interest.is_hangup() || interest.is_error() {
       changes[n] = libc::kevent {
         ident: fd as libc::uintptr_t,
         filter: libc::EVFILT_READ,
@@ -398,12 +400,21 @@ impl ReadinessPoll for OsPoller {
 
   fn event_interest(event: &Self::NativeEvent) -> Interest {
     // kqueue returns one event per filter, so only one will be set
-    match event.filter {
+    let mut interest = match event.filter {
       libc::EVFILT_READ => Interest::READ,
       libc::EVFILT_WRITE => Interest::WRITE,
-      libc::EVFILT_TIMER => Interest::TIMER,
+      libc::EVFILT_TIMER => return Interest::TIMER,
       _ => Interest::READ, // Fallback
+    };
+
+    if event.flags & libc::EV_EOF != 0 {
+      interest |= Interest::HANGUP;
     }
+    if event.flags & libc::EV_ERROR != 0 {
+      interest |= Interest::ERROR;
+    }
+
+    interest
   }
 }
 
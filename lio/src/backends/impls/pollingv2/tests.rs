@@ -670,12 +670,39 @@ where
   assert!(n >= 1, "Should get event when peer closes");
 
   let interest = P::event_interest(&events[0]);
-  // Different platforms may report this differently (readable, error, or hangup)
-  // At minimum we should get some event
-  assert!(
-    interest.is_readable() || interest.is_writable(),
-    "Should get some interest signaled on peer close"
-  );
+  // The fd stays readable so a read can observe EOF, and the hangup is
+  // surfaced on its own bit
+  assert!(interest.is_readable(), "Peer close should be readable");
+  assert!(interest.is_hangup(), "Peer close should signal HANGUP");
+
+  Ok(())
+}
+
+/// Test registering only for hangup readiness
+pub fn test_hangup_interest<P>(poller: P) -> io::Result<()>
+where
+  P: ReadinessPoll,
+  P::NativeEvent: Clone,
+{
+  let (sock1, sock2) = create_socket_pair()?;
+  let fd1 = sock1.as_raw_fd();
+  make_nonblocking(fd1)?;
+
+  // SAFETY: NativeEvent is a plain C struct, all-zero is a valid value.
+  let mut events = vec![unsafe { std::mem::zeroed() }; 16];
+
+  poller.add(fd1, 1, Interest::HANGUP | Interest::ERROR)?;
+
+  // Nothing has happened yet
+  let n = poller.wait(&mut events, Some(Duration::from_millis(10)))?;
+  assert_eq!(n, 0, "Should get no event before the peer closes");
+
+  drop(sock2);
+
+  let n = poller.wait(&mut events, Some(Duration::from_millis(100)))?;
+  assert_eq!(n, 1, "Should get event when peer closes");
+  assert_eq!(P::event_key(&events[0]), 1);
+  assert!(P::event_interest(&events[0]).is_hangup());
 
   Ok(())
 }
@@ -1427,6 +1454,14 @@ macro_rules! generate_tests {
         .expect("test_peer_closed: failed when testing socket peer close handling");
     }
 
+    #[test]
+    fn test_hangup_interest() {
+      println!("Running test: registering for hangup readiness only");
+      let poller = $poller;
+      crate::backends::impls::pollingv2::tests::test_hangup_interest(poller)
+        .expect("test_hangup_interest: failed when testing hangup-only interest");
+    }
+
     #[test]
     fn test_modify_to_no_interest() {
       println!("Running test: modifying interest to none (edge case)");
@@ -66,6 +66,14 @@ pub trait BufLike: Sealed {
   ///
   /// - `bytes`: bytes written (for writes) or bytes read (for reads)
   fn after(self, bytes: usize) -> Self;
+
+  /// Index of the buffer in the ring's registered buffer table, if it is in
+  /// one, see [`BufStore::register_fixed`].
+  ///
+  /// Defaults to `None`.
+  fn fixed_index(&self) -> Option<u16> {
+    None
+  }
 }
 
 impl BufLike for Vec<u8> {
@@ -90,13 +98,195 @@ impl BufLike for Vec<u8> {
   }
 }
 
+// Fixed-size buffers can't shrink to the bytes transferred, callers use the
+// count from the result instead.
+
+impl BufLike for Box<[u8]> {
+  fn buf(&self) -> &[u8] {
+    self
+  }
+
+  fn after(self, _: usize) -> Self {
+    self
+  }
+}
+
+/// Stored inline in the operation, which is heap-allocated before the
+/// kernel sees the pointer, so the array doesn't move while in flight.
+impl<const N: usize> BufLike for [u8; N] {
+  fn buf(&self) -> &[u8] {
+    self
+  }
+
+  fn after(self, _: usize) -> Self {
+    self
+  }
+}
+
+impl<const N: usize> BufLike for Box<[u8; N]> {
+  fn buf(&self) -> &[u8] {
+    &self[..]
+  }
+
+  fn after(self, _: usize) -> Self {
+    self
+  }
+}
+
+/// A buffer operations only write into, like reads and receives.
+///
+/// Implemented for every [`BufLike`] but [`SharedBuf`], whose memory other
+/// clones may be reading while the kernel writes, and for [`UninitBuf`],
+/// which doesn't implement [`BufLike`] since its memory can't be read before
+/// the kernel filled it.
+pub trait IoBufMut: Sealed {
+  /// The memory the kernel may write into.
+  fn uninit_ptr(&mut self) -> *mut MaybeUninit<u8>;
+
+  /// How many bytes the kernel may write at [`uninit_ptr`](Self::uninit_ptr).
+  fn uninit_len(&self) -> usize;
+
+  /// Called after the operation wrote `bytes` bytes.
+  fn after(self, bytes: usize) -> Self;
+
+  /// Index of the buffer in the ring's registered buffer table, if it is in
+  /// one, see [`BufStore::register_fixed`].
+  ///
+  /// Defaults to `None`.
+  fn fixed_index(&self) -> Option<u16> {
+    None
+  }
+}
+
+/// A [`BufLike`] nothing else can see while the kernel writes into it.
+trait Unshared: BufLike {}
+
+impl Unshared for Vec<u8> {}
+impl Unshared for Box<[u8]> {}
+impl<const N: usize> Unshared for [u8; N] {}
+impl<const N: usize> Unshared for Box<[u8; N]> {}
+impl<'a> Unshared for LentBuf<'a> {}
+
+impl<B> IoBufMut for B
+where
+  B: Unshared,
+{
+  fn uninit_ptr(&mut self) -> *mut MaybeUninit<u8> {
+    self.buf().as_ptr().cast_mut().cast()
+  }
+
+  fn uninit_len(&self) -> usize {
+    self.buf().len()
+  }
+
+  fn after(self, bytes: usize) -> Self {
+    BufLike::after(self, bytes)
+  }
+
+  fn fixed_index(&self) -> Option<u16> {
+    BufLike::fixed_index(self)
+  }
+}
+
+/// A receive buffer that is never zeroed.
+///
+/// `vec![0u8; n]` writes every byte before the kernel overwrites them
+/// anyway. This only allocates, and after the operation exposes the bytes
+/// the kernel filled in, never the uninitialized rest. It only implements
+/// [`IoBufMut`], so it can't be handed to writes and sends.
+///
+/// # Example
+///
+/// ```
+/// use lio::buf::UninitBuf;
+///
+/// let buf = UninitBuf::new(64 * 1024);
+/// assert_eq!(buf.capacity(), 64 * 1024);
+/// assert!(buf.filled().is_empty());
+/// ```
+///
+/// Writing it would send uninitialized memory:
+///
+/// ```compile_fail
+/// use lio::{api, buf::UninitBuf};
+///
+/// let fd = api::resource::Resource::stdout();
+/// api::write(&fd, UninitBuf::new(64));
+/// ```
+pub struct UninitBuf {
+  buf: Vec<MaybeUninit<u8>>,
+  filled: usize,
+}
+
+impl UninitBuf {
+  /// Allocates room for `capacity` bytes without initializing them.
+  pub fn new(capacity: usize) -> Self {
+    Self { buf: Box::new_uninit_slice(capacity).into_vec(), filled: 0 }
+  }
+
+  /// Bytes the buffer can take.
+  pub fn capacity(&self) -> usize {
+    self.buf.len()
+  }
+
+  /// The bytes filled in by the last operation.
+  pub fn filled(&self) -> &[u8] {
+    // SAFETY: The first `filled` bytes were written by the kernel.
+    unsafe { slice::from_raw_parts(self.buf.as_ptr().cast(), self.filled) }
+  }
+
+  /// Forgets the filled bytes so the buffer can be reused.
+  pub fn clear(&mut self) {
+    self.filled = 0;
+  }
+
+  /// Returns the filled bytes, without copying them.
+  pub fn into_vec(self) -> Vec<u8> {
+    let mut buf = std::mem::ManuallyDrop::new(self.buf);
+    // SAFETY: MaybeUninit<u8> has the layout of u8, the allocation is
+    // handed over as is and its first `filled` bytes are initialized.
+    unsafe {
+      Vec::from_raw_parts(buf.as_mut_ptr().cast(), self.filled, buf.capacity())
+    }
+  }
+}
+
+impl std::ops::Deref for UninitBuf {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    self.filled()
+  }
+}
+
+impl Sealed for UninitBuf {}
+
+impl IoBufMut for UninitBuf {
+  fn uninit_ptr(&mut self) -> *mut MaybeUninit<u8> {
+    self.buf.as_mut_ptr()
+  }
+
+  fn uninit_len(&self) -> usize {
+    self.buf.len()
+  }
+
+  fn after(mut self, bytes: usize) -> Self {
+    self.filled = bytes.min(self.buf.len());
+    self
+  }
+}
+
 impl<B> Sealed for B where B: BufLike {}
 
 use std::{
-  array,
-  cell::UnsafeCell,
+  alloc::{self, Layout},
+  mem::MaybeUninit,
+  ptr::{self, NonNull},
   slice,
-  sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+  sync::{
+    Arc, OnceLock,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+  },
   time::Duration,
 };
 
@@ -124,27 +314,33 @@ unsafe impl<'a> Sync for LentBuf<'a> {}
 
 impl<'a> BufLike for LentBuf<'a> {
   fn buf(&self) -> &[u8] {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
     // SAFETY: We have exclusive access via in_use flag
     unsafe { &*cell.buf.get() }
   }
 
   fn after(self, bw: usize) -> Self {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
+    let cap = self.pool.slab_size();
     assert!(
-      bw <= BUF_LEN,
+      bw <= cap,
       "LentBuf::after: bytes written ({}) exceeds buffer capacity ({})",
       bw,
-      BUF_LEN
+      cap
     );
     cell.len.store(bw, Ordering::Release);
     self
   }
+
+  fn fixed_index(&self) -> Option<u16> {
+    let registered = self.pool.registered.load(Ordering::Acquire);
+    ((self.index as usize) < registered).then_some(self.index as u16)
+  }
 }
 
 impl<'a> AsRef<[u8]> for LentBuf<'a> {
   fn as_ref(&self) -> &[u8] {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
     let pos = cell.pos.load(Ordering::Acquire);
     let len = cell.len.load(Ordering::Acquire);
     // SAFETY: We have exclusive access via in_use flag
@@ -154,13 +350,14 @@ impl<'a> AsRef<[u8]> for LentBuf<'a> {
 
 impl<'a> Drop for LentBuf<'a> {
   fn drop(&mut self) {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
 
     #[cfg(feature = "zeroize")]
     self.zeroize();
 
     // Mark buffer as available
     cell.in_use.store(false, Ordering::Release);
+    self.pool.in_use.fetch_sub(1, Ordering::Relaxed);
 
     // Return index to free list
     let _ = self.pool.free_tx.send(self.index);
@@ -168,6 +365,11 @@ impl<'a> Drop for LentBuf<'a> {
 }
 
 impl<'a> LentBuf<'a> {
+  /// The alignment of this buffer's memory, see [`BufStore::with_alignment`].
+  pub fn alignment(&self) -> usize {
+    self.pool.alignment()
+  }
+
   /// Securely erases the buffer contents and resets position/length.
   ///
   /// # When to Use
@@ -180,7 +382,7 @@ impl<'a> LentBuf<'a> {
   /// # Security Properties
   ///
   /// - Guarantees memory is overwritten (compiler cannot optimize away)
-  /// - Clears the full buffer capacity (4096 bytes), not just the valid data range
+  /// - Clears the full buffer capacity (4096 bytes by default), not just the valid data range
   /// - Resets `pos` and `len` to 0
   /// - Uses [`zeroize::Zeroize`](https://docs.rs/zeroize) trait for secure erasure
   ///
@@ -206,7 +408,7 @@ impl<'a> LentBuf<'a> {
   /// ```
   #[cfg(feature = "zeroize")]
   pub fn zeroize(&mut self) {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
     zeroize::Zeroize::zeroize(
       // SAFETY: We have exclusive access via in_use flag
       unsafe { &mut *cell.buf.get() },
@@ -234,7 +436,7 @@ pub struct LentBufIter<'a, 'b> {
 impl<'a, 'b> Iterator for LentBufIter<'a, 'b> {
   type Item = u8;
   fn next(&mut self) -> Option<Self::Item> {
-    let cell = &self.buf.pool.buffers[self.buf.index as usize];
+    let cell = self.buf.pool.cell(self.buf.index);
     let pos = cell.pos.load(Ordering::Acquire);
     let len = cell.len.load(Ordering::Acquire);
     let remaining = len - pos;
@@ -273,14 +475,14 @@ impl<'a, 'b> Iterator for LentBufIter<'a, 'b> {
 #[cfg(feature = "bytes")]
 impl<'a> bytes::Buf for LentBuf<'a> {
   fn remaining(&self) -> usize {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
     let pos = cell.pos.load(Ordering::Acquire);
     let len = cell.len.load(Ordering::Acquire);
     len - pos
   }
 
   fn chunk(&self) -> &[u8] {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
     let pos = cell.pos.load(Ordering::Acquire);
     let len = cell.len.load(Ordering::Acquire);
     // SAFETY: We have exclusive access via in_use flag
@@ -288,7 +490,7 @@ impl<'a> bytes::Buf for LentBuf<'a> {
   }
 
   fn advance(&mut self, cnt: usize) {
-    let cell = &self.pool.buffers[self.index as usize];
+    let cell = self.pool.cell(self.index);
     let pos = cell.pos.load(Ordering::Acquire);
     let len = cell.len.load(Ordering::Acquire);
     let remaining = len - pos;
@@ -325,6 +527,77 @@ impl<'a> LentBuf<'a> {
   pub fn windows(&self, window_size: usize) -> std::slice::Windows<'_, u8> {
     self.as_ref().windows(window_size)
   }
+
+  /// Freezes the buffer into a [`SharedBuf`] that can be cloned cheaply.
+  ///
+  /// The received bytes stay where they are, and the buffer is returned to
+  /// the pool once the last clone is dropped.
+  pub fn share(self) -> SharedBuf<'a> {
+    SharedBuf(Arc::new(self))
+  }
+}
+
+/// A read-only, reference-counted [`LentBuf`].
+///
+/// Created with [`LentBuf::share`] after a `recv` or `read` has filled the
+/// buffer. Cloning only bumps a reference count, so one received message can
+/// be handed to many `send`s without copying the payload.
+///
+/// As a [`BufLike`] it exposes the filled bytes, and a completed `send`
+/// doesn't change it, so every clone keeps seeing the whole message. It
+/// isn't an [`IoBufMut`], so reads and receives can't write into memory the
+/// other clones are using.
+///
+/// # Example
+///
+/// ```
+/// use lio::buf::BufStore;
+///
+/// let pool = BufStore::with_capacity(1);
+/// let shared = pool.try_get().unwrap().share();
+/// let other = shared.clone();
+/// assert_eq!(shared.as_ref().as_ptr(), other.as_ref().as_ptr());
+///
+/// drop(shared);
+/// assert_eq!(pool.available(), 0); // `other` still holds the buffer
+/// drop(other);
+/// assert_eq!(pool.available(), 1);
+/// ```
+///
+/// Reading into it would race with the other clones:
+///
+/// ```compile_fail
+/// use lio::{api, buf::BufStore};
+///
+/// let pool: &'static BufStore = Box::leak(Box::new(BufStore::with_capacity(1)));
+/// let shared = pool.try_get().unwrap().share();
+/// let fd = api::resource::Resource::stdin();
+/// api::read(&fd, shared.clone());
+/// ```
+#[derive(Clone)]
+pub struct SharedBuf<'a>(Arc<LentBuf<'a>>);
+
+impl<'a> SharedBuf<'a> {
+  /// Returns the number of handles sharing this buffer.
+  pub fn ref_count(&self) -> usize {
+    Arc::strong_count(&self.0)
+  }
+}
+
+impl<'a> BufLike for SharedBuf<'a> {
+  fn buf(&self) -> &[u8] {
+    self.as_ref()
+  }
+
+  fn after(self, _: usize) -> Self {
+    self
+  }
+}
+
+impl<'a> AsRef<[u8]> for SharedBuf<'a> {
+  fn as_ref(&self) -> &[u8] {
+    self.0.as_ref().as_ref()
+  }
 }
 
 const BUF_LEN: usize = 4096;
@@ -333,7 +606,7 @@ const BUF_LEN: usize = 4096;
 ///
 /// Contains the actual buffer data and atomic metadata for thread-safe access.
 struct BufCell {
-  buf: UnsafeCell<[u8; BUF_LEN]>,
+  buf: Slab,
   len: AtomicUsize,
   pos: AtomicUsize,
   in_use: AtomicBool,
@@ -343,11 +616,13 @@ struct BufCell {
 // via the atomic in_use flag. Only one thread can access the buffer at a time
 // (enforced by the CAS in try_get).
 unsafe impl Sync for BufCell {}
+// SAFETY: The slab is owned by the cell and freed only when it drops.
+unsafe impl Send for BufCell {}
 
 impl BufCell {
-  fn new() -> Self {
+  fn new(layout: Layout) -> Self {
     Self {
-      buf: UnsafeCell::new(array::from_fn::<_, BUF_LEN, _>(|_| 0)),
+      buf: Slab::new(layout),
       len: AtomicUsize::new(0),
       pos: AtomicUsize::new(0),
       in_use: AtomicBool::new(false),
@@ -355,14 +630,44 @@ impl BufCell {
   }
 }
 
+/// Zeroed heap memory for one buffer, laid out as the pool asks.
+struct Slab {
+  ptr: NonNull<u8>,
+  layout: Layout,
+}
+
+impl Slab {
+  fn new(layout: Layout) -> Self {
+    // SAFETY: BufStore only builds layouts with a non-zero size.
+    let ptr = unsafe { alloc::alloc_zeroed(layout) };
+    let ptr =
+      NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+    Self { ptr, layout }
+  }
+
+  fn get(&self) -> *mut [u8] {
+    ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.layout.size())
+  }
+}
+
+impl Drop for Slab {
+  fn drop(&mut self) {
+    // SAFETY: ptr was allocated in Slab::new with this layout.
+    unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+  }
+}
+
 /// A pool of reusable buffers for I/O operations.
 ///
 /// Provides zero-allocation buffer lending using an index-based design.
-/// All buffers are allocated upfront, and lending/returning uses lock-free operations.
+/// Buffers are allocated upfront, and lending/returning uses lock-free operations.
+/// A pool created with [`with_growth`](Self::with_growth) allocates more
+/// buffers on demand when it runs dry, up to a fixed cap.
 ///
 /// # Design
 ///
-/// - All buffers allocated once at initialization (zero runtime allocations)
+/// - Buffers allocated at initialization, or once when the pool grows; a
+///   buffer is never freed or moved while the pool is alive
 /// - Index-based lending (LentBuf holds index, not mutex guard)
 /// - Lock-free free list using crossbeam SegQueue
 /// - Atomic flags for exclusive access (no mutexes per buffer)
@@ -381,9 +686,28 @@ impl BufCell {
 /// }
 /// ```
 pub struct BufStore {
-  buffers: Box<[BufCell]>,
+  /// One slot per buffer the pool may ever hold, the first `allocated` are set.
+  buffers: Box<[OnceLock<Box<BufCell>>]>,
+  allocated: AtomicUsize,
+  in_use: AtomicUsize,
+  peak_in_use: AtomicUsize,
   free_tx: Sender<u32>,
   free_rx: Receiver<u32>,
+  /// How many buffers, from index 0, are registered as fixed buffers.
+  registered: Arc<AtomicUsize>,
+  /// Size and alignment of every buffer.
+  layout: Layout,
+}
+
+/// A snapshot of a [`BufStore`]'s usage, see [`BufStore::metrics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufStoreMetrics {
+  /// Buffers currently lent out.
+  pub in_use: usize,
+  /// Highest number of buffers lent out at once.
+  pub peak_in_use: usize,
+  /// Buffers allocated so far, including those that came from growing.
+  pub allocated: usize,
 }
 
 impl Default for BufStore {
@@ -402,21 +726,124 @@ impl BufStore {
   ///
   /// - `cap`: Number of 4096-byte buffers to allocate in the pool
   pub fn with_capacity(cap: usize) -> Self {
-    let mut buffers = Vec::with_capacity(cap);
+    Self::with_growth(cap, cap)
+  }
+
+  /// Creates a pool that starts with `initial` buffers and grows on demand.
+  ///
+  /// When every buffer is lent out, [`try_get`](Self::try_get) and friends
+  /// allocate a new one instead of failing, until `max` buffers exist. Past
+  /// that the pool behaves like one created with [`with_capacity`](Self::with_capacity).
+  ///
+  /// # Panics
+  ///
+  /// Panics if `initial > max`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::buf::BufStore;
+  ///
+  /// let pool = BufStore::with_growth(1, 2);
+  /// let a = pool.try_get().unwrap();
+  /// let b = pool.try_get().unwrap(); // Allocated on demand
+  /// assert!(pool.try_get().is_none()); // Capped at 2
+  /// assert_eq!(pool.metrics().allocated, 2);
+  /// ```
+  pub fn with_growth(initial: usize, max: usize) -> Self {
+    assert!(initial <= max, "BufStore: initial ({initial}) > max ({max})");
+    let layout = Layout::from_size_align(BUF_LEN, 1).expect("valid layout");
+    Self::with_layout(initial, max, layout)
+  }
+
+  /// Creates a pool of `count` buffers of `slab_size` bytes, each starting
+  /// on an `align`-byte boundary.
+  ///
+  /// Files opened with `O_DIRECT` need buffers aligned to the device's
+  /// logical block size (often 512 or 4096 bytes), and transfer sizes that
+  /// are multiples of it. With a default pool a `read_at` on such a file
+  /// fails with `EINVAL`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `align` isn't a power of two, or if `slab_size` is zero.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::buf::BufStore;
+  ///
+  /// let pool = BufStore::with_alignment(4096, 8, 4096);
+  /// let buf = pool.try_get().unwrap();
+  /// assert_eq!(buf.alignment(), 4096);
+  /// ```
+  pub fn with_alignment(slab_size: usize, count: usize, align: usize) -> Self {
+    assert!(slab_size > 0, "BufStore: slab_size must not be zero");
+    let layout =
+      Layout::from_size_align(slab_size, align).unwrap_or_else(|_| {
+        panic!("BufStore: invalid alignment {align} for {slab_size}-byte slabs")
+      });
+    Self::with_layout(count, count, layout)
+  }
+
+  fn with_layout(initial: usize, max: usize, layout: Layout) -> Self {
     let (free_tx, free_rx) = crossbeam_channel::unbounded();
+    let buffers: Box<[_]> = (0..max).map(|_| OnceLock::new()).collect();
 
-    for i in 0..cap {
-      buffers.push(BufCell::new());
+    for (i, slot) in buffers.iter().take(initial).enumerate() {
+      let _ = slot.set(Box::new(BufCell::new(layout)));
       // Pre-populate the channel with all buffer indices
       free_tx.send(i as u32).expect("channel should not be full");
     }
 
-    Self { buffers: buffers.into_boxed_slice(), free_tx, free_rx }
+    Self {
+      buffers,
+      allocated: AtomicUsize::new(initial),
+      in_use: AtomicUsize::new(0),
+      peak_in_use: AtomicUsize::new(0),
+      free_tx,
+      free_rx,
+      registered: Arc::new(AtomicUsize::new(0)),
+      layout,
+    }
+  }
+
+  fn cell(&self, index: u32) -> &BufCell {
+    self.buffers[index as usize]
+      .get()
+      .expect("BufStore invariant violated: index of unallocated buffer")
+  }
+
+  /// Allocates one more buffer if the pool is below its cap, returning its index.
+  ///
+  /// The index is reserved before its slot is set, so `allocated` may count
+  /// a slot another thread is still filling in. Readers walking the pool
+  /// stop at the first unset slot.
+  fn grow(&self) -> Option<u32> {
+    let mut index = self.allocated.load(Ordering::Acquire);
+    loop {
+      if index >= self.buffers.len() {
+        return None;
+      }
+      match self.allocated.compare_exchange_weak(
+        index,
+        index + 1,
+        Ordering::AcqRel,
+        Ordering::Acquire,
+      ) {
+        Ok(_) => break,
+        Err(current) => index = current,
+      }
+    }
+    // The index was reserved above, so nobody else sets this slot.
+    let _ = self.buffers[index].set(Box::new(BufCell::new(self.layout)));
+    Some(index as u32)
   }
 
   /// Tries to borrow a buffer from the pool.
   ///
-  /// Returns `None` if all buffers are currently in use.
+  /// Returns `None` if all buffers are currently in use and the pool can't
+  /// grow any further.
   /// The buffer is automatically returned to the pool when dropped.
   ///
   /// **Zero heap allocations** - just pops an index and returns a small stack struct.
@@ -428,7 +855,10 @@ impl BufStore {
   /// - `Some(LentBuf)`: A borrowed buffer from the pool
   /// - `None`: All buffers are in use
   pub fn try_get(&self) -> Option<LentBuf<'_>> {
-    let index = self.free_rx.try_recv().ok()?;
+    let index = match self.free_rx.try_recv() {
+      Ok(index) => index,
+      Err(_) => self.grow()?,
+    };
     Some(self.acquire_buffer(index))
   }
 
@@ -441,6 +871,9 @@ impl BufStore {
   ///
   /// A borrowed buffer from the pool
   pub fn get(&self) -> LentBuf<'_> {
+    if let Some(buf) = self.try_get() {
+      return buf;
+    }
     let index = self.free_rx.recv().expect("channel should not disconnect");
     self.acquire_buffer(index)
   }
@@ -458,6 +891,9 @@ impl BufStore {
   /// - `Some(LentBuf)`: A borrowed buffer from the pool
   /// - `None`: Timeout expired before a buffer became available
   pub fn get_timeout(&self, timeout: Duration) -> Option<LentBuf<'_>> {
+    if let Some(buf) = self.try_get() {
+      return Some(buf);
+    }
     match self.free_rx.recv_timeout(timeout) {
       Ok(index) => Some(self.acquire_buffer(index)),
       Err(_) => None,
@@ -466,7 +902,7 @@ impl BufStore {
 
   /// Internal helper to acquire a buffer by index.
   fn acquire_buffer(&self, index: u32) -> LentBuf<'_> {
-    let cell = &self.buffers[index as usize];
+    let cell = self.cell(index);
     let was_in_use = cell.in_use.swap(true, Ordering::Acquire);
 
     assert!(
@@ -479,19 +915,474 @@ impl BufStore {
     cell.len.store(0, Ordering::Relaxed);
     cell.pos.store(0, Ordering::Relaxed);
 
+    let in_use = self.in_use.fetch_add(1, Ordering::Relaxed) + 1;
+    self.peak_in_use.fetch_max(in_use, Ordering::Relaxed);
+
     LentBuf { index, pool: self }
   }
 
-  /// Returns the total capacity of the buffer pool.
+  /// Returns the number of buffers allocated so far.
   pub fn capacity(&self) -> usize {
+    self.allocated.load(Ordering::Acquire)
+  }
+
+  /// Returns the size of each buffer in bytes.
+  pub fn slab_size(&self) -> usize {
+    self.layout.size()
+  }
+
+  /// Returns the alignment every buffer's memory is guaranteed to have.
+  ///
+  /// This is 1 unless the pool was created with [`with_alignment`](Self::with_alignment).
+  pub fn alignment(&self) -> usize {
+    self.layout.align()
+  }
+
+  /// Returns the number of buffers the pool may grow to.
+  pub fn max_capacity(&self) -> usize {
     self.buffers.len()
   }
 
-  /// Returns the number of currently available buffers.
+  /// Returns a snapshot of the pool's usage counters.
+  pub fn metrics(&self) -> BufStoreMetrics {
+    BufStoreMetrics {
+      in_use: self.in_use.load(Ordering::Relaxed),
+      peak_in_use: self.peak_in_use.load(Ordering::Relaxed),
+      allocated: self.capacity(),
+    }
+  }
+
+  /// Registers the pool's buffers as fixed buffers of the ring.
+  ///
+  /// Once registered, `read`s and flag-less `recv`s into a [`LentBuf`] from
+  /// this pool use the ring's fixed buffer opcodes, which skip mapping the
+  /// buffer on every operation. Only the buffers allocated by now are
+  /// registered, ones added later by growing use the normal path.
+  ///
+  /// A ring has one fixed buffer table, so registering a second pool on the
+  /// same [`Lio`](crate::Lio) fails with `EBUSY`. Backends without fixed
+  /// buffers fail with [`io::ErrorKind::Unsupported`](std::io::ErrorKind::Unsupported)
+  /// and keep working with the pool as before. The pool must outlive the
+  /// [`Lio`](crate::Lio) it's registered with.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, buf::BufStore};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let pool = BufStore::with_capacity(64);
+  /// let receiver = pool.register_fixed().with_lio(&lio).send();
+  /// lio.try_run().unwrap();
+  /// if let Err(err) = receiver.recv() {
+  ///     eprintln!("not using fixed buffers: {err}");
+  /// }
+  /// ```
+  #[cfg(unix)]
+  pub fn register_fixed(
+    &self,
+  ) -> crate::api::io::Io<crate::api::ops::RegisterBuffers> {
+    // The kernel caps a ring's table at 16384 buffers. A slot reserved by a
+    // concurrent grow may not be set yet, registration stops before it.
+    let count = self.capacity().min(1 << 14);
+    let iovecs = self.buffers[..count]
+      .iter()
+      .map_while(OnceLock::get)
+      .map(|cell| libc::iovec {
+        iov_base: cell.buf.get().cast(),
+        iov_len: self.slab_size(),
+      })
+      .collect();
+    crate::api::io::Io::from_op(crate::api::ops::RegisterBuffers::new(
+      iovecs,
+      self.registered.clone(),
+    ))
+  }
+
+  /// Returns the number of buffers [`try_get`](Self::try_get) can lend
+  /// right now, counting ones the pool may still grow.
+  ///
+  /// Servers can check this to apply backpressure before the pool runs dry.
   ///
   /// Note: This is a snapshot and may be stale immediately.
   pub fn available(&self) -> usize {
-    self.free_rx.len()
+    let headroom = self.buffers.len() - self.capacity();
+    self.free_rx.len() + headroom
+  }
+}
+
+/// Buffers the kernel picks from when data arrives, instead of each receive
+/// bringing its own.
+///
+/// With many idle connections, giving every pending `recv` its own buffer
+/// ties up memory that is rarely written to. A group is shared by all of
+/// them: [`recv`](Self::recv) leaves the choice to the kernel, which takes a
+/// free buffer from the group only once data is there. The chosen buffer is
+/// handed out as a [`SelectedBuf`] and goes back to the group with
+/// [`recycle`](Self::recycle).
+///
+/// Groups are identified by a `bgid` that is unique per [`Lio`](crate::Lio),
+/// providing another group under a `bgid` still in use fails with
+/// `EEXIST`. The driver keeps a group's memory until
+/// [`remove`](Self::remove) completes, a group dropped without it holds on
+/// to its memory as long as the `Lio`.
+///
+/// Only the io_uring backend has buffer groups, others fail with
+/// [`io::ErrorKind::Unsupported`](std::io::ErrorKind::Unsupported).
+///
+/// # Example
+///
+/// ```no_run
+/// use lio::{api::resource::Resource, buf::BufferGroup};
+///
+/// async fn serve(socket: &Resource) -> std::io::Result<()> {
+///     let group = BufferGroup::provide(vec![vec![0u8; 4096]; 64], 1).await?;
+///     while let Some(buf) = group.recv(socket, 0).await? {
+///         if buf.is_empty() {
+///             break;
+///         }
+///         println!("buffer {} got {} bytes", buf.bid(), buf.len());
+///         group.recycle(buf).await?;
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg(unix)]
+#[derive(Clone)]
+pub struct BufferGroup {
+  memory: Arc<GroupMemory>,
+}
+
+#[cfg(unix)]
+impl BufferGroup {
+  /// Provides `bufs` to the kernel as group `bgid`, resolving to the group.
+  ///
+  /// The buffers get ids `0..bufs.len()`, in order. They are moved into
+  /// one allocation, their contents don't matter.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `bufs` is empty or has more than `u16::MAX` buffers, or if
+  /// the buffers are empty or don't all have the same length.
+  pub fn provide(
+    bufs: Vec<Vec<u8>>,
+    bgid: u16,
+  ) -> crate::api::io::Io<crate::api::ops::ProvideBuffers> {
+    let count = u16::try_from(bufs.len())
+      .ok()
+      .filter(|&count| count > 0)
+      .unwrap_or_else(|| {
+        panic!(
+          "BufferGroup: expected 1 to {} buffers, got {}",
+          u16::MAX,
+          bufs.len()
+        )
+      });
+    let buf_len = bufs[0].len();
+    assert!(buf_len > 0, "BufferGroup: buffers must not be empty");
+    assert!(
+      bufs.iter().all(|buf| buf.len() == buf_len),
+      "BufferGroup: buffers must all have the same length"
+    );
+    assert!(
+      u32::try_from(buf_len).is_ok_and(|len| len <= i32::MAX as u32),
+      "BufferGroup: buffers of {buf_len} bytes are too large"
+    );
+
+    let mut bytes = bufs.concat();
+    let memory =
+      GroupMemory { ptr: bytes.as_mut_ptr(), bytes, buf_len, count, bgid };
+    let group = Self { memory: Arc::new(memory) };
+    crate::api::io::Io::from_op(crate::api::ops::ProvideBuffers::new(
+      group, 0, count,
+    ))
+  }
+
+  /// Receives from `fd` into a buffer of the group, like
+  /// [`api::recv`](crate::api::recv).
+  ///
+  /// Resolves to `None` at end of stream if the kernel didn't take a buffer
+  /// for it. Fails with `ENOBUFS` when every buffer is handed out.
+  pub fn recv(
+    &self,
+    fd: &crate::api::resource::Resource,
+    flags: i32,
+  ) -> crate::api::io::Io<crate::api::ops::RecvSelect> {
+    crate::api::io::Io::from_op(crate::api::ops::RecvSelect::new(
+      fd.clone(),
+      self.memory.clone(),
+      flags,
+    ))
+  }
+
+  /// Reads from `fd` into buffers of the group as data arrives, one
+  /// [`SelectedBuf`] per chunk, like repeated [`api::read`](crate::api::read)s.
+  ///
+  /// Consume it with [`into_stream`](crate::api::io::Io::into_stream). Made
+  /// for pipes, character devices and other pollable files, to tail them
+  /// without submitting every read. The last item is `None` at end of file.
+  /// See [`ReadMulti`](crate::api::ops::ReadMulti) for when the stream
+  /// ends.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use futures_util::StreamExt;
+  /// use lio::{api::resource::Resource, buf::BufferGroup};
+  ///
+  /// async fn tail(pipe: &Resource) -> std::io::Result<()> {
+  ///     let group = BufferGroup::provide(vec![vec![0u8; 4096]; 16], 2).await?;
+  ///     let mut chunks = group.read_multi(pipe).into_stream();
+  ///     while let Some(Some(buf)) = chunks.next().await.transpose()? {
+  ///         println!("{}", String::from_utf8_lossy(&buf));
+  ///         group.recycle(buf).await?;
+  ///     }
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn read_multi(
+    &self,
+    fd: &crate::api::resource::Resource,
+  ) -> crate::api::io::Io<crate::api::ops::ReadMulti> {
+    crate::api::io::Io::from_op(crate::api::ops::ReadMulti::new(
+      fd.clone(),
+      self.memory.clone(),
+    ))
+  }
+
+  /// Gives a selected buffer back to the group, so the kernel can pick it
+  /// again.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `buf` came from another group.
+  pub fn recycle(
+    &self,
+    buf: SelectedBuf,
+  ) -> crate::api::io::Io<crate::api::ops::ProvideBuffers> {
+    assert!(
+      Arc::ptr_eq(&self.memory, &buf.memory),
+      "BufferGroup::recycle: buffer {} is from another group",
+      buf.bid
+    );
+    crate::api::io::Io::from_op(crate::api::ops::ProvideBuffers::new(
+      self.clone(),
+      buf.bid,
+      1,
+    ))
+  }
+
+  /// Takes the group's buffers back from the kernel, resolving to how many
+  /// it still had.
+  pub fn remove(&self) -> crate::api::io::Io<crate::api::ops::RemoveBuffers> {
+    crate::api::io::Io::from_op(crate::api::ops::RemoveBuffers::new(
+      self.memory.count,
+      self.memory.bgid,
+    ))
+  }
+
+  /// The group id passed to [`provide`](Self::provide).
+  pub fn bgid(&self) -> u16 {
+    self.memory.bgid
+  }
+
+  /// The length of each buffer.
+  pub fn buf_len(&self) -> usize {
+    self.memory.buf_len
+  }
+
+  pub(crate) fn memory(&self) -> &Arc<GroupMemory> {
+    &self.memory
+  }
+}
+
+#[cfg(unix)]
+impl std::fmt::Debug for BufferGroup {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("BufferGroup")
+      .field("bgid", &self.memory.bgid)
+      .field("count", &self.memory.count)
+      .field("buf_len", &self.memory.buf_len)
+      .finish()
+  }
+}
+
+/// The memory of a [`BufferGroup`], `count` buffers of `buf_len` bytes.
+///
+/// Opaque, it only shows up in [`Op::ProvideBuffers`](crate::op::Op).
+#[cfg(unix)]
+pub struct GroupMemory {
+  /// Taken once, the kernel writes through it while `bytes` isn't touched.
+  ptr: *mut u8,
+  #[allow(dead_code)]
+  bytes: Vec<u8>,
+  buf_len: usize,
+  count: u16,
+  bgid: u16,
+}
+
+// SAFETY: The memory is only written by the kernel, into buffers it picked,
+// and read through a SelectedBuf once the kernel is done with them.
+#[cfg(unix)]
+unsafe impl Send for GroupMemory {}
+// SAFETY: A shared GroupMemory only hands out its address and sizes, which
+// never change. Nothing reads or writes the bytes through it: the kernel
+// writes into buffers it picked, and each is only read through the one
+// SelectedBuf holding its bid, until that is recycled.
+#[cfg(unix)]
+unsafe impl Sync for GroupMemory {}
+
+#[cfg(unix)]
+impl GroupMemory {
+  pub(crate) fn buf_ptr(&self, bid: u16) -> *mut u8 {
+    // SAFETY: bid is below count, so this stays within bytes.
+    unsafe { self.ptr.add(bid as usize * self.buf_len) }
+  }
+
+  pub(crate) fn buf_len(&self) -> usize {
+    self.buf_len
+  }
+
+  pub(crate) fn bgid(&self) -> u16 {
+    self.bgid
+  }
+}
+
+/// The buffer groups the kernel may still pick from, kept by the driver.
+///
+/// The kernel keeps the addresses of provided buffers until they are
+/// removed, so a group's memory is held here from the provide until its
+/// removal completed, whether or not the [`BufferGroup`] is still around.
+#[cfg(unix)]
+#[derive(Default)]
+pub(crate) struct LiveGroups {
+  by_bgid: std::collections::HashMap<u16, LiveGroup>,
+  /// Provides that listed a group and removals, by op id.
+  pending: std::collections::HashMap<u64, u16>,
+}
+
+#[cfg(unix)]
+struct LiveGroup {
+  memory: Arc<GroupMemory>,
+  /// The provide that listed the group, until it completes.
+  providing: Option<u64>,
+  /// The last removal, cleared by any later provide.
+  removing: Option<u64>,
+}
+
+#[cfg(unix)]
+impl LiveGroups {
+  /// Tracks op `id` as it is scheduled, failing with `EEXIST` if it
+  /// provides to a `bgid` another group is still listed under.
+  pub(crate) fn admit(
+    &mut self,
+    id: u64,
+    op: &crate::op::Op,
+  ) -> Result<(), i32> {
+    match op {
+      crate::op::Op::ProvideBuffers { bgid, memory, .. } => {
+        match self.by_bgid.get_mut(bgid) {
+          Some(live) if !Arc::ptr_eq(&live.memory, memory) => {
+            return Err(libc::EEXIST);
+          }
+          // Executed in order, so it lists the buffers again after the
+          // removal.
+          Some(live) => live.removing = None,
+          None => {
+            let live = LiveGroup {
+              memory: memory.clone(),
+              providing: Some(id),
+              removing: None,
+            };
+            self.by_bgid.insert(*bgid, live);
+            self.pending.insert(id, *bgid);
+          }
+        }
+      }
+      crate::op::Op::RemoveBuffers { bgid, .. } => {
+        if let Some(live) = self.by_bgid.get_mut(bgid) {
+          live.removing = Some(id);
+          self.pending.insert(id, *bgid);
+        }
+      }
+      _ => {}
+    }
+    Ok(())
+  }
+
+  /// Lets go of a group once the kernel no longer lists it: its provide
+  /// failed or its removal completed.
+  pub(crate) fn complete(&mut self, id: u64, result: isize) {
+    let Some(bgid) = self.pending.remove(&id) else { return };
+    let Some(live) = self.by_bgid.get_mut(&bgid) else { return };
+    let unlisted = if live.providing == Some(id) {
+      live.providing = None;
+      result < 0 && live.removing.is_none()
+    } else if live.removing == Some(id) {
+      live.removing = None;
+      result >= 0 || result == -(libc::ENOENT as isize)
+    } else {
+      false
+    };
+    if unlisted {
+      self.by_bgid.remove(&bgid);
+    }
+  }
+}
+
+/// A buffer the kernel picked from a [`BufferGroup`], holding the received
+/// bytes.
+///
+/// Dereferences to the received bytes. Hand it back with
+/// [`BufferGroup::recycle`], or the kernel can't pick it again.
+#[cfg(unix)]
+pub struct SelectedBuf {
+  memory: Arc<GroupMemory>,
+  bid: u16,
+  len: usize,
+}
+
+#[cfg(unix)]
+impl SelectedBuf {
+  pub(crate) fn new(memory: Arc<GroupMemory>, bid: u16, len: usize) -> Self {
+    assert!(
+      bid < memory.count && len <= memory.buf_len,
+      "SelectedBuf: kernel picked buffer {bid} with {len} bytes out of range"
+    );
+    Self { memory, bid, len }
+  }
+
+  /// The id of the buffer within its group.
+  pub fn bid(&self) -> u16 {
+    self.bid
+  }
+}
+
+#[cfg(unix)]
+impl std::ops::Deref for SelectedBuf {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    // SAFETY: The kernel wrote len bytes to this buffer and doesn't touch it
+    // until it is recycled, which takes the SelectedBuf.
+    unsafe { slice::from_raw_parts(self.memory.buf_ptr(self.bid), self.len) }
+  }
+}
+
+#[cfg(unix)]
+impl AsRef<[u8]> for SelectedBuf {
+  fn as_ref(&self) -> &[u8] {
+    self
+  }
+}
+
+#[cfg(unix)]
+impl std::fmt::Debug for SelectedBuf {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SelectedBuf")
+      .field("bid", &self.bid)
+      .field("len", &self.len)
+      .finish()
   }
 }
 
@@ -506,6 +1397,8 @@ mod tests {
     assert_send::<super::BufStore>();
     assert_send::<super::LentBuf>();
     assert_sync::<super::LentBuf>();
+    assert_send::<super::SharedBuf>();
+    assert_sync::<super::SharedBuf>();
   }
 
   #[test]
@@ -515,7 +1408,7 @@ mod tests {
     let buf = store.try_get().expect("should get buffer from pool");
 
     // Set up buffer data
-    let cell = &store.buffers[buf.index as usize];
+    let cell = store.cell(buf.index);
     unsafe {
       let buf_ptr = cell.buf.get();
       (*buf_ptr)[0] = b'A';
@@ -541,7 +1434,7 @@ mod tests {
     let store = Box::leak(Box::new(BufStore::with_capacity(1)));
     let buf = store.try_get().expect("should get buffer from pool");
 
-    let cell = &store.buffers[buf.index as usize];
+    let cell = store.cell(buf.index);
     unsafe {
       let buf_ptr = cell.buf.get();
       (*buf_ptr)[0] = b'A';
@@ -568,7 +1461,7 @@ mod tests {
     let store = Box::leak(Box::new(BufStore::with_capacity(1)));
     let buf = store.try_get().expect("should get buffer from pool");
 
-    let cell = &store.buffers[buf.index as usize];
+    let cell = store.cell(buf.index);
     unsafe {
       let buf_ptr = cell.buf.get();
       (*buf_ptr)[0] = b'A';
@@ -595,7 +1488,7 @@ mod tests {
     let store = Box::leak(Box::new(BufStore::with_capacity(1)));
     let mut buf = store.try_get().expect("should get buffer from pool");
 
-    let cell = &store.buffers[buf.index as usize];
+    let cell = store.cell(buf.index);
     unsafe {
       let buf_ptr = cell.buf.get();
       (*buf_ptr)[0] = b'H';
@@ -636,6 +1529,27 @@ mod tests {
   // BufStore Tests
   // ============================================================================
 
+  #[test]
+  fn test_bufstore_with_alignment() {
+    let store = Box::leak(Box::new(BufStore::with_alignment(512, 3, 4096)));
+    assert_eq!(store.slab_size(), 512);
+    assert_eq!(store.alignment(), 4096);
+
+    let bufs: Vec<_> = (0..3).map(|_| store.try_get().unwrap()).collect();
+    for buf in &bufs {
+      assert_eq!(buf.alignment(), 4096);
+      assert_eq!(buf.buf().len(), 512);
+      assert_eq!(buf.buf().as_ptr() as usize % 4096, 0);
+    }
+    assert!(store.try_get().is_none());
+  }
+
+  #[test]
+  #[should_panic(expected = "invalid alignment")]
+  fn test_bufstore_rejects_bad_alignment() {
+    BufStore::with_alignment(4096, 1, 3);
+  }
+
   #[test]
   fn test_bufstore_basic_allocation() {
     let store = Box::leak(Box::new(BufStore::with_capacity(4)));
@@ -674,7 +1588,7 @@ mod tests {
       let buf = store.try_get().expect("should get buffer");
 
       // Write some data
-      let cell = &store.buffers[buf.index as usize];
+      let cell = store.cell(buf.index);
       unsafe {
         (*cell.buf.get())[0] = i;
       }
@@ -686,7 +1600,7 @@ mod tests {
       let buf2 = store.try_get().expect("should get buffer after drop");
 
       // Buffer should be reset (len = 0)
-      let cell2 = &store.buffers[buf2.index as usize];
+      let cell2 = store.cell(buf2.index);
       assert_eq!(
         cell2.len.load(Ordering::Acquire),
         0,
@@ -827,7 +1741,7 @@ mod tests {
           for _ in 0..iterations {
             if let Some(buf) = store.try_get() {
               // Simulate some work
-              let cell = &store.buffers[buf.index as usize];
+              let cell = store.cell(buf.index);
               unsafe {
                 (*cell.buf.get())[0] = 42;
               }
@@ -878,7 +1792,7 @@ mod tests {
               acquired_count += 1;
 
               // Hold buffer briefly
-              let cell = &store.buffers[buf.index as usize];
+              let cell = store.cell(buf.index);
               cell.len.store(42, Ordering::Release);
 
               // Small delay to increase contention
@@ -955,4 +1869,157 @@ mod tests {
     let available: Vec<_> = (0..32).filter_map(|_| store.try_get()).collect();
     assert_eq!(available.len(), 32, "no buffers should be lost");
   }
+
+  #[test]
+  fn test_bufstore_grows_up_to_cap() {
+    let store = Box::leak(Box::new(BufStore::with_growth(2, 4)));
+    assert_eq!(store.capacity(), 2);
+    assert_eq!(store.max_capacity(), 4);
+
+    let bufs: Vec<_> = (0..4)
+      .map(|i| store.try_get().unwrap_or_else(|| panic!("lease {i} failed")))
+      .collect();
+    assert_eq!(store.capacity(), 4, "should have grown to the cap");
+
+    // Past the cap leasing fails instead of growing further.
+    assert!(store.try_get().is_none());
+    assert!(store.get_timeout(Duration::from_millis(1)).is_none());
+    assert_eq!(store.capacity(), 4);
+
+    let mut indices: Vec<_> = bufs.iter().map(|b| b.index).collect();
+    indices.sort();
+    assert_eq!(indices, [0, 1, 2, 3]);
+
+    // Grown buffers go back to the free list like the initial ones.
+    drop(bufs);
+    assert_eq!(store.available(), 4);
+    let _buf = store.try_get().expect("should reuse a grown buffer");
+    assert_eq!(store.capacity(), 4);
+  }
+
+  #[test]
+  fn test_bufstore_metrics() {
+    let store = Box::leak(Box::new(BufStore::with_growth(1, 3)));
+    let metrics = store.metrics();
+    assert_eq!(
+      metrics,
+      BufStoreMetrics { in_use: 0, peak_in_use: 0, allocated: 1 }
+    );
+
+    let a = store.try_get().unwrap();
+    let b = store.try_get().unwrap();
+    drop(a);
+    let metrics = store.metrics();
+    assert_eq!(metrics.in_use, 1);
+    assert_eq!(metrics.peak_in_use, 2);
+    assert_eq!(metrics.allocated, 2);
+
+    drop(b);
+    assert_eq!(store.metrics().in_use, 0);
+    assert_eq!(store.metrics().peak_in_use, 2);
+  }
+
+  #[test]
+  #[should_panic(expected = "initial (2) > max (1)")]
+  fn test_bufstore_growth_initial_above_max() {
+    let _ = BufStore::with_growth(2, 1);
+  }
+
+  #[test]
+  fn test_bufstore_concurrent_growth_hands_out_each_index_once() {
+    let store: &'static BufStore =
+      Box::leak(Box::new(BufStore::with_growth(0, 64)));
+    let handles: Vec<_> = (0..8)
+      .map(|_| {
+        std::thread::spawn(move || {
+          std::iter::from_fn(|| store.grow()).collect::<Vec<_>>()
+        })
+      })
+      .collect();
+    let mut indices: Vec<u32> =
+      handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+    indices.sort();
+    assert_eq!(indices, (0..64).collect::<Vec<_>>());
+    assert_eq!(store.capacity(), 64);
+    assert!(store.grow().is_none());
+  }
+
+  #[test]
+  fn test_bufstore_available_counts_growth() {
+    let store = Box::leak(Box::new(BufStore::with_growth(1, 3)));
+    assert_eq!(store.available(), 3);
+
+    let a = store.try_get().unwrap();
+    let b = store.try_get().unwrap();
+    assert_eq!(store.available(), 1);
+
+    drop(a);
+    drop(b);
+    assert_eq!(store.available(), 3);
+  }
+
+  #[cfg(unix)]
+  fn provide_op(memory: &Arc<GroupMemory>) -> crate::op::Op {
+    crate::op::Op::ProvideBuffers {
+      addr: memory.buf_ptr(0),
+      len: memory.buf_len as u32,
+      nbufs: memory.count,
+      bgid: memory.bgid,
+      bid: 0,
+      memory: memory.clone(),
+    }
+  }
+
+  #[cfg(unix)]
+  fn group_memory(bgid: u16) -> Arc<GroupMemory> {
+    let mut bytes = vec![0u8; 64];
+    let ptr = bytes.as_mut_ptr();
+    Arc::new(GroupMemory { ptr, bytes, buf_len: 16, count: 4, bgid })
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn test_live_groups_hold_memory_until_removed() {
+    let mut groups = LiveGroups::default();
+    let memory = group_memory(7);
+    groups.admit(1, &provide_op(&memory)).unwrap();
+    groups.complete(1, 0);
+    assert_eq!(Arc::strong_count(&memory), 2);
+
+    // Another group can't take the bgid, recycling into the same one can.
+    let other = group_memory(7);
+    assert_eq!(groups.admit(2, &provide_op(&other)), Err(libc::EEXIST));
+    groups.admit(3, &provide_op(&memory)).unwrap();
+
+    let remove = crate::op::Op::RemoveBuffers { nbufs: 4, bgid: 7 };
+    groups.admit(4, &remove).unwrap();
+    groups.complete(4, 4);
+    assert_eq!(Arc::strong_count(&memory), 1);
+    groups.admit(5, &provide_op(&other)).unwrap();
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn test_live_groups_provide_after_remove_keeps_memory() {
+    let mut groups = LiveGroups::default();
+    let memory = group_memory(3);
+    groups.admit(1, &provide_op(&memory)).unwrap();
+    groups.complete(1, 0);
+
+    let remove = crate::op::Op::RemoveBuffers { nbufs: 4, bgid: 3 };
+    groups.admit(2, &remove).unwrap();
+    groups.admit(3, &provide_op(&memory)).unwrap();
+    groups.complete(2, 4);
+    assert_eq!(Arc::strong_count(&memory), 2);
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn test_live_groups_failed_provide_releases() {
+    let mut groups = LiveGroups::default();
+    let memory = group_memory(1);
+    groups.admit(1, &provide_op(&memory)).unwrap();
+    groups.complete(1, -(libc::EOPNOTSUPP as isize));
+    assert_eq!(Arc::strong_count(&memory), 1);
+  }
 }
@@ -0,0 +1,122 @@
+//! Cancelling groups of operations at once.
+
+use std::sync::{
+  Arc,
+  atomic::{AtomicBool, Ordering},
+};
+
+/// Cancels every operation it was attached to, see
+/// [`Io::with_cancel`](crate::api::io::Io::with_cancel).
+///
+/// Clones share the same state, so a parent task can hand clones to its
+/// children and abort all of their I/O with one [`cancel`](Self::cancel).
+/// The driver checks tokens each time it runs: operations still in flight
+/// then complete with `ECANCELED`, ones that already finished keep their
+/// result.
+///
+/// # Example
+///
+/// ```no_run
+/// use lio::{CancellationToken, Lio, api};
+///
+/// let lio = Lio::new(64).unwrap();
+/// let token = CancellationToken::new();
+/// let fd = api::resource::Resource::stdin();
+/// let receiver =
+///   api::read(&fd, vec![0u8; 64]).with_lio(&lio).with_cancel(&token).send();
+///
+/// token.cancel();
+/// lio.run().unwrap();
+/// let (result, _buf) = receiver.recv();
+/// assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+  cancelled: Arc<AtomicBool>,
+  /// Ops cancelled through this token fail with `EINTR` instead, see
+  /// [`CancelHandle`].
+  interrupt: bool,
+  /// Another token the op is attached to, when it has several.
+  parent: Option<Arc<CancellationToken>>,
+}
+
+impl CancellationToken {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Cancels every operation attached to this token, now or later.
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Release);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled_errno().is_some()
+  }
+
+  /// The error the ops attached to this token should fail with, if it was
+  /// cancelled.
+  pub(crate) fn cancelled_errno(&self) -> Option<i32> {
+    if self.cancelled.load(Ordering::Acquire) {
+      return Some(if self.interrupt { libc::EINTR } else { libc::ECANCELED });
+    }
+    self.parent.as_ref().and_then(|parent| parent.cancelled_errno())
+  }
+
+  /// A token cancelled when either `self` or `other` is.
+  pub(crate) fn or(self, other: CancellationToken) -> Self {
+    let parent = match self.parent {
+      Some(parent) => (*parent).clone().or(other),
+      None => other,
+    };
+    Self { parent: Some(Arc::new(parent)), ..self }
+  }
+}
+
+/// Cancels a single operation, see
+/// [`Io::cancel_handle`](crate::api::io::Io::cancel_handle).
+///
+/// Unlike [`CancellationToken`], the operation fails with
+/// [`ErrorKind::Interrupted`](std::io::ErrorKind::Interrupted) rather than
+/// `ECANCELED`, and still returns its buffer. Clones share the same state.
+///
+/// # Example
+///
+/// ```no_run
+/// use lio::{Lio, api};
+///
+/// let lio = Lio::new(64).unwrap();
+/// let fd = api::resource::Resource::stdin();
+/// let mut read = api::read(&fd, vec![0u8; 64]).with_lio(&lio);
+/// let handle = read.cancel_handle();
+/// let receiver = read.send();
+///
+/// handle.cancel();
+/// lio.run().unwrap();
+/// let (result, _buf) = receiver.recv();
+/// assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Interrupted);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CancelHandle {
+  token: CancellationToken,
+}
+
+impl CancelHandle {
+  pub(crate) fn new() -> Self {
+    Self { token: CancellationToken { interrupt: true, ..Default::default() } }
+  }
+
+  pub(crate) fn token(&self) -> &CancellationToken {
+    &self.token
+  }
+
+  /// Cancels the operation. The driver aborts it the next time it runs,
+  /// unless it already completed.
+  pub fn cancel(&self) {
+    self.token.cancel();
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.token.cancelled.load(Ordering::Acquire)
+  }
+}
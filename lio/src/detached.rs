@@ -0,0 +1,126 @@
+//! Results of detached operations, kept until they are claimed by id.
+
+use std::{
+  any::Any,
+  collections::HashMap,
+  future::Future,
+  marker::PhantomData,
+  pin::Pin,
+  sync::{Arc, Mutex},
+  task::{Context, Poll, Waker},
+};
+
+/// Names a detached operation whose result the driver keeps, see
+/// [`Io::detach_with_id`](crate::api::io::Io::detach_with_id).
+///
+/// Claim the result with [`Lio::await_id`](crate::Lio::await_id). It is
+/// held until then, so an id that is never claimed keeps its result, and
+/// its share of [`Lio::set_pending_memory_cap`](crate::Lio::set_pending_memory_cap),
+/// for the lifetime of the driver.
+#[derive(Debug)]
+#[must_use = "the result is kept until claimed with Lio::await_id"]
+pub struct OpId<R> {
+  key: u64,
+  _result: PhantomData<fn() -> R>,
+}
+
+impl<R> OpId<R> {
+  /// Raw value of the id, unique per driver.
+  pub fn as_u64(&self) -> u64 {
+    self.key
+  }
+}
+
+enum Slot {
+  Running(Option<Waker>),
+  Done(Box<dyn Any + Send>),
+}
+
+#[derive(Default)]
+struct Slots {
+  next: u64,
+  slots: HashMap<u64, (Slot, usize)>,
+  /// Sum of the result sizes reserved by the slots.
+  bytes: usize,
+}
+
+/// Where detached results wait, shared with the callbacks that fill it.
+#[derive(Clone, Default)]
+pub(crate) struct Detached(Arc<Mutex<Slots>>);
+
+impl Detached {
+  fn lock(&self) -> std::sync::MutexGuard<'_, Slots> {
+    self.0.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  /// Memory held for results that haven't been claimed.
+  pub(crate) fn bytes(&self) -> usize {
+    self.lock().bytes
+  }
+
+  /// Takes a slot for a result of type `R`.
+  pub(crate) fn reserve<R>(&self) -> OpId<R> {
+    let bytes = size_of::<R>();
+    let mut slots = self.lock();
+    let key = slots.next;
+    slots.next += 1;
+    slots.slots.insert(key, (Slot::Running(None), bytes));
+    slots.bytes += bytes;
+    OpId { key, _result: PhantomData }
+  }
+
+  /// Stores the result for `id`, waking whoever awaits it.
+  pub(crate) fn fill<R: Send + 'static>(&self, key: u64, result: R) {
+    let mut slots = self.lock();
+    let Some((slot, _)) = slots.slots.get_mut(&key) else {
+      panic!("lio bookkeeping bug: detached slot doesn't exist.");
+    };
+    if let Slot::Running(Some(waker)) =
+      std::mem::replace(slot, Slot::Done(Box::new(result)))
+    {
+      waker.wake();
+    }
+  }
+
+  fn poll_take<R: 'static>(&self, key: u64, cx: &mut Context<'_>) -> Poll<R> {
+    let mut slots = self.lock();
+    match slots.slots.remove(&key) {
+      Some((Slot::Done(result), bytes)) => {
+        slots.bytes -= bytes;
+        let result =
+          result.downcast::<R>().expect("OpId is typed by its result");
+        Poll::Ready(*result)
+      }
+      Some((Slot::Running(_), bytes)) => {
+        let waker = Some(cx.waker().clone());
+        slots.slots.insert(key, (Slot::Running(waker), bytes));
+        Poll::Pending
+      }
+      None => panic!("AwaitId polled after completion"),
+    }
+  }
+}
+
+/// Future returned by [`Lio::await_id`](crate::Lio::await_id).
+///
+/// Like any operation the driver has to keep running for it to complete.
+#[must_use = "futures do nothing unless polled"]
+pub struct AwaitId<R> {
+  detached: Detached,
+  key: u64,
+  _result: PhantomData<fn() -> R>,
+}
+
+impl<R> AwaitId<R> {
+  pub(crate) fn new(detached: Detached, id: OpId<R>) -> Self {
+    Self { detached, key: id.key, _result: PhantomData }
+  }
+}
+
+impl<R: 'static> Future for AwaitId<R> {
+  type Output = R;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<R> {
+    self.detached.poll_take(self.key, cx)
+  }
+}
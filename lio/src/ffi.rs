@@ -25,6 +25,8 @@
 //!
 //! `intptr_t` is used for file descriptors / handles so that the same header
 //! works on both Unix (32-bit `int` fd) and Windows (pointer-sized `HANDLE`).
+//! The API itself is only built on Unix for now, the feature does nothing
+//! on Windows.
 //!
 //! ## Buffer ownership
 //!
@@ -41,7 +43,11 @@ use std::{
 
 use crate::{
   Lio,
-  api::{self, resource::Resource},
+  api::{
+    self,
+    flags::{RecvFlags, SendFlags},
+    resource::Resource,
+  },
   net_utils,
 };
 
@@ -509,7 +515,7 @@ pub unsafe extern "C" fn lio_send(
   // SAFETY: caller guarantees fd is valid per fn contract
   let resource = unsafe { fd_to_resource(fd) };
   // SAFETY: caller guarantees lio is valid per fn contract
-  api::send(&resource, vec, Some(flags))
+  api::send(&resource, vec, Some(SendFlags::from_raw(flags)))
     .with_lio(&unsafe { handle(lio) }.inner)
     .when_done(move |(res, mut buf)| {
       let code = match res {
@@ -549,7 +555,7 @@ pub unsafe extern "C" fn lio_recv(
   // SAFETY: caller guarantees fd is valid per fn contract
   let resource = unsafe { fd_to_resource(fd) };
   // SAFETY: caller guarantees lio is valid per fn contract
-  api::recv(&resource, vec, Some(flags))
+  api::recv(&resource, vec, Some(RecvFlags::from_raw(flags)))
     .with_lio(&unsafe { handle(lio) }.inner)
     .when_done(move |(res, mut buf)| {
       let code = match res {
@@ -0,0 +1,188 @@
+//! Waiting on a shared `u32` without polling it.
+
+use std::{
+  collections::VecDeque,
+  os::fd::{FromRawFd, OwnedFd, RawFd},
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicU32, Ordering},
+  },
+};
+
+use crate::{
+  api::{io::Io, ops, resource::Resource},
+  op::Op,
+};
+
+/// A `u32` that operations can wait on until another task wakes them.
+///
+/// This is the building block for async condition variables and the like:
+/// a waiter checks some condition, then calls [`wait`](Self::wait) with the
+/// value it saw, and is parked until someone changes the value and calls
+/// [`wake`](Self::wake). If the value already moved on when the wait starts,
+/// it fails with `EAGAIN` right away, so no wakeup is lost in between.
+///
+/// On io_uring (Linux 6.7 and later) waiters are parked by the kernel with
+/// `FUTEX_WAIT`. Elsewhere each waiter polls a pipe of its own, which a
+/// wake hangs up, so it costs two fds while parked.
+///
+/// Like a futex, a wait may complete without a matching wake. Always check
+/// the condition again after waking up.
+///
+/// Clones share the same value.
+///
+/// # Example
+///
+/// ```no_run
+/// use lio::AsyncFutex;
+/// use std::sync::atomic::Ordering;
+///
+/// async fn example(ready: &AsyncFutex) {
+///     while ready.value().load(Ordering::Acquire) == 0 {
+///         // EAGAIN means the value changed already, look again.
+///         let _ = ready.wait(0).await;
+///     }
+/// }
+///
+/// async fn signal(ready: &AsyncFutex) -> std::io::Result<()> {
+///     ready.value().store(1, Ordering::Release);
+///     ready.wake(u32::MAX).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct AsyncFutex {
+  word: Arc<FutexWord>,
+}
+
+impl AsyncFutex {
+  pub fn new(value: u32) -> Self {
+    Self {
+      word: Arc::new(FutexWord {
+        value: AtomicU32::new(value),
+        ..Default::default()
+      }),
+    }
+  }
+
+  /// The value waiters compare against.
+  pub fn value(&self) -> &AtomicU32 {
+    &self.word.value
+  }
+
+  /// Waits until woken, if the value still equals `expected`.
+  ///
+  /// Fails with `EAGAIN` ([`WouldBlock`](std::io::ErrorKind::WouldBlock))
+  /// when it doesn't.
+  pub fn wait(&self, expected: u32) -> Io<ops::FutexWait> {
+    Io::from_op(ops::FutexWait::new(self.word.clone(), expected))
+  }
+
+  /// Wakes up to `n` waiters, resolving to how many were woken.
+  pub fn wake(&self, n: u32) -> Io<ops::FutexWake> {
+    Io::from_op(ops::FutexWake::new(self.word.clone(), n))
+  }
+}
+
+impl std::fmt::Debug for AsyncFutex {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("AsyncFutex").field("value", &self.word.value).finish()
+  }
+}
+
+/// The state behind an [`AsyncFutex`], shared with its operations.
+#[derive(Default)]
+pub struct FutexWord {
+  value: AtomicU32,
+  /// Waiters parked by backends without a futex opcode.
+  parked: Mutex<Parked>,
+}
+
+#[derive(Default)]
+struct Parked {
+  next_token: u64,
+  /// The write end of each parked waiter's pipe, oldest first.
+  waiters: VecDeque<(u64, OwnedFd)>,
+}
+
+/// A waiter parked by [`FutexWord::park`], held by its wait op.
+///
+/// Dropping it, once the op completes, is cancelled or its future is
+/// dropped, takes the waiter off the word, so wakes only go to live ones.
+pub struct Parker {
+  word: Arc<FutexWord>,
+  token: u64,
+  /// The backend may only hold the raw fd while polling it.
+  _read: Resource,
+}
+
+impl Drop for Parker {
+  fn drop(&mut self) {
+    let mut parked = self.word.lock();
+    parked.waiters.retain(|(token, _)| *token != self.token);
+  }
+}
+
+impl FutexWord {
+  #[cfg(linux)]
+  pub(crate) fn as_ptr(&self) -> *const u32 {
+    self.value.as_ptr()
+  }
+
+  /// Emulates a futex wait for backends without one: parks a waiter on a
+  /// fresh pipe and returns the poll on its read end, which completes once
+  /// [`wake_parked`](Self::wake_parked) closes the write end.
+  ///
+  /// Returns a negated `EAGAIN` if the value isn't `expected`. That is
+  /// checked under the lock wakers take, so a wake can't slip in between.
+  ///
+  /// # Safety
+  ///
+  /// `parker` must be valid for writes and outlive the returned op, it
+  /// points into the boxed [`FutexWait`](ops::FutexWait) op.
+  pub(crate) unsafe fn park(
+    word: &Arc<Self>,
+    expected: u32,
+    parker: *mut Option<Parker>,
+  ) -> Result<Op, isize> {
+    let mut parked = word.lock();
+    if word.value.load(Ordering::Acquire) != expected {
+      return Err(-(libc::EAGAIN as isize));
+    }
+    let mut fds: [RawFd; 2] = [-1; 2];
+    // SAFETY: fds is a local array of two fds.
+    let res = unsafe { ops::pipe_blocking(&mut fds, libc::O_CLOEXEC) };
+    if res < 0 {
+      return Err(res);
+    }
+    // SAFETY: The pipe was just created, so both fds are valid and owned by
+    // nobody else.
+    let (read, write) =
+      unsafe { (Resource::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    let token = parked.next_token;
+    parked.next_token += 1;
+    parked.waiters.push_back((token, write));
+    drop(parked);
+
+    let fd = read.clone();
+    // SAFETY: The caller guarantees parker is valid for writes.
+    unsafe {
+      *parker = Some(Parker { word: word.clone(), token, _read: read });
+    }
+    Ok(Op::Poll { fd, events: libc::POLLIN })
+  }
+
+  /// Wakes up to `n` waiters parked by [`park`](Self::park), oldest first,
+  /// returning how many.
+  pub(crate) fn wake_parked(&self, n: u32) -> usize {
+    let mut parked = self.lock();
+    let woken = parked.waiters.len().min(n as usize);
+    // Closing the write end hangs up the pipe, which completes the poll.
+    parked.waiters.drain(..woken);
+    woken
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, Parked> {
+    self.parked.lock().unwrap_or_else(|e| e.into_inner())
+  }
+}
@@ -19,7 +19,8 @@
 //! It uses the most efficient I/O available based on OS. It uses:
 //! - **Linux**: io_uring, epoll as backup.
 //! - **BSD/Apple**: kqueue.
-//! - **Windows**: I/O Completion Ports.
+//! - **Windows**: I/O Completion Ports. Files only for now: sockets and the
+//!   [`net`] module are Unix-only.
 //!
 //! As lio is platform-independent, it needs to abstract over OS resources like
 //! files/sockets. `lio` calls these [resources](crate::api::resource).
@@ -47,16 +48,23 @@
 #[macro_use]
 mod macros;
 pub mod buf;
-#[cfg(feature = "unstable_ffi")]
+#[cfg(all(unix, feature = "unstable_ffi"))]
 pub mod ffi;
+#[cfg(unix)]
 mod net_utils;
 
+#[cfg(unix)]
 pub mod net;
 
 pub mod fs;
 
 pub use buf::BufResult;
 
+/// The io_uring bindings, for building entries of a
+/// [`RawOperation`](typed_op::RawOperation).
+#[cfg(target_os = "linux")]
+pub use lio_uring;
+
 pub mod op;
 pub mod typed_op;
 
@@ -67,9 +75,26 @@ mod registration;
 pub mod backends;
 
 pub mod api;
+mod cancel;
+pub use cancel::{CancelHandle, CancellationToken};
+mod detached;
+pub use detached::{AwaitId, OpId};
+#[cfg(unix)]
+mod futex;
+#[cfg(unix)]
+pub use futex::AsyncFutex;
+#[cfg(unix)]
+mod worker;
+#[cfg(unix)]
+pub use worker::spawn_blocking;
 #[cfg_attr(docsrs, doc(hidden))]
 pub mod test_utils;
 
 // Re-export core types
 mod lio;
-pub use lio::{Lio, install_global, uninstall_global};
+pub use lio::{
+  Completion, Lio, OpInfo, SqFullPolicy, WaitStrategy, debug_dump, deferred,
+  drain_and_exit, install_global, uninstall_global,
+};
+#[cfg(target_os = "linux")]
+pub use lio::{init_with_affinity, try_init_with_uring_params};
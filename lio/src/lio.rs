@@ -1,10 +1,23 @@
 use crate::{
+  CancellationToken,
+  api::resource::Resource,
   backends::{IoBackend, OpStore},
+  detached::{AwaitId, Detached, OpId},
   op::Op,
-  registration::Registration,
+  registration::{Registration, notifier::OpCallback},
 };
 
-use std::{cell::RefCell, io, rc::Rc, task::Waker, time::Duration};
+use std::{
+  cell::RefCell,
+  collections::{HashMap, HashSet, VecDeque},
+  io, mem,
+  rc::Rc,
+  task::Waker,
+  time::{Duration, Instant},
+};
+
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, RawFd};
 
 thread_local! {
   static GLOBAL_LIO: RefCell<Option<Lio>> = const { RefCell::new(None) };
@@ -28,6 +41,83 @@ pub fn install_global(lio: Lio) {
   });
 }
 
+/// Pins the current thread to `cpu` and installs a new global Lio with
+/// capacity `cap` on it.
+///
+/// The driver runs on whichever thread owns it, so this is meant to be
+/// called at the start of a thread-per-core worker: every later
+/// submission and completion for this Lio is then handled on `cpu`.
+///
+/// # Errors
+///
+/// Fails if `sched_setaffinity` rejects `cpu`, for example because it is
+/// out of range or outside the process's allowed set, or if creating the
+/// Lio fails. Nothing is installed in that case.
+///
+/// # Panics
+///
+/// Panics if a global Lio is already installed on this thread.
+///
+/// # Example
+///
+/// ```no_run
+/// std::thread::spawn(|| {
+///     lio::init_with_affinity(1024, 2).unwrap();
+///     // Drive I/O on CPU 2 from here on.
+/// });
+/// ```
+#[cfg(target_os = "linux")]
+pub fn init_with_affinity(cap: usize, cpu: usize) -> io::Result<()> {
+  // SAFETY: cpu_set_t is a plain bitmask, all zeroes is the empty set.
+  let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+  if cpu >= libc::CPU_SETSIZE as usize {
+    return Err(io::Error::from_raw_os_error(libc::EINVAL));
+  }
+  // SAFETY: cpu was checked to be within the set.
+  unsafe { libc::CPU_SET(cpu, &mut set) };
+  // Pid 0 is the calling thread.
+  syscall!(sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set))?;
+  install_global(Lio::new(cap)?);
+  Ok(())
+}
+
+/// Installs a new global Lio with capacity `cap`, whose io_uring is set up
+/// with `params`.
+///
+/// Meant for servers that want a kernel SQPOLL thread, see
+/// [`Params::sqpoll`](lio_uring::Params::sqpoll): it picks submissions up
+/// from the queue, so the hot path makes no `io_uring_enter` syscalls while
+/// it is busy. Before Linux 5.11 the kernel only allows SQPOLL with
+/// `CAP_SYS_NICE`. Without it the ring is set up without SQPOLL instead,
+/// and everything else works the same.
+///
+/// `params.sq_entries` is replaced by `cap`.
+///
+/// # Errors
+///
+/// Fails if creating the Lio fails. Nothing is installed in that case.
+///
+/// # Panics
+///
+/// Panics if a global Lio is already installed on this thread.
+///
+/// # Example
+///
+/// ```no_run
+/// use lio::lio_uring::Params;
+///
+/// lio::try_init_with_uring_params(Params::default().sqpoll(2000), 1024).unwrap();
+/// ```
+#[cfg(target_os = "linux")]
+pub fn try_init_with_uring_params(
+  params: lio_uring::Params,
+  cap: usize,
+) -> io::Result<()> {
+  use crate::backends::io_uring::IoUring;
+  install_global(Lio::new_with_backend(IoUring::with_params(params), cap)?);
+  Ok(())
+}
+
 /// Uninstalls the global Lio instance for the current thread.
 ///
 /// Returns the previously installed Lio, or `None` if no global was installed.
@@ -35,6 +125,68 @@ pub fn uninstall_global() -> Option<Lio> {
   GLOBAL_LIO.with(|global| global.borrow_mut().take())
 }
 
+/// Drains the global Lio, then uninstalls it.
+///
+/// New operations are refused from here on, see [`Lio::drain`]. Once every
+/// operation in flight has completed, the global is uninstalled and the
+/// driver freed along with its last reference. Meant for shutting a thread
+/// down cleanly, for example after a signal.
+///
+/// # Errors
+///
+/// If `timeout` elapses first, returns how many operations are still in
+/// flight and leaves the global installed: their buffers may still be in
+/// use by the kernel, so it is not freed. Call again to keep waiting.
+///
+/// # Panics
+///
+/// Panics if no global Lio is installed on this thread, including when it
+/// already exited.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// lio::install_global(lio::Lio::new(1024).unwrap());
+/// // ... serve until asked to stop ...
+/// if let Err(stragglers) = lio::drain_and_exit(Some(Duration::from_secs(5))) {
+///     eprintln!("{stragglers} operations didn't finish in time");
+/// }
+/// ```
+pub fn drain_and_exit(timeout: Option<Duration>) -> Result<(), usize> {
+  let lio = get_global().expect(
+    "No Lio instance available. Call install_global(lio) first, and exit only once.",
+  );
+  lio.drain(timeout)?;
+  uninstall_global();
+  Ok(())
+}
+
+/// Runs `f`, then submits everything it scheduled on the global Lio at
+/// once, see [`Lio::deferred`].
+///
+/// # Panics
+///
+/// Panics if no global Lio is installed on this thread.
+pub fn deferred<R>(f: impl FnOnce() -> R) -> io::Result<R> {
+  get_global()
+    .expect("No Lio instance available. Call install_global(lio) first.")
+    .deferred(f)
+}
+
+/// Lists the operations in flight on the global Lio, see
+/// [`Lio::debug_dump`].
+///
+/// # Panics
+///
+/// Panics if no global Lio is installed on this thread.
+pub fn debug_dump() -> Vec<OpInfo> {
+  get_global()
+    .expect("No Lio instance available. Call install_global(lio) first.")
+    .debug_dump()
+}
+
 /// Returns a clone of the global Lio instance for the current thread.
 ///
 /// Returns `None` if no global Lio has been installed.
@@ -42,9 +194,469 @@ pub(crate) fn get_global() -> Option<Lio> {
   GLOBAL_LIO.with(|global| global.borrow().clone())
 }
 
+/// What [`Lio`] does when the backend's submission queue is full.
+///
+/// Set with [`Lio::set_sq_full_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SqFullPolicy {
+  /// Submit what is queued and, if that doesn't free a slot, process
+  /// completions until one frees up.
+  #[default]
+  Block,
+  /// Fail the operation with `EBUSY` ([`io::ErrorKind::ResourceBusy`]).
+  Error,
+  /// Buffer the operation in userspace and hand it to the backend once
+  /// there is room again.
+  Grow,
+}
+
+/// How [`Lio::run`] waits for completions.
+///
+/// Set with [`Lio::set_wait_strategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WaitStrategy {
+  /// Block in the backend until something completes.
+  #[default]
+  Block,
+  /// Busy-poll for up to `spin` before blocking, as long as completions
+  /// have recently been arriving less than `spin` apart. Once they slow
+  /// down, waits block straight away again.
+  ///
+  /// Trades CPU time for latency while under load, without costing
+  /// anything when idle.
+  Adaptive {
+    /// Longest time to busy-poll, also the completion gap below which the
+    /// driver counts as busy.
+    spin: Duration,
+  },
+}
+
+/// A completed operation whose [`when_done`](crate::api::io::Io::when_done)
+/// callback hasn't run yet, handed to the handler set with
+/// [`Lio::on_batch`].
+pub struct Completion {
+  callback: OpCallback,
+  res: isize,
+}
+
+impl Completion {
+  /// Raw result of the operation: the syscall's return value, or `-errno`.
+  pub fn result(&self) -> isize {
+    self.res
+  }
+
+  /// Runs the operation's callback with its result.
+  pub fn run(self) {
+    self.callback.call(self.res);
+  }
+}
+
+type BatchHandler = Box<dyn FnMut(Vec<Completion>)>;
+
+/// In-flight op ids grouped by the resource they act on.
+///
+/// Holding a [`Resource`] clone keeps the fd open, so an entry can't be
+/// confused with a later fd that reuses the same number.
+#[derive(Default)]
+struct FdIndex {
+  by_fd: HashMap<Resource, Vec<u64>>,
+  fd_of: HashMap<u64, Resource>,
+}
+
+impl FdIndex {
+  fn insert(&mut self, res: Resource, id: u64) {
+    self.by_fd.entry(res.clone()).or_default().push(id);
+    self.fd_of.insert(id, res);
+  }
+
+  fn remove(&mut self, id: u64) {
+    let Some(res) = self.fd_of.remove(&id) else { return };
+    if let Some(ids) = self.by_fd.get_mut(&res) {
+      ids.retain(|&other| other != id);
+      if ids.is_empty() {
+        self.by_fd.remove(&res);
+      }
+    }
+  }
+
+  fn get(&self, res: &Resource) -> &[u64] {
+    self.by_fd.get(res).map_or(&[], Vec::as_slice)
+  }
+}
+
+/// An operation that hasn't completed yet, as listed by
+/// [`Lio::debug_dump`].
+#[derive(Debug, Clone)]
+pub struct OpInfo {
+  /// Id the operation was scheduled under.
+  pub id: u64,
+  /// Name of the operation, such as `"READ"` or `"TIMEOUT"`.
+  pub opcode: &'static str,
+  /// The fd the operation acts on. Directory fds of `*at` operations don't
+  /// count.
+  #[cfg(unix)]
+  pub fd: Option<RawFd>,
+  /// Time since the operation was scheduled.
+  pub age: Duration,
+  /// Tag set with [`Io::with_tag`](crate::api::io::Io::with_tag).
+  pub tag: Option<u64>,
+}
+
+/// What [`Lio::debug_dump`] needs to know about a scheduled op.
+struct Scheduled {
+  opcode: &'static str,
+  #[cfg(unix)]
+  fd: Option<RawFd>,
+  at: Instant,
+  /// Whether a barrier on the same resource has to wait for it.
+  write: bool,
+  tag: Option<u64>,
+}
+
+impl Scheduled {
+  fn new(op: &Op) -> Self {
+    Self {
+      opcode: op.name(),
+      #[cfg(unix)]
+      fd: op.resource().map(AsRawFd::as_raw_fd),
+      at: Instant::now(),
+      write: op.is_write(),
+      tag: None,
+    }
+  }
+}
+
+/// An op held back until the writes scheduled before it on the same
+/// resource complete, see [`TypedOp::after_writes`](crate::typed_op::TypedOp::after_writes).
+struct Barrier {
+  id: u64,
+  op: Op,
+  timeout: Option<Duration>,
+  /// Ids of the writes it still waits for.
+  pending: Vec<u64>,
+}
+
+/// Hands `op` to the backend, with a deadline if it has one.
+fn push_to(
+  io: &mut dyn IoBackend,
+  id: u64,
+  op: Op,
+  timeout: Option<Duration>,
+) -> io::Result<()> {
+  match timeout {
+    Some(timeout) => io.push_with_timeout(id, op, timeout),
+    None => io.push(id, op),
+  }
+}
+
 struct LioInner {
   store: OpStore,
+  /// Which in-flight ops target which fd.
+  by_fd: FdIndex,
+  /// Every op that hasn't completed yet, by id.
+  scheduled: HashMap<u64, Scheduled>,
+  /// Set by [`Lio::drain`], new ops fail with `ECANCELED`.
+  closed: bool,
   io: Box<dyn IoBackend>,
+  sq_full_policy: SqFullPolicy,
+  /// Ops buffered by [`SqFullPolicy::Grow`], in submission order.
+  overflow: VecDeque<(u64, Op, Option<Duration>)>,
+  /// Limit set by [`Lio::set_max_in_flight`].
+  max_in_flight: Option<usize>,
+  /// Ops admitted past the limit and not completed yet.
+  in_flight: usize,
+  /// Ops waiting for the in-flight count to drop below the limit.
+  parked: VecDeque<(u64, Op, Option<Duration>)>,
+  /// Ops waiting for earlier writes to their resource.
+  barriers: Vec<Barrier>,
+  /// Limit set by [`Lio::set_pending_memory_cap`].
+  pending_memory_cap: Option<usize>,
+  /// Results of detached ops, until they are claimed.
+  detached: Detached,
+  /// Tokens attached with [`Io::with_cancel`](crate::api::io::Io::with_cancel),
+  /// by op id. Dropped once the op completes or is cancelled.
+  cancel_tokens: HashMap<u64, CancellationToken>,
+  /// Ops cancelled through a [`CancelHandle`](crate::CancelHandle), whose
+  /// `ECANCELED` is reported as `EINTR`.
+  interrupted: HashSet<u64>,
+  /// Ops that failed before reaching the backend, reported on the next run.
+  rejected: Vec<(u64, isize)>,
+  /// Callbacks of completed ops, run once the driver state is released so
+  /// they can schedule new ops.
+  callbacks: Vec<(OpCallback, isize)>,
+  /// Set with [`Lio::on_batch`], taken out while it runs.
+  batch_handler: Option<BatchHandler>,
+  /// Ops pushed to the backend since the last flush.
+  unsubmitted: usize,
+  /// Flushes that handed at least one op to the kernel.
+  submits: u64,
+  wait_strategy: WaitStrategy,
+  /// When the backend last reported a completion.
+  last_activity: Option<Instant>,
+  /// Moving average of the time between completions.
+  avg_gap: Option<Duration>,
+  /// Waits that busy-polled under [`WaitStrategy::Adaptive`].
+  spins: u64,
+  /// Buffer groups the kernel may pick from. After `io`, so their memory
+  /// outlives the ring.
+  #[cfg(unix)]
+  groups: crate::buf::LiveGroups,
+  /// Descriptor held in reserve for accepts, see
+  /// [`SpareFd`](crate::api::ops::SpareFd).
+  #[cfg(unix)]
+  spare: crate::api::ops::SpareFd,
+  /// Fixed file table of the ring, see [`FixedFd`](crate::api::ops::FixedFd).
+  #[cfg(target_os = "linux")]
+  fixed: std::sync::Arc<crate::api::ops::FixedTable>,
+}
+
+/// Memory one op takes in the overflow or parked queue.
+const QUEUED_OP_BYTES: usize = mem::size_of::<(u64, Op, Option<Duration>)>();
+
+impl LioInner {
+  /// Memory held by ops waiting in userspace: the overflow and parked
+  /// queues, the barriers and unclaimed detached results.
+  fn queued_bytes(&self) -> usize {
+    let barriers: usize = self
+      .barriers
+      .iter()
+      .map(|barrier| {
+        mem::size_of::<Barrier>()
+          + barrier.pending.len() * mem::size_of::<u64>()
+      })
+      .sum();
+    (self.overflow.len() + self.parked.len()) * QUEUED_OP_BYTES
+      + barriers
+      + self.detached.bytes()
+  }
+
+  /// Whether `bytes` more can be queued without going over
+  /// [`Lio::set_pending_memory_cap`].
+  fn has_room(&self, bytes: usize) -> bool {
+    self.pending_memory_cap.is_none_or(|cap| self.queued_bytes() + bytes <= cap)
+  }
+
+  /// Fails op `id` before it started, on the next run.
+  fn reject(&mut self, id: u64, errno: i32) {
+    self.in_flight += 1;
+    self.rejected.push((id, -(errno as isize)));
+  }
+
+  /// Moves buffered ops into the backend while it has room.
+  ///
+  /// Returns whether anything was moved.
+  fn drain_overflow(&mut self) -> bool {
+    let mut moved = false;
+    while !self.io.is_full() {
+      let Some((id, op, timeout)) = self.overflow.pop_front() else { break };
+      match push_to(&mut *self.io, id, op, timeout) {
+        Ok(()) => self.unsubmitted += 1,
+        Err(err) => {
+          let errno = err.raw_os_error().unwrap_or(libc::EIO);
+          self.rejected.push((id, -(errno as isize)));
+        }
+      }
+      moved = true;
+    }
+    moved
+  }
+
+  /// Submits the ops pushed to the backend so far.
+  fn flush(&mut self) -> io::Result<()> {
+    self.io.flush()?;
+    if self.unsubmitted > 0 {
+      self.unsubmitted = 0;
+      self.submits += 1;
+    }
+    Ok(())
+  }
+
+  fn under_limit(&self) -> bool {
+    self.max_in_flight.is_none_or(|max| self.in_flight < max)
+  }
+
+  /// Admits parked ops while under the in-flight limit. They go through the
+  /// overflow buffer so they keep their place ahead of newer ops.
+  fn release_parked(&mut self) {
+    while self.under_limit() {
+      let Some(entry) = self.parked.pop_front() else { break };
+      self.in_flight += 1;
+      self.overflow.push_back(entry);
+    }
+  }
+
+  /// Parks the barriers whose writes are all among `completed`, so they are
+  /// admitted like any other op.
+  fn release_barriers(&mut self, completed: &[(u64, isize)]) {
+    if self.barriers.is_empty() {
+      return;
+    }
+    for barrier in &mut self.barriers {
+      barrier
+        .pending
+        .retain(|id| !completed.iter().any(|(done, _)| done == id));
+    }
+    let (ready, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.barriers)
+      .into_iter()
+      .partition(|barrier| barrier.pending.is_empty());
+    self.barriers = waiting;
+    for barrier in ready {
+      self.parked.push_back((barrier.id, barrier.op, barrier.timeout));
+    }
+  }
+
+  /// Waits for completions. Intermediate results of multishot ops are
+  /// queued for their streams right away, final ones are returned.
+  fn wait(
+    &mut self,
+    timeout: Option<Duration>,
+  ) -> io::Result<Vec<(u64, isize)>> {
+    let mut completed = Vec::new();
+    let mut arrived = false;
+    for c in self.io.wait_timeout(timeout)? {
+      arrived = true;
+      if !c.more {
+        completed.push((c.op_id, c.result));
+        continue;
+      }
+      let Some(op) = self.store.get_mut(c.op_id) else {
+        panic!("lio bookkeeping bug: completed op doesn't exist in store.");
+      };
+      op.push_shot(c.result);
+    }
+    if arrived {
+      self.record_activity();
+    }
+    Ok(completed)
+  }
+
+  fn record_activity(&mut self) {
+    let now = Instant::now();
+    if let Some(last) = self.last_activity {
+      let gap = now - last;
+      self.avg_gap = Some(self.avg_gap.map_or(gap, |avg| (avg * 7 + gap) / 8));
+    }
+    self.last_activity = Some(now);
+  }
+
+  /// Like [`wait`](Self::wait), but busy-polls first if the wait strategy
+  /// says so.
+  fn wait_adaptive(
+    &mut self,
+    timeout: Option<Duration>,
+  ) -> io::Result<Vec<(u64, isize)>> {
+    let WaitStrategy::Adaptive { spin } = self.wait_strategy else {
+      return self.wait(timeout);
+    };
+    let busy = self.last_activity.is_some_and(|last| last.elapsed() < spin)
+      && self.avg_gap.is_some_and(|gap| gap < spin);
+    if timeout == Some(Duration::ZERO) || !busy {
+      return self.wait(timeout);
+    }
+
+    self.spins += 1;
+    let start = Instant::now();
+    let budget = timeout.map_or(spin, |timeout| timeout.min(spin));
+    loop {
+      let seen = self.last_activity;
+      let completed = self.wait(Some(Duration::ZERO))?;
+      if !completed.is_empty() || self.last_activity != seen {
+        return Ok(completed);
+      }
+      if start.elapsed() >= budget {
+        break;
+      }
+      std::hint::spin_loop();
+    }
+    self.wait(timeout.map(|timeout| timeout.saturating_sub(start.elapsed())))
+  }
+
+  /// Cancels the ops whose token was cancelled.
+  fn cancel_requested(&mut self) -> io::Result<()> {
+    let cancelled: Vec<(u64, i32)> = self
+      .cancel_tokens
+      .iter()
+      .filter_map(|(id, token)| Some((*id, token.cancelled_errno()?)))
+      .collect();
+    for (id, errno) in cancelled {
+      self.cancel_tokens.remove(&id);
+      if errno == libc::EINTR {
+        self.interrupted.insert(id);
+      }
+      self.cancel(id)?;
+    }
+    Ok(())
+  }
+
+  /// Aborts the op `id`. Ops the backend hasn't seen yet complete with
+  /// `-ECANCELED` on the next run, the rest are left to the backend.
+  fn cancel(&mut self, id: u64) -> io::Result<()> {
+    let canceled = -(libc::ECANCELED as isize);
+    if let Some(i) = self.parked.iter().position(|(op_id, ..)| *op_id == id) {
+      self.parked.remove(i);
+      // Completing it releases a slot it never took.
+      self.in_flight += 1;
+      self.rejected.push((id, canceled));
+    } else if let Some(i) = self.barriers.iter().position(|b| b.id == id) {
+      self.barriers.remove(i);
+      self.in_flight += 1;
+      self.rejected.push((id, canceled));
+    } else if let Some(i) =
+      self.overflow.iter().position(|(op_id, ..)| *op_id == id)
+    {
+      self.overflow.remove(i);
+      self.rejected.push((id, canceled));
+    } else {
+      match self.io.cancel(id) {
+        Err(err) if err.kind() != io::ErrorKind::Unsupported => {
+          return Err(err);
+        }
+        _ => {}
+      }
+    }
+    Ok(())
+  }
+
+  fn complete(&mut self, completed: &[(u64, isize)]) {
+    self.in_flight -= completed.len();
+
+    // Collect IDs to remove (callbacks consume the result, wakers don't)
+    let mut to_remove = Vec::new();
+
+    for (op_id, result) in completed {
+      self.by_fd.remove(*op_id);
+      self.scheduled.remove(op_id);
+      self.cancel_tokens.remove(op_id);
+      #[cfg(unix)]
+      self.groups.complete(*op_id, *result);
+      #[cfg(unix)]
+      self.spare.complete(*op_id, *result);
+      let mut result = *result;
+      if self.interrupted.remove(op_id) && result == -(libc::ECANCELED as isize)
+      {
+        result = -(libc::EINTR as isize);
+      }
+      let Some(op) = self.store.get_mut(*op_id) else {
+        panic!("lio bookkeeping bug: completed op doesn't exist in store.");
+      };
+      let callback = op.set_done(result);
+
+      // If the result was consumed (callback path), mark for removal.
+      // Waker path leaves result in place for check_done to consume.
+      if op.result_consumed() {
+        to_remove.push(*op_id);
+      }
+      self.callbacks.extend(callback);
+    }
+
+    // Remove consumed entries
+    for id in to_remove {
+      self.store.remove(id);
+    }
+
+    self.release_barriers(completed);
+    self.release_parked();
+  }
 }
 
 #[derive(Clone)]
@@ -92,6 +704,11 @@ impl Lio {
       use crate::backends::io_uring::IoUring;
       Self::new_with_backend(IoUring::new(), cap)
     }
+    #[cfg(windows)]
+    {
+      use crate::backends::Iocp;
+      Self::new_with_backend(Iocp::new(), cap)
+    }
   }
 
   /// Creates a new Lio driver with the specified backend and capacity.
@@ -115,24 +732,415 @@ impl Lio {
   {
     backend.init(cap)?;
 
-    let inner =
-      LioInner { io: Box::new(backend), store: OpStore::with_capacity(cap) };
+    let inner = LioInner {
+      io: Box::new(backend),
+      store: OpStore::with_capacity(cap),
+      by_fd: FdIndex::default(),
+      scheduled: HashMap::new(),
+      closed: false,
+      sq_full_policy: SqFullPolicy::default(),
+      overflow: VecDeque::new(),
+      max_in_flight: None,
+      in_flight: 0,
+      parked: VecDeque::new(),
+      barriers: Vec::new(),
+      pending_memory_cap: None,
+      detached: Detached::default(),
+      cancel_tokens: HashMap::new(),
+      interrupted: HashSet::new(),
+      rejected: Vec::new(),
+      callbacks: Vec::new(),
+      batch_handler: None,
+      unsubmitted: 0,
+      submits: 0,
+      wait_strategy: WaitStrategy::default(),
+      last_activity: None,
+      avg_gap: None,
+      spins: 0,
+      #[cfg(unix)]
+      groups: Default::default(),
+      #[cfg(unix)]
+      spare: Default::default(),
+      #[cfg(target_os = "linux")]
+      fixed: Default::default(),
+    };
     Ok(Self { inner: Rc::new(RefCell::new(inner)) })
   }
 
+  /// Sets what happens when an operation is scheduled while the backend's
+  /// submission queue is full. Defaults to [`SqFullPolicy::Block`].
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::{Lio, SqFullPolicy};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// lio.set_sq_full_policy(SqFullPolicy::Grow);
+  /// ```
+  pub fn set_sq_full_policy(&self, policy: SqFullPolicy) {
+    self.inner.borrow_mut().sq_full_policy = policy;
+  }
+
+  /// Sets how [`run`](Self::run) waits for completions. Defaults to
+  /// [`WaitStrategy::Block`].
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::{Lio, WaitStrategy};
+  /// use std::time::Duration;
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// lio.set_wait_strategy(WaitStrategy::Adaptive { spin: Duration::from_micros(50) });
+  /// ```
+  pub fn set_wait_strategy(&self, strategy: WaitStrategy) {
+    self.inner.borrow_mut().wait_strategy = strategy;
+  }
+
+  /// Number of waits that busy-polled instead of blocking, see
+  /// [`WaitStrategy::Adaptive`].
+  pub fn spin_count(&self) -> u64 {
+    self.inner.borrow().spins
+  }
+
+  /// Caps how many operations may be in flight at once, `None` (the
+  /// default) removes the cap.
+  ///
+  /// Operations scheduled past the cap wait inside the driver and start, in
+  /// scheduling order, as earlier ones complete. Until then a future stays
+  /// pending and a [`Receiver`](crate::api::io::Receiver) blocks, so a
+  /// producer awaiting its operations can't outrun the kernel.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::Lio;
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// lio.set_max_in_flight(Some(4));
+  /// ```
+  pub fn set_max_in_flight(&self, max: Option<usize>) {
+    let mut inner = self.inner.borrow_mut();
+    inner.max_in_flight = max;
+    inner.release_parked();
+  }
+
+  /// Caps the memory operations waiting in userspace may take, `None` (the
+  /// default) removes the cap.
+  ///
+  /// Operations wait in userspace when buffered by [`SqFullPolicy::Grow`],
+  /// held back by [`set_max_in_flight`](Self::set_max_in_flight) or by
+  /// [`fsync_after_writes`](crate::api::fsync_after_writes). Under heavy
+  /// load these queues can grow without bound. Past the cap, new operations
+  /// fail straight away with `ENOBUFS` instead of queueing. Their buffers
+  /// belong to the operations and aren't counted, only the queue entries.
+  ///
+  /// Results kept for [`await_id`](Self::await_id) count too, past the cap
+  /// [`detach_with_id`](crate::api::io::Io::detach_with_id) fails.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::Lio;
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// lio.set_pending_memory_cap(Some(64 * 1024));
+  /// ```
+  pub fn set_pending_memory_cap(&self, bytes: Option<usize>) {
+    self.inner.borrow_mut().pending_memory_cap = bytes;
+  }
+
+  /// Claims the result of an operation detached with
+  /// [`Io::detach_with_id`](crate::api::io::Io::detach_with_id).
+  ///
+  /// The future resolves once the operation has completed, right away if it
+  /// already has. Only the driver the operation was detached on knows the
+  /// id.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  ///
+  /// async fn example(lio: &Lio) -> std::io::Result<()> {
+  ///     let fd = api::resource::Resource::stdin();
+  ///     let id = api::read(&fd, vec![0u8; 64]).with_lio(lio).detach_with_id()?;
+  ///
+  ///     // Do other work, then pick the result up.
+  ///     let (result, buf) = lio.await_id(id).await;
+  ///     println!("Read {:?}", &buf[..result? as usize]);
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn await_id<R: 'static>(&self, id: OpId<R>) -> AwaitId<R> {
+    AwaitId::new(self.inner.borrow().detached.clone(), id)
+  }
+
+  /// Reserves room for a detached result, failing with `ENOBUFS` past
+  /// [`set_pending_memory_cap`](Self::set_pending_memory_cap).
+  pub(crate) fn reserve_detached<R>(&self) -> io::Result<(Detached, OpId<R>)> {
+    let inner = self.inner.borrow();
+    if !inner.has_room(mem::size_of::<R>()) {
+      return Err(io::Error::from_raw_os_error(libc::ENOBUFS));
+    }
+    Ok((inner.detached.clone(), inner.detached.reserve()))
+  }
+
+  /// Stops accepting operations and runs until the ones in flight complete.
+  ///
+  /// Operations scheduled from now on, including from completion callbacks,
+  /// fail with `ECANCELED`. With a `timeout`, gives up once it elapses and
+  /// returns how many operations are still in flight, whose buffers the
+  /// kernel may still be using. A driver error is reported the same way.
+  ///
+  /// See [`drain_and_exit`](crate::drain_and_exit) to also uninstall the
+  /// global Lio.
+  pub fn drain(&self, timeout: Option<Duration>) -> Result<(), usize> {
+    self.inner.borrow_mut().closed = true;
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+      let pending = self.inner.borrow().scheduled.len();
+      if pending == 0 {
+        return Ok(());
+      }
+      let wait = match deadline {
+        Some(deadline) => match deadline.checked_duration_since(Instant::now())
+        {
+          Some(left) if !left.is_zero() => Some(left),
+          _ => return Err(pending),
+        },
+        None => None,
+      };
+      if self.run_inner(wait).is_err() {
+        return Err(self.inner.borrow().scheduled.len());
+      }
+    }
+  }
+
+  /// Whether both handles refer to the same driver.
+  pub(crate) fn ptr_eq(&self, other: &Lio) -> bool {
+    Rc::ptr_eq(&self.inner, &other.inner)
+  }
+
+  /// Number of operations started and not yet completed. Operations held
+  /// back by [`set_max_in_flight`](Self::set_max_in_flight) don't count.
+  pub fn in_flight(&self) -> usize {
+    self.inner.borrow().in_flight
+  }
+
+  /// Number of times scheduled operations were handed to the kernel. Each
+  /// one is a single `io_uring_enter` on io_uring, however many operations
+  /// it carried.
+  pub fn submit_count(&self) -> u64 {
+    self.inner.borrow().submits
+  }
+
+  /// Hands the completed [`when_done`](crate::api::io::Io::when_done)
+  /// callbacks of each run to `handler` in one go, instead of calling them
+  /// one by one.
+  ///
+  /// `handler` decides when each [`Completion`] runs, so an event loop
+  /// integration can move a whole batch over with a single wakeup. A
+  /// completion dropped without running never calls its callback. Runs
+  /// without completed callbacks don't call `handler`. A later call
+  /// replaces the handler.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::Lio;
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// lio.on_batch(|completions| {
+  ///   for completion in completions {
+  ///     completion.run();
+  ///   }
+  /// });
+  /// ```
+  pub fn on_batch<F>(&self, handler: F)
+  where
+    F: FnMut(Vec<Completion>) + 'static,
+  {
+    self.inner.borrow_mut().batch_handler = Some(Box::new(handler));
+  }
+
+  /// Runs `f`, then submits everything it scheduled on this Lio at once.
+  ///
+  /// Scheduling only queues an operation, it is submitted on the next
+  /// [`run`](Self::run). Wrapping a group of operations in `deferred`
+  /// submits them together right away instead, so they start without
+  /// waiting for the event loop and still cost a single submission.
+  ///
+  /// # Example
+  ///
+  /// ```no_run
+  /// use lio::{Lio, api};
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let (a, b) = (api::resource::Resource::stdin(), api::resource::Resource::stdin());
+  /// let (ra, rb) = lio.deferred(|| {
+  ///     (
+  ///         api::read(&a, vec![0u8; 64]).with_lio(&lio).send(),
+  ///         api::read(&b, vec![0u8; 64]).with_lio(&lio).send(),
+  ///     )
+  /// }).unwrap();
+  /// ```
+  pub fn deferred<R>(&self, f: impl FnOnce() -> R) -> io::Result<R> {
+    let output = f();
+    self.inner.borrow_mut().flush()?;
+    Ok(output)
+  }
+
+  /// Lists every operation scheduled on this Lio that hasn't completed yet,
+  /// oldest first. Includes operations held back by
+  /// [`set_max_in_flight`](Self::set_max_in_flight).
+  ///
+  /// Meant for debugging futures that never complete.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use lio::{Lio, api};
+  /// use std::time::Duration;
+  ///
+  /// let lio = Lio::new(64).unwrap();
+  /// let _rx = api::timeout(Duration::from_secs(60)).with_lio(&lio).send();
+  /// for op in lio.debug_dump() {
+  ///     println!("#{} {} for {:?}", op.id, op.opcode, op.age);
+  /// }
+  /// ```
+  pub fn debug_dump(&self) -> Vec<OpInfo> {
+    let inner = self.inner.borrow();
+    let mut ops: Vec<OpInfo> = inner
+      .scheduled
+      .iter()
+      .map(|(&id, op)| OpInfo {
+        id,
+        opcode: op.opcode,
+        #[cfg(unix)]
+        fd: op.fd,
+        age: op.at.elapsed(),
+        tag: op.tag,
+      })
+      .collect();
+    ops.sort_by_key(|op| std::cmp::Reverse(op.age));
+    ops
+  }
+
   pub(crate) fn schedule(
     &self,
     op: Op,
+    timeout: Option<Duration>,
+    after_writes: bool,
     notifier: Registration,
   ) -> io::Result<u64> {
     let mut inner = self.inner.borrow_mut();
     // Inserting first because of a stable pointer to push is required.
     let id = inner.store.insert(notifier);
+    if let Some(res) = op.resource() {
+      inner.by_fd.insert(res.clone(), id);
+    }
+    inner.scheduled.insert(id, Scheduled::new(&op));
+    #[cfg(unix)]
+    inner.spare.admit(id, &op);
+
+    if inner.closed {
+      inner.reject(id, libc::ECANCELED);
+      return Ok(id);
+    }
+
+    #[cfg(unix)]
+    if let Err(errno) = inner.groups.admit(id, &op) {
+      inner.reject(id, errno);
+      return Ok(id);
+    }
+
+    // A slot index means nothing to another ring.
+    #[cfg(target_os = "linux")]
+    if op
+      .fixed_table()
+      .is_some_and(|table| !std::sync::Arc::ptr_eq(table, &inner.fixed))
+    {
+      inner.reject(id, libc::EBADF);
+      return Ok(id);
+    }
+
+    if after_writes {
+      let pending: Vec<u64> = op
+        .resource()
+        .map(|res| inner.by_fd.get(res))
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .filter(|other| inner.scheduled.get(other).is_some_and(|s| s.write))
+        .collect();
+      if !pending.is_empty() {
+        let bytes =
+          mem::size_of::<Barrier>() + pending.len() * mem::size_of::<u64>();
+        if !inner.has_room(bytes) {
+          inner.reject(id, libc::ENOBUFS);
+          return Ok(id);
+        }
+        inner.barriers.push(Barrier { id, op, timeout, pending });
+        return Ok(id);
+      }
+    }
+
+    if !inner.parked.is_empty() || !inner.under_limit() {
+      if !inner.has_room(QUEUED_OP_BYTES) {
+        inner.reject(id, libc::ENOBUFS);
+        return Ok(id);
+      }
+      inner.parked.push_back((id, op, timeout));
+      return Ok(id);
+    }
+    inner.in_flight += 1;
+
+    #[cfg(any(test, feature = "test-utils"))]
+    if let Some(errno) = crate::test_utils::take_injected_error(&op) {
+      inner.rejected.push((id, -(errno as isize)));
+      return Ok(id);
+    }
+
+    // Keep submission order: nothing may overtake already buffered ops.
+    if inner.io.is_full() || !inner.overflow.is_empty() {
+      match inner.sq_full_policy {
+        SqFullPolicy::Grow if !inner.has_room(QUEUED_OP_BYTES) => {
+          inner.rejected.push((id, -(libc::ENOBUFS as isize)));
+          return Ok(id);
+        }
+        SqFullPolicy::Grow => {
+          inner.overflow.push_back((id, op, timeout));
+          return Ok(id);
+        }
+        SqFullPolicy::Error if inner.io.is_full() => {
+          inner.rejected.push((id, -(libc::EBUSY as isize)));
+          return Ok(id);
+        }
+        SqFullPolicy::Error => {}
+        SqFullPolicy::Block => {
+          let inner = &mut *inner;
+          inner.drain_overflow();
+          inner.flush()?;
+          while inner.io.is_full() {
+            let completed = inner.wait(None)?;
+            inner.complete(&completed);
+          }
+        }
+      }
+    }
 
-    match inner.io.push(id, op) {
-      Ok(()) => Ok(id),
+    match push_to(&mut *inner.io, id, op, timeout) {
+      Ok(()) => {
+        inner.unsubmitted += 1;
+        Ok(id)
+      }
       Err(err) => {
         assert!(inner.store.remove(id));
+        inner.by_fd.remove(id);
+        inner.scheduled.remove(&id);
+        inner.in_flight -= 1;
         Err(err)
       }
     }
@@ -160,41 +1168,121 @@ impl Lio {
   }
 
   fn run_inner(&self, timeout: Option<Duration>) -> io::Result<usize> {
-    let mut inner = self.inner.borrow_mut();
-    inner.io.flush()?;
+    #[cfg(target_os = "linux")]
+    self.close_released_slots();
+    #[cfg(unix)]
+    self.shed_pending()?;
+    let completed = self.complete_inner(timeout)?;
 
-    // Copy completion data to release borrow on inner.io
-    let completed: Vec<_> = inner
-      .io
-      .wait_timeout(timeout)?
-      .iter()
-      .map(|c| (c.op_id, c.result))
-      .collect();
+    // Callbacks run without the driver borrowed, anything they schedule is
+    // queued in the backend and submitted right after.
+    let (callbacks, handler) = {
+      let mut inner = self.inner.borrow_mut();
+      let callbacks = std::mem::take(&mut inner.callbacks);
+      let handler =
+        if callbacks.is_empty() { None } else { inner.batch_handler.take() };
+      (callbacks, handler)
+    };
+    if !callbacks.is_empty() {
+      match handler {
+        Some(mut handler) => {
+          handler(
+            callbacks
+              .into_iter()
+              .map(|(callback, res)| Completion { callback, res })
+              .collect(),
+          );
+          // Unless the handler replaced itself.
+          self.inner.borrow_mut().batch_handler.get_or_insert(handler);
+        }
+        None => {
+          for (callback, res) in callbacks {
+            callback.call(res);
+          }
+        }
+      }
+      self.inner.borrow_mut().flush()?;
+    }
 
-    // Collect IDs to remove (callbacks consume the result, wakers don't)
-    let mut to_remove = Vec::new();
+    Ok(completed)
+  }
 
-    for (op_id, result) in &completed {
-      let Some(op) = inner.store.get_mut(*op_id) else {
-        panic!("lio bookkeeping bug: completed op doesn't exist in store.");
-      };
-      op.set_done(*result);
+  /// Frees the slots of dropped [`FixedFd`](crate::api::ops::FixedFd)s.
+  #[cfg(target_os = "linux")]
+  fn close_released_slots(&self) {
+    let table = self.fixed_table();
+    for slot in table.take_released() {
+      let fd = crate::api::ops::FixedFd::new(slot, table.clone());
+      crate::api::close_fixed(fd).with_lio(self).detach();
+    }
+  }
 
-      // If the result was consumed (callback path), mark for removal.
-      // Waker path leaves result in place for check_done to consume.
-      if op.result_consumed() {
-        to_remove.push(*op_id);
+  /// Accepts the pending connection of a listener that ran out of
+  /// descriptors into the spare and closes it, see
+  /// [`SpareFd`](crate::api::ops::SpareFd).
+  #[cfg(unix)]
+  fn shed_pending(&self) -> io::Result<()> {
+    use crate::{api::ops::Accept, typed_op::TypedOp};
+
+    let Some(listener) = self.inner.borrow_mut().spare.lend() else {
+      return Ok(());
+    };
+    let mut accept = Box::new(Accept::new(listener));
+    let op = accept.into_op();
+    // The connection is closed as soon as it's handed back.
+    let registration =
+      Registration::new_callback_boxed::<Accept, _>(drop, accept);
+    let id = self.schedule(
+      op,
+      Some(crate::api::ops::SHED_TIMEOUT),
+      false,
+      registration,
+    );
+    self.inner.borrow_mut().spare.lent_to(id.as_ref().ok().copied());
+    id.map(|_| ())
+  }
+
+  /// The fixed file table [`FixedFd`](crate::api::ops::FixedFd)s of this
+  /// driver release their slots to.
+  #[cfg(target_os = "linux")]
+  pub(crate) fn fixed_table(
+    &self,
+  ) -> std::sync::Arc<crate::api::ops::FixedTable> {
+    self.inner.borrow().fixed.clone()
+  }
+
+  fn complete_inner(&self, timeout: Option<Duration>) -> io::Result<usize> {
+    let mut inner = self.inner.borrow_mut();
+    let inner = &mut *inner;
+
+    inner.cancel_requested()?;
+
+    // Each flush may free up room for more buffered ops.
+    loop {
+      inner.flush()?;
+      if !inner.drain_overflow() {
+        break;
       }
     }
 
-    // Remove consumed entries
-    for id in to_remove {
-      inner.store.remove(id);
-    }
+    // Rejected ops are already complete, so don't block waiting for others.
+    let timeout =
+      if inner.rejected.is_empty() { timeout } else { Some(Duration::ZERO) };
+
+    let mut completed = inner.wait_adaptive(timeout)?;
+    completed.append(&mut inner.rejected);
+
+    inner.complete(&completed);
 
     Ok(completed.len())
   }
 
+  /// Ids of the in-flight ops acting on `res`, in submission order.
+  #[cfg(test)]
+  pub(crate) fn ops_for(&self, res: &Resource) -> Vec<u64> {
+    self.inner.borrow().by_fd.get(res).to_vec()
+  }
+
   pub(crate) fn check_done(&self, key: u64) -> Result<isize, Error> {
     let mut inner = self.inner.borrow_mut();
     match inner.store.get_mut(key) {
@@ -207,6 +1295,52 @@ impl Lio {
     }
   }
 
+  /// Takes the next result of the multishot op `id`, see
+  /// [`Registration::next_shot`]. Registers `waker` if nothing is ready.
+  pub(crate) fn next_shot(
+    &self,
+    id: u64,
+    waker: &Waker,
+  ) -> Option<Option<isize>> {
+    let mut inner = self.inner.borrow_mut();
+    let Some(entry) = inner.store.get_mut(id) else {
+      panic!("lio bookkeeping bug: operation entry not found");
+    };
+    let next = entry.next_shot();
+    match next {
+      Some(None) => assert!(inner.store.remove(id)),
+      Some(Some(_)) => {}
+      None => entry.set_waker(waker.clone()),
+    }
+    next
+  }
+
+  /// Cancels the multishot op `id` after its stream was dropped. Its entry
+  /// goes away once the backend reports the op finished.
+  pub(crate) fn abandon(&self, id: u64) {
+    let mut inner = self.inner.borrow_mut();
+    let Some(entry) = inner.store.get_mut(id) else { return };
+    if entry.abandon() {
+      assert!(inner.store.remove(id));
+      return;
+    }
+    // Dropping can't report errors. Without backend support the op keeps
+    // running, its results are discarded.
+    let _ = inner.cancel(id);
+  }
+
+  /// Aborts the op `id` once `token` is cancelled, see
+  /// [`Io::with_cancel`](crate::api::io::Io::with_cancel).
+  pub(crate) fn attach_cancel(&self, id: u64, token: CancellationToken) {
+    self.inner.borrow_mut().cancel_tokens.insert(id, token);
+  }
+
+  pub(crate) fn attach_tag(&self, id: u64, tag: u64) {
+    if let Some(op) = self.inner.borrow_mut().scheduled.get_mut(&id) {
+      op.tag = Some(tag);
+    }
+  }
+
   pub(crate) fn set_waker(&self, id: u64, waker: Waker) {
     let mut inner = self.inner.borrow_mut();
     if let Some(entry) = inner.store.get_mut(id) {
@@ -214,3 +1348,40 @@ impl Lio {
     }
   }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+  use super::*;
+  use crate::api;
+  use std::os::fd::FromRawFd;
+
+  #[test]
+  fn test_fd_index_tracks_ops_per_fd() {
+    let lio = Lio::new(64).unwrap();
+
+    let mut fds = [0; 2];
+    // SAFETY: fds is a valid two-element array.
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    // SAFETY: Both fds were just returned by pipe and are owned here.
+    let (read_end, write_end) =
+      unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) };
+
+    // Nothing has been written, so both reads stay in flight.
+    let (sender, receiver) = std::sync::mpsc::channel();
+    api::read(&read_end, vec![0u8; 8]).with_lio(&lio).send_with(sender.clone());
+    api::read(&read_end, vec![0u8; 8]).with_lio(&lio).send_with(sender);
+    lio.try_run().unwrap();
+
+    assert_eq!(lio.ops_for(&read_end).len(), 2);
+    assert!(lio.ops_for(&write_end).is_empty());
+
+    // EOF completes both reads, which drops them from the index.
+    drop(write_end);
+    for _ in 0..2 {
+      while receiver.try_recv().is_err() {
+        lio.run_timeout(Duration::from_millis(10)).unwrap();
+      }
+    }
+    assert!(lio.ops_for(&read_end).is_empty());
+  }
+}
@@ -152,6 +152,7 @@ macro_rules! doc_op {
     };
 }
 
+#[cfg(unix)]
 macro_rules! syscall {
   (raw $fn: ident ( $($arg: expr),* $(,)* ) ? ) => {{
       let val = syscall!(raw $fn ($($arg),*));
@@ -210,8 +211,12 @@ pub(crate) mod __internal {
 
 macro_rules! assert_op_max_size {
   ($op_type:ty) => {
+    assert_op_max_size!($op_type, test_op_size);
+  };
+  // Modules with several ops name the other tests.
+  ($op_type:ty, $test:ident) => {
     #[test]
-    fn test_op_size() {
+    fn $test() {
       // Inline storage size - change this one number to adjust limit for all operations
 
       let size = std::mem::size_of::<$op_type>();
@@ -9,6 +9,10 @@
 //! - [`Socket`]: Low-level async socket wrapper that provides direct access to socket operations
 //! - [`TcpListener`]: High-level TCP server for accepting incoming connections
 //! - [`TcpSocket`]: High-level TCP client/server connection for sending and receiving data
+//! - [`OwnedReadHalf`] and [`OwnedWriteHalf`]: The halves of a split `TcpSocket`
+//! - [`DirectTcpSocket`]: TCP connection in an io_uring fixed file slot (Linux only)
+//! - [`UdpSocket`]: UDP socket for sending and receiving datagrams
+//! - [`serve`]: Accept loop that handles each connection concurrently
 //!
 //! # Features
 //!
@@ -26,27 +30,27 @@
 //! ## TCP Echo Server
 //!
 //! ```rust,ignore
-//! use lio::net::TcpListener;
+//! use lio::net::{TcpListener, serve};
 //!
 //! async fn echo_server() -> std::io::Result<()> {
 //!     let listener = TcpListener::bind_async("127.0.0.1:8080").await?;
 //!     println!("Server listening on 127.0.0.1:8080");
 //!
-//!     loop {
-//!         let (socket, addr) = listener.accept().await?;
-//!         println!("New connection from: {}", addr);
-//!
+//!     // Each connection is handled concurrently with the others.
+//!     serve(listener, |socket| async move {
 //!         // Echo data back to the client
 //!         let buffer = vec![0u8; 1024];
 //!         let (result, buffer) = socket.recv(buffer).await;
-//!     let bytes_read = result? as usize;
+//!         let Ok(bytes_read) = result else { return };
 //!
 //!         if bytes_read > 0 {
-//!             let (result, _) = socket.send(buffer[..bytes_read].to_vec()).await;
-//!             let bytes_sent = result? as usize;
-//!             println!("Echoed {} bytes", bytes_sent);
+//!             let (result, _) = socket.send(buffer[..bytes_read as usize].to_vec()).await;
+//!             if let Ok(bytes_sent) = result {
+//!                 println!("Echoed {} bytes", bytes_sent);
+//!             }
 //!         }
-//!     }
+//!     })
+//!     .await
 //! }
 //! ```
 //!
@@ -129,9 +133,19 @@
 //! - [`crate::api::resource`]: Resource management for file descriptors
 //! - [`crate::api::io`]: Io type for async operations
 
+#[cfg(target_os = "linux")]
+mod proxy;
+mod serve;
 mod socket;
 mod tcp;
+#[cfg(unix)]
+mod udp;
 
+#[cfg(target_os = "linux")]
+pub use proxy::*;
+pub use serve::*;
 pub use socket::*;
 pub use tcp::*;
+#[cfg(unix)]
+pub use udp::*;
 pub mod ops;
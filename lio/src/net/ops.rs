@@ -11,14 +11,28 @@
 //!
 //! - [`SocketAccept`]: Accept operation that returns a [`Socket`]
 //! - [`SocketNew`]: Socket creation operation that returns a [`Socket`]
+//! - [`Counted`]: Adds the bytes another operation transferred to a
+//!   [`TcpSocket`] counter
+//! - `TcpAcceptMultishot`: Multishot accept that yields [`TcpSocket`]s
+//! - `TcpAcceptDirect`: Accept operation that returns a `DirectTcpSocket`
+//!   (Linux only)
 
-use std::{io, net::SocketAddr, os::fd::FromRawFd};
+use std::{
+  io,
+  net::SocketAddr,
+  os::fd::FromRawFd,
+  sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+  },
+  time::Duration,
+};
 
 #[allow(unused_imports)] // TcpListener used in doc links
 use crate::{
   api::{ops, resource::FromResource},
   net::{Socket, TcpListener, TcpSocket},
-  typed_op::TypedOp,
+  typed_op::{DetachSafe, TypedOp},
 };
 
 /// Accept operation specialized for [`Socket`].
@@ -113,3 +127,138 @@ impl TypedOp for TcpAccept {
     Ok((TcpSocket::from_resource(resource), addr))
   }
 }
+
+/// Multishot accept that yields [`TcpSocket`]s.
+///
+/// Returned by [`TcpListener::accept_multishot()`].
+#[cfg(unix)]
+pub struct TcpAcceptMultishot {
+  inner: ops::AcceptMultishot,
+}
+
+#[cfg(unix)]
+impl TcpAcceptMultishot {
+  pub(crate) fn new(res: crate::api::resource::Resource) -> Self {
+    Self { inner: ops::AcceptMultishot::new(res) }
+  }
+}
+
+#[cfg(unix)]
+impl crate::typed_op::MultishotOp for TcpAcceptMultishot {
+  type Item = io::Result<(TcpSocket, SocketAddr)>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.inner.into_op()
+  }
+
+  fn extract_item(&self, res: isize) -> Self::Item {
+    let (resource, addr) = self.inner.extract_item(res)?;
+    Ok((TcpSocket::from_resource(resource), addr))
+  }
+
+  fn resubmit(&self, res: isize) -> bool {
+    self.inner.resubmit(res)
+  }
+}
+
+/// Accept operation that returns a [`DirectTcpSocket`](crate::net::DirectTcpSocket).
+///
+/// Returned by [`TcpListener::accept_direct()`].
+#[cfg(target_os = "linux")]
+pub struct TcpAcceptDirect {
+  inner: ops::AcceptDirect,
+}
+
+#[cfg(target_os = "linux")]
+impl TcpAcceptDirect {
+  pub(crate) fn new(
+    res: crate::api::resource::Resource,
+    table: Arc<ops::FixedTable>,
+  ) -> Self {
+    Self { inner: ops::AcceptDirect::new(res, table) }
+  }
+}
+
+#[cfg(target_os = "linux")]
+impl TypedOp for TcpAcceptDirect {
+  type Result = io::Result<(crate::net::DirectTcpSocket, SocketAddr)>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.inner.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    let (fd, addr) = self.inner.extract_result(res)?;
+    Ok((crate::net::DirectTcpSocket::new(fd), addr))
+  }
+}
+
+/// Installs a regular descriptor for a
+/// [`DirectTcpSocket`](crate::net::DirectTcpSocket), returning a [`TcpSocket`].
+///
+/// Returned by [`DirectTcpSocket::install_fd()`](crate::net::DirectTcpSocket::install_fd).
+#[cfg(target_os = "linux")]
+pub struct TcpInstallFixed {
+  inner: ops::InstallFixed,
+}
+
+#[cfg(target_os = "linux")]
+impl TcpInstallFixed {
+  pub(crate) fn new(fd: &ops::FixedFd) -> Self {
+    Self { inner: ops::InstallFixed::new(fd) }
+  }
+}
+
+#[cfg(target_os = "linux")]
+impl TypedOp for TcpInstallFixed {
+  type Result = io::Result<TcpSocket>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.inner.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.inner.extract_result(res).map(TcpSocket::from_resource)
+  }
+}
+
+/// Adds the bytes transferred by the wrapped operation to a counter.
+///
+/// Returned by the send and receive methods of [`TcpSocket`], which feed
+/// [`TcpSocket::bytes_read`] and [`TcpSocket::bytes_written`]. Failed
+/// operations don't count.
+pub struct Counted<O> {
+  inner: O,
+  counter: Arc<AtomicU64>,
+}
+
+impl<O> Counted<O> {
+  pub(crate) fn new(inner: O, counter: Arc<AtomicU64>) -> Self {
+    Self { inner, counter }
+  }
+}
+
+impl<O: TypedOp> TypedOp for Counted<O> {
+  type Result = O::Result;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.inner.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    if res > 0 {
+      self.counter.fetch_add(res as u64, Ordering::Relaxed);
+    }
+    self.inner.extract_result(res)
+  }
+
+  fn timeout(&self) -> Option<Duration> {
+    self.inner.timeout()
+  }
+
+  fn after_writes(&self) -> bool {
+    self.inner.after_writes()
+  }
+}
+
+impl<O: DetachSafe> DetachSafe for Counted<O> {}
@@ -0,0 +1,183 @@
+use std::{
+  io,
+  os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+};
+
+use crate::{
+  api::{io::Io, ops::SpawnBlocking, resource::AsResource},
+  net::Socket,
+  typed_op::TypedOp,
+};
+
+/// Most bytes moved by one `splice`, the default pipe capacity.
+const CHUNK: usize = 64 * 1024;
+
+/// Forwards bytes between `a` and `b` in both directions until either side
+/// reaches EOF. Resolves to the bytes moved `a → b` and `b → a`.
+///
+/// Data moves through a pipe with `splice(2)`, so it is never copied into
+/// userspace. When one side reaches EOF, the write half of the other is
+/// shut down, bytes already in flight the other way are delivered, and the
+/// proxy finishes. It runs on the blocking pool.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lio::net::{Socket, proxy};
+///
+/// async fn forward(client: Socket, upstream: Socket) -> std::io::Result<()> {
+///     let (sent, received) = proxy(client, upstream).await?;
+///     println!("{sent} bytes up, {received} bytes down");
+///     Ok(())
+/// }
+/// ```
+pub fn proxy(a: Socket, b: Socket) -> Io<Proxy> {
+  Io::from_op(Proxy::new(a, b))
+}
+
+/// Splices between two sockets on the blocking pool, see [`proxy`].
+pub struct Proxy(SpawnBlocking<io::Result<(u64, u64)>>);
+
+assert_op_max_size!(Proxy);
+
+impl Proxy {
+  pub(crate) fn new(a: Socket, b: Socket) -> Self {
+    Self(SpawnBlocking::new(move || {
+      run(a.as_resource().as_raw_fd(), b.as_resource().as_raw_fd())
+    }))
+  }
+}
+
+impl TypedOp for Proxy {
+  type Result = io::Result<(u64, u64)>;
+
+  fn into_op(&mut self) -> crate::op::Op {
+    self.0.into_op()
+  }
+
+  fn extract_result(self, res: isize) -> Self::Result {
+    self.0.extract_result(res)?
+  }
+}
+
+/// One way through the proxy: `src` is spliced into a pipe, the pipe into
+/// `dst`.
+struct Direction {
+  src: RawFd,
+  dst: RawFd,
+  pipe_read: OwnedFd,
+  pipe_write: OwnedFd,
+  /// Bytes sitting in the pipe.
+  buffered: usize,
+  moved: u64,
+  eof: bool,
+}
+
+impl Direction {
+  fn new(src: RawFd, dst: RawFd) -> io::Result<Self> {
+    let mut fds = [0; 2];
+    syscall!(pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC))?;
+    // SAFETY: pipe2 succeeded, so both fds are valid and owned by nobody else.
+    let (pipe_read, pipe_write) =
+      unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    Ok(Self {
+      src,
+      dst,
+      pipe_read,
+      pipe_write,
+      buffered: 0,
+      moved: 0,
+      eof: false,
+    })
+  }
+
+  /// What this direction waits for: room in `dst` while the pipe holds
+  /// data, otherwise data on `src`. Nothing once `src` is no longer read.
+  fn pollfd(&self, reading: bool) -> libc::pollfd {
+    let (fd, events) = if self.buffered > 0 {
+      (self.dst, libc::POLLOUT)
+    } else if reading && !self.eof {
+      (self.src, libc::POLLIN)
+    } else {
+      // poll(2) skips negative fds.
+      (-1, 0)
+    };
+    libc::pollfd { fd, events, revents: 0 }
+  }
+
+  /// Makes progress after poll reported the fd from
+  /// [`pollfd`](Self::pollfd) ready.
+  fn advance(&mut self) -> io::Result<()> {
+    if self.buffered > 0 {
+      let n = splice(self.pipe_read.as_raw_fd(), self.dst, self.buffered)?;
+      self.buffered -= n;
+      self.moved += n as u64;
+      return Ok(());
+    }
+
+    match splice(self.src, self.pipe_write.as_raw_fd(), CHUNK)? {
+      0 => {
+        self.eof = true;
+        // Pass the EOF on. The peer may already be gone, which is fine.
+        match syscall!(shutdown(self.dst, libc::SHUT_WR)) {
+          Err(err) if err.raw_os_error() != Some(libc::ENOTCONN) => {
+            return Err(err);
+          }
+          _ => {}
+        }
+      }
+      n => self.buffered = n,
+    }
+    Ok(())
+  }
+}
+
+/// Moves up to `len` bytes, returning 0 on EOF of `from`. Fails with
+/// [`io::ErrorKind::WouldBlock`] if nothing could be moved right now.
+fn splice(from: RawFd, to: RawFd, len: usize) -> io::Result<usize> {
+  // Null offsets use the fds' own positions, as pipes and sockets require.
+  match syscall!(splice(
+    from,
+    std::ptr::null_mut(),
+    to,
+    std::ptr::null_mut(),
+    len,
+    libc::SPLICE_F_MOVE | libc::SPLICE_F_NONBLOCK,
+  )) {
+    Ok(n) => Ok(n as usize),
+    Err(err)
+      if matches!(err.raw_os_error(), Some(libc::EAGAIN | libc::EINTR)) =>
+    {
+      Err(io::Error::from(io::ErrorKind::WouldBlock))
+    }
+    Err(err) => Err(err),
+  }
+}
+
+fn run(a: RawFd, b: RawFd) -> io::Result<(u64, u64)> {
+  let mut dirs = [Direction::new(a, b)?, Direction::new(b, a)?];
+  loop {
+    // After the first EOF only what is already in the pipes is delivered.
+    let reading = !dirs.iter().any(|dir| dir.eof);
+    if !reading && dirs.iter().all(|dir| dir.buffered == 0) {
+      break;
+    }
+
+    let mut fds = [dirs[0].pollfd(reading), dirs[1].pollfd(reading)];
+    match syscall!(poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1)) {
+      Err(err) if err.raw_os_error() == Some(libc::EINTR) => continue,
+      result => result?,
+    };
+
+    for (dir, fd) in dirs.iter_mut().zip(&fds) {
+      if fd.revents == 0 {
+        continue;
+      }
+      match dir.advance() {
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        result => result?,
+      }
+    }
+  }
+  Ok((dirs[0].moved, dirs[1].moved))
+}
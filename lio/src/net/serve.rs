@@ -0,0 +1,124 @@
+use std::{
+  future::{Future, IntoFuture},
+  io,
+  pin::Pin,
+  task::{Context, Poll},
+};
+
+use crate::{
+  Lio,
+  api::io::IoFuture,
+  net::{TcpListener, TcpSocket, ops::TcpAccept},
+};
+
+/// Accepts connections on `listener` and runs `handler` on each of them.
+///
+/// Connections are handled concurrently: while one handler waits on I/O,
+/// the others and the accept loop keep making progress. The returned future
+/// drives all of them from whichever task polls it, so no spawning executor
+/// is needed.
+///
+/// The future only completes if accepting fails, with that error. Handlers
+/// still running at that point are dropped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lio::net::{TcpListener, serve};
+///
+/// async fn echo() -> std::io::Result<()> {
+///     let listener = TcpListener::bind_async("127.0.0.1:8080").await?;
+///     serve(listener, |socket| async move {
+///         let mut buf = vec![0u8; 1024];
+///         loop {
+///             let (result, returned) = socket.recv(buf).await;
+///             let n = match result {
+///                 Ok(0) | Err(_) => return,
+///                 Ok(n) => n as usize,
+///             };
+///             let (result, returned) = socket.send(returned[..n].to_vec()).await;
+///             if result.is_err() {
+///                 return;
+///             }
+///             buf = returned;
+///             buf.resize(1024, 0);
+///         }
+///     })
+///     .await
+/// }
+/// ```
+pub fn serve<H, Fut>(listener: TcpListener, handler: H) -> Serve<H, Fut>
+where
+  H: FnMut(TcpSocket) -> Fut,
+  Fut: Future<Output = ()>,
+{
+  Serve { listener, handler, lio: None, accept: None, conns: Vec::new() }
+}
+
+/// The accept loop returned by [`serve`].
+pub struct Serve<H, Fut> {
+  listener: TcpListener,
+  handler: H,
+  lio: Option<Lio>,
+  /// The pending accept, created on first poll.
+  accept: Option<IoFuture<TcpAccept>>,
+  /// Handlers of the connections that are still open.
+  conns: Vec<Pin<Box<Fut>>>,
+}
+
+// Nothing in `Serve` is structurally pinned, the handler futures are boxed.
+impl<H, Fut> Unpin for Serve<H, Fut> {}
+
+impl<H, Fut> Serve<H, Fut> {
+  /// Accepts through `lio` instead of the globally installed instance.
+  ///
+  /// Only the accept loop is affected, operations in the handler pick their
+  /// own instance.
+  pub fn with_lio(mut self, lio: &Lio) -> Self {
+    self.lio = Some(lio.clone());
+    self
+  }
+
+  /// Number of connections whose handler hasn't finished yet.
+  pub fn connections(&self) -> usize {
+    self.conns.len()
+  }
+
+  fn next_accept(&self) -> IoFuture<TcpAccept> {
+    let accept = self.listener.accept();
+    match &self.lio {
+      Some(lio) => accept.with_lio(lio).into_future(),
+      None => accept.into_future(),
+    }
+  }
+}
+
+impl<H, Fut> Future for Serve<H, Fut>
+where
+  H: FnMut(TcpSocket) -> Fut,
+  Fut: Future<Output = ()>,
+{
+  type Output = io::Result<()>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let this = self.get_mut();
+
+    loop {
+      let accept = match &mut this.accept {
+        Some(accept) => accept,
+        None => this.accept.insert(this.next_accept()),
+      };
+      match Pin::new(accept).poll(cx) {
+        Poll::Ready(Ok((socket, _))) => {
+          this.accept = None;
+          this.conns.push(Box::pin((this.handler)(socket)));
+        }
+        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+        Poll::Pending => break,
+      }
+    }
+
+    this.conns.retain_mut(|conn| conn.as_mut().poll(cx).is_pending());
+    Poll::Pending
+  }
+}
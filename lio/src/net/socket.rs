@@ -1,10 +1,13 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 
 use crate::{
   api::{
     self,
     io::Io,
-    ops::{Bind, Connect, Listen, Recv, Send, Shutdown},
+    ops::{
+      Bind, Connect, Interest, Listen, PendingBytes, Poll, Recv, RecvAppend,
+      Send, SetSockOpt, Shutdown, WithTimeout, Writev,
+    },
     resource::{AsResource, FromResource, IntoResource, Resource},
   },
   net::ops::{SocketAccept, SocketNew},
@@ -73,6 +76,13 @@ use crate::{
 /// ```
 pub struct Socket(Resource);
 
+/// `SO_LINGER` counts clock ticks on Apple platforms, `SO_LINGER_SEC` is the
+/// portable one in seconds.
+#[cfg(target_vendor = "apple")]
+const SO_LINGER: libc::c_int = libc::SO_LINGER_SEC;
+#[cfg(not(target_vendor = "apple"))]
+const SO_LINGER: libc::c_int = libc::SO_LINGER;
+
 impl IntoResource for Socket {
   fn into_resource(self) -> Resource {
     self.0
@@ -270,6 +280,74 @@ impl Socket {
     api::recv(&self.0, vec, None)
   }
 
+  /// Receives into `vec` after its first `at` bytes, for growing a buffer
+  /// across several reads without copying the partial frame.
+  ///
+  /// Data lands in `vec[at..vec.capacity()]`, so reserve room before calling.
+  /// On success the result is the new filled length, `at` plus the bytes
+  /// received, and `vec.len()` is set to match. `at` may be less than
+  /// `vec.len()`, in which case bytes from `at` onwards are overwritten.
+  ///
+  /// # Panics
+  ///
+  /// If `at` is past `vec.capacity()`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::Socket;
+  ///
+  /// async fn example(socket: Socket) -> std::io::Result<()> {
+  ///     let mut buffer = Vec::with_capacity(1024);
+  ///     loop {
+  ///         let at = buffer.len();
+  ///         buffer.reserve(512);
+  ///         let (result, returned) = socket.recv_append(buffer, at).await;
+  ///         buffer = returned;
+  ///         if result? == at {
+  ///             break; // peer closed
+  ///         }
+  ///     }
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn recv_append(&self, vec: Vec<u8>, at: usize) -> Io<RecvAppend> {
+    Io::from_op(RecvAppend::new(self.0.clone(), vec, at))
+  }
+
+  /// Like [`recv`](Self::recv), but gives up if no data arrives within
+  /// `timeout`.
+  ///
+  /// On expiry the result is an error of kind
+  /// [`TimedOut`](std::io::ErrorKind::TimedOut) and the buffer is returned
+  /// untouched. The socket stays open and usable.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::{io, time::Duration};
+  /// use lio::net::Socket;
+  ///
+  /// async fn reap_idle(socket: Socket) -> io::Result<()> {
+  ///     let buffer = vec![0u8; 1024];
+  ///     let (result, _buffer) =
+  ///         socket.recv_timeout(buffer, Duration::from_secs(30)).await;
+  ///     match result {
+  ///         Err(err) if err.kind() == io::ErrorKind::TimedOut => {
+  ///             socket.shutdown(libc::SHUT_RDWR).await
+  ///         }
+  ///         other => other.map(drop),
+  ///     }
+  /// }
+  /// ```
+  pub fn recv_timeout(
+    &self,
+    vec: Vec<u8>,
+    timeout: Duration,
+  ) -> Io<WithTimeout<Recv<Vec<u8>>>> {
+    Io::from_op(WithTimeout::new(Recv::new(self.0.clone(), vec, None), timeout))
+  }
+
   /// Sends data through the socket.
   ///
   /// This operation writes data to the socket and returns both the buffer and the
@@ -301,6 +379,44 @@ impl Socket {
     api::send(&self.0, vec, None)
   }
 
+  /// Like [`send`](Self::send), but gives up if the socket can't take the
+  /// data within `timeout`.
+  ///
+  /// On expiry the result is an error of kind
+  /// [`TimedOut`](std::io::ErrorKind::TimedOut) and nothing was sent. The
+  /// socket stays open and usable.
+  pub fn send_timeout(
+    &self,
+    vec: Vec<u8>,
+    timeout: Duration,
+  ) -> Io<WithTimeout<Send<Vec<u8>>>> {
+    Io::from_op(WithTimeout::new(Send::new(self.0.clone(), vec, None), timeout))
+  }
+
+  /// Sends several buffers through the socket in order, like one
+  /// concatenated [`send`](Self::send).
+  ///
+  /// Any number of buffers may be passed, lists longer than `IOV_MAX` are
+  /// split up. See [`api::writev`].
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::Socket;
+  ///
+  /// async fn example(socket: Socket) -> std::io::Result<()> {
+  ///     let header = b"len: 5\n".to_vec();
+  ///     let body = b"hello".to_vec();
+  ///     let (result, _bufs) = socket.send_vectored(vec![header, body]).await;
+  ///     println!("Sent {} bytes", result?);
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn send_vectored(&self, bufs: Vec<Vec<u8>>) -> Io<Writev<Vec<u8>>> {
+    api::writev(&self.0, bufs)
+  }
+
   /// Shuts down part or all of the socket connection.
   ///
   /// This operation disables further send and/or receive operations on the socket.
@@ -332,6 +448,52 @@ impl Socket {
     api::shutdown(&self.0, how)
   }
 
+  /// Waits until the socket is ready for the given interest.
+  ///
+  /// Resolves with the [`Readiness`](crate::api::ops::Readiness) the socket
+  /// ended up in, without reading or writing anything. This is the hook for
+  /// TLS crates that do their own I/O on the raw fd, e.g. driving rustls'
+  /// `read_tls`/`write_tls` once the socket is readable/writable.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::api::ops::Interest;
+  /// use lio::net::Socket;
+  ///
+  /// async fn example(socket: Socket) -> std::io::Result<()> {
+  ///     let readiness = socket.ready(Interest::READABLE).await?;
+  ///     if readiness.is_readable() {
+  ///         // Hand the fd to the TLS session's read_tls.
+  ///     }
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn ready(&self, interest: Interest) -> Io<Poll> {
+    api::poll(&self.0, interest)
+  }
+
+  /// Returns how many received bytes are buffered and not read yet, without
+  /// a blocking ioctl. See [`api::pending_bytes`].
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::Socket;
+  ///
+  /// async fn example(socket: Socket) -> std::io::Result<()> {
+  ///     let pending = socket.pending_bytes().await?;
+  ///     let (result, buf) = socket.recv(vec![0u8; pending.max(1)]).await;
+  ///     result?;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn pending_bytes(&self) -> Io<PendingBytes> {
+    api::pending_bytes(&self.0)
+  }
+
   /// Returns the local address this socket is bound to.
   pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
     use std::os::fd::AsRawFd;
@@ -379,4 +541,167 @@ impl Socket {
       )),
     }
   }
+
+  /// Sets `SO_LINGER`, how closing the socket treats unsent data.
+  ///
+  /// With `Some(timeout)`, `close` blocks until buffered data is sent or the
+  /// timeout runs out, and a zero timeout resets the connection instead.
+  /// With `None` (the default), `close` returns at once and the kernel sends
+  /// the data in the background.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::Socket;
+  /// use std::time::Duration;
+  ///
+  /// async fn example() -> std::io::Result<()> {
+  ///     let socket = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).await?;
+  ///     socket.set_linger(Some(Duration::from_secs(5)))?;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn set_linger(&self, linger: Option<Duration>) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let optval = libc::linger {
+      l_onoff: linger.is_some() as libc::c_int,
+      l_linger: linger.map_or(0, |d| d.as_secs() as libc::c_int),
+    };
+    syscall!(setsockopt(
+      self.0.as_raw_fd(),
+      libc::SOL_SOCKET,
+      SO_LINGER,
+      &optval as *const _ as *const libc::c_void,
+      std::mem::size_of::<libc::linger>() as libc::socklen_t,
+    ))?;
+    Ok(())
+  }
+
+  /// Returns the `SO_LINGER` timeout, see [`set_linger`](Self::set_linger).
+  pub fn linger(&self) -> std::io::Result<Option<Duration>> {
+    use std::os::fd::AsRawFd;
+
+    let mut optval = libc::linger { l_onoff: 0, l_linger: 0 };
+    let mut len = std::mem::size_of::<libc::linger>() as libc::socklen_t;
+    syscall!(getsockopt(
+      self.0.as_raw_fd(),
+      libc::SOL_SOCKET,
+      SO_LINGER,
+      &mut optval as *mut _ as *mut libc::c_void,
+      &mut len,
+    ))?;
+    Ok(
+      (optval.l_onoff != 0)
+        .then(|| Duration::from_secs(optval.l_linger as u64)),
+    )
+  }
+
+  /// Restricts an `AF_INET6` socket to IPv6 peers (`IPV6_V6ONLY`).
+  ///
+  /// With `false` the socket is dual-stack and also talks to IPv4 peers,
+  /// which show up as v4-mapped addresses (`::ffff:a.b.c.d`). The default
+  /// differs between operating systems, so set it explicitly when it matters.
+  /// It must be set before [`bind`](Self::bind).
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::Socket;
+  ///
+  /// async fn example() -> std::io::Result<()> {
+  ///     let socket = Socket::new(libc::AF_INET6, libc::SOCK_STREAM, 0).await?;
+  ///     socket.set_only_v6(false)?;
+  ///     socket.bind("[::]:8080".parse().unwrap()).await?;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn set_only_v6(&self, only_v6: bool) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let optval = only_v6 as libc::c_int;
+    syscall!(setsockopt(
+      self.0.as_raw_fd(),
+      libc::IPPROTO_IPV6,
+      libc::IPV6_V6ONLY,
+      &optval as *const _ as *const libc::c_void,
+      std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+    ))?;
+    Ok(())
+  }
+
+  /// Returns whether this `AF_INET6` socket only talks to IPv6 peers.
+  ///
+  /// See [`set_only_v6`](Self::set_only_v6).
+  pub fn only_v6(&self) -> std::io::Result<bool> {
+    use std::os::fd::AsRawFd;
+
+    let mut optval: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    syscall!(getsockopt(
+      self.0.as_raw_fd(),
+      libc::IPPROTO_IPV6,
+      libc::IPV6_V6ONLY,
+      &mut optval as *mut _ as *mut libc::c_void,
+      &mut len,
+    ))?;
+    Ok(optval != 0)
+  }
+
+  /// Sets `SO_REUSEADDR`, which lets a listener bind an address that still
+  /// has connections in `TIME_WAIT`, like right after a restart.
+  ///
+  /// Stream sockets from [`new`](Self::new) already have it set. It must be
+  /// set before [`bind`](Self::bind).
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::Socket;
+  ///
+  /// async fn example() -> std::io::Result<()> {
+  ///     let socket = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).await?;
+  ///     socket.set_reuseaddr(true).await?;
+  ///     socket.bind("127.0.0.1:8080".parse().unwrap()).await?;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn set_reuseaddr(&self, reuse: bool) -> Io<SetSockOpt> {
+    Io::from_op(SetSockOpt::int(
+      self.0.clone(),
+      libc::SOL_SOCKET,
+      libc::SO_REUSEADDR,
+      reuse as i32,
+    ))
+  }
+
+  /// Sets `SO_REUSEPORT`, which lets several sockets bind the same address.
+  ///
+  /// On Linux incoming connections are spread over the listeners, which
+  /// must all set it and run as the same user. It must be set before
+  /// [`bind`](Self::bind).
+  pub fn set_reuseport(&self, reuse: bool) -> Io<SetSockOpt> {
+    Io::from_op(SetSockOpt::int(
+      self.0.clone(),
+      libc::SOL_SOCKET,
+      libc::SO_REUSEPORT,
+      reuse as i32,
+    ))
+  }
+
+  /// Sets `TCP_NODELAY`, which sends small writes right away instead of
+  /// batching them (Nagle's algorithm).
+  ///
+  /// Set on a listener, accepted connections inherit it.
+  pub fn set_nodelay(&self, nodelay: bool) -> Io<SetSockOpt> {
+    Io::from_op(SetSockOpt::int(
+      self.0.clone(),
+      libc::IPPROTO_TCP,
+      libc::TCP_NODELAY,
+      nodelay as i32,
+    ))
+  }
 }
@@ -1,8 +1,15 @@
 use std::{
   io,
-  net::{SocketAddr, ToSocketAddrs},
+  net::{SocketAddr, SocketAddrV6, ToSocketAddrs},
+  sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+  },
+  time::Duration,
 };
 
+#[cfg(unix)]
+use crate::net::ops::TcpAcceptMultishot;
 use crate::{
   api::{
     self,
@@ -10,11 +17,19 @@ use crate::{
     ops::{self, Recv, Shutdown},
     resource::{AsResource, FromResource, IntoResource, Resource},
   },
-  net::ops::TcpAccept,
+  net::ops::{Counted, TcpAccept},
+};
+#[cfg(target_os = "linux")]
+use crate::{
+  buf::{BufLike, IoBufMut},
+  net::ops::{TcpAcceptDirect, TcpInstallFixed},
 };
 
 use super::socket::Socket;
 
+/// Buffer size [`TcpSocket::close_graceful`] discards incoming data with.
+const DRAIN_LEN: usize = 4096;
+
 /// A TCP socket server, listening for connections.
 ///
 /// `TcpListener` provides a high-level interface for creating TCP servers. After being
@@ -100,8 +115,8 @@ impl TcpListener {
   pub async fn bind_async(addr: impl ToSocketAddrs) -> io::Result<Self> {
     let mut addrs = addr.to_socket_addrs()?;
 
-    let socket = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).await?;
     let addr = addrs.next().unwrap();
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_STREAM, 0).await?;
     socket.bind(addr).await?;
     socket.listen().await?;
     Ok(TcpListener(socket))
@@ -128,13 +143,57 @@ impl TcpListener {
   pub fn bind_sync(addr: impl ToSocketAddrs) -> io::Result<Self> {
     let mut addrs = addr.to_socket_addrs()?;
 
-    let socket = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).wait()?;
     let addr = addrs.next().unwrap();
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_STREAM, 0).wait()?;
     socket.bind(addr).wait()?;
     socket.listen().wait()?;
     Ok(TcpListener(socket))
   }
 
+  /// Creates a new IPv6 `TcpListener` asynchronously, choosing between
+  /// dual-stack and IPv6-only.
+  ///
+  /// With `only_v6 = false` the listener also accepts IPv4 clients, whose
+  /// addresses are reported as v4-mapped (`::ffff:a.b.c.d`). See
+  /// [`Socket::set_only_v6`].
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::TcpListener;
+  ///
+  /// async fn example() -> std::io::Result<()> {
+  ///     // Serve both IPv4 and IPv6 clients from one socket
+  ///     let listener =
+  ///         TcpListener::bind_v6_async("[::]:8080".parse().unwrap(), false).await?;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub async fn bind_v6_async(
+    addr: SocketAddrV6,
+    only_v6: bool,
+  ) -> io::Result<Self> {
+    let socket = Socket::new(libc::AF_INET6, libc::SOCK_STREAM, 0).await?;
+    socket.set_only_v6(only_v6)?;
+    socket.bind(addr.into()).await?;
+    socket.listen().await?;
+    Ok(TcpListener(socket))
+  }
+
+  /// Creates a new IPv6 `TcpListener` synchronously, choosing between
+  /// dual-stack and IPv6-only.
+  ///
+  /// This is the blocking version of [`bind_v6_async`](Self::bind_v6_async).
+  #[allow(deprecated)]
+  pub fn bind_v6_sync(addr: SocketAddrV6, only_v6: bool) -> io::Result<Self> {
+    let socket = Socket::new(libc::AF_INET6, libc::SOCK_STREAM, 0).wait()?;
+    socket.set_only_v6(only_v6)?;
+    socket.bind(addr.into()).wait()?;
+    socket.listen().wait()?;
+    Ok(TcpListener(socket))
+  }
+
   /// Accepts a new incoming connection from this listener.
   ///
   /// This function will await until a new TCP connection is established. When a connection
@@ -168,10 +227,173 @@ impl TcpListener {
     Io::from_op(socket_accept_op)
   }
 
+  /// Accepts every incoming connection from a single submission.
+  ///
+  /// Turn the result into a stream with
+  /// [`into_stream`](crate::api::io::Io::into_stream); it yields one item per
+  /// connection. On io_uring this is one multishot accept request, other
+  /// backends keep the listener registered for readiness.
+  ///
+  /// io_uring ends the request on the first failed accept. The stream yields
+  /// that error and, when it is transient (such as running out of file
+  /// descriptors), submits the accept again. Otherwise the stream ends.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use futures_util::StreamExt;
+  /// use lio::{Lio, net::TcpListener};
+  ///
+  /// async fn example(lio: &Lio, listener: &TcpListener) -> std::io::Result<()> {
+  ///     let mut conns = listener.accept_multishot().with_lio(lio).into_stream();
+  ///     while let Some(conn) = conns.next().await {
+  ///         let (socket, addr) = conn?;
+  ///         println!("Accepted connection from: {}", addr);
+  ///     }
+  ///     Ok(())
+  /// }
+  /// ```
+  #[cfg(unix)]
+  pub fn accept_multishot(&self) -> Io<TcpAcceptMultishot> {
+    Io::from_op(TcpAcceptMultishot::new(self.0.as_resource().clone()))
+  }
+
+  /// Accepts a connection into a fixed file slot of the io_uring instance.
+  ///
+  /// The returned [`DirectTcpSocket`] has no regular descriptor, which saves
+  /// the kernel an fd table lookup on every send and receive. Its slot is in
+  /// the fixed file table of `lio`, which the returned [`Io`] is bound to.
+  /// Only the io_uring backend supports this, others fail with `EOPNOTSUPP`.
+  #[cfg(target_os = "linux")]
+  pub fn accept_direct(&self, lio: &crate::Lio) -> Io<TcpAcceptDirect> {
+    Io::from_op(TcpAcceptDirect::new(
+      self.0.as_resource().clone(),
+      lio.fixed_table(),
+    ))
+    .with_lio(lio)
+  }
+
   /// Returns the local address this listener is bound to.
   pub fn local_addr(&self) -> io::Result<SocketAddr> {
     self.0.local_addr()
   }
+
+  /// Returns whether this IPv6 listener rejects IPv4 clients.
+  pub fn only_v6(&self) -> io::Result<bool> {
+    self.0.only_v6()
+  }
+
+  /// Returns a builder that sets socket options before the listener binds.
+  ///
+  /// `bind_async` and `bind_sync` bind right after creating the socket, so
+  /// options that only count before `bind`, like `SO_REUSEPORT`, need this.
+  pub fn builder() -> TcpListenerBuilder {
+    TcpListenerBuilder::default()
+  }
+}
+
+/// Creates a [`TcpListener`] with socket options set before it binds.
+///
+/// Options left unset keep the defaults of [`Socket::new`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lio::net::TcpListener;
+///
+/// async fn example() -> std::io::Result<()> {
+///     // Several processes can serve the same port
+///     let listener = TcpListener::builder()
+///         .reuseport(true)
+///         .nodelay(true)
+///         .bind_async("0.0.0.0:8080")
+///         .await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TcpListenerBuilder {
+  reuseaddr: Option<bool>,
+  reuseport: Option<bool>,
+  nodelay: Option<bool>,
+}
+
+impl TcpListenerBuilder {
+  /// Sets `SO_REUSEADDR`, see [`Socket::set_reuseaddr`].
+  pub fn reuseaddr(mut self, reuse: bool) -> Self {
+    self.reuseaddr = Some(reuse);
+    self
+  }
+
+  /// Sets `SO_REUSEPORT`, see [`Socket::set_reuseport`].
+  pub fn reuseport(mut self, reuse: bool) -> Self {
+    self.reuseport = Some(reuse);
+    self
+  }
+
+  /// Sets `TCP_NODELAY`, which accepted connections inherit. See
+  /// [`Socket::set_nodelay`].
+  pub fn nodelay(mut self, nodelay: bool) -> Self {
+    self.nodelay = Some(nodelay);
+    self
+  }
+
+  /// Binds like [`TcpListener::bind_async`], setting the options first.
+  pub async fn bind_async(
+    &self,
+    addr: impl ToSocketAddrs,
+  ) -> io::Result<TcpListener> {
+    let addr = first_addr(addr)?;
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_STREAM, 0).await?;
+    for option in self.options(&socket) {
+      option.await?;
+    }
+    socket.bind(addr).await?;
+    socket.listen().await?;
+    Ok(TcpListener(socket))
+  }
+
+  /// Binds like [`TcpListener::bind_sync`], setting the options first.
+  #[allow(deprecated)]
+  pub fn bind_sync(&self, addr: impl ToSocketAddrs) -> io::Result<TcpListener> {
+    let addr = first_addr(addr)?;
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_STREAM, 0).wait()?;
+    for option in self.options(&socket) {
+      option.wait()?;
+    }
+    socket.bind(addr).wait()?;
+    socket.listen().wait()?;
+    Ok(TcpListener(socket))
+  }
+
+  /// The ops setting each option that was set.
+  fn options(&self, socket: &Socket) -> Vec<Io<ops::SetSockOpt>> {
+    let mut options = Vec::new();
+    if let Some(reuse) = self.reuseaddr {
+      options.push(socket.set_reuseaddr(reuse));
+    }
+    if let Some(reuse) = self.reuseport {
+      options.push(socket.set_reuseport(reuse));
+    }
+    if let Some(nodelay) = self.nodelay {
+      options.push(socket.set_nodelay(nodelay));
+    }
+    options
+  }
+}
+
+pub(super) fn first_addr(addr: impl ToSocketAddrs) -> io::Result<SocketAddr> {
+  addr.to_socket_addrs()?.next().ok_or_else(|| {
+    io::Error::new(io::ErrorKind::InvalidInput, "no address to bind to")
+  })
+}
+
+pub(super) fn domain_of(addr: &SocketAddr) -> libc::c_int {
+  match addr {
+    SocketAddr::V4(_) => libc::AF_INET,
+    SocketAddr::V6(_) => libc::AF_INET6,
+  }
 }
 
 /// A TCP socket connection.
@@ -229,7 +451,14 @@ impl TcpListener {
 ///     Ok(())
 /// }
 /// ```
-pub struct TcpSocket(Socket);
+pub struct TcpSocket(Socket, Traffic);
+
+/// Bytes moved over a [`TcpSocket`], shared with its in-flight operations.
+#[derive(Default)]
+struct Traffic {
+  read: Arc<AtomicU64>,
+  written: Arc<AtomicU64>,
+}
 
 impl IntoResource for TcpSocket {
   fn into_resource(self) -> Resource {
@@ -245,7 +474,7 @@ impl AsResource for TcpSocket {
 
 impl FromResource for TcpSocket {
   fn from_resource(resource: Resource) -> Self {
-    Self(Socket::from_resource(resource))
+    Self(Socket::from_resource(resource), Traffic::default())
   }
 }
 
@@ -272,9 +501,41 @@ impl TcpSocket {
   /// }
   /// ```
   pub async fn connect_async(addr: SocketAddr) -> io::Result<Self> {
-    let socket = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).await?;
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_STREAM, 0).await?;
     api::connect(&socket, addr).await?;
-    Ok(TcpSocket(socket))
+    Ok(TcpSocket(socket, Traffic::default()))
+  }
+
+  /// Opens a TCP connection, giving up with
+  /// [`TimedOut`](io::ErrorKind::TimedOut) if it isn't established within
+  /// `timeout`.
+  ///
+  /// The deadline is enforced by the backend, see
+  /// [`WithTimeout`](ops::WithTimeout). On expiry the half-open socket is
+  /// closed. The abandoned connect holds its own reference to the socket
+  /// until the backend completes it, so its fd is closed exactly once, by
+  /// whichever of the two lets go last.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::{net::SocketAddr, time::Duration};
+  /// use lio::net::TcpSocket;
+  ///
+  /// async fn example() -> std::io::Result<()> {
+  ///     let addr: SocketAddr = "10.0.0.1:8080".parse().unwrap();
+  ///     let socket = TcpSocket::connect_timeout(addr, Duration::from_secs(3)).await?;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub async fn connect_timeout(
+    addr: SocketAddr,
+    timeout: Duration,
+  ) -> io::Result<Self> {
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_STREAM, 0).await?;
+    socket.connect(addr).timeout(timeout).await?;
+    Ok(TcpSocket(socket, Traffic::default()))
   }
 
   /// Opens a TCP connection to a remote host synchronously.
@@ -298,9 +559,9 @@ impl TcpSocket {
   /// ```
   #[allow(deprecated)]
   pub fn connect_sync(addr: SocketAddr) -> io::Result<Self> {
-    let socket = Socket::new(libc::AF_INET, libc::SOCK_STREAM, 0).wait()?;
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_STREAM, 0).wait()?;
     api::connect(&socket, addr).wait()?;
-    Ok(TcpSocket(socket))
+    Ok(TcpSocket(socket, Traffic::default()))
   }
 
   /// Receives data from the socket into the provided buffer.
@@ -335,8 +596,33 @@ impl TcpSocket {
   ///     Ok(())
   /// }
   /// ```
-  pub fn recv(&self, vec: Vec<u8>) -> Io<Recv<Vec<u8>>> {
-    self.0.recv(vec)
+  pub fn recv(&self, vec: Vec<u8>) -> Io<Counted<Recv<Vec<u8>>>> {
+    self.count_read(Recv::new(self.0.as_resource().clone(), vec, None))
+  }
+
+  /// Receives into `vec` after its first `at` bytes, returning the new
+  /// filled length.
+  ///
+  /// See [`Socket::recv_append`].
+  pub fn recv_append(
+    &self,
+    vec: Vec<u8>,
+    at: usize,
+  ) -> Io<Counted<ops::RecvAppend>> {
+    self.count_read(ops::RecvAppend::new(self.0.as_resource().clone(), vec, at))
+  }
+
+  /// Receives data, giving up with [`TimedOut`](io::ErrorKind::TimedOut) if
+  /// none arrives within `timeout`.
+  ///
+  /// See [`Socket::recv_timeout`].
+  pub fn recv_timeout(
+    &self,
+    vec: Vec<u8>,
+    timeout: Duration,
+  ) -> Io<Counted<ops::WithTimeout<Recv<Vec<u8>>>>> {
+    let recv = Recv::new(self.0.as_resource().clone(), vec, None);
+    self.count_read(ops::WithTimeout::new(recv, timeout))
   }
 
   /// Sends data through the socket.
@@ -370,8 +656,58 @@ impl TcpSocket {
   ///     Ok(())
   /// }
   /// ```
-  pub fn send(&self, vec: Vec<u8>) -> Io<ops::Send<Vec<u8>>> {
-    self.0.send(vec)
+  pub fn send(&self, vec: Vec<u8>) -> Io<Counted<ops::Send<Vec<u8>>>> {
+    self.count_written(ops::Send::new(self.0.as_resource().clone(), vec, None))
+  }
+
+  /// Sends data, giving up with [`TimedOut`](io::ErrorKind::TimedOut) if the
+  /// socket can't take it within `timeout`.
+  ///
+  /// See [`Socket::send_timeout`].
+  pub fn send_timeout(
+    &self,
+    vec: Vec<u8>,
+    timeout: Duration,
+  ) -> Io<Counted<ops::WithTimeout<ops::Send<Vec<u8>>>>> {
+    let send = ops::Send::new(self.0.as_resource().clone(), vec, None);
+    self.count_written(ops::WithTimeout::new(send, timeout))
+  }
+
+  /// Sends several buffers through the socket in order.
+  ///
+  /// See [`Socket::send_vectored`].
+  pub fn send_vectored(
+    &self,
+    bufs: Vec<Vec<u8>>,
+  ) -> Io<Counted<ops::Writev<Vec<u8>>>> {
+    self.count_written(ops::Writev::new(self.0.as_resource().clone(), bufs))
+  }
+
+  /// Total bytes received over this connection so far.
+  ///
+  /// Counts every successful receive made through this `TcpSocket`, once
+  /// the operation completes. Receives through the raw
+  /// [`Resource`] don't count.
+  pub fn bytes_read(&self) -> u64 {
+    self.1.read.load(Ordering::Relaxed)
+  }
+
+  /// Total bytes sent over this connection so far.
+  ///
+  /// Like [`bytes_read`](Self::bytes_read), for sends.
+  pub fn bytes_written(&self) -> u64 {
+    self.1.written.load(Ordering::Relaxed)
+  }
+
+  fn count_read<O: crate::typed_op::TypedOp>(&self, op: O) -> Io<Counted<O>> {
+    Io::from_op(Counted::new(op, self.1.read.clone()))
+  }
+
+  fn count_written<O: crate::typed_op::TypedOp>(
+    &self,
+    op: O,
+  ) -> Io<Counted<O>> {
+    Io::from_op(Counted::new(op, self.1.written.clone()))
   }
 
   /// Shuts down the read, write, or both halves of this connection.
@@ -416,4 +752,264 @@ impl TcpSocket {
   pub fn shutdown(&self, how: i32) -> Io<Shutdown> {
     self.0.shutdown(how)
   }
+
+  /// Returns how many received bytes are buffered and not read yet, see
+  /// [`Socket::pending_bytes`].
+  pub fn pending_bytes(&self) -> Io<ops::PendingBytes> {
+    self.0.pending_bytes()
+  }
+
+  /// Waits until the connection is ready for the given interest.
+  ///
+  /// See [`Socket::ready`].
+  pub fn ready(&self, interest: ops::Interest) -> Io<ops::Poll> {
+    self.0.ready(interest)
+  }
+
+  /// Sets `SO_LINGER`, see [`Socket::set_linger`].
+  pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+    self.0.set_linger(linger)
+  }
+
+  /// Returns the `SO_LINGER` timeout, see [`Socket::set_linger`].
+  pub fn linger(&self) -> io::Result<Option<Duration>> {
+    self.0.linger()
+  }
+
+  /// Closes the connection without losing data in flight.
+  ///
+  /// Closing a socket that still has unread data makes the kernel send a
+  /// RST, and the peer may discard bytes it hasn't read yet. This instead
+  /// shuts down the write side, so the peer sees EOF after everything sent,
+  /// reads and discards until the peer closes its side too, and only then
+  /// closes the descriptor.
+  ///
+  /// It waits for the peer indefinitely; wrap it in a timeout if the peer
+  /// might never close.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::TcpListener;
+  ///
+  /// async fn example() -> std::io::Result<()> {
+  ///     let listener = TcpListener::bind_async("127.0.0.1:8080").await?;
+  ///     let (socket, _) = listener.accept().await?;
+  ///
+  ///     let (result, _) = socket.send(b"HTTP/1.1 200 OK\r\n\r\n".to_vec()).await;
+  ///     result?;
+  ///     socket.close_graceful().await?;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub async fn close_graceful(self) -> io::Result<()> {
+    self.shutdown(libc::SHUT_WR).await?;
+    let mut buf = vec![0u8; DRAIN_LEN];
+    loop {
+      let (result, returned) = self.recv(buf).await;
+      if result? == 0 {
+        return Ok(());
+      }
+      buf = returned;
+    }
+  }
+
+  /// Splits the connection into a half that receives and a half that sends,
+  /// so each can be moved to its own task.
+  ///
+  /// Both halves hold a clone of the same [`Resource`], so the descriptor
+  /// is closed exactly once, when the second of them is dropped. The byte
+  /// counters carry over, [`bytes_read`](OwnedReadHalf::bytes_read) and
+  /// [`bytes_written`](OwnedWriteHalf::bytes_written) keep counting from
+  /// where this socket left off.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use lio::net::TcpSocket;
+  /// use std::net::SocketAddr;
+  ///
+  /// async fn example() -> std::io::Result<()> {
+  ///     let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+  ///     let socket = TcpSocket::connect_async(addr).await?;
+  ///     let (reader, writer) = socket.into_split();
+  ///
+  ///     let (result, _) = writer.send(b"ping".to_vec()).await;
+  ///     result?;
+  ///     writer.shutdown().await?;
+  ///
+  ///     let (result, buffer) = reader.recv(vec![0u8; 1024]).await;
+  ///     let bytes_read = result? as usize;
+  ///
+  ///     Ok(())
+  /// }
+  /// ```
+  pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+    let TcpSocket(socket, Traffic { read, written }) = self;
+    let read_half = OwnedReadHalf {
+      socket: Socket::from_resource(socket.as_resource().clone()),
+      read,
+    };
+    (read_half, OwnedWriteHalf { socket, written })
+  }
+}
+
+/// The receiving half of a [`TcpSocket`], from
+/// [`into_split`](TcpSocket::into_split).
+pub struct OwnedReadHalf {
+  socket: Socket,
+  read: Arc<AtomicU64>,
+}
+
+impl AsResource for OwnedReadHalf {
+  fn as_resource(&self) -> &Resource {
+    self.socket.as_resource()
+  }
+}
+
+impl OwnedReadHalf {
+  /// Receives data from the connection, see [`TcpSocket::recv`].
+  pub fn recv(&self, vec: Vec<u8>) -> Io<Counted<Recv<Vec<u8>>>> {
+    let recv = Recv::new(self.socket.as_resource().clone(), vec, None);
+    Io::from_op(Counted::new(recv, self.read.clone()))
+  }
+
+  /// Receives into `vec` after its first `at` bytes, see
+  /// [`TcpSocket::recv_append`].
+  pub fn recv_append(
+    &self,
+    vec: Vec<u8>,
+    at: usize,
+  ) -> Io<Counted<ops::RecvAppend>> {
+    let recv = ops::RecvAppend::new(self.socket.as_resource().clone(), vec, at);
+    Io::from_op(Counted::new(recv, self.read.clone()))
+  }
+
+  /// Receives data with a deadline, see [`TcpSocket::recv_timeout`].
+  pub fn recv_timeout(
+    &self,
+    vec: Vec<u8>,
+    timeout: Duration,
+  ) -> Io<Counted<ops::WithTimeout<Recv<Vec<u8>>>>> {
+    let recv = Recv::new(self.socket.as_resource().clone(), vec, None);
+    let recv = ops::WithTimeout::new(recv, timeout);
+    Io::from_op(Counted::new(recv, self.read.clone()))
+  }
+
+  /// Total bytes received over the connection so far, see
+  /// [`TcpSocket::bytes_read`].
+  pub fn bytes_read(&self) -> u64 {
+    self.read.load(Ordering::Relaxed)
+  }
+}
+
+/// The sending half of a [`TcpSocket`], from
+/// [`into_split`](TcpSocket::into_split).
+pub struct OwnedWriteHalf {
+  socket: Socket,
+  written: Arc<AtomicU64>,
+}
+
+impl AsResource for OwnedWriteHalf {
+  fn as_resource(&self) -> &Resource {
+    self.socket.as_resource()
+  }
+}
+
+impl OwnedWriteHalf {
+  /// Sends data through the connection, see [`TcpSocket::send`].
+  pub fn send(&self, vec: Vec<u8>) -> Io<Counted<ops::Send<Vec<u8>>>> {
+    let send = ops::Send::new(self.socket.as_resource().clone(), vec, None);
+    Io::from_op(Counted::new(send, self.written.clone()))
+  }
+
+  /// Sends data with a deadline, see [`TcpSocket::send_timeout`].
+  pub fn send_timeout(
+    &self,
+    vec: Vec<u8>,
+    timeout: Duration,
+  ) -> Io<Counted<ops::WithTimeout<ops::Send<Vec<u8>>>>> {
+    let send = ops::Send::new(self.socket.as_resource().clone(), vec, None);
+    let send = ops::WithTimeout::new(send, timeout);
+    Io::from_op(Counted::new(send, self.written.clone()))
+  }
+
+  /// Sends several buffers in order, see [`TcpSocket::send_vectored`].
+  pub fn send_vectored(
+    &self,
+    bufs: Vec<Vec<u8>>,
+  ) -> Io<Counted<ops::Writev<Vec<u8>>>> {
+    let send = ops::Writev::new(self.socket.as_resource().clone(), bufs);
+    Io::from_op(Counted::new(send, self.written.clone()))
+  }
+
+  /// Shuts down the write side of the connection, so the peer reads EOF
+  /// after everything sent so far.
+  ///
+  /// The read half keeps receiving. The descriptor stays open until both
+  /// halves are dropped.
+  pub fn shutdown(&self) -> Io<Shutdown> {
+    self.socket.shutdown(libc::SHUT_WR)
+  }
+
+  /// Total bytes sent over the connection so far, see
+  /// [`TcpSocket::bytes_written`].
+  pub fn bytes_written(&self) -> u64 {
+    self.written.load(Ordering::Relaxed)
+  }
+}
+
+/// A TCP connection held in a fixed file slot, from
+/// [`TcpListener::accept_direct`].
+///
+/// Sends and receives go through the slot, skipping the fd table lookup. It
+/// has no regular descriptor until [`install_fd`](Self::install_fd) asks for
+/// one, which takes the place of `as_raw_fd` for this type. It owns the slot
+/// and only works on the [`Lio`](crate::Lio) that accepted it. Dropping it
+/// frees the slot, [`close`](Self::close) does too and reports the result.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct DirectTcpSocket(ops::FixedFd);
+
+#[cfg(target_os = "linux")]
+impl DirectTcpSocket {
+  pub(crate) fn new(fd: ops::FixedFd) -> Self {
+    Self(fd)
+  }
+
+  /// Returns the fixed file slot this connection is in.
+  pub fn fixed_fd(&self) -> &ops::FixedFd {
+    &self.0
+  }
+
+  /// Receives data from the connection into `buf`, see [`TcpSocket::recv`].
+  pub fn recv<B>(&self, buf: B) -> Io<ops::RecvFixed<B>>
+  where
+    B: IoBufMut + Send + Sync,
+  {
+    api::recv_fixed(&self.0, buf, None)
+  }
+
+  /// Sends data from `buf` on the connection, see [`TcpSocket::send`].
+  pub fn send<B>(&self, buf: B) -> Io<ops::SendFixed<B>>
+  where
+    B: BufLike + Send + Sync,
+  {
+    api::send_fixed(&self.0, buf, None)
+  }
+
+  /// Installs a regular descriptor for the connection, for APIs that need
+  /// one.
+  ///
+  /// The returned [`TcpSocket`] owns the new descriptor. This one keeps
+  /// working and still holds the slot.
+  pub fn install_fd(&self) -> Io<TcpInstallFixed> {
+    Io::from_op(TcpInstallFixed::new(&self.0))
+  }
+
+  /// Frees the fixed file slot.
+  pub fn close(self) -> Io<ops::CloseFixed> {
+    api::close_fixed(self.0)
+  }
 }
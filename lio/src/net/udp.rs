@@ -0,0 +1,107 @@
+use std::{
+  io,
+  net::{SocketAddr, ToSocketAddrs},
+};
+
+use crate::api::{
+  io::Io,
+  ops::{RecvFrom, SendTo},
+  resource::{AsResource, FromResource, IntoResource, Resource},
+};
+
+use super::{
+  socket::Socket,
+  tcp::{domain_of, first_addr},
+};
+
+/// A UDP socket.
+///
+/// After binding to a local address, `UdpSocket` sends datagrams to any
+/// address with [`send_to`](Self::send_to), and receives datagrams from
+/// anyone along with their sender with [`recv_from`](Self::recv_from).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lio::net::UdpSocket;
+///
+/// async fn example() -> std::io::Result<()> {
+///     let socket = UdpSocket::bind_async("127.0.0.1:0").await?;
+///
+///     // Ask a DNS server something
+///     let server = "127.0.0.1:53".parse().unwrap();
+///     let (result, _query) = socket.send_to(b"query".to_vec(), server).await;
+///     result?;
+///
+///     // Wait for the answer
+///     let (result, answer) = socket.recv_from(vec![0u8; 512]).await;
+///     let (len, from) = result?;
+///     println!("{} bytes from {}: {:?}", len, from, &answer[..len]);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct UdpSocket(Socket);
+
+impl IntoResource for UdpSocket {
+  fn into_resource(self) -> Resource {
+    self.0.into_resource()
+  }
+}
+
+impl AsResource for UdpSocket {
+  fn as_resource(&self) -> &Resource {
+    self.0.as_resource()
+  }
+}
+
+impl FromResource for UdpSocket {
+  fn from_resource(resource: Resource) -> Self {
+    Self(Socket::from_resource(resource))
+  }
+}
+
+impl UdpSocket {
+  /// Creates a UDP socket bound to the specified address asynchronously.
+  ///
+  /// Binding with a port number of 0 lets the OS pick a free port, see
+  /// [`local_addr`](Self::local_addr).
+  pub async fn bind_async(addr: impl ToSocketAddrs) -> io::Result<Self> {
+    let addr = first_addr(addr)?;
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_DGRAM, 0).await?;
+    socket.bind(addr).await?;
+    Ok(UdpSocket(socket))
+  }
+
+  /// Creates a UDP socket bound to the specified address synchronously.
+  ///
+  /// This is the blocking version of [`bind_async`](Self::bind_async).
+  #[allow(deprecated)]
+  pub fn bind_sync(addr: impl ToSocketAddrs) -> io::Result<Self> {
+    let addr = first_addr(addr)?;
+    let socket = Socket::new(domain_of(&addr), libc::SOCK_DGRAM, 0).wait()?;
+    socket.bind(addr).wait()?;
+    Ok(UdpSocket(socket))
+  }
+
+  /// Sends `vec` as one datagram to `addr`.
+  ///
+  /// Resolves with the number of bytes sent, which for UDP is the whole
+  /// datagram.
+  pub fn send_to(&self, vec: Vec<u8>, addr: SocketAddr) -> Io<SendTo<Vec<u8>>> {
+    Io::from_op(SendTo::new(self.0.as_resource().clone(), vec, addr))
+  }
+
+  /// Receives one datagram into `vec`.
+  ///
+  /// Resolves with its length and the address it came from. A datagram
+  /// larger than `vec`'s capacity is truncated.
+  pub fn recv_from(&self, vec: Vec<u8>) -> Io<RecvFrom<Vec<u8>>> {
+    Io::from_op(RecvFrom::new(self.0.as_resource().clone(), vec))
+  }
+
+  /// Returns the local address this socket is bound to.
+  pub fn local_addr(&self) -> io::Result<SocketAddr> {
+    self.0.local_addr()
+  }
+}
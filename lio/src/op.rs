@@ -9,6 +9,13 @@ use std::time::Duration;
 
 #[cfg(unix)]
 use std::os::fd::RawFd;
+#[cfg(unix)]
+use std::sync::Arc;
+
+#[cfg(target_os = "linux")]
+use crate::api::ops::FixedTable;
+#[cfg(unix)]
+use crate::futex::FutexWord;
 
 use crate::api::resource::Resource;
 
@@ -107,9 +114,12 @@ pub enum Op {
   // ═══════════════════════════════════════════════════════════════════════════════
   // Buffer operations - buffer field owns the data, ptr/len point into it
   // ═══════════════════════════════════════════════════════════════════════════════
+  /// `buf_index` is set when the buffer is in the ring's registered buffer
+  /// table, see [`BufStore::register_fixed`](crate::buf::BufStore::register_fixed).
   Read {
     fd: Resource,
     buffer: OpBuf,
+    buf_index: Option<u16>,
   },
   Write {
     fd: Resource,
@@ -125,25 +135,114 @@ pub enum Op {
     offset: i64,
     buffer: OpBuf,
   },
+  #[cfg(unix)]
   Send {
     fd: Resource,
     flags: i32,
     buffer: OpBuf,
   },
+  /// [`Send`](Op::Send) without copying the buffer into the kernel.
+  /// `buf_index` is set like [`Read`](Op::Read)'s.
+  ///
+  /// On io_uring the kernel reads the buffer after the byte count is
+  /// known, the op only completes once it reports being done with it.
+  #[cfg(unix)]
+  SendZc {
+    fd: Resource,
+    flags: i32,
+    buffer: OpBuf,
+    buf_index: Option<u16>,
+  },
+  /// `buf_index` is set like [`Read`](Op::Read)'s.
+  #[cfg(unix)]
   Recv {
     fd: Resource,
     flags: i32,
     buffer: OpBuf,
+    buf_index: Option<u16>,
+  },
+  /// `sendmsg(2)` with a single iovec and a destination address. `msg`
+  /// points into the boxed [`SendTo`](crate::api::ops::SendTo) op.
+  #[cfg(unix)]
+  SendMsg {
+    fd: Resource,
+    msg: *const libc::msghdr,
+  },
+  /// `recvmsg(2)` with a single iovec, recording the sender's address.
+  /// `msg` points into the boxed [`RecvFrom`](crate::api::ops::RecvFrom) op.
+  #[cfg(unix)]
+  RecvMsg {
+    fd: Resource,
+    msg: *mut libc::msghdr,
+  },
+  /// Provides `nbufs` buffers of `len` bytes at `addr` to group `bgid`,
+  /// numbered from `bid`. Points into `memory`, which the driver keeps until
+  /// the group is removed.
+  #[cfg(unix)]
+  ProvideBuffers {
+    addr: *mut u8,
+    len: u32,
+    nbufs: u16,
+    bgid: u16,
+    bid: u16,
+    memory: std::sync::Arc<crate::buf::GroupMemory>,
+  },
+  /// Removes up to `nbufs` buffers from group `bgid`.
+  #[cfg(unix)]
+  RemoveBuffers {
+    nbufs: u16,
+    bgid: u16,
+  },
+  /// `recv(2)` into a buffer of group `bgid` the kernel picks. The result
+  /// carries the buffer id, see [`RecvSelect`](crate::api::ops::RecvSelect).
+  #[cfg(unix)]
+  RecvSelect {
+    fd: Resource,
+    len: u32,
+    bgid: u16,
+    flags: i32,
+  },
+  /// `read(2)` into a buffer of group `bgid` the kernel picks, once per
+  /// chunk of data while `multishot`. See
+  /// [`ReadMulti`](crate::api::ops::ReadMulti).
+  #[cfg(unix)]
+  ReadSelect {
+    fd: Resource,
+    len: u32,
+    bgid: u16,
+    multishot: bool,
+  },
+  /// Registers `count` buffers as the ring's fixed buffer table.
+  #[cfg(unix)]
+  RegisterBuffers {
+    iov: *const libc::iovec,
+    count: usize,
+  },
+  /// Scatters into `iovcnt` buffers, which may be more than `IOV_MAX`.
+  #[cfg(unix)]
+  Readv {
+    fd: Resource,
+    iov: *const libc::iovec,
+    iovcnt: usize,
+  },
+  /// Gathers `iovcnt` buffers, which may be more than `IOV_MAX`.
+  #[cfg(unix)]
+  Writev {
+    fd: Resource,
+    iov: *const libc::iovec,
+    iovcnt: usize,
   },
 
   // ═══════════════════════════════════════════════════════════════════════════════
   // Socket operations
   // ═══════════════════════════════════════════════════════════════════════════════
+  #[cfg(unix)]
   Accept {
     fd: Resource,
     addr: *mut libc::sockaddr_storage,
     len: *mut libc::socklen_t,
   },
+  #[cfg(unix)]
   Connect {
     fd: Resource,
     addr: *const libc::sockaddr_storage,
@@ -151,24 +250,89 @@ pub enum Op {
     /// Tracks whether connect() has been called (for EISCONN handling)
     connect_called: bool,
   },
+  #[cfg(unix)]
   Bind {
     fd: Resource,
     addr: *const libc::sockaddr_storage,
     addrlen: libc::socklen_t,
   },
+  #[cfg(unix)]
   Listen {
     fd: Resource,
     backlog: i32,
   },
+  #[cfg(unix)]
   Shutdown {
     fd: Resource,
     how: i32,
   },
+  #[cfg(unix)]
   Socket {
     domain: i32,
     ty: i32,
     proto: i32,
   },
+  /// Waits for readiness, completes with the `revents` mask.
+  #[cfg(unix)]
+  Poll {
+    fd: Resource,
+    events: i16,
+  },
+  /// Like [`Op::Poll`], but stays armed and completes once per readiness
+  /// event until cancelled.
+  #[cfg(unix)]
+  PollMultishot {
+    fd: Resource,
+    events: i16,
+  },
+  /// Bytes received on a socket and not read yet, like `SIOCINQ`.
+  #[cfg(unix)]
+  PendingBytes {
+    fd: Resource,
+  },
+  /// Accepts connections until cancelled, completing once per connection
+  /// with its descriptor. The peer address isn't recorded.
+  #[cfg(unix)]
+  AcceptMultishot {
+    fd: Resource,
+  },
+  /// Like [`Op::Accept`], but into a free slot of the ring's fixed file
+  /// table. Completes with the slot index. Only io_uring supports it.
+  #[cfg(target_os = "linux")]
+  AcceptDirect {
+    fd: Resource,
+    table: Arc<FixedTable>,
+    addr: *mut libc::sockaddr_storage,
+    len: *mut libc::socklen_t,
+  },
+  /// [`Op::Recv`] on a fixed file slot.
+  #[cfg(target_os = "linux")]
+  RecvFixed {
+    slot: u32,
+    table: Arc<FixedTable>,
+    flags: i32,
+    buffer: OpBuf,
+  },
+  /// [`Op::Send`] on a fixed file slot.
+  #[cfg(target_os = "linux")]
+  SendFixed {
+    slot: u32,
+    table: Arc<FixedTable>,
+    flags: i32,
+    buffer: OpBuf,
+  },
+  /// Installs a regular fd for a fixed file slot, completes with the fd.
+  #[cfg(target_os = "linux")]
+  InstallFixed {
+    slot: u32,
+    table: Arc<FixedTable>,
+  },
+  /// Frees a fixed file slot.
+  #[cfg(target_os = "linux")]
+  CloseFixed {
+    slot: u32,
+    table: Arc<FixedTable>,
+  },
 
   // ═══════════════════════════════════════════════════════════════════════════════
   // File operations
@@ -196,6 +360,78 @@ pub enum Op {
     fd: Resource,
     size: u64,
   },
+  #[cfg(unix)]
+  Fadvise {
+    fd: Resource,
+    offset: u64,
+    len: i64,
+    advice: i32,
+  },
+  #[cfg(unix)]
+  Fallocate {
+    fd: Resource,
+    offset: u64,
+    len: u64,
+    mode: i32,
+  },
+  #[cfg(unix)]
+  SyncFileRange {
+    fd: Resource,
+    offset: u64,
+    len: u32,
+    flags: u32,
+  },
+  #[cfg(unix)]
+  Dup {
+    fd: Resource,
+  },
+  #[cfg(unix)]
+  Dup2 {
+    old_fd: Resource,
+    new_fd: Resource,
+  },
+  #[cfg(unix)]
+  Futimens {
+    fd: Resource,
+    /// `[atime, mtime]`
+    times: *const libc::timespec,
+  },
+  #[cfg(unix)]
+  UtimensAt {
+    dir_fd: Resource,
+    path: *const c_char,
+    /// `[atime, mtime]`
+    times: *const libc::timespec,
+    flags: i32,
+  },
+  /// Waits for a child to change state and writes how to `info`.
+  #[cfg(unix)]
+  WaitId {
+    idtype: libc::idtype_t,
+    id: libc::id_t,
+    options: i32,
+    info: std::sync::Arc<crate::api::ops::WaitInfo>,
+  },
+  /// Writes the metadata of `path` to `buf`. Points into the boxed
+  /// [`Statx`](crate::api::ops::Statx) op.
+  #[cfg(target_os = "linux")]
+  Statx {
+    dir_fd: Resource,
+    path: *const c_char,
+    flags: i32,
+    mask: u32,
+    buf: *mut libc::statx,
+  },
+
+  // ═══════════════════════════════════════════════════════════════════════════════
+  // Memory mapping
+  // ═══════════════════════════════════════════════════════════════════════════════
+  #[cfg(unix)]
+  Madvise {
+    addr: *mut u8,
+    len: usize,
+    advice: i32,
+  },
 
   // ═══════════════════════════════════════════════════════════════════════════════
   // Link operations
@@ -211,24 +447,309 @@ pub enum Op {
     target: *const c_char,
     linkpath: *const c_char,
   },
+  RenameAt {
+    old_dir_fd: Resource,
+    old_path: *const c_char,
+    new_dir_fd: Resource,
+    new_path: *const c_char,
+    /// `renameat2` flags, such as `RENAME_NOREPLACE`.
+    flags: u32,
+  },
+  MkDirAt {
+    dir_fd: Resource,
+    path: *const c_char,
+    mode: u32,
+  },
+  UnlinkAt {
+    dir_fd: Resource,
+    path: *const c_char,
+    flags: i32,
+  },
 
   // ═══════════════════════════════════════════════════════════════════════════════
   // Misc
   // ═══════════════════════════════════════════════════════════════════════════════
+  /// Waits on the futex word while it equals `expected`. Completes with
+  /// `-EAGAIN` if it doesn't. `parker` points into the boxed
+  /// [`FutexWait`](crate::api::ops::FutexWait) op.
+  #[cfg(unix)]
+  FutexWait {
+    word: Arc<FutexWord>,
+    expected: u32,
+    parker: *mut Option<crate::futex::Parker>,
+  },
+  /// Wakes up to `count` waiters of the futex word, completes with how
+  /// many were woken.
+  #[cfg(unix)]
+  FutexWake {
+    word: Arc<FutexWord>,
+    count: u32,
+  },
+  /// Creates a pipe, writing the read and write ends to `fds`. Points into
+  /// the boxed [`Pipe`](crate::api::ops::Pipe) op.
+  #[cfg(unix)]
+  Pipe {
+    fds: *mut [RawFd; 2],
+    flags: i32,
+  },
+  /// Moves `len` bytes from `fd_in` to `fd_out`, one of which is a pipe.
+  /// An offset of `-1` uses and advances the fd's own position.
+  #[cfg(target_os = "linux")]
+  Splice {
+    fd_in: Resource,
+    off_in: i64,
+    fd_out: Resource,
+    off_out: i64,
+    len: u32,
+    flags: u32,
+  },
+  #[cfg(target_os = "linux")]
+  EpollCtl {
+    epfd: Resource,
+    fd: Resource,
+    op: i32,
+    event: *const libc::epoll_event,
+  },
   #[cfg(target_os = "linux")]
   Tee {
     fd_in: Resource,
     fd_out: Resource,
     size: u32,
   },
+  /// Pointers into the CStrings and value buffer of the TypedOp.
+  #[cfg(target_os = "linux")]
+  GetXattr {
+    path: *const libc::c_char,
+    name: *const libc::c_char,
+    value: *mut libc::c_void,
+    len: u32,
+  },
+  #[cfg(target_os = "linux")]
+  SetXattr {
+    path: *const libc::c_char,
+    name: *const libc::c_char,
+    value: *const libc::c_void,
+    len: u32,
+    flags: i32,
+  },
+  #[cfg(target_os = "linux")]
+  FGetXattr {
+    fd: Resource,
+    name: *const libc::c_char,
+    value: *mut libc::c_void,
+    len: u32,
+  },
+  #[cfg(target_os = "linux")]
+  FSetXattr {
+    fd: Resource,
+    name: *const libc::c_char,
+    value: *const libc::c_void,
+    len: u32,
+    flags: i32,
+  },
   Timeout {
     duration: Duration,
     #[cfg(target_os = "linux")]
-    timer_fd: Resource,
-    #[cfg(target_os = "linux")]
     timespec: *const libc::timespec,
   },
   Nop,
+  /// A user-defined op, see [`RawOperation`](crate::typed_op::RawOperation).
+  /// Points into the boxed [`Custom`](crate::api::ops::Custom) op.
+  Custom {
+    op: *const dyn crate::typed_op::RawOperation,
+  },
+}
+
+impl Op {
+  /// Upper-case name of the operation, such as `"READ"` or `"TIMEOUT"`.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Op::Read { .. } => "READ",
+      Op::Write { .. } => "WRITE",
+      Op::ReadAt { .. } => "READ_AT",
+      Op::WriteAt { .. } => "WRITE_AT",
+      #[cfg(unix)]
+      Op::Send { .. } => "SEND",
+      #[cfg(unix)]
+      Op::SendZc { .. } => "SEND_ZC",
+      #[cfg(unix)]
+      Op::Recv { .. } => "RECV",
+      #[cfg(unix)]
+      Op::SendMsg { .. } => "SENDMSG",
+      #[cfg(unix)]
+      Op::RecvMsg { .. } => "RECVMSG",
+      #[cfg(unix)]
+      Op::ProvideBuffers { .. } => "PROVIDE_BUFFERS",
+      #[cfg(unix)]
+      Op::RemoveBuffers { .. } => "REMOVE_BUFFERS",
+      #[cfg(unix)]
+      Op::RecvSelect { .. } => "RECV_SELECT",
+      #[cfg(unix)]
+      Op::ReadSelect { multishot: true, .. } => "READ_MULTISHOT",
+      #[cfg(unix)]
+      Op::ReadSelect { .. } => "READ_SELECT",
+      #[cfg(unix)]
+      Op::RegisterBuffers { .. } => "REGISTER_BUFFERS",
+      #[cfg(unix)]
+      Op::Readv { .. } => "READV",
+      #[cfg(unix)]
+      Op::Writev { .. } => "WRITEV",
+      #[cfg(unix)]
+      Op::Accept { .. } => "ACCEPT",
+      #[cfg(unix)]
+      Op::AcceptMultishot { .. } => "ACCEPT_MULTISHOT",
+      #[cfg(unix)]
+      Op::Connect { .. } => "CONNECT",
+      #[cfg(unix)]
+      Op::Bind { .. } => "BIND",
+      #[cfg(unix)]
+      Op::Listen { .. } => "LISTEN",
+      #[cfg(unix)]
+      Op::Shutdown { .. } => "SHUTDOWN",
+      #[cfg(unix)]
+      Op::Socket { .. } => "SOCKET",
+      #[cfg(unix)]
+      Op::Poll { .. } => "POLL",
+      #[cfg(unix)]
+      Op::PollMultishot { .. } => "POLL_MULTISHOT",
+      #[cfg(unix)]
+      Op::PendingBytes { .. } => "PENDING_BYTES",
+      #[cfg(target_os = "linux")]
+      Op::AcceptDirect { .. } => "ACCEPT_DIRECT",
+      #[cfg(target_os = "linux")]
+      Op::RecvFixed { .. } => "RECV_FIXED",
+      #[cfg(target_os = "linux")]
+      Op::SendFixed { .. } => "SEND_FIXED",
+      #[cfg(target_os = "linux")]
+      Op::InstallFixed { .. } => "FIXED_FD_INSTALL",
+      #[cfg(target_os = "linux")]
+      Op::CloseFixed { .. } => "CLOSE_FIXED",
+      Op::OpenAt { .. } => "OPENAT",
+      Op::Close { .. } => "CLOSE",
+      Op::Fsync { .. } => "FSYNC",
+      Op::Truncate { .. } => "TRUNCATE",
+      #[cfg(unix)]
+      Op::Fadvise { .. } => "FADVISE",
+      #[cfg(unix)]
+      Op::Fallocate { .. } => "FALLOCATE",
+      #[cfg(unix)]
+      Op::SyncFileRange { .. } => "SYNC_FILE_RANGE",
+      #[cfg(unix)]
+      Op::Dup { .. } => "DUP",
+      #[cfg(unix)]
+      Op::Dup2 { .. } => "DUP2",
+      #[cfg(unix)]
+      Op::Futimens { .. } => "FUTIMENS",
+      #[cfg(unix)]
+      Op::UtimensAt { .. } => "UTIMENSAT",
+      #[cfg(unix)]
+      Op::WaitId { .. } => "WAITID",
+      #[cfg(target_os = "linux")]
+      Op::Statx { .. } => "STATX",
+      #[cfg(unix)]
+      Op::Madvise { .. } => "MADVISE",
+      Op::LinkAt { .. } => "LINKAT",
+      Op::SymlinkAt { .. } => "SYMLINKAT",
+      Op::RenameAt { .. } => "RENAMEAT",
+      Op::MkDirAt { .. } => "MKDIRAT",
+      Op::UnlinkAt { .. } => "UNLINKAT",
+      #[cfg(unix)]
+      Op::FutexWait { .. } => "FUTEX_WAIT",
+      #[cfg(unix)]
+      Op::FutexWake { .. } => "FUTEX_WAKE",
+      #[cfg(unix)]
+      Op::Pipe { .. } => "PIPE",
+      #[cfg(target_os = "linux")]
+      Op::Splice { .. } => "SPLICE",
+      #[cfg(target_os = "linux")]
+      Op::EpollCtl { .. } => "EPOLL_CTL",
+      #[cfg(target_os = "linux")]
+      Op::Tee { .. } => "TEE",
+      #[cfg(target_os = "linux")]
+      Op::GetXattr { .. } => "GETXATTR",
+      #[cfg(target_os = "linux")]
+      Op::SetXattr { .. } => "SETXATTR",
+      #[cfg(target_os = "linux")]
+      Op::FGetXattr { .. } => "FGETXATTR",
+      #[cfg(target_os = "linux")]
+      Op::FSetXattr { .. } => "FSETXATTR",
+      Op::Timeout { .. } => "TIMEOUT",
+      Op::Nop => "NOP",
+      Op::Custom { .. } => "CUSTOM",
+    }
+  }
+
+  /// Whether the op writes data to its resource.
+  pub(crate) fn is_write(&self) -> bool {
+    match self {
+      Op::Write { .. } | Op::WriteAt { .. } => true,
+      #[cfg(unix)]
+      Op::Writev { .. } => true,
+      _ => false,
+    }
+  }
+
+  /// The resource this op primarily acts on, if any.
+  ///
+  /// Directory fds of `*at` ops don't count, they only anchor a path.
+  pub(crate) fn resource(&self) -> Option<&Resource> {
+    match self {
+      Op::Read { fd, .. }
+      | Op::Write { fd, .. }
+      | Op::ReadAt { fd, .. }
+      | Op::WriteAt { fd, .. }
+      | Op::Fsync { fd }
+      | Op::Truncate { fd, .. } => Some(fd),
+      #[cfg(unix)]
+      Op::Send { fd, .. }
+      | Op::SendZc { fd, .. }
+      | Op::Recv { fd, .. }
+      | Op::Accept { fd, .. }
+      | Op::Connect { fd, .. }
+      | Op::Bind { fd, .. }
+      | Op::Listen { fd, .. }
+      | Op::Shutdown { fd, .. }
+      | Op::Dup { fd }
+      | Op::Futimens { fd, .. }
+      | Op::Fadvise { fd, .. }
+      | Op::Fallocate { fd, .. }
+      | Op::SyncFileRange { fd, .. }
+      | Op::Poll { fd, .. }
+      | Op::PollMultishot { fd, .. }
+      | Op::AcceptMultishot { fd }
+      | Op::PendingBytes { fd }
+      | Op::SendMsg { fd, .. }
+      | Op::RecvMsg { fd, .. }
+      | Op::RecvSelect { fd, .. }
+      | Op::ReadSelect { fd, .. }
+      | Op::Readv { fd, .. }
+      | Op::Writev { fd, .. } => Some(fd),
+      #[cfg(unix)]
+      Op::Dup2 { old_fd, .. } => Some(old_fd),
+      #[cfg(target_os = "linux")]
+      Op::Tee { fd_in, .. }
+      | Op::Splice { fd_in, .. }
+      | Op::AcceptDirect { fd: fd_in, .. } => Some(fd_in),
+      #[cfg(target_os = "linux")]
+      Op::EpollCtl { epfd, .. } => Some(epfd),
+      #[cfg(target_os = "linux")]
+      Op::FGetXattr { fd, .. } | Op::FSetXattr { fd, .. } => Some(fd),
+      _ => None,
+    }
+  }
+
+  /// The fixed file table whose slots this op uses, if any.
+  #[cfg(target_os = "linux")]
+  pub(crate) fn fixed_table(&self) -> Option<&Arc<FixedTable>> {
+    match self {
+      Op::AcceptDirect { table, .. }
+      | Op::RecvFixed { table, .. }
+      | Op::SendFixed { table, .. }
+      | Op::InstallFixed { table, .. }
+      | Op::CloseFixed { table, .. } => Some(table),
+      _ => None,
+    }
+  }
 }
 
 // SAFETY: Op contains raw pointers but they point to data owned by ErasedBuffer
@@ -2,8 +2,6 @@ use std::task::Waker;
 
 use crate::typed_op::TypedOp;
 
-use super::Registration;
-
 // Option's is for ownership rules.
 pub(crate) enum Notifier {
   Waker(Option<Waker>),
@@ -23,29 +21,28 @@ impl Notifier {
   {
     Self::Callback(OpCallback::new::<T, F>(callback, typed_op))
   }
+  /// Replaces the stored waker, the latest one is the one that gets woken.
+  ///
+  /// Combinators like `select!` poll the same future with different wakers,
+  /// and only the most recent one is guaranteed to poll it again.
   pub fn set_waker(&mut self, waker: Waker) -> bool {
     match self {
-      Self::Waker(old) => {
-        let _ = old.replace(waker);
+      Self::Waker(slot) => {
+        match slot {
+          Some(old) if old.will_wake(&waker) => {}
+          _ => *slot = Some(waker),
+        }
         true
       }
       Self::Callback(_) => false,
     }
   }
-  pub fn call(self, reg: &mut Registration) {
-    match self {
-      Self::Waker(Some(waker)) => waker.wake(),
-      Self::Waker(None) => {}
-      Self::Callback(call) => call.call(reg),
-    }
-  }
 }
 
 pub(crate) struct OpCallback {
   callback: *const (),
   typed_op: *const (),
-  call_callback_fn:
-    for<'a> fn(*const (), *const (), isize, &'a mut Registration),
+  call_callback_fn: fn(*const (), *const (), isize),
 }
 
 impl Drop for OpCallback {
@@ -104,18 +101,14 @@ impl OpCallback {
     }
   }
 
-  pub fn call(self, reg: &mut Registration) {
-    let res = reg
-      .try_take_result()
-      .expect("Result should be available when callback is called");
-    (self.call_callback_fn)(self.callback, self.typed_op, res, reg);
+  pub fn call(self, res: isize) {
+    (self.call_callback_fn)(self.callback, self.typed_op, res);
   }
 
   fn call_callback<T, F>(
     callback_ptr: *const (),
     typed_op_ptr: *const (),
     res: isize,
-    _reg: &mut Registration,
   ) where
     T: TypedOp,
     F: FnOnce(T::Result),
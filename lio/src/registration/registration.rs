@@ -1,4 +1,4 @@
-use std::mem;
+use std::{collections::VecDeque, mem};
 
 pub mod notifier;
 // mod stored;
@@ -15,10 +15,21 @@ pub struct RegistrationInner {
   pub(crate) notifier: Notifier,
 }
 
+/// Results of a multishot op not yet taken by its stream.
+pub struct Shots {
+  results: VecDeque<isize>,
+  waker: Option<Waker>,
+  /// The op completed for the last time.
+  finished: bool,
+  /// The stream was dropped, results are discarded.
+  abandoned: bool,
+}
+
 // NOTE: OpRegistration should **NEVER** impl Sync.
 pub enum Registration {
   Pending(RegistrationInner),
   Done(Option<isize>),
+  Multishot(Shots),
 }
 
 impl Registration {
@@ -53,9 +64,19 @@ impl Registration {
     })
   }
 
+  pub fn new_multishot(waker: Waker) -> Self {
+    Self::Multishot(Shots {
+      results: VecDeque::new(),
+      waker: Some(waker),
+      finished: false,
+      abandoned: false,
+    })
+  }
+
   /// Sets the waker, replacing any existing waker
   pub fn set_waker(&mut self, waker: Waker) {
     match self {
+      Self::Multishot(shots) => shots.waker = Some(waker),
       Self::Done(ret) => {
         assert!(ret.is_some());
         waker.wake();
@@ -66,28 +87,99 @@ impl Registration {
     };
   }
 
-  // TODO: Way to remove
-  pub fn set_done(&mut self, res: isize) {
+  /// Marks the registration as done with `res`.
+  ///
+  /// A waker is woken right away. A callback is handed back instead of being
+  /// run, so the driver can call it after releasing its own state: callbacks
+  /// may schedule new operations.
+  pub(crate) fn set_done(&mut self, res: isize) -> Option<(OpCallback, isize)> {
+    if let Self::Multishot(shots) = self {
+      shots.finished = true;
+      shots.push(res);
+      return None;
+    }
     match mem::replace(self, Self::Done(Some(res))) {
-      Self::Pending(RegistrationInner { notifier }) => {
-        notifier.call(self);
-      }
-      Self::Done { .. } => {
+      Self::Pending(RegistrationInner { notifier }) => match notifier {
+        Notifier::Waker(waker) => {
+          if let Some(waker) = waker {
+            waker.wake();
+          }
+          None
+        }
+        Notifier::Callback(callback) => {
+          // The callback consumes the result.
+          *self = Self::Done(None);
+          Some((callback, res))
+        }
+      },
+      Self::Done { .. } | Self::Multishot(_) => {
         panic!("what");
       }
     }
   }
 
+  /// Queues an intermediate result of a multishot op and wakes its stream.
+  pub(crate) fn push_shot(&mut self, res: isize) {
+    let Self::Multishot(shots) = self else {
+      panic!("lio bookkeeping bug: more results for a oneshot op");
+    };
+    shots.push(res);
+  }
+
+  /// Takes the next result of a multishot op.
+  ///
+  /// Returns `Some(None)` once the op finished and every result was taken,
+  /// `None` while more may still arrive.
+  pub(crate) fn next_shot(&mut self) -> Option<Option<isize>> {
+    let Self::Multishot(shots) = self else {
+      panic!("lio bookkeeping bug: not a multishot op");
+    };
+    match shots.results.pop_front() {
+      Some(res) => Some(Some(res)),
+      None if shots.finished => Some(None),
+      None => None,
+    }
+  }
+
+  /// Marks a multishot op's stream as gone. Returns whether the op already
+  /// finished, so its entry can be removed right away.
+  pub(crate) fn abandon(&mut self) -> bool {
+    let Self::Multishot(shots) = self else {
+      panic!("lio bookkeeping bug: not a multishot op");
+    };
+    shots.abandoned = true;
+    shots.results.clear();
+    shots.waker = None;
+    shots.finished
+  }
+
   pub fn try_take_result(&mut self) -> Option<isize> {
     match self {
       Self::Done(t) => Some(t.take().expect("Already taken")),
-      Self::Pending(_) => None,
+      Self::Pending(_) | Self::Multishot(_) => None,
     }
   }
 
   /// Returns true if this registration has completed and its result was consumed.
-  /// This happens for callbacks which consume the result immediately in set_done.
+  /// This happens for callbacks, which take the result in set_done, and for
+  /// multishot ops whose stream is gone.
   pub fn result_consumed(&self) -> bool {
-    matches!(self, Self::Done(None))
+    match self {
+      Self::Done(res) => res.is_none(),
+      Self::Multishot(shots) => shots.abandoned && shots.finished,
+      Self::Pending(_) => false,
+    }
+  }
+}
+
+impl Shots {
+  fn push(&mut self, res: isize) {
+    if self.abandoned {
+      return;
+    }
+    self.results.push_back(res);
+    if let Some(waker) = self.waker.take() {
+      waker.wake();
+    }
   }
 }
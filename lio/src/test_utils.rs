@@ -3,12 +3,61 @@
 //! This module provides helper functions for creating sockets using lio's
 //! async operations. Only available when building tests.
 
+#[cfg(any(test, feature = "test-utils"))]
+use std::cell::RefCell;
+
+#[cfg(unix)]
 use crate::api;
+#[cfg(unix)]
 use crate::api::{io::Io, ops};
+#[cfg(any(test, feature = "test-utils"))]
+use crate::op::Op;
+
+#[cfg(any(test, feature = "test-utils"))]
+type Predicate = Box<dyn Fn(&Op) -> bool>;
+
+#[cfg(any(test, feature = "test-utils"))]
+thread_local! {
+  static INJECTED_ERRORS: RefCell<Vec<(Predicate, i32)>> =
+    const { RefCell::new(Vec::new()) };
+}
+
+/// Makes the next operation scheduled on this thread that matches `predicate`
+/// fail with `errno`, without reaching the kernel.
+///
+/// Each call fails one operation. The failure is reported on the next run of
+/// the event loop, like any other completion, so error handling can be tested
+/// without provoking the condition on a real socket or file. Only built
+/// with the `test-utils` feature.
+///
+/// # Example
+///
+/// ```
+/// use lio::{op::Op, test_utils::inject_error};
+///
+/// inject_error(|op| matches!(op, Op::Recv { .. }), libc::ECONNRESET);
+/// ```
+#[cfg(any(test, feature = "test-utils"))]
+#[doc(hidden)]
+pub fn inject_error(predicate: impl Fn(&Op) -> bool + 'static, errno: i32) {
+  INJECTED_ERRORS
+    .with(|errors| errors.borrow_mut().push((Box::new(predicate), errno)));
+}
+
+/// Removes and returns the errno injected for `op`, if any.
+#[cfg(any(test, feature = "test-utils"))]
+pub(crate) fn take_injected_error(op: &Op) -> Option<i32> {
+  INJECTED_ERRORS.with(|errors| {
+    let mut errors = errors.borrow_mut();
+    let index = errors.iter().position(|(predicate, _)| predicate(op))?;
+    Some(errors.remove(index).1)
+  })
+}
 
 /// Creates a Unix stream socket using lio operations (blocking).
 ///
 /// Returns the Resource which must be closed by the caller using `lio::close()`.
+#[cfg(unix)]
 #[doc(hidden)]
 pub fn unix_stream_socket() -> Io<ops::Socket> {
   api::socket(libc::AF_UNIX, libc::SOCK_STREAM, 0)
@@ -17,6 +66,7 @@ pub fn unix_stream_socket() -> Io<ops::Socket> {
 /// Creates a Unix datagram socket using lio operations (blocking).
 ///
 /// Returns the Resource which must be closed by the caller using `lio::close()`.
+#[cfg(unix)]
 #[doc(hidden)]
 pub fn unix_dgram_socket() -> Io<ops::Socket> {
   api::socket(libc::AF_UNIX, libc::SOCK_DGRAM, 0)
@@ -25,6 +75,7 @@ pub fn unix_dgram_socket() -> Io<ops::Socket> {
 /// Creates a TCP IPv4 socket using lio operations.
 ///
 /// Returns a Io that can be used with `.send()`, `.when_done()`, `.blocking()`, etc.
+#[cfg(unix)]
 #[doc(hidden)]
 pub fn tcp_socket() -> Io<ops::Socket> {
   api::socket(libc::AF_INET, libc::SOCK_STREAM, 0)
@@ -33,6 +84,7 @@ pub fn tcp_socket() -> Io<ops::Socket> {
 /// Creates a TCP IPv6 socket using lio operations.
 ///
 /// Returns a Io that can be used with `.send()`, `.when_done()`, `.blocking()`, etc.
+#[cfg(unix)]
 #[doc(hidden)]
 pub fn tcp6_socket() -> Io<ops::Socket> {
   api::socket(libc::AF_INET6, libc::SOCK_STREAM, libc::IPPROTO_TCP)
@@ -41,6 +93,7 @@ pub fn tcp6_socket() -> Io<ops::Socket> {
 /// Creates a UDP IPv4 socket using lio operations.
 ///
 /// Returns a Io that can be used with `.send()`, `.when_done()`, `.blocking()`, etc.
+#[cfg(unix)]
 #[doc(hidden)]
 pub fn udp_socket() -> Io<ops::Socket> {
   api::socket(libc::AF_INET, libc::SOCK_DGRAM, libc::IPPROTO_UDP)
@@ -49,6 +102,7 @@ pub fn udp_socket() -> Io<ops::Socket> {
 /// Creates a UDP IPv6 socket using lio operations.
 ///
 /// Returns a Io that can be used with `.send()`, `.when_done()`, `.blocking()`, etc.
+#[cfg(unix)]
 #[doc(hidden)]
 pub fn udp6_socket() -> Io<ops::Socket> {
   api::socket(libc::AF_INET6, libc::SOCK_DGRAM, libc::IPPROTO_UDP)
@@ -4,6 +4,8 @@
 //! The [`TypedOp`] trait maintains compile-time type safety while allowing operations
 //! to be executed through the unified Op enum interface.
 
+use std::time::Duration;
+
 use crate::op::Op;
 
 /// Core trait for type-safe operations that can be converted to Op enum.
@@ -57,6 +59,96 @@ pub trait TypedOp: Send + Sync + 'static {
   /// This method assumes that `self` contains the correct data that was
   /// used to create the Op, ensuring type-safe result extraction.
   fn extract_result(self, op_result: isize) -> Self::Result;
+
+  /// How long the operation may stay in flight before it completes with
+  /// `-ETIMEDOUT` instead.
+  ///
+  /// Defaults to no limit.
+  fn timeout(&self) -> Option<Duration> {
+    None
+  }
+
+  /// Whether the operation must wait until every write to its resource that
+  /// is still in flight when it is scheduled has completed.
+  ///
+  /// Defaults to `false`.
+  fn after_writes(&self) -> bool {
+    false
+  }
+}
+
+/// An operation that completes many times from a single submission, like a
+/// multishot poll.
+///
+/// Consumed through [`MultishotStream`](crate::api::multishot::MultishotStream),
+/// each completion becomes one item.
+#[allow(clippy::wrong_self_convention)]
+pub trait MultishotOp: Send + Sync + 'static {
+  /// What each completion produces.
+  type Item: Send;
+
+  /// Convert this operation into the type-erased Op enum.
+  fn into_op(&mut self) -> Op;
+
+  /// Extract one item from a raw result.
+  fn extract_item(&self, op_result: isize) -> Self::Item;
+
+  /// Whether to submit the operation again when the backend ends it with
+  /// `op_result` as the last completion, instead of ending the stream.
+  ///
+  /// Defaults to `false`.
+  fn resubmit(&self, op_result: isize) -> bool {
+    let _ = op_result;
+    false
+  }
+}
+
+/// An operation that can run to completion with nobody waiting for it, see
+/// [`Io::detach`](crate::api::io::Io::detach).
+///
+/// Implemented for operations that own everything they touch and whose
+/// result is safe to drop unseen, like [`Close`](crate::api::ops::Close) or
+/// a [`Write`](crate::api::ops::Write) of an owned buffer. Operations that
+/// hand data back, such as reads into a buffer, don't implement it: detaching
+/// them would throw that data away.
+pub trait DetachSafe: TypedOp {}
+
+/// A user-defined operation the backends can execute directly.
+///
+/// [`Op`] is a closed enum, so an opcode lio doesn't know about has no
+/// variant of its own. Implementing this trait and submitting the value
+/// through [`api::custom`](crate::api::custom) routes it through
+/// [`Op::Custom`](crate::op::Op::Custom) instead, and the result comes back
+/// through [`Io`](crate::api::io::Io) like any built-in op.
+///
+/// Each backend picks one of the two methods:
+///
+/// - io_uring pushes the entry from [`create_entry`](Self::create_entry).
+/// - The poller and IOCP backends call [`run_blocking`](Self::run_blocking)
+///   on the event loop thread as soon as the op is pushed.
+///
+/// # Safety
+///
+/// lio cannot check anything about a custom op, so the implementor promises:
+///
+/// - The entry returned by `create_entry` is a valid submission for the
+///   running kernel. Its `user_data` is overwritten by lio, and it must not
+///   set `IOSQE_CQE_SKIP_SUCCESS` or be a multishot request, because lio
+///   expects exactly one completion per op.
+/// - Every pointer in the entry points into `self` or into memory that
+///   outlives `self`. The op is kept at a stable address until its
+///   completion is reaped, and dropped afterwards, never before.
+/// - Descriptors in the entry stay open until the op completes.
+/// - `run_blocking` returns the syscall result on success and `-errno` on
+///   failure, and it doesn't block for long, since it stalls every other op
+///   on the same [`Lio`](crate::Lio).
+pub unsafe trait RawOperation: Send + Sync + 'static {
+  /// Build the io_uring submission for this op.
+  #[cfg(target_os = "linux")]
+  fn create_entry(&self) -> lio_uring::Entry;
+
+  /// Run the op synchronously, returning the result or `-errno`.
+  fn run_blocking(&self) -> isize;
 }
 
 pub struct ResultNotMatching;
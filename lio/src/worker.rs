@@ -1,7 +1,73 @@
-//! Worker module - deprecated in thread-per-core design.
+//! Thread pool for [`spawn_blocking`].
 //!
-//! In the new thread-per-core model, there are no worker threads.
-//! Each thread owns its own `Lio` instance directly.
-//! This module is kept as a stub for backwards compatibility during migration.
+//! The driver itself stays thread-per-core, each thread owns its own `Lio`.
+//! These threads only run user closures that would otherwise stall an event
+//! loop, and hand results back through an ordinary I/O completion.
 
-// TODO: Remove this module once migration is complete
+use std::{num::NonZeroUsize, sync::OnceLock, thread};
+
+use crossbeam_channel::Sender;
+
+use crate::api::{io::Io, ops::SpawnBlocking};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static POOL: OnceLock<Sender<Job>> = OnceLock::new();
+
+/// Runs `job` on the shared blocking pool, starting the pool on first use.
+///
+/// The pool has one thread per available CPU and lives for the rest of the
+/// process.
+pub(crate) fn execute(job: Job) {
+  let pool = POOL.get_or_init(|| {
+    let (tx, rx) = crossbeam_channel::unbounded::<Job>();
+    let threads = thread::available_parallelism().map_or(4, NonZeroUsize::get);
+    for i in 0..threads {
+      let rx = rx.clone();
+      thread::Builder::new()
+        .name(format!("lio-blocking-{i}"))
+        .spawn(move || {
+          for job in rx {
+            job();
+          }
+        })
+        .expect("lio: failed to spawn blocking pool thread");
+    }
+    tx
+  });
+  pool.send(job).expect("lio: blocking pool threads exited");
+}
+
+/// Runs `f` on a shared thread pool and completes with its return value.
+///
+/// Use this to offload CPU-bound or otherwise blocking work without stalling
+/// the thread driving [`Lio`](crate::Lio). The returned [`Io`] is consumed
+/// like any I/O operation: awaited, given a callback, or sent to a channel.
+///
+/// `f` starts running right away, even if the returned [`Io`] is never
+/// scheduled. If `f` panics, the panic is resumed where the result is
+/// consumed.
+///
+/// # Errors
+///
+/// Fails only if the completion channel to the driver can't be created.
+///
+/// # Example
+///
+/// ```no_run
+/// use lio::Lio;
+///
+/// let lio = Lio::new(64).unwrap();
+/// let receiver = lio::spawn_blocking(|| (0..1_000u64).sum::<u64>())
+///   .with_lio(&lio)
+///   .send();
+/// lio.run().unwrap();
+/// assert_eq!(receiver.recv().unwrap(), 499_500);
+/// ```
+pub fn spawn_blocking<F, T>(f: F) -> Io<SpawnBlocking<T>>
+where
+  F: FnOnce() -> T + Send + 'static,
+  T: Send + Sync + 'static,
+{
+  Io::from_op(SpawnBlocking::new(f))
+}
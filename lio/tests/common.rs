@@ -3,7 +3,7 @@ use std::{
 };
 
 use lio::{Lio, api, api::io::Receiver, api::resource::Resource};
-use std::os::fd::{AsFd, AsRawFd};
+use std::os::fd::{AsFd, AsRawFd, FromRawFd};
 
 /// Utility function to create a unique temporary file path for proptest tests.
 /// Returns a CString path that includes the process ID and a unique value to avoid conflicts.
@@ -117,8 +117,43 @@ impl Drop for TempFile {
   }
 }
 
+/// Opens `file` for reading and writing, creating it if needed.
+#[allow(dead_code)]
+pub fn create(file: &TempFile) -> Resource {
+  let fd = unsafe {
+    libc::open(
+      file.path.as_ptr(),
+      libc::O_CREAT | libc::O_RDWR | libc::O_CLOEXEC,
+      0o644,
+    )
+  };
+  assert!(fd >= 0, "Failed to create test file");
+  unsafe { Resource::from_raw_fd(fd) }
+}
+
+/// A pipe as `(read_end, write_end)`.
+#[allow(dead_code)]
+pub fn pipe() -> (Resource, Resource) {
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) }
+}
+
+/// The read end of a pipe holding `data`, with the write end closed.
+#[allow(dead_code)]
+pub fn filled_pipe(data: &[u8]) -> Resource {
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  let written =
+    unsafe { libc::write(fds[1], data.as_ptr().cast(), data.len()) };
+  assert_eq!(written, data.len() as isize);
+  unsafe { libc::close(fds[1]) };
+  unsafe { Resource::from_raw_fd(fds[0]) }
+}
+
 /// Poll the lio event loop until a result is received on the channel.
 /// Blocks in kqueue/epoll for up to 5ms per iteration — no busy-spin, no attempt cap.
+#[allow(dead_code)]
 pub fn poll_until_recv<T>(lio: &mut Lio, receiver: &mpsc::Receiver<T>) -> T {
   loop {
     lio.run_timeout(Duration::from_millis(5)).unwrap();
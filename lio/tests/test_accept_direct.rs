@@ -0,0 +1,118 @@
+#![cfg(target_os = "linux")]
+//! Connections accepted into fixed file slots.
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{
+  Lio,
+  api::resource::AsResource,
+  net::{DirectTcpSocket, TcpListener},
+};
+use std::{
+  io::{Read, Write},
+  net::{SocketAddr, TcpStream},
+  os::fd::AsRawFd,
+  sync::mpsc,
+  time::Duration,
+};
+
+/// Accepts `client` into a fixed slot, `None` if the backend can't.
+fn accept(
+  lio: &mut Lio,
+  listener: &TcpListener,
+) -> Option<(DirectTcpSocket, SocketAddr)> {
+  let (sender, receiver) = mpsc::channel();
+  listener.accept_direct(lio).send_with(sender);
+  match poll_until_recv(lio, &receiver) {
+    Ok(accepted) => Some(accepted),
+    // Not io_uring, or a kernel without direct descriptors.
+    Err(err)
+      if matches!(
+        err.raw_os_error(),
+        Some(libc::EOPNOTSUPP | libc::EINVAL)
+      ) =>
+    {
+      None
+    }
+    Err(err) => panic!("accept_direct failed: {err}"),
+  }
+}
+
+#[test]
+fn test_accept_direct_recv_send() {
+  let mut lio = Lio::new(64).unwrap();
+  let listener = TcpListener::bind_sync("127.0.0.1:0").unwrap();
+  let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+  let Some((socket, peer)) = accept(&mut lio, &listener) else { return };
+  assert_eq!(peer, client.local_addr().unwrap());
+
+  client.write_all(b"ping").unwrap();
+  let (sender, receiver) = mpsc::channel();
+  socket.recv(vec![0u8; 16]).with_lio(&lio).send_with(sender);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(received.expect("recv failed"), 4);
+  assert_eq!(buf, b"ping");
+
+  let (sender, receiver) = mpsc::channel();
+  socket.send(b"pong".to_vec()).with_lio(&lio).send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), 4);
+  let mut reply = [0u8; 4];
+  client.read_exact(&mut reply).unwrap();
+  assert_eq!(&reply, b"pong");
+
+  // FIXED_FD_INSTALL needs Linux 6.8, older kernels reject the opcode.
+  let (sender, receiver) = mpsc::channel();
+  socket.install_fd().with_lio(&lio).send_with(sender);
+  match poll_until_recv(&mut lio, &receiver) {
+    Ok(installed) => {
+      assert!(installed.as_resource().as_raw_fd() >= 0);
+      let (sender, receiver) = mpsc::channel();
+      installed.send(b"fd".to_vec()).with_lio(&lio).send_with(sender);
+      let (sent, _) = poll_until_recv(&mut lio, &receiver);
+      assert_eq!(sent.expect("send over installed fd failed"), 2);
+      client.read_exact(&mut reply[..2]).unwrap();
+      assert_eq!(&reply[..2], b"fd");
+    }
+    Err(err) => {
+      assert_eq!(err.raw_os_error(), Some(libc::EINVAL), "{err}");
+    }
+  }
+
+  let (sender, receiver) = mpsc::channel();
+  socket.close().with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("close_fixed failed");
+}
+
+#[test]
+fn test_dropping_direct_socket_frees_slot() {
+  let mut lio = Lio::new(64).unwrap();
+  let listener = TcpListener::bind_sync("127.0.0.1:0").unwrap();
+  let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+  let Some((socket, _)) = accept(&mut lio, &listener) else { return };
+  // Dropped off the driver thread, the next run closes the slot.
+  std::thread::spawn(move || drop(socket)).join().unwrap();
+  lio.try_run().unwrap();
+
+  // The slot held the only reference to the connection.
+  client.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+  let mut buf = [0u8; 1];
+  assert_eq!(client.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn test_direct_socket_rejected_by_other_lio() {
+  let mut lio = Lio::new(64).unwrap();
+  let mut other = Lio::new(64).unwrap();
+  let listener = TcpListener::bind_sync("127.0.0.1:0").unwrap();
+  let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+  let Some((socket, _)) = accept(&mut lio, &listener) else { return };
+  let (sender, receiver) = mpsc::channel();
+  socket.send(b"x".to_vec()).with_lio(&other).send_with(sender);
+  let (sent, _) = poll_until_recv(&mut other, &receiver);
+  assert_eq!(sent.unwrap_err().raw_os_error(), Some(libc::EBADF));
+}
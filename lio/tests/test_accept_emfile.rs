@@ -0,0 +1,75 @@
+//! Accepting while the process is out of file descriptors.
+//!
+//! Lowers RLIMIT_NOFILE for the whole process, so it lives in its own test
+//! binary.
+
+mod common;
+
+use common::{get_bound_addr, poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api};
+use std::io::Read;
+use std::net::TcpStream;
+use std::os::fd::{FromRawFd, OwnedFd};
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+/// Opens descriptors until the process hits EMFILE.
+fn exhaust_fds() -> Vec<OwnedFd> {
+  let mut fds = Vec::new();
+  loop {
+    let fd = unsafe { libc::open(c"/dev/null".as_ptr(), libc::O_RDONLY) };
+    if fd < 0 {
+      let err = std::io::Error::last_os_error();
+      assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+      return fds;
+    }
+    fds.push(unsafe { OwnedFd::from_raw_fd(fd) });
+  }
+}
+
+#[test]
+fn test_accept_recovers_from_emfile() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let addr = get_bound_addr(&pair.server_sock);
+
+  let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+  assert_eq!(unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) }, 0);
+  let lowered = libc::rlimit { rlim_cur: limit.rlim_cur.min(512), ..limit };
+  assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &lowered) }, 0);
+
+  // Queue a connection, then leave no descriptor to accept it into.
+  let mut shed = TcpStream::connect(addr).unwrap();
+  shed.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+  let (sender, receiver) = mpsc::channel();
+  let accept = api::accept(&pair.server_sock).with_lio(&lio);
+  let fillers = exhaust_fds();
+  accept.send_with(sender.clone());
+
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+
+  // The driver accepts the pending connection and closes it to clear the
+  // backlog.
+  for _ in 0..5 {
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+  }
+  let mut buf = [0u8; 1];
+  match shed.read(&mut buf) {
+    Ok(n) => assert_eq!(n, 0, "shed connection should see EOF"),
+    Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::ConnectionReset),
+  }
+
+  // With the backlog empty, the next accept waits instead of failing again.
+  api::accept(&pair.server_sock).with_lio(&lio).send_with(sender);
+  for _ in 0..5 {
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+    assert!(matches!(receiver.try_recv(), Err(TryRecvError::Empty)));
+  }
+
+  drop(fillers);
+  let _client = TcpStream::connect(addr).unwrap();
+  poll_until_recv(&mut lio, &receiver).expect("accept should recover");
+
+  assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) }, 0);
+}
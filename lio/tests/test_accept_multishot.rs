@@ -0,0 +1,57 @@
+#![cfg(unix)]
+//! Accepting many connections from one multishot submission.
+
+use futures_util::StreamExt;
+use lio::{Lio, net::TcpListener};
+use std::{
+  future::Future,
+  net::TcpStream,
+  pin::pin,
+  task::{Context, Poll, Waker},
+  time::{Duration, Instant},
+};
+
+/// Runs `lio` until `future` resolves.
+fn block_on<F: Future>(lio: &Lio, future: F) -> F::Output {
+  let mut future = pin!(future);
+  let mut cx = Context::from_waker(Waker::noop());
+  loop {
+    if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+      return out;
+    }
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+  }
+}
+
+#[test]
+fn test_accept_multishot_yields_each_connection() {
+  let lio = Lio::new(64).unwrap();
+  let listener = TcpListener::bind_sync("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  let mut conns = listener.accept_multishot().with_lio(&lio).into_stream();
+  // Submits the accept.
+  let mut cx = Context::from_waker(Waker::noop());
+  assert!(conns.poll_next_unpin(&mut cx).is_pending());
+
+  let mut clients = Vec::new();
+  for _ in 0..3 {
+    let client = TcpStream::connect(addr).unwrap();
+    let (_socket, peer) = block_on(&lio, conns.next())
+      .expect("stream ended early")
+      .expect("accept failed");
+    assert_eq!(peer, client.local_addr().unwrap());
+    clients.push(client);
+  }
+
+  // One submission served every connection.
+  assert_eq!(lio.in_flight(), 1);
+
+  drop(conns);
+
+  let start = Instant::now();
+  while lio.in_flight() > 0 {
+    assert!(start.elapsed() < Duration::from_secs(5), "op wasn't cancelled");
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+  }
+}
@@ -0,0 +1,46 @@
+#![cfg(target_os = "linux")]
+
+use std::mem;
+
+fn affinity() -> libc::cpu_set_t {
+  let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+  let ret = unsafe {
+    libc::sched_getaffinity(0, mem::size_of::<libc::cpu_set_t>(), &mut set)
+  };
+  assert_eq!(ret, 0, "{}", std::io::Error::last_os_error());
+  set
+}
+
+#[test]
+fn test_init_with_affinity_pins_thread() {
+  // Pick a CPU this process may actually run on.
+  let allowed = affinity();
+  let cpu = (0..libc::CPU_SETSIZE as usize)
+    .rev()
+    .find(|&cpu| unsafe { libc::CPU_ISSET(cpu, &allowed) })
+    .expect("no CPU in affinity mask");
+
+  std::thread::spawn(move || {
+    lio::init_with_affinity(64, cpu).unwrap();
+
+    let set = affinity();
+    assert_eq!(unsafe { libc::CPU_COUNT(&set) }, 1);
+    assert!(unsafe { libc::CPU_ISSET(cpu, &set) });
+
+    // The Lio was installed for this thread.
+    assert!(lio::uninstall_global().is_some());
+  })
+  .join()
+  .unwrap();
+}
+
+#[test]
+fn test_init_with_affinity_rejects_bad_cpu() {
+  std::thread::spawn(|| {
+    let err = lio::init_with_affinity(64, libc::CPU_SETSIZE as usize);
+    assert_eq!(err.unwrap_err().raw_os_error(), Some(libc::EINVAL));
+    assert!(lio::uninstall_global().is_none(), "nothing should be installed");
+  })
+  .join()
+  .unwrap();
+}
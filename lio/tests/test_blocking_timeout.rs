@@ -0,0 +1,47 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api};
+use std::{
+  sync::mpsc,
+  time::{Duration, Instant},
+};
+
+#[test]
+fn test_blocking_timeout_returns_result() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::send(&pair.client_sock, b"hello".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).0.expect("send failed");
+
+  let (result, buf) = api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .blocking_timeout(Duration::from_secs(5))
+    .expect("recv timed out");
+  let n = result.expect("recv failed") as usize;
+  assert_eq!(&buf[..n], b"hello");
+}
+
+#[test]
+fn test_blocking_timeout_cancels_hung_op() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let start = Instant::now();
+  let result = api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .blocking_timeout(Duration::from_millis(50));
+  assert!(result.is_none());
+  assert!(start.elapsed() >= Duration::from_millis(50));
+
+  // The cancelled recv leaves the driver shortly after.
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while lio.in_flight() > 0 {
+    assert!(Instant::now() < deadline, "cancelled recv never completed");
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{
+  Lio, api,
+  api::resource::Resource,
+  buf::{BufLike, BufStore},
+};
+use std::{
+  io,
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+};
+
+fn register(lio: &mut Lio, pool: &BufStore) -> bool {
+  let (sender, receiver) = mpsc::channel();
+  pool.register_fixed().with_lio(lio).send_with(sender);
+  match poll_until_recv(lio, &receiver) {
+    Ok(()) => true,
+    Err(err) => {
+      assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+      false
+    }
+  }
+}
+
+#[test]
+fn test_register_fixed_read_into_lent_buf() {
+  let mut lio = Lio::new(64).unwrap();
+  let pool: &'static BufStore = Box::leak(Box::new(BufStore::with_capacity(4)));
+  let registered = register(&mut lio, pool);
+
+  let buf = pool.try_get().unwrap();
+  assert_eq!(buf.fixed_index().is_some(), registered);
+
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  let (read_end, write_end) =
+    unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) };
+  let n =
+    unsafe { libc::write(write_end.as_raw_fd(), b"fixed".as_ptr().cast(), 5) };
+  assert_eq!(n, 5);
+
+  let (sender, receiver) = mpsc::channel();
+  api::read(&read_end, buf).with_lio(&lio).send_with(sender);
+  let (read, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(read.expect("read failed"), 5);
+  assert_eq!(buf.as_ref(), b"fixed");
+}
+
+#[test]
+fn test_register_fixed_recv_into_lent_buf() {
+  let mut lio = Lio::new(64).unwrap();
+  let pool: &'static BufStore = Box::leak(Box::new(BufStore::with_capacity(4)));
+  register(&mut lio, pool);
+  let pair = setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::send(&pair.client_sock, b"over tcp".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).0.expect("send failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&pair.accepted_fd, pool.try_get().unwrap(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(received.expect("recv failed"), 8);
+  assert_eq!(buf.as_ref(), b"over tcp");
+}
+
+#[test]
+fn test_buffers_grown_after_registration_are_not_fixed() {
+  let mut lio = Lio::new(64).unwrap();
+  let pool: &'static BufStore =
+    Box::leak(Box::new(BufStore::with_growth(1, 2)));
+  let registered = register(&mut lio, pool);
+
+  let first = pool.try_get().unwrap();
+  let grown = pool.try_get().unwrap();
+  assert_eq!(first.fixed_index(), registered.then_some(0));
+  assert_eq!(grown.fixed_index(), None);
+}
+
+#[test]
+fn test_send_zc_from_fixed_buf() {
+  let mut lio = Lio::new(64).unwrap();
+  let pool: &'static BufStore = Box::leak(Box::new(BufStore::with_capacity(4)));
+  register(&mut lio, pool);
+  let pair = setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::send(&pair.client_sock, b"echo".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).0.expect("send failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&pair.accepted_fd, pool.try_get().unwrap(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(received.expect("recv failed"), 4);
+
+  // Straight back out of the same slot.
+  let (sender, receiver) = mpsc::channel();
+  api::send_zc(&pair.accepted_fd, buf, None).with_lio(&lio).send_with(sender);
+  let (sent, _buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send_zc failed"), 4);
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&pair.client_sock, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(received.expect("recv failed"), 4);
+  assert_eq!(&buf[..4], b"echo");
+}
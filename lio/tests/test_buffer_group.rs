@@ -0,0 +1,167 @@
+#![cfg(unix)]
+//! Receives into buffers the kernel picks from a provided group.
+
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use futures_util::StreamExt;
+use lio::{Lio, api::resource::Resource, buf::BufferGroup};
+use std::{
+  future::Future,
+  io,
+  os::fd::{AsRawFd, FromRawFd, OwnedFd},
+  pin::pin,
+  sync::mpsc,
+  task::{Context, Poll, Waker},
+  time::Duration,
+};
+
+fn provide(
+  lio: &mut Lio,
+  bufs: Vec<Vec<u8>>,
+  bgid: u16,
+) -> Option<BufferGroup> {
+  let (sender, receiver) = mpsc::channel();
+  BufferGroup::provide(bufs, bgid).with_lio(lio).send_with(sender);
+  match poll_until_recv(lio, &receiver) {
+    Ok(group) => Some(group),
+    Err(err) => {
+      assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+      None
+    }
+  }
+}
+
+fn send(fd: &impl AsRawFd, data: &[u8]) {
+  let sent =
+    unsafe { libc::send(fd.as_raw_fd(), data.as_ptr().cast(), data.len(), 0) };
+  assert_eq!(sent, data.len() as isize);
+}
+
+/// Runs `lio` until `future` resolves.
+fn block_on<F: Future>(lio: &Lio, future: F) -> F::Output {
+  let mut future = pin!(future);
+  let mut cx = Context::from_waker(Waker::noop());
+  loop {
+    if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+      return out;
+    }
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+  }
+}
+
+#[test]
+fn test_buffer_group_recv_reports_bid() {
+  let mut lio = Lio::new(64).unwrap();
+  let Some(group) = provide(&mut lio, vec![vec![0u8; 64]; 2], 7) else {
+    return;
+  };
+  assert_eq!(group.bgid(), 7);
+  assert_eq!(group.buf_len(), 64);
+  let pair = setup_tcp_pair(&mut lio);
+
+  send(&pair.client_sock, b"first");
+  let (sender, receiver) = mpsc::channel();
+  group.recv(&pair.accepted_fd, 0).with_lio(&lio).send_with(sender);
+  let first = poll_until_recv(&mut lio, &receiver).unwrap().unwrap();
+  assert_eq!(&first[..], b"first");
+
+  send(&pair.client_sock, b"second");
+  let (sender, receiver) = mpsc::channel();
+  group.recv(&pair.accepted_fd, 0).with_lio(&lio).send_with(sender);
+  let second = poll_until_recv(&mut lio, &receiver).unwrap().unwrap();
+  assert_eq!(&second[..], b"second");
+  assert_ne!(first.bid(), second.bid());
+
+  // Both buffers are handed out.
+  send(&pair.client_sock, b"third");
+  let (sender, receiver) = mpsc::channel();
+  group.recv(&pair.accepted_fd, 0).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::ENOBUFS));
+
+  let bid = first.bid();
+  let (sender, receiver) = mpsc::channel();
+  group.recycle(first).with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("recycle failed");
+
+  let (sender, receiver) = mpsc::channel();
+  group.recv(&pair.accepted_fd, 0).with_lio(&lio).send_with(sender);
+  let third = poll_until_recv(&mut lio, &receiver).unwrap().unwrap();
+  assert_eq!(&third[..], b"third");
+  assert_eq!(third.bid(), bid);
+}
+
+#[test]
+fn test_buffer_group_remove() {
+  let mut lio = Lio::new(64).unwrap();
+  let Some(group) = provide(&mut lio, vec![vec![0u8; 16]; 3], 8) else {
+    return;
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  group.remove().with_lio(&lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).expect("remove failed"), 3);
+}
+
+#[test]
+fn test_buffer_group_read_multi_streams_chunks() {
+  let mut lio = Lio::new(64).unwrap();
+  let Some(group) = provide(&mut lio, vec![vec![0u8; 16]; 4], 10) else {
+    return;
+  };
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  let (read_end, write_end) =
+    unsafe { (Resource::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+
+  let mut chunks = group.read_multi(&read_end).with_lio(&lio).into_stream();
+  for chunk in [&b"first"[..], b"second"] {
+    assert_eq!(
+      unsafe { libc::write(fds[1], chunk.as_ptr().cast(), chunk.len()) },
+      chunk.len() as isize
+    );
+    let buf = block_on(&lio, chunks.next())
+      .expect("stream ended early")
+      .expect("read failed")
+      .expect("no buffer picked");
+    assert_eq!(&buf[..], chunk);
+    let (sender, receiver) = mpsc::channel();
+    group.recycle(buf).with_lio(&lio).send_with(sender);
+    poll_until_recv(&mut lio, &receiver).expect("recycle failed");
+  }
+
+  // End of file is the last item.
+  drop(write_end);
+  let eof = block_on(&lio, chunks.next()).expect("stream ended early");
+  assert!(eof.expect("read failed").is_none());
+  assert!(block_on(&lio, chunks.next()).is_none());
+}
+
+#[test]
+#[should_panic(expected = "same length")]
+fn test_buffer_group_rejects_mixed_lengths() {
+  let _ = BufferGroup::provide(vec![vec![0u8; 16], vec![0u8; 8]], 9);
+}
+
+#[test]
+fn test_buffer_group_bgid_taken_until_removed() {
+  let mut lio = Lio::new(64).unwrap();
+  let Some(group) = provide(&mut lio, vec![vec![0u8; 64]; 2], 9) else {
+    return;
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  BufferGroup::provide(vec![vec![0u8; 64]; 2], 9)
+    .with_lio(&lio)
+    .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+
+  let (sender, receiver) = mpsc::channel();
+  group.remove().with_lio(&lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).unwrap(), 2);
+  drop(group);
+
+  assert!(provide(&mut lio, vec![vec![0u8; 64]; 2], 9).is_some());
+}
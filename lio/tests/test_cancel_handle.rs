@@ -0,0 +1,82 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{CancellationToken, Lio, api};
+use std::{io, sync::mpsc, time::Duration};
+
+#[test]
+fn test_cancel_handle_interrupts_pending_recv() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  let mut recv =
+    api::recv(&pair.accepted_fd, vec![0u8; 16], None).with_lio(&lio);
+  let handle = recv.cancel_handle();
+  recv.send_with(sender);
+
+  // The recv is idle.
+  lio.run_timeout(Duration::from_millis(20)).unwrap();
+  assert!(receiver.try_recv().is_err());
+
+  handle.clone().cancel();
+  assert!(handle.is_cancelled());
+  let (result, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+  // The buffer comes back untouched.
+  assert_eq!(buf.len(), 16);
+  assert_eq!(lio.in_flight(), 0);
+}
+
+#[test]
+fn test_cancel_handle_interrupts_recv_with_timeout() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  let mut recv = api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .timeout(Duration::from_secs(10))
+    .with_lio(&lio);
+  let handle = recv.cancel_handle();
+  recv.send_with(sender);
+  lio.run_timeout(Duration::from_millis(20)).unwrap();
+
+  // Cancelled before its timeout, so it was interrupted, not timed out.
+  handle.cancel();
+  let (result, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+}
+
+#[test]
+fn test_cancel_handle_leaves_token_and_other_ops_alone() {
+  let mut lio = Lio::new(64).unwrap();
+  let first = setup_tcp_pair(&mut lio);
+  let second = setup_tcp_pair(&mut lio);
+
+  let token = CancellationToken::new();
+  let (sender, receiver) = mpsc::channel();
+  let mut recv = api::recv(&first.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .with_cancel(&token);
+  let handle = recv.cancel_handle();
+  recv.send_with(sender);
+  let (other_tx, other_rx) = mpsc::channel();
+  api::recv(&second.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .with_cancel(&token)
+    .send_with(other_tx);
+  lio.run_timeout(Duration::from_millis(20)).unwrap();
+
+  // Only the op the handle belongs to is interrupted.
+  handle.cancel();
+  let (result, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+  lio.run_timeout(Duration::from_millis(20)).unwrap();
+  assert!(other_rx.try_recv().is_err());
+
+  // The shared token still cancels the rest.
+  assert!(!token.is_cancelled());
+  token.cancel();
+  let (result, _) = poll_until_recv(&mut lio, &other_rx);
+  assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{CancellationToken, Lio, api};
+use std::{sync::mpsc, time::Duration};
+
+#[test]
+fn test_cancel_token_aborts_all_attached_ops() {
+  let mut lio = Lio::new(64).unwrap();
+  let first = setup_tcp_pair(&mut lio);
+  let second = setup_tcp_pair(&mut lio);
+
+  let token = CancellationToken::new();
+  let (sender, receiver) = mpsc::channel();
+  for pair in [&first, &second] {
+    api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+      .with_lio(&lio)
+      .with_cancel(&token)
+      .send_with(sender.clone());
+  }
+
+  // Both reads are idle.
+  lio.run_timeout(Duration::from_millis(20)).unwrap();
+  assert!(receiver.try_recv().is_err());
+
+  token.cancel();
+  for _ in 0..2 {
+    let (result, _) = poll_until_recv(&mut lio, &receiver);
+    assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+  }
+  assert_eq!(lio.in_flight(), 0);
+}
+
+#[test]
+fn test_cancel_token_leaves_other_ops_alone() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let token = CancellationToken::new();
+  let (cancelled_tx, cancelled_rx) = mpsc::channel();
+  api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .with_cancel(&token)
+    .send_with(cancelled_tx);
+  token.cancel();
+  let (result, _) = poll_until_recv(&mut lio, &cancelled_rx);
+  assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+
+  // A recv without the token still gets the data.
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .send_with(sender);
+  api::send(&pair.client_sock, b"hello".to_vec(), None)
+    .with_lio(&lio)
+    .when_done(|(res, _)| assert_eq!(res.unwrap(), 5));
+  let (result, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(&buf[..result.unwrap() as usize], b"hello");
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{
+  Lio, api,
+  api::resource::FromResource,
+  net::{Socket, TcpSocket},
+};
+use std::{
+  future::Future,
+  os::fd::AsRawFd,
+  pin::pin,
+  sync::mpsc,
+  task::{Context, Poll, Waker},
+  time::Duration,
+};
+
+#[test]
+fn test_set_linger_roundtrip() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let socket = Socket::from_resource(pair.client_sock);
+
+  assert_eq!(socket.linger().unwrap(), None);
+  socket.set_linger(Some(Duration::from_secs(3))).unwrap();
+  assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(3)));
+  socket.set_linger(None).unwrap();
+  assert_eq!(socket.linger().unwrap(), None);
+}
+
+#[test]
+fn test_close_graceful_delivers_last_bytes() {
+  let mut lio = Lio::new(64).unwrap();
+  lio::install_global(lio.clone());
+  let pair = setup_tcp_pair(&mut lio);
+  let client = pair.client_sock.as_raw_fd();
+  let server = TcpSocket::from_resource(pair.accepted_fd);
+
+  // Data the server never reads: a plain close would answer it with a RST.
+  let (sender, receiver) = mpsc::channel();
+  api::send(&pair.client_sock, b"unread".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sender.clone());
+  poll_until_recv(&mut lio, &receiver).0.expect("client send failed");
+  server.send(b"last bytes".to_vec()).with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).0.expect("server send failed");
+
+  let mut close = pin!(server.close_graceful());
+  let mut cx = Context::from_waker(Waker::noop());
+
+  // The client gets everything the server sent, then EOF.
+  let mut received = Vec::new();
+  loop {
+    assert!(close.as_mut().poll(&mut cx).is_pending());
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+    let mut buf = [0u8; 64];
+    let n = unsafe {
+      libc::recv(client, buf.as_mut_ptr().cast(), buf.len(), libc::MSG_DONTWAIT)
+    };
+    match n {
+      0 => break,
+      n if n > 0 => received.extend_from_slice(&buf[..n as usize]),
+      _ => {
+        let err = std::io::Error::last_os_error();
+        assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock, "{err}");
+      }
+    }
+  }
+  assert_eq!(received, b"last bytes");
+
+  // Once the client closes its side too, the server finishes closing.
+  assert_eq!(unsafe { libc::shutdown(client, libc::SHUT_WR) }, 0);
+  let result = loop {
+    if let Poll::Ready(result) = close.as_mut().poll(&mut cx) {
+      break result;
+    }
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  };
+  result.expect("close_graceful failed");
+
+  lio::uninstall_global();
+}
@@ -0,0 +1,37 @@
+#![cfg(target_os = "linux")]
+//! Closes descriptors by number, so it lives in its own test binary where no
+//! other test can have descriptors in the range.
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api};
+use std::sync::mpsc;
+
+fn is_open(fd: i32) -> bool {
+  let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+  flags != -1
+}
+
+#[test]
+fn test_close_range_closes_inherited_fds() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let fds: Vec<i32> = (0..5)
+    .map(|_| unsafe { libc::open(c"/dev/null".as_ptr(), libc::O_RDONLY) })
+    .collect();
+  assert!(fds.iter().all(|&fd| fd > 2 && is_open(fd)));
+  let first = *fds.iter().min().unwrap() as u32;
+  let last = *fds.iter().max().unwrap() as u32;
+
+  let (sender, receiver) = mpsc::channel();
+  api::close_range(first, last, 0).with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("close_range failed");
+
+  for fd in fds {
+    assert!(!is_open(fd), "fd {fd} should be closed");
+  }
+  for stdio in 0..=2 {
+    assert!(is_open(stdio), "stdio fd {stdio} should stay open");
+  }
+}
@@ -0,0 +1,87 @@
+#![cfg(unix)]
+//! Connecting with a deadline.
+
+use lio::{Lio, net::TcpSocket};
+use std::{
+  future::IntoFuture,
+  io::Read,
+  net::TcpListener,
+  pin::pin,
+  task::{Context, Poll, Waker},
+  time::Duration,
+};
+
+/// Runs `lio` until `future` resolves.
+fn block_on<F: IntoFuture>(lio: &Lio, future: F) -> F::Output {
+  let mut future = pin!(future.into_future());
+  let mut cx = Context::from_waker(Waker::noop());
+  loop {
+    if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+      return out;
+    }
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+  }
+}
+
+#[test]
+fn test_connect_timeout_connects() {
+  let lio = Lio::new(64).unwrap();
+  lio::install_global(lio.clone());
+
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  let socket =
+    block_on(&lio, TcpSocket::connect_timeout(addr, Duration::from_secs(5)))
+      .expect("connect failed");
+  let (mut accepted, _) = listener.accept().unwrap();
+
+  let (sent, _) = block_on(&lio, socket.send(b"ping".to_vec()));
+  assert_eq!(sent.expect("send failed"), 4);
+  let mut buf = [0u8; 4];
+  accepted.read_exact(&mut buf).unwrap();
+  assert_eq!(&buf, b"ping");
+
+  lio::uninstall_global();
+}
+
+// Linux drops SYNs to a listener whose accept queue is full, so a connect to
+// it stays in flight until something gives up.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_connect_timeout_expires() {
+  use std::{io, net::TcpStream, os::fd::AsRawFd, time::Instant};
+
+  let lio = Lio::new(64).unwrap();
+  lio::install_global(lio.clone());
+
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+  // SAFETY: the fd belongs to a bound socket owned by `listener`.
+  assert_eq!(unsafe { libc::listen(listener.as_raw_fd(), 0) }, 0);
+
+  let mut held = Vec::new();
+  loop {
+    match TcpStream::connect_timeout(&addr, Duration::from_millis(100)) {
+      Ok(stream) => held.push(stream),
+      Err(e) if e.kind() == io::ErrorKind::TimedOut => break,
+      Err(e) => panic!("filling the accept queue failed: {e}"),
+    }
+    assert!(held.len() < 64, "accept queue never filled");
+  }
+
+  let start = Instant::now();
+  let err = block_on(
+    &lio,
+    TcpSocket::connect_timeout(addr, Duration::from_millis(100)),
+  )
+  .err()
+  .expect("connected to a full listener");
+  assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+  assert!(start.elapsed() < Duration::from_secs(1));
+
+  // The abandoned connect has been completed, nothing holds the socket.
+  assert_eq!(lio.in_flight(), 0);
+
+  lio::uninstall_global();
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api, typed_op::RawOperation};
+use std::sync::mpsc;
+
+/// A nop defined outside the crate.
+struct CustomNop {
+  tag: u32,
+}
+
+// SAFETY: A nop entry carries no pointers or descriptors, and running it
+// blocking does nothing.
+unsafe impl RawOperation for CustomNop {
+  #[cfg(target_os = "linux")]
+  fn create_entry(&self) -> lio::lio_uring::Entry {
+    lio::lio_uring::operation::Nop::new().build()
+  }
+
+  fn run_blocking(&self) -> isize {
+    0
+  }
+}
+
+/// Closes an invalid descriptor, failing with `EBADF`.
+struct CloseInvalid;
+
+// SAFETY: The entry only carries a descriptor number, which the kernel
+// rejects, and run_blocking makes no syscall at all.
+unsafe impl RawOperation for CloseInvalid {
+  #[cfg(target_os = "linux")]
+  fn create_entry(&self) -> lio::lio_uring::Entry {
+    lio::lio_uring::operation::Close::new(-1).build()
+  }
+
+  fn run_blocking(&self) -> isize {
+    -(libc::EBADF as isize)
+  }
+}
+
+#[test]
+fn test_custom_nop() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::custom(CustomNop { tag: 7 }).with_lio(&lio).send_with(sender);
+  let (res, op) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(res.expect("custom nop failed"), 0);
+  assert_eq!(op.tag, 7, "the op should be handed back");
+}
+
+#[test]
+fn test_custom_error() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::custom(CloseInvalid).with_lio(&lio).send_with(sender);
+  let (res, _) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{
+  Lio,
+  api::{self, ops::Interest, resource::Resource},
+};
+use std::{
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+  thread,
+  time::Duration,
+};
+
+#[test]
+fn test_debug_dump_lists_pending_timeout() {
+  let lio = Lio::new(64).unwrap();
+  assert!(lio.debug_dump().is_empty());
+
+  let _timeout = api::timeout(Duration::from_secs(60)).with_lio(&lio).send();
+  lio.try_run().unwrap();
+  thread::sleep(Duration::from_millis(20));
+
+  let ops = lio.debug_dump();
+  assert_eq!(ops.len(), 1);
+  assert_eq!(ops[0].opcode, "TIMEOUT");
+  assert_eq!(ops[0].fd, None);
+  assert!(ops[0].age >= Duration::from_millis(20));
+  assert!(ops[0].age < Duration::from_secs(60));
+}
+
+#[test]
+fn test_debug_dump_reports_fd_and_drops_completed() {
+  let mut lio = Lio::new(64).unwrap();
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  let (read, write) =
+    unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) };
+
+  let (sender, receiver) = mpsc::channel();
+  api::poll(&read, Interest::READABLE).with_lio(&lio).send_with(sender);
+  lio.try_run().unwrap();
+
+  let ops = lio.debug_dump();
+  assert_eq!(ops.len(), 1);
+  assert_eq!(ops[0].opcode, "POLL");
+  assert_eq!(ops[0].fd, Some(read.as_raw_fd()));
+
+  let n = unsafe { libc::write(write.as_raw_fd(), b"x".as_ptr().cast(), 1) };
+  assert_eq!(n, 1);
+  poll_until_recv(&mut lio, &receiver).expect("poll failed");
+
+  assert!(lio.debug_dump().is_empty());
+}
+
+#[test]
+fn test_debug_dump_global() {
+  let lio = Lio::new(64).unwrap();
+  lio::install_global(lio.clone());
+
+  let _nop = api::nop().send();
+  let ops = lio::debug_dump();
+  assert_eq!(ops.len(), 1);
+  assert_eq!(ops[0].opcode, "NOP");
+
+  lio::uninstall_global();
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{filled_pipe, poll_recv, poll_until_recv};
+use lio::{Lio, api};
+use std::sync::mpsc;
+
+#[test]
+fn test_deferred_batches_into_one_submit() {
+  let mut lio = Lio::new(64).unwrap();
+  let (a, b) = (filled_pipe(b"first"), filled_pipe(b"second"));
+
+  let before = lio.submit_count();
+  let (sender, receiver) = mpsc::channel();
+  lio
+    .deferred(|| {
+      api::read(&a, vec![0u8; 16]).with_lio(&lio).send_with(sender.clone());
+      api::read(&b, vec![0u8; 16]).with_lio(&lio).send_with(sender);
+    })
+    .unwrap();
+  assert_eq!(lio.submit_count(), before + 1);
+
+  let mut read: Vec<Vec<u8>> = (0..2)
+    .map(|_| {
+      let (n, buf) = poll_until_recv(&mut lio, &receiver);
+      buf[..n.expect("read failed") as usize].to_vec()
+    })
+    .collect();
+  read.sort();
+  assert_eq!(read, [b"first".to_vec(), b"second".to_vec()]);
+
+  // Both were already submitted, running the loop adds no submission.
+  assert_eq!(lio.submit_count(), before + 1);
+}
+
+#[test]
+fn test_deferred_global() {
+  let mut lio = Lio::new(64).unwrap();
+  lio::install_global(lio.clone());
+  let a = filled_pipe(b"global");
+
+  let before = lio.submit_count();
+  let mut receiver =
+    lio::deferred(|| api::read(&a, vec![0u8; 16]).send()).unwrap();
+  assert_eq!(lio.submit_count(), before + 1);
+
+  let (n, buf) = poll_recv(&mut lio, &mut receiver);
+  assert_eq!(&buf[..n.unwrap() as usize], b"global");
+
+  lio::uninstall_global();
+}
@@ -0,0 +1,33 @@
+use lio::{
+  Lio,
+  api::{self, resource::Resource},
+};
+use std::{
+  os::fd::{AsRawFd, FromRawFd},
+  time::{Duration, Instant},
+};
+
+#[test]
+fn test_detached_write_completes() {
+  let lio = Lio::new(64).unwrap();
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  let (read_end, write_end) =
+    unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) };
+
+  api::write(&write_end, b"detached".to_vec()).with_lio(&lio).detach();
+  api::nop().with_lio(&lio).detach();
+
+  // Nobody holds a handle, so wait for the driver to drain them.
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while lio.in_flight() > 0 {
+    assert!(Instant::now() < deadline, "detached ops never completed");
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+
+  let mut out = [0u8; 8];
+  let n =
+    unsafe { libc::read(read_end.as_raw_fd(), out.as_mut_ptr().cast(), 8) };
+  assert_eq!(n, 8);
+  assert_eq!(&out, b"detached");
+}
@@ -0,0 +1,68 @@
+//! Tests for [`Io::detach_with_id`] and [`Lio::await_id`].
+
+mod common;
+
+use common::filled_pipe;
+use lio::{Lio, api};
+use std::{
+  future::Future,
+  pin::pin,
+  task::{Context, Poll, Waker},
+  time::{Duration, Instant},
+};
+
+fn block_on<F: Future>(lio: &Lio, fut: F) -> F::Output {
+  let mut fut = pin!(fut);
+  let mut cx = Context::from_waker(Waker::noop());
+  let deadline = Instant::now() + Duration::from_secs(5);
+  loop {
+    if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+      return out;
+    }
+    assert!(Instant::now() < deadline, "future never completed");
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+}
+
+#[test]
+fn test_detached_read_claimed_by_id() {
+  let lio = Lio::new(64).unwrap();
+  let pipe = filled_pipe(b"later");
+
+  let id = api::read(&pipe, vec![0u8; 16])
+    .with_lio(&lio)
+    .detach_with_id()
+    .expect("detach failed");
+
+  // The read finishes with nobody waiting for it.
+  for _ in 0..10 {
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+
+  let (result, buf) = block_on(&lio, lio.await_id(id));
+  assert_eq!(result.expect("read failed"), 5);
+  assert_eq!(buf, b"later");
+}
+
+#[test]
+fn test_await_id_before_completion() {
+  let lio = Lio::new(64).unwrap();
+  let pipe = filled_pipe(b"soon");
+
+  let id = api::read(&pipe, vec![0u8; 16])
+    .with_lio(&lio)
+    .detach_with_id()
+    .expect("detach failed");
+  let (result, buf) = block_on(&lio, lio.await_id(id));
+  assert_eq!(result.expect("read failed"), 4);
+  assert_eq!(buf, b"soon");
+}
+
+#[test]
+fn test_detach_over_memory_cap_fails() {
+  let lio = Lio::new(64).unwrap();
+  lio.set_pending_memory_cap(Some(0));
+
+  let err = api::nop().with_lio(&lio).detach_with_id().unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::ENOBUFS));
+}
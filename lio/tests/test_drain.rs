@@ -0,0 +1,61 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api};
+use std::{sync::mpsc, time::Duration};
+
+#[test]
+fn test_drain_waits_for_in_flight_ops() {
+  let lio = Lio::new(64).unwrap();
+  let (sender, receiver) = mpsc::channel();
+  api::timeout(Duration::from_millis(30)).with_lio(&lio).send_with(sender);
+
+  assert_eq!(lio.drain(None), Ok(()));
+  let result =
+    receiver.try_recv().expect("drain returned before the op completed");
+  assert!(result.is_ok(), "timeout should expire normally: {result:?}");
+  assert_eq!(lio.in_flight(), 0);
+}
+
+#[test]
+fn test_drain_refuses_new_ops() {
+  let mut lio = Lio::new(64).unwrap();
+  assert_eq!(lio.drain(Some(Duration::from_millis(10))), Ok(()));
+
+  let (sender, receiver) = mpsc::channel();
+  api::nop().with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::ECANCELED));
+}
+
+#[test]
+fn test_drain_timeout_counts_stragglers() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  // Nothing is ever sent, so the recv never completes.
+  let (sender, _receiver) = mpsc::channel();
+  api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .send_with(sender);
+  assert_eq!(lio.drain(Some(Duration::from_millis(20))), Err(1));
+}
+
+#[test]
+fn test_drain_and_exit_uninstalls_global() {
+  std::thread::spawn(|| {
+    let lio = Lio::new(64).unwrap();
+    lio::install_global(lio);
+    let receiver = api::timeout(Duration::from_millis(10)).send();
+
+    assert_eq!(lio::drain_and_exit(Some(Duration::from_secs(5))), Ok(()));
+    receiver.recv().expect("timeout failed");
+    assert!(lio::uninstall_global().is_none());
+
+    // Exiting twice panics.
+    let second = std::panic::catch_unwind(|| lio::drain_and_exit(None));
+    assert!(second.is_err());
+  })
+  .join()
+  .unwrap();
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{pipe, poll_until_recv};
+use lio::{
+  Lio,
+  api::{dup, dup2, resource::Resource, write},
+};
+use std::{
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+};
+
+fn read_all(res: &Resource, len: usize) -> Vec<u8> {
+  let mut buf = vec![0u8; len];
+  let n =
+    unsafe { libc::read(res.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+  assert!(n >= 0);
+  buf.truncate(n as usize);
+  buf
+}
+
+#[test]
+fn test_dup_pipe_write_end() {
+  let mut lio = Lio::new(64).unwrap();
+  let (read_end, write_end) = pipe();
+
+  let (sender, receiver) = mpsc::channel();
+  dup(&write_end).with_lio(&mut lio).send_with(sender);
+  let duplicate = poll_until_recv(&mut lio, &receiver).expect("dup failed");
+  assert_ne!(duplicate.as_raw_fd(), write_end.as_raw_fd());
+
+  let flags = unsafe { libc::fcntl(duplicate.as_raw_fd(), libc::F_GETFD) };
+  assert!(flags & libc::FD_CLOEXEC != 0);
+
+  // The original write end isn't needed for the data to flow.
+  drop(write_end);
+
+  let (sender, receiver) = mpsc::channel();
+  write(&duplicate, b"through the dup".to_vec())
+    .with_lio(&mut lio)
+    .send_with(sender);
+  let (written, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("write failed"), 15);
+
+  assert_eq!(read_all(&read_end, 64), b"through the dup");
+}
+
+#[test]
+fn test_dup2_redirects_target() {
+  let mut lio = Lio::new(64).unwrap();
+  let (read_a, write_a) = pipe();
+  let (read_b, write_b) = pipe();
+
+  // Make write_b point at pipe a.
+  let (sender, receiver) = mpsc::channel();
+  dup2(&write_a, &write_b).with_lio(&mut lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("dup2 failed");
+
+  let (sender, receiver) = mpsc::channel();
+  write(&write_b, b"redirected".to_vec()).with_lio(&mut lio).send_with(sender);
+  let (written, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("write failed"), 10);
+
+  assert_eq!(read_all(&read_a, 64), b"redirected");
+
+  // Pipe b lost its only writer, so it reads EOF.
+  drop(write_b);
+  drop(write_a);
+  assert!(read_all(&read_b, 64).is_empty());
+}
+
+#[test]
+fn test_dup_invalid_fd() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  dup(&unsafe { Resource::from_raw_fd(-1) })
+    .with_lio(&mut lio)
+    .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).expect_err("should fail");
+  assert_eq!(err.raw_os_error(), Some(libc::EBADF));
+}
@@ -0,0 +1,70 @@
+#![cfg(target_os = "linux")]
+mod common;
+
+use common::{pipe, poll_until_recv};
+use lio::{Lio, api, api::resource::Resource};
+use std::{
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+};
+
+fn epoll() -> Resource {
+  let fd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+  assert!(fd >= 0, "epoll_create1 failed");
+  unsafe { Resource::from_raw_fd(fd) }
+}
+
+fn ctl(
+  lio: &mut Lio,
+  epfd: &Resource,
+  fd: &Resource,
+  op: i32,
+  event: libc::epoll_event,
+) -> std::io::Result<()> {
+  let (sender, receiver) = mpsc::channel();
+  api::epoll_ctl(epfd, fd, op, event).with_lio(lio).send_with(sender);
+  poll_until_recv(lio, &receiver)
+}
+
+fn ready(epfd: &Resource) -> Vec<u64> {
+  let mut events = [libc::epoll_event { events: 0, u64: 0 }; 4];
+  let n =
+    unsafe { libc::epoll_wait(epfd.as_raw_fd(), events.as_mut_ptr(), 4, 100) };
+  assert!(n >= 0, "epoll_wait failed");
+  events[..n as usize].iter().map(|e| e.u64).collect()
+}
+
+#[test]
+fn test_epoll_ctl_add_and_delete() {
+  let mut lio = Lio::new(64).unwrap();
+  let epfd = epoll();
+  let (read_end, write_end) = pipe();
+
+  let event = libc::epoll_event { events: libc::EPOLLIN as u32, u64: 42 };
+  ctl(&mut lio, &epfd, &read_end, libc::EPOLL_CTL_ADD, event)
+    .expect("EPOLL_CTL_ADD failed");
+
+  let n =
+    unsafe { libc::write(write_end.as_raw_fd(), b"x".as_ptr().cast(), 1) };
+  assert_eq!(n, 1);
+  assert_eq!(ready(&epfd), [42]);
+
+  let unused = libc::epoll_event { events: 0, u64: 0 };
+  ctl(&mut lio, &epfd, &read_end, libc::EPOLL_CTL_DEL, unused)
+    .expect("EPOLL_CTL_DEL failed");
+  assert!(ready(&epfd).is_empty());
+}
+
+#[test]
+fn test_epoll_ctl_add_twice() {
+  let mut lio = Lio::new(64).unwrap();
+  let epfd = epoll();
+  let (read_end, _write_end) = pipe();
+
+  let event = libc::epoll_event { events: libc::EPOLLIN as u32, u64: 1 };
+  ctl(&mut lio, &epfd, &read_end, libc::EPOLL_CTL_ADD, event)
+    .expect("EPOLL_CTL_ADD failed");
+  let err = ctl(&mut lio, &epfd, &read_end, libc::EPOLL_CTL_ADD, event)
+    .expect_err("fd is already registered");
+  assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+}
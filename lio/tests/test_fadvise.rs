@@ -0,0 +1,52 @@
+#![cfg(unix)]
+mod common;
+
+use common::{TempFile, poll_until_recv};
+use lio::{Lio, api, api::resource::Resource};
+use std::{os::fd::FromRawFd, sync::mpsc};
+
+#[test]
+fn test_fadvise_dontneed() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("fadvise_dontneed");
+  let resource = unsafe {
+    let fd =
+      libc::open(file.path.as_ptr(), libc::O_CREAT | libc::O_RDWR, 0o644);
+    assert!(fd >= 0, "Failed to create test file");
+    libc::write(fd, b"streamed".as_ptr().cast(), 8);
+    Resource::from_raw_fd(fd)
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  #[cfg(target_os = "linux")]
+  api::fadvise(&resource, 0, 0, libc::POSIX_FADV_DONTNEED)
+    .with_lio(&lio)
+    .send_with(sender);
+  #[cfg(not(target_os = "linux"))]
+  api::fadvise(&resource, 0, 0, 4).with_lio(&lio).send_with(sender);
+  let result = poll_until_recv(&mut lio, &receiver);
+
+  if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+    result.expect("fadvise failed");
+  } else {
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Unsupported);
+  }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fadvise_invalid_advice() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("fadvise_invalid");
+  let resource = unsafe {
+    let fd =
+      libc::open(file.path.as_ptr(), libc::O_CREAT | libc::O_RDWR, 0o644);
+    assert!(fd >= 0, "Failed to create test file");
+    Resource::from_raw_fd(fd)
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  api::fadvise(&resource, 0, 0, -1).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).expect_err("bogus advice");
+  assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+}
@@ -0,0 +1,47 @@
+#![cfg(unix)]
+mod common;
+
+use common::{TempFile, create, poll_until_recv};
+use lio::{Lio, api, api::resource::Resource};
+use std::{os::fd::FromRawFd, sync::mpsc};
+
+#[test]
+fn test_fallocate_one_mib() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("fallocate_one_mib");
+  let resource = create(&file);
+
+  let (sender, receiver) = mpsc::channel();
+  api::fallocate(&resource, 0, 1 << 20, 0).with_lio(&lio).send_with(sender);
+  let result = poll_until_recv(&mut lio, &receiver);
+
+  if !cfg!(target_os = "linux") {
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Unsupported);
+    return;
+  }
+  result.expect("fallocate failed");
+
+  // SAFETY: AT_FDCWD is never closed.
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+  let (sender, receiver) = mpsc::channel();
+  api::statx(&cwd, file.path.clone(), 0, u32::MAX)
+    .with_lio(&lio)
+    .send_with(sender);
+  let meta = poll_until_recv(&mut lio, &receiver).expect("statx failed");
+  assert_eq!(meta.size, 1 << 20);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fallocate_invalid_len() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("fallocate_invalid_len");
+  let resource = create(&file);
+
+  for len in [0, u64::MAX] {
+    let (sender, receiver) = mpsc::channel();
+    api::fallocate(&resource, 0, len, 0).with_lio(&lio).send_with(sender);
+    let err = poll_until_recv(&mut lio, &receiver).expect_err("bad len");
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL), "len {len}");
+  }
+}
@@ -1,4 +1,4 @@
-//! Tests for file operations: fsync, linkat, symlink, nop, and openat fixes.
+//! Tests for file operations: fsync, linkat, symlink, readlink, read_file, write_file, rename, mkdir, unlink, nop, open_dir, and openat fixes.
 
 mod common;
 
@@ -152,6 +152,178 @@ fn test_openat_nonexistent() {
   std::mem::forget(cwd);
 }
 
+#[test]
+fn test_open_dir_then_openat() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::open_dir(CString::new("/tmp").unwrap()).with_lio(&lio).send_with(sender);
+  let dir = poll_until_recv(&mut lio, &receiver).expect("open_dir failed");
+
+  let name = format!("lio_open_dir_{}", std::process::id());
+  let (sender, receiver) = mpsc::channel();
+  api::openat(
+    &dir,
+    CString::new(name.clone()).unwrap(),
+    libc::O_CREAT | libc::O_WRONLY | libc::O_TRUNC,
+  )
+  .with_lio(&lio)
+  .send_with(sender);
+  let fd = poll_until_recv(&mut lio, &receiver)
+    .expect("openat relative to the dir handle failed");
+  drop(fd);
+
+  let path = std::path::Path::new("/tmp").join(&name);
+  assert!(path.exists(), "file should be created under the dir handle");
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_open_dir_on_file() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("open_dir_file");
+  std::fs::write(temp.path.to_str().unwrap(), b"").unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::open_dir(temp.path.clone()).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+
+  assert_eq!(err.raw_os_error(), Some(libc::ENOTDIR));
+}
+
+// ============================================================================
+// Rename tests
+// ============================================================================
+
+#[test]
+fn test_renameat_replaces_target() {
+  let mut lio = Lio::new(64).unwrap();
+  let src = TempFile::new("renameat_src");
+  let dst = TempFile::new("renameat_dst");
+  std::fs::write(src.path.to_str().unwrap(), b"new").unwrap();
+  std::fs::write(dst.path.to_str().unwrap(), b"old").unwrap();
+
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+  let (sender, receiver) = mpsc::channel();
+  api::renameat(&cwd, src.path.clone(), &cwd, dst.path.clone())
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("renameat should succeed");
+
+  assert!(!std::path::Path::new(src.path.to_str().unwrap()).exists());
+  assert_eq!(std::fs::read(dst.path.to_str().unwrap()).unwrap(), b"new");
+}
+
+#[test]
+fn test_rename_noreplace_moves_into_place() {
+  let mut lio = Lio::new(64).unwrap();
+  let src = TempFile::new("rename_noreplace_src");
+  let dst = TempFile::new("rename_noreplace_dst");
+  std::fs::write(src.path.to_str().unwrap(), b"lock").unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::rename_noreplace(src.path.clone(), dst.path.clone())
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("rename should succeed");
+
+  assert_eq!(std::fs::read(dst.path.to_str().unwrap()).unwrap(), b"lock");
+}
+
+#[test]
+fn test_rename_noreplace_existing_target() {
+  let mut lio = Lio::new(64).unwrap();
+  let src = TempFile::new("rename_noreplace_exists_src");
+  let dst = TempFile::new("rename_noreplace_exists_dst");
+  std::fs::write(src.path.to_str().unwrap(), b"new").unwrap();
+  std::fs::write(dst.path.to_str().unwrap(), b"old").unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::rename_noreplace(src.path.clone(), dst.path.clone())
+    .with_lio(&lio)
+    .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+
+  assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+  assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+  assert_eq!(std::fs::read(src.path.to_str().unwrap()).unwrap(), b"new");
+  assert_eq!(std::fs::read(dst.path.to_str().unwrap()).unwrap(), b"old");
+}
+
+#[test]
+fn test_renameat2_noreplace_existing_target() {
+  let mut lio = Lio::new(64).unwrap();
+  let src = TempFile::new("renameat2_src");
+  let dst = TempFile::new("renameat2_dst");
+  std::fs::write(src.path.to_str().unwrap(), b"new").unwrap();
+  std::fs::write(dst.path.to_str().unwrap(), b"old").unwrap();
+
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+  let (sender, receiver) = mpsc::channel();
+  api::renameat2(
+    &cwd,
+    src.path.clone(),
+    &cwd,
+    dst.path.clone(),
+    api::ops::RENAME_NOREPLACE,
+  )
+  .with_lio(&lio)
+  .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+
+  assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+  assert_eq!(std::fs::read(dst.path.to_str().unwrap()).unwrap(), b"old");
+}
+
+// ============================================================================
+// Mkdir / unlink tests
+// ============================================================================
+
+#[test]
+fn test_mkdirat_then_unlinkat_removedir() {
+  let mut lio = Lio::new(64).unwrap();
+  let dir = TempFile::new("mkdirat_dir");
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+  let path = std::path::Path::new(dir.path.to_str().unwrap());
+
+  let (sender, receiver) = mpsc::channel();
+  api::mkdirat(&cwd, dir.path.clone(), 0o755)
+    .with_lio(&lio)
+    .send_with(sender.clone());
+  poll_until_recv(&mut lio, &receiver).expect("mkdirat should succeed");
+  assert!(path.is_dir());
+
+  api::mkdirat(&cwd, dir.path.clone(), 0o755).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+
+  let (sender, receiver) = mpsc::channel();
+  api::unlinkat(&cwd, dir.path.clone(), libc::AT_REMOVEDIR)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("unlinkat should succeed");
+  assert!(!path.exists());
+}
+
+#[test]
+fn test_unlinkat_file() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("unlinkat_file");
+  std::fs::write(file.path.to_str().unwrap(), b"bye").unwrap();
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+
+  let (sender, receiver) = mpsc::channel();
+  api::unlinkat(&cwd, file.path.clone(), 0)
+    .with_lio(&lio)
+    .send_with(sender.clone());
+  poll_until_recv(&mut lio, &receiver).expect("unlinkat should succeed");
+  assert!(!std::path::Path::new(file.path.to_str().unwrap()).exists());
+
+  api::unlinkat(&cwd, file.path.clone(), 0).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+}
+
 // ============================================================================
 // Fsync tests
 // ============================================================================
@@ -417,3 +589,181 @@ fn test_symlinkat_dangling() {
 
   std::mem::forget(cwd);
 }
+
+// ============================================================================
+// Readlinkat tests
+// ============================================================================
+
+#[test]
+fn test_readlinkat_roundtrip() {
+  let mut lio = Lio::new(64).unwrap();
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+
+  // Longer than the first buffer readlinkat tries, so it has to grow.
+  let short = CString::new("/tmp/lio_readlink_target").unwrap();
+  let long = CString::new(format!("/tmp/{}", "d/".repeat(300))).unwrap();
+
+  for (i, target) in [short, long].into_iter().enumerate() {
+    let link_path = CString::new(format!(
+      "/tmp/lio_test_readlink_{}_{i}",
+      std::process::id()
+    ))
+    .unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    api::symlinkat(&cwd, target.clone(), link_path.clone())
+      .with_lio(&lio)
+      .send_with(sender);
+    poll_until_recv(&mut lio, &receiver).expect("symlinkat should succeed");
+
+    let (sender, receiver) = mpsc::channel();
+    api::readlinkat(&cwd, link_path.clone()).with_lio(&lio).send_with(sender);
+    let read = poll_until_recv(&mut lio, &receiver);
+
+    unsafe { libc::unlink(link_path.as_ptr()) };
+    assert_eq!(read.expect("readlinkat should succeed"), target);
+  }
+
+  std::mem::forget(cwd);
+}
+
+#[test]
+fn test_readlinkat_not_a_symlink() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("readlink_regular");
+  unsafe {
+    let fd =
+      libc::open(temp.path.as_ptr(), libc::O_CREAT | libc::O_WRONLY, 0o644);
+    libc::close(fd);
+  }
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+
+  let (sender, receiver) = mpsc::channel();
+  api::readlinkat(&cwd, temp.path.clone()).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+
+  std::mem::forget(cwd);
+}
+
+// ============================================================================
+// Read file tests
+// ============================================================================
+
+#[test]
+fn test_read_file_contents() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("read_file");
+  let contents: Vec<u8> = (0..20_000u32).map(|i| i as u8).collect();
+  std::fs::write(temp.path.to_str().unwrap(), &contents).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::read_file(temp.path.clone()).with_lio(&lio).send_with(sender);
+  let read = poll_until_recv(&mut lio, &receiver).expect("read_file failed");
+
+  assert_eq!(read, contents);
+}
+
+#[test]
+fn test_read_file_empty() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("read_file_empty");
+  std::fs::write(temp.path.to_str().unwrap(), b"").unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::read_file(temp.path.clone()).with_lio(&lio).send_with(sender);
+  let read = poll_until_recv(&mut lio, &receiver).expect("read_file failed");
+
+  assert!(read.is_empty());
+}
+
+#[test]
+fn test_read_file_nonexistent() {
+  let mut lio = Lio::new(64).unwrap();
+  let path = CString::new("/tmp/lio_test_read_file_missing_12345").unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::read_file(path).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+
+  assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+}
+
+// ============================================================================
+// Write file tests
+// ============================================================================
+
+#[test]
+fn test_write_file_durable_roundtrip() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("write_file");
+  std::fs::write(temp.path.to_str().unwrap(), b"old contents, longer").unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::write_file(temp.path.clone(), b"new".to_vec(), true)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("write_file failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::read_file(temp.path.clone()).with_lio(&lio).send_with(sender);
+  let read = poll_until_recv(&mut lio, &receiver).expect("read_file failed");
+  assert_eq!(read, b"new");
+}
+
+#[test]
+fn test_write_file_atomic_replaces() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("write_file_atomic");
+  let path = temp.path.to_str().unwrap();
+  std::fs::write(path, b"old").unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::write_file_atomic(temp.path.clone(), b"replaced".to_vec())
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("write_file_atomic failed");
+
+  assert_eq!(std::fs::read(path).unwrap(), b"replaced");
+  assert!(tmp_files(path).is_empty(), "temp file should be gone");
+}
+
+#[test]
+fn test_write_file_atomic_skips_taken_temp_names() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("write_file_atomic_taken");
+  let path = temp.path.to_str().unwrap();
+  // Names a crashed or concurrent writer could have left behind.
+  let stale: Vec<_> = (0..16)
+    .map(|n| format!("{}.tmp.{}.{n}", path, std::process::id()))
+    .collect();
+  for tmp in &stale {
+    std::fs::write(tmp, b"stale").unwrap();
+  }
+
+  let (sender, receiver) = mpsc::channel();
+  api::write_file_atomic(temp.path.clone(), b"replaced".to_vec())
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("write_file_atomic failed");
+
+  assert_eq!(std::fs::read(path).unwrap(), b"replaced");
+  for tmp in &stale {
+    assert_eq!(std::fs::read(tmp).unwrap(), b"stale");
+    std::fs::remove_file(tmp).unwrap();
+  }
+  assert!(tmp_files(path).is_empty(), "temp file should be gone");
+}
+
+/// Temporary files `write_file_atomic` left next to `path`.
+fn tmp_files(path: &str) -> Vec<std::path::PathBuf> {
+  let path = std::path::Path::new(path);
+  let prefix = format!("{}.tmp.", path.file_name().unwrap().to_str().unwrap());
+  std::fs::read_dir(path.parent().unwrap())
+    .unwrap()
+    .map(|entry| entry.unwrap().path())
+    .filter(|p| {
+      p.file_name().unwrap().to_str().is_some_and(|n| n.starts_with(&prefix))
+    })
+    .collect()
+}
@@ -0,0 +1,55 @@
+//! I/O with fixed-size buffers: arrays, `Box<[u8]>` and `Box<[u8; N]>`.
+
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api};
+use std::sync::mpsc;
+
+#[test]
+fn test_send_array_then_read_into_boxed_array() {
+  let mut lio = Lio::new(64).unwrap();
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd } =
+    setup_tcp_pair(&mut lio);
+
+  let prefix: [u8; 4] = 5u32.to_be_bytes();
+  let (sender, receiver) = mpsc::channel();
+  api::send(&client_sock, prefix, None).with_lio(&lio).send_with(sender);
+  let (sent, returned) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), 4);
+  assert_eq!(returned, prefix);
+
+  let buf = Box::new([0u8; 64]);
+  let addr = buf.as_ptr();
+  let (sender, receiver) = mpsc::channel();
+  api::read(&accepted_fd, buf).with_lio(&lio).send_with(sender);
+  let (read, returned) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(read.expect("read failed"), 4);
+  assert_eq!(returned.as_ptr(), addr, "the same allocation comes back");
+  assert_eq!(returned[..4], prefix);
+  assert!(returned[4..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn test_boxed_slice_round_trip() {
+  let mut lio = Lio::new(64).unwrap();
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd } =
+    setup_tcp_pair(&mut lio);
+
+  let frame: Box<[u8]> = b"frame".to_vec().into_boxed_slice();
+  let (sender, receiver) = mpsc::channel();
+  api::send(&client_sock, frame, None).with_lio(&lio).send_with(sender);
+  let (sent, returned) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), 5);
+  assert_eq!(&*returned, b"frame");
+
+  let buf: Box<[u8]> = vec![0u8; 16].into_boxed_slice();
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&accepted_fd, buf, None).with_lio(&lio).send_with(sender);
+  let (received, returned) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(received.expect("recv failed"), 5);
+  assert_eq!(returned.len(), 16, "length is unchanged");
+  assert_eq!(&returned[..5], b"frame");
+}
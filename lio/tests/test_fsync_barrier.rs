@@ -0,0 +1,67 @@
+mod common;
+
+use common::{TempFile, poll_until_recv};
+use lio::{Lio, api, api::resource::Resource};
+use std::{ffi::CString, os::fd::FromRawFd, sync::mpsc};
+
+#[derive(Debug, PartialEq)]
+enum Done {
+  Write(usize),
+  Fsync,
+}
+
+fn open_rw(path: &CString) -> Resource {
+  // SAFETY: path is a valid C string, the fd is owned by the Resource.
+  unsafe {
+    let fd = libc::open(
+      path.as_ptr(),
+      libc::O_CREAT | libc::O_RDWR | libc::O_TRUNC,
+      0o644,
+    );
+    assert!(fd >= 0, "Failed to create test file");
+    Resource::from_raw_fd(fd)
+  }
+}
+
+#[test]
+fn test_fsync_after_writes_completes_last() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("fsync_barrier");
+  let fd = open_rw(&temp.path);
+
+  let (sender, receiver) = mpsc::channel();
+  for i in 0..3 {
+    let sender = sender.clone();
+    let data = vec![b'a' + i as u8; 4096];
+    api::write_at(&fd, data, (i * 4096) as i64).with_lio(&lio).when_done(
+      move |(res, _)| {
+        res.expect("write failed");
+        sender.send(Done::Write(i)).unwrap();
+      },
+    );
+  }
+  api::fsync_after_writes(&fd).with_lio(&lio).when_done(move |res| {
+    res.expect("fsync failed");
+    sender.send(Done::Fsync).unwrap();
+  });
+
+  let mut order = Vec::new();
+  for _ in 0..4 {
+    order.push(poll_until_recv(&mut lio, &receiver));
+  }
+  assert_eq!(order.last(), Some(&Done::Fsync), "order: {order:?}");
+  for i in 0..3 {
+    assert!(order.contains(&Done::Write(i)), "order: {order:?}");
+  }
+}
+
+#[test]
+fn test_fsync_after_writes_without_pending_writes() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("fsync_barrier_idle");
+  let fd = open_rw(&temp.path);
+
+  let (sender, receiver) = mpsc::channel();
+  api::fsync_after_writes(&fd).with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("fsync failed");
+}
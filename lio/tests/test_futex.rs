@@ -0,0 +1,77 @@
+#![cfg(unix)]
+//! Waiting on and waking an AsyncFutex.
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{AsyncFutex, Lio};
+use std::{
+  io,
+  sync::{atomic::Ordering, mpsc},
+  time::Duration,
+};
+
+#[test]
+fn test_futex_wait_on_changed_value() {
+  let mut lio = Lio::new(64).unwrap();
+  let futex = AsyncFutex::new(1);
+
+  let (sender, receiver) = mpsc::channel();
+  futex.wait(0).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+}
+
+#[test]
+fn test_futex_wake_without_waiters() {
+  let mut lio = Lio::new(64).unwrap();
+  let futex = AsyncFutex::new(0);
+
+  let (sender, receiver) = mpsc::channel();
+  futex.wake(u32::MAX).with_lio(&lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).expect("wake failed"), 0);
+}
+
+#[test]
+fn test_futex_wake_resumes_waiter() {
+  let mut lio = Lio::new(64).unwrap();
+  let futex = AsyncFutex::new(0);
+
+  let (wait_sender, wait_receiver) = mpsc::channel();
+  futex.wait(0).with_lio(&lio).send_with(wait_sender);
+  // Submit the wait before the value changes.
+  lio.run_timeout(Duration::from_millis(5)).unwrap();
+  assert!(wait_receiver.try_recv().is_err());
+
+  futex.value().store(1, Ordering::Release);
+  // The waiter may not be parked yet, so retry until it is woken.
+  let mut woken = 0;
+  while woken == 0 {
+    let (sender, receiver) = mpsc::channel();
+    futex.wake(1).with_lio(&lio).send_with(sender);
+    woken = poll_until_recv(&mut lio, &receiver).expect("wake failed");
+  }
+  assert_eq!(woken, 1);
+  poll_until_recv(&mut lio, &wait_receiver).expect("wait failed");
+}
+
+#[test]
+fn test_futex_cancelled_waiter_is_not_woken() {
+  let mut lio = Lio::new(64).unwrap();
+  let futex = AsyncFutex::new(0);
+
+  let (cancelled_sender, cancelled_receiver) = mpsc::channel();
+  let mut cancelled = futex.wait(0).with_lio(&lio);
+  let handle = cancelled.cancel_handle();
+  cancelled.send_with(cancelled_sender);
+  lio.run_timeout(Duration::from_millis(5)).unwrap();
+
+  handle.cancel();
+  let err = poll_until_recv(&mut lio, &cancelled_receiver).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+
+  // The cancelled waiter is gone, so the wake has nobody to wake.
+  let (sender, receiver) = mpsc::channel();
+  futex.wake(1).with_lio(&lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).expect("wake failed"), 0);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api};
+use std::sync::mpsc;
+
+#[test]
+fn test_get_so_error_connected() {
+  let mut lio = Lio::new(64).unwrap();
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd: _ } =
+    setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::get_so_error(&client_sock).with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("no error should be pending");
+}
+
+#[test]
+fn test_getsockopt_raw_bytes() {
+  let mut lio = Lio::new(64).unwrap();
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd: _ } =
+    setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::getsockopt(&client_sock, libc::SOL_SOCKET, libc::SO_TYPE)
+    .with_lio(&lio)
+    .send_with(sender);
+  let raw = poll_until_recv(&mut lio, &receiver).expect("getsockopt failed");
+
+  assert_eq!(raw.len(), std::mem::size_of::<libc::c_int>());
+  let ty = libc::c_int::from_ne_bytes(raw.try_into().unwrap());
+  assert_eq!(ty, libc::SOCK_STREAM);
+}
+
+#[test]
+fn test_getsockopt_not_a_socket() {
+  let mut lio = Lio::new(64).unwrap();
+  let stdin = api::resource::Resource::stdin();
+
+  let (sender, receiver) = mpsc::channel();
+  api::get_so_error(&stdin).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+
+  assert_eq!(err.raw_os_error(), Some(libc::ENOTSOCK));
+}
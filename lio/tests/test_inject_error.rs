@@ -0,0 +1,65 @@
+#![cfg(feature = "test-utils")]
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, op::Op, test_utils::inject_error};
+use std::{
+  future::{Future, IntoFuture},
+  pin::pin,
+  sync::mpsc,
+  task::{Context, Poll, Waker},
+  time::Duration,
+};
+
+#[test]
+fn test_injected_econnreset_on_recv() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  inject_error(|op| matches!(op, Op::Recv { .. }), libc::ECONNRESET);
+
+  let mut future = pin!(
+    api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+      .with_lio(&lio)
+      .into_future()
+  );
+  let mut cx = Context::from_waker(Waker::noop());
+  let (result, _) = loop {
+    if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+      break result;
+    }
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  };
+  assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::ECONNRESET));
+}
+
+#[test]
+fn test_injected_error_is_one_shot_and_selective() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  inject_error(|op| matches!(op, Op::Recv { .. }), libc::ECONNRESET);
+
+  // A send doesn't match, so it goes through untouched.
+  let (sender, receiver) = mpsc::channel();
+  api::send(&pair.client_sock, b"hi".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), 2);
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .send_with(sender.clone());
+  let (first, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(first.unwrap_err().raw_os_error(), Some(libc::ECONNRESET));
+
+  // The injection was used up, the data is still waiting in the socket.
+  api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (second, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(second.expect("recv failed"), 2);
+  assert_eq!(buf, b"hi");
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api};
+use std::{
+  io,
+  sync::mpsc,
+  time::{Duration, Instant},
+};
+
+#[test]
+fn test_io_timeout_returns_buffer_on_expiry() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let start = Instant::now();
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .timeout(Duration::from_millis(100))
+    .with_lio(&lio)
+    .send_with(sender);
+  let (result, buf) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+  assert!(start.elapsed() >= Duration::from_millis(100));
+  assert_eq!(buf.len(), 16, "buffer should come back untouched");
+}
+
+#[test]
+fn test_io_timeout_completes_before_deadline() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let (sent_tx, sent_rx) = mpsc::channel();
+  api::send(&pair.client_sock, b"hello".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sent_tx);
+  let (sent, _) = poll_until_recv(&mut lio, &sent_rx);
+  assert_eq!(sent.expect("send failed"), 5);
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&pair.accepted_fd, vec![0u8; 16], None)
+    .timeout(Duration::from_secs(5))
+    .with_lio(&lio)
+    .send_with(sender);
+  let (result, buf) = poll_until_recv(&mut lio, &receiver);
+  let n = result.expect("recv failed") as usize;
+  assert_eq!(&buf[..n], b"hello");
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{
+  Lio,
+  net::{Socket, TcpSocket},
+};
+use std::{
+  net::{IpAddr, Ipv4Addr, SocketAddr},
+  sync::mpsc,
+};
+
+fn socket(lio: &mut Lio, domain: libc::c_int) -> Socket {
+  let (sender, receiver) = mpsc::channel();
+  Socket::new(domain, libc::SOCK_STREAM, 0).with_lio(lio).send_with(sender);
+  poll_until_recv(lio, &receiver).expect("socket failed")
+}
+
+#[test]
+fn test_dual_stack_accepts_v4_mapped_client() {
+  let mut lio = Lio::new(64).unwrap();
+  let server = socket(&mut lio, libc::AF_INET6);
+  server.set_only_v6(false).expect("set_only_v6 failed");
+  assert!(!server.only_v6().unwrap());
+
+  let (sender, receiver) = mpsc::channel();
+  server.bind("[::]:0".parse().unwrap()).with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("bind failed");
+  let (sender, receiver) = mpsc::channel();
+  server.listen().with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("listen failed");
+  let port = server.local_addr().unwrap().port();
+
+  let client = socket(&mut lio, libc::AF_INET);
+  let (sender, receiver) = mpsc::channel();
+  client
+    .connect(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+    .with_lio(&lio)
+    .send_with(sender);
+  let (accept_sender, accept_receiver) = mpsc::channel();
+  server.accept().with_lio(&lio).send_with(accept_sender);
+
+  poll_until_recv(&mut lio, &receiver).expect("connect failed");
+  let (_accepted, peer) =
+    poll_until_recv(&mut lio, &accept_receiver).expect("accept failed");
+
+  let SocketAddr::V6(peer) = peer else {
+    panic!("dual-stack listener reported a v4 peer: {peer}");
+  };
+  assert_eq!(peer.ip().to_ipv4_mapped(), Some(Ipv4Addr::LOCALHOST));
+  assert_eq!(
+    client.local_addr().unwrap().ip(),
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+  );
+}
+
+#[test]
+fn test_only_v6_round_trips() {
+  let mut lio = Lio::new(64).unwrap();
+  let socket = socket(&mut lio, libc::AF_INET6);
+
+  socket.set_only_v6(true).unwrap();
+  assert!(socket.only_v6().unwrap());
+  socket.set_only_v6(false).unwrap();
+  assert!(!socket.only_v6().unwrap());
+}
+
+#[test]
+fn test_connect_async_reaches_v6_address() {
+  use std::future::Future;
+  use std::task::{Context, Poll, Waker};
+
+  let lio = Lio::new(64).unwrap();
+  lio::install_global(lio.clone());
+  let listener = std::net::TcpListener::bind("[::1]:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  let mut connect = std::pin::pin!(TcpSocket::connect_async(addr));
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut attempts = 0;
+  let _socket = loop {
+    if let Poll::Ready(result) = connect.as_mut().poll(&mut cx) {
+      break result.expect("connect to a v6 address failed");
+    }
+    attempts += 1;
+    assert!(attempts < 1000, "connect never finished");
+    lio.run_timeout(std::time::Duration::from_millis(5)).unwrap();
+  };
+  let (_accepted, peer) = listener.accept().unwrap();
+  assert!(peer.is_ipv6());
+
+  lio::uninstall_global();
+}
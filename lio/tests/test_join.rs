@@ -0,0 +1,86 @@
+#![cfg(unix)]
+mod common;
+
+use common::TempFile;
+use lio::{
+  Lio,
+  api::{
+    self,
+    io::{join, join_all},
+    resource::Resource,
+  },
+};
+use std::{
+  os::fd::FromRawFd,
+  time::{Duration, Instant},
+};
+
+#[test]
+fn test_join_all_keeps_input_order() {
+  let lio = Lio::new(64).unwrap();
+  let file = TempFile::new("join_all_order");
+  std::fs::write(file.path.to_str().unwrap(), b"aaaabbbbccccdddd").unwrap();
+  let fd = unsafe {
+    let fd = libc::open(file.path.as_ptr(), libc::O_RDONLY);
+    assert!(fd >= 0, "Failed to open test file");
+    Resource::from_raw_fd(fd)
+  };
+
+  let reads = (0..4)
+    .map(|i| api::read_at(&fd, vec![0u8; 4], i * 4).with_lio(&lio))
+    .collect();
+  let results = join_all(reads);
+
+  let chunks: Vec<_> = results
+    .into_iter()
+    .map(|(result, buf)| {
+      assert_eq!(result.expect("read_at failed"), 4);
+      buf
+    })
+    .collect();
+  assert_eq!(chunks, [b"aaaa", b"bbbb", b"cccc", b"dddd"]);
+  assert_eq!(lio.in_flight(), 0);
+}
+
+#[test]
+fn test_join_all_runs_concurrently() {
+  let lio = Lio::new(64).unwrap();
+
+  let start = Instant::now();
+  let sleeps = (0..3)
+    .map(|_| api::sleep(Duration::from_millis(60)).with_lio(&lio))
+    .collect();
+  for result in join_all(sleeps) {
+    result.expect("sleep failed");
+  }
+
+  // One after the other would take 180ms.
+  let elapsed = start.elapsed();
+  assert!(elapsed >= Duration::from_millis(60), "woke early: {elapsed:?}");
+  assert!(elapsed < Duration::from_millis(150), "slept in turn: {elapsed:?}");
+}
+
+#[test]
+fn test_join_all_empty() {
+  let results = join_all(Vec::<lio::api::io::Io<api::ops::Nop>>::new());
+  assert!(results.is_empty());
+}
+
+#[test]
+fn test_join_mixed_ops() {
+  let lio = Lio::new(64).unwrap();
+
+  let (slept, nop) = join(
+    api::sleep(Duration::from_millis(10)).with_lio(&lio),
+    api::nop().with_lio(&lio),
+  );
+  slept.expect("sleep failed");
+  nop.expect("nop failed");
+}
+
+#[test]
+#[should_panic(expected = "different Lios")]
+fn test_join_different_lios_panics() {
+  let (a, b) = (Lio::new(8).unwrap(), Lio::new(8).unwrap());
+  let _ = join(api::nop().with_lio(&a), api::nop().with_lio(&b));
+}
@@ -0,0 +1,80 @@
+//! Tests for [`Lio::set_max_in_flight`].
+
+mod common;
+
+use common::filled_pipe;
+use lio::{Lio, api, api::resource::Resource};
+use std::{
+  future::{Future, IntoFuture},
+  pin::pin,
+  sync::mpsc,
+  task::{Context, Poll, Waker},
+  time::Duration,
+};
+
+#[test]
+fn test_no_more_than_max_in_flight() {
+  let lio = Lio::new(64).unwrap();
+  lio.set_max_in_flight(Some(4));
+
+  let (sender, receiver) = mpsc::channel();
+  let pipes: Vec<Resource> = (0..8u8).map(|i| filled_pipe(&[i; 4])).collect();
+  for pipe in &pipes {
+    api::read(pipe, vec![0u8; 4]).with_lio(&lio).send_with(sender.clone());
+  }
+  assert_eq!(lio.in_flight(), 4, "only the first 4 reads should start");
+
+  let mut results = Vec::new();
+  while results.len() < pipes.len() {
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+    assert!(lio.in_flight() <= 4, "{} ops in flight", lio.in_flight());
+    results.extend(receiver.try_iter());
+  }
+  assert_eq!(lio.in_flight(), 0);
+
+  let mut firsts: Vec<u8> = results
+    .into_iter()
+    .map(|(res, buf)| {
+      assert_eq!(res.expect("read failed"), 4);
+      buf[0]
+    })
+    .collect();
+  firsts.sort();
+  assert_eq!(firsts, (0..8).collect::<Vec<u8>>());
+}
+
+#[test]
+fn test_parked_future_stays_pending() {
+  let lio = Lio::new(64).unwrap();
+  lio.set_max_in_flight(Some(1));
+
+  let first_pipe = filled_pipe(b"one");
+  let second_pipe = filled_pipe(b"two");
+  let mut first =
+    pin!(api::read(&first_pipe, vec![0u8; 3]).with_lio(&lio).into_future());
+  let mut second =
+    pin!(api::read(&second_pipe, vec![0u8; 3]).with_lio(&lio).into_future());
+  let mut cx = Context::from_waker(Waker::noop());
+
+  assert!(first.as_mut().poll(&mut cx).is_pending());
+  assert!(second.as_mut().poll(&mut cx).is_pending());
+  assert_eq!(lio.in_flight(), 1);
+
+  let (res, buf) = loop {
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+    if let Poll::Ready(out) = first.as_mut().poll(&mut cx) {
+      break out;
+    }
+  };
+  assert_eq!(res.unwrap(), 3);
+  assert_eq!(&buf, b"one");
+
+  let (res, buf) = loop {
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+    if let Poll::Ready(out) = second.as_mut().poll(&mut cx) {
+      break out;
+    }
+  };
+  assert_eq!(res.unwrap(), 3);
+  assert_eq!(&buf, b"two");
+}
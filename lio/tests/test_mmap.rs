@@ -0,0 +1,133 @@
+mod common;
+
+use common::{TempFile, poll_until_recv};
+use lio::{
+  Lio,
+  api::{madvise, mmap, msync, resource::Resource},
+};
+use std::{os::fd::FromRawFd, sync::mpsc};
+
+fn create_file(file: &TempFile, len: i64) -> Resource {
+  unsafe {
+    let fd = libc::open(
+      file.path.as_ptr(),
+      libc::O_CREAT | libc::O_RDWR | libc::O_TRUNC,
+      0o644,
+    );
+    assert!(fd >= 0, "Failed to create test file");
+    assert_eq!(libc::ftruncate(fd, len), 0);
+    Resource::from_raw_fd(fd)
+  }
+}
+
+#[test]
+fn test_mmap_write_msync_persists() {
+  let mut lio = Lio::new(256).unwrap();
+  let file = TempFile::new("mmap_persist");
+  let resource = create_file(&file, 4096);
+
+  let (sender, receiver) = mpsc::channel();
+  mmap(
+    &resource,
+    4096,
+    libc::PROT_READ | libc::PROT_WRITE,
+    libc::MAP_SHARED,
+    0,
+  )
+  .with_lio(&mut lio)
+  .send_with(sender);
+
+  let mut region =
+    poll_until_recv(&mut lio, &receiver).expect("Failed to mmap file");
+  assert_eq!(region.len(), 4096);
+  assert!(region.as_slice().unwrap().iter().all(|b| *b == 0));
+
+  region.as_mut_slice().unwrap()[..11].copy_from_slice(b"hello mmap!");
+
+  let (sender, receiver) = mpsc::channel();
+  msync(region, libc::MS_SYNC).with_lio(&mut lio).send_with(sender);
+  let (result, region) = poll_until_recv(&mut lio, &receiver);
+  result.expect("Failed to msync region");
+  drop(region);
+
+  // Read back through a separate descriptor to confirm the data hit the file.
+  unsafe {
+    let fd = libc::open(file.path.as_ptr(), libc::O_RDONLY);
+    assert!(fd >= 0);
+    let mut buf = [0u8; 11];
+    let n = libc::pread(fd, buf.as_mut_ptr().cast(), buf.len(), 0);
+    libc::close(fd);
+    assert_eq!(n, 11);
+    assert_eq!(&buf, b"hello mmap!");
+  }
+}
+
+#[test]
+fn test_mmap_read_only_sees_file_contents() {
+  let mut lio = Lio::new(256).unwrap();
+  let file = TempFile::new("mmap_read_only");
+  let resource = create_file(&file, 0);
+
+  unsafe {
+    let fd = std::os::fd::AsRawFd::as_raw_fd(&resource);
+    libc::write(fd, b"0123456789".as_ptr().cast(), 10);
+  }
+
+  let (sender, receiver) = mpsc::channel();
+  mmap(&resource, 10, libc::PROT_READ, libc::MAP_PRIVATE, 0)
+    .with_lio(&mut lio)
+    .send_with(sender);
+
+  let mut region =
+    poll_until_recv(&mut lio, &receiver).expect("Failed to mmap file");
+  assert_eq!(region.as_slice(), Some(&b"0123456789"[..]));
+  // Not mapped PROT_WRITE, so there's no way to write to it.
+  assert!(region.as_mut_slice().is_none());
+}
+
+#[test]
+fn test_mmap_invalid_fd() {
+  let mut lio = Lio::new(256).unwrap();
+  let resource = unsafe { Resource::from_raw_fd(-1) };
+
+  let (sender, receiver) = mpsc::channel();
+  mmap(&resource, 4096, libc::PROT_READ, libc::MAP_SHARED, 0)
+    .with_lio(&mut lio)
+    .send_with(sender);
+
+  let err = poll_until_recv(&mut lio, &receiver)
+    .expect_err("mmap of invalid fd should fail");
+  assert_eq!(err.raw_os_error(), Some(libc::EBADF));
+}
+
+#[test]
+fn test_madvise_dontneed_drops_private_changes() {
+  let mut lio = Lio::new(256).unwrap();
+  let file = TempFile::new("madvise_dontneed");
+  let resource = create_file(&file, 4096);
+
+  let (sender, receiver) = mpsc::channel();
+  mmap(
+    &resource,
+    4096,
+    libc::PROT_READ | libc::PROT_WRITE,
+    libc::MAP_PRIVATE,
+    0,
+  )
+  .with_lio(&lio)
+  .send_with(sender);
+  let mut region =
+    poll_until_recv(&mut lio, &receiver).expect("Failed to mmap file");
+  region.as_mut_slice().unwrap()[..5].copy_from_slice(b"dirty");
+
+  let (sender, receiver) = mpsc::channel();
+  madvise(region, libc::MADV_DONTNEED).with_lio(&lio).send_with(sender);
+  let (result, region) = poll_until_recv(&mut lio, &receiver);
+  result.expect("Failed to madvise region");
+  assert_eq!(region.len(), 4096);
+
+  // Linux refaults dropped private pages from the file, the BSDs may keep
+  // them.
+  #[cfg(target_os = "linux")]
+  assert!(region.as_slice().unwrap().iter().all(|b| *b == 0));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{
+  Lio,
+  api::{self, resource::Resource},
+};
+use std::{net::SocketAddr, sync::mpsc};
+
+fn udp_socket(lio: &mut Lio) -> (Resource, SocketAddr) {
+  let (sender, receiver) = mpsc::channel();
+  lio::test_utils::udp_socket().with_lio(lio).send_with(sender);
+  let sock = poll_until_recv(lio, &receiver).expect("socket failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::bind(&sock, "127.0.0.1:0".parse().unwrap())
+    .with_lio(lio)
+    .send_with(sender);
+  poll_until_recv(lio, &receiver).expect("bind failed");
+  let addr = common::get_bound_addr(&sock);
+  (sock, addr)
+}
+
+#[test]
+fn test_sendmmsg_then_recvmmsg() {
+  let mut lio = Lio::new(64).unwrap();
+  let (tx_sock, tx_addr) = udp_socket(&mut lio);
+  let (rx_sock, rx_addr) = udp_socket(&mut lio);
+
+  let payloads = [b"one".to_vec(), b"two!".to_vec(), b"three".to_vec()];
+  let msgs = payloads.iter().map(|p| (p.clone(), rx_addr)).collect();
+
+  let (sender, receiver) = mpsc::channel();
+  api::sendmmsg(&tx_sock, msgs).with_lio(&lio).send_with(sender);
+  let sent = poll_until_recv(&mut lio, &receiver).expect("sendmmsg failed");
+  let sent: Vec<usize> =
+    sent.into_iter().map(|r| r.expect("datagram failed")).collect();
+  assert_eq!(sent, [3, 4, 5]);
+
+  // All three are queued on the loopback socket by now.
+  let (sender, receiver) = mpsc::channel();
+  api::recvmmsg(&rx_sock, 8).with_lio(&lio).send_with(sender);
+  let received = poll_until_recv(&mut lio, &receiver).expect("recvmmsg failed");
+
+  assert_eq!(received.len(), 3);
+  for ((payload, from), expected) in received.iter().zip(&payloads) {
+    assert_eq!(payload, expected);
+    assert_eq!(*from, tx_addr);
+  }
+}
+
+#[test]
+fn test_sendmmsg_reports_each_failure() {
+  let mut lio = Lio::new(64).unwrap();
+  let (tx_sock, _) = udp_socket(&mut lio);
+  let (_rx_sock, rx_addr) = udp_socket(&mut lio);
+
+  // An IPv6 destination can't be reached from an AF_INET socket.
+  let bad: SocketAddr = "[::1]:9".parse().unwrap();
+  let msgs = vec![
+    (b"ok".to_vec(), rx_addr),
+    (b"bad".to_vec(), bad),
+    (b"ok again".to_vec(), rx_addr),
+  ];
+
+  let (sender, receiver) = mpsc::channel();
+  api::sendmmsg(&tx_sock, msgs).with_lio(&lio).send_with(sender);
+  let results = poll_until_recv(&mut lio, &receiver).expect("sendmmsg failed");
+
+  assert_eq!(results.len(), 3);
+  assert_eq!(*results[0].as_ref().unwrap(), 2);
+  assert!(results[1].is_err());
+  assert_eq!(*results[2].as_ref().unwrap(), 8);
+}
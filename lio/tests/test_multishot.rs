@@ -0,0 +1,91 @@
+//! Tests for multishot operations consumed as streams.
+
+use futures_util::StreamExt;
+use lio::{
+  Lio,
+  api::{self, ops::Interest, resource::Resource},
+};
+use std::{
+  future::Future,
+  os::fd::{AsRawFd, FromRawFd, OwnedFd},
+  pin::pin,
+  task::{Context, Poll, Waker},
+  time::{Duration, Instant},
+};
+
+fn pipe() -> (Resource, OwnedFd) {
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  unsafe { (Resource::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) }
+}
+
+fn write_byte(fd: &OwnedFd) {
+  assert_eq!(
+    unsafe { libc::write(fd.as_raw_fd(), b"x".as_ptr().cast(), 1) },
+    1
+  );
+}
+
+fn drain(fd: &Resource) {
+  let mut buf = [0u8; 16];
+  unsafe { libc::read(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+}
+
+/// Runs `lio` until `future` resolves.
+fn block_on<F: Future>(lio: &Lio, future: F) -> F::Output {
+  let mut future = pin!(future);
+  let mut cx = Context::from_waker(Waker::noop());
+  loop {
+    if let Poll::Ready(out) = future.as_mut().poll(&mut cx) {
+      return out;
+    }
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+  }
+}
+
+#[test]
+fn test_poll_multishot_stream_yields_each_event() {
+  let lio = Lio::new(64).unwrap();
+  let (read_end, write_end) = pipe();
+
+  let mut events = api::poll_multishot(&read_end, Interest::READABLE)
+    .with_lio(&lio)
+    .into_stream();
+
+  // Nothing to report yet, this submits the poll.
+  let mut cx = Context::from_waker(Waker::noop());
+  assert!(events.poll_next_unpin(&mut cx).is_pending());
+
+  for _ in 0..2 {
+    write_byte(&write_end);
+    let readiness = block_on(&lio, events.next())
+      .expect("stream ended early")
+      .expect("poll failed");
+    assert!(readiness.is_readable());
+    drain(&read_end);
+  }
+
+  // One submission served both events.
+  assert_eq!(lio.in_flight(), 1);
+}
+
+#[test]
+fn test_dropping_stream_cancels_op() {
+  let lio = Lio::new(64).unwrap();
+  let (read_end, _write_end) = pipe();
+
+  let mut events = api::poll_multishot(&read_end, Interest::READABLE)
+    .with_lio(&lio)
+    .into_stream();
+  let mut cx = Context::from_waker(Waker::noop());
+  assert!(events.poll_next_unpin(&mut cx).is_pending());
+  assert_eq!(lio.in_flight(), 1);
+
+  drop(events);
+
+  let start = Instant::now();
+  while lio.in_flight() > 0 {
+    assert!(start.elapsed() < Duration::from_secs(5), "op wasn't cancelled");
+    lio.run_timeout(Duration::from_millis(10)).unwrap();
+  }
+}
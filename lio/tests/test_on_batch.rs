@@ -0,0 +1,45 @@
+use lio::{Lio, api};
+use std::{
+  cell::RefCell,
+  rc::Rc,
+  sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+  },
+  time::{Duration, Instant},
+};
+
+#[test]
+fn test_on_batch_gets_all_completions_of_a_run() {
+  let lio = Lio::new(64).unwrap();
+  let batches = Rc::new(RefCell::new(Vec::new()));
+  let handled = batches.clone();
+  lio.on_batch(move |completions| {
+    handled.borrow_mut().push(
+      completions
+        .iter()
+        .map(|completion| completion.result())
+        .collect::<Vec<_>>(),
+    );
+    for completion in completions {
+      completion.run();
+    }
+  });
+
+  let called = Arc::new(AtomicUsize::new(0));
+  for _ in 0..10 {
+    let called = called.clone();
+    api::nop().with_lio(&lio).when_done(move |res| {
+      res.expect("nop failed");
+      called.fetch_add(1, Ordering::SeqCst);
+    });
+  }
+
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while called.load(Ordering::SeqCst) < 10 {
+    assert!(Instant::now() < deadline, "callbacks never ran");
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+
+  assert_eq!(*batches.borrow(), vec![vec![0isize; 10]]);
+}
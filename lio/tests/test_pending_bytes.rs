@@ -0,0 +1,54 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, net::TcpListener};
+use std::{
+  io::Write,
+  net::TcpStream,
+  sync::mpsc,
+  time::{Duration, Instant},
+};
+
+#[test]
+fn test_pending_bytes_counts_unread_data() {
+  let mut lio = Lio::new(64).unwrap();
+  let listener = TcpListener::bind_sync("127.0.0.1:0").unwrap();
+  let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  listener.accept().with_lio(&lio).send_with(sender);
+  let (socket, _) =
+    poll_until_recv(&mut lio, &receiver).expect("accept failed");
+
+  let (sender, receiver) = mpsc::channel();
+  socket.pending_bytes().with_lio(&lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).unwrap(), 0);
+
+  client.write_all(&[7u8; 100]).unwrap();
+
+  // Loopback delivery is quick but not synchronous with the write.
+  let deadline = Instant::now() + Duration::from_secs(5);
+  loop {
+    let (sender, receiver) = mpsc::channel();
+    socket.pending_bytes().with_lio(&lio).send_with(sender);
+    let pending =
+      poll_until_recv(&mut lio, &receiver).expect("pending_bytes failed");
+    if pending == 100 {
+      break;
+    }
+    assert!(pending < 100, "{pending} bytes pending");
+    assert!(Instant::now() < deadline, "only {pending} bytes arrived");
+    std::thread::sleep(Duration::from_millis(1));
+  }
+
+  let (sender, receiver) = mpsc::channel();
+  socket.recv(vec![0u8; 100]).with_lio(&lio).send_with(sender);
+  let (read, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(read.unwrap(), 100);
+
+  let (sender, receiver) = mpsc::channel();
+  socket.pending_bytes().with_lio(&lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).unwrap(), 0);
+}
@@ -0,0 +1,50 @@
+//! Tests for [`Lio::set_pending_memory_cap`].
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api};
+use std::sync::mpsc;
+
+#[test]
+fn test_over_cap_fails_instead_of_queueing() {
+  let mut lio = Lio::new(64).unwrap();
+  lio.set_max_in_flight(Some(1));
+  // Room for a few parked ops, nowhere near unbounded.
+  lio.set_pending_memory_cap(Some(1024));
+
+  let (sender, receiver) = mpsc::channel();
+  for _ in 0..1000 {
+    api::nop().with_lio(&lio).send_with(sender.clone());
+  }
+  assert_eq!(lio.in_flight(), 1);
+
+  let results: Vec<_> =
+    (0..1000).map(|_| poll_until_recv(&mut lio, &receiver)).collect();
+  let refused = results
+    .iter()
+    .filter(|res| {
+      res.as_ref().is_err_and(|err| err.raw_os_error() == Some(libc::ENOBUFS))
+    })
+    .count();
+  let done = results.iter().filter(|res| res.is_ok()).count();
+
+  assert_eq!(refused + done, 1000, "{results:?}");
+  assert!(refused > 0, "nothing hit the cap");
+  assert!(done > 1, "some ops should have fit under the cap");
+  assert_eq!(lio.in_flight(), 0);
+}
+
+#[test]
+fn test_no_cap_queues_everything() {
+  let mut lio = Lio::new(64).unwrap();
+  lio.set_max_in_flight(Some(1));
+
+  let (sender, receiver) = mpsc::channel();
+  for _ in 0..100 {
+    api::nop().with_lio(&lio).send_with(sender.clone());
+  }
+  for _ in 0..100 {
+    poll_until_recv(&mut lio, &receiver).expect("nop failed");
+  }
+}
@@ -0,0 +1,58 @@
+#![cfg(unix)]
+//! Pipes created through the event loop.
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api};
+use std::{os::fd::AsRawFd, sync::mpsc};
+
+#[test]
+fn test_pipe_carries_data() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::pipe(libc::O_CLOEXEC).with_lio(&lio).send_with(sender);
+  let (read_end, write_end) =
+    poll_until_recv(&mut lio, &receiver).expect("pipe failed");
+  assert_ne!(read_end.as_raw_fd(), write_end.as_raw_fd());
+  let fd_flags = unsafe { libc::fcntl(read_end.as_raw_fd(), libc::F_GETFD) };
+  assert_ne!(fd_flags & libc::FD_CLOEXEC, 0);
+
+  let (sender, receiver) = mpsc::channel();
+  api::write(&write_end, b"through the pipe".to_vec())
+    .with_lio(&lio)
+    .send_with(sender);
+  let (written, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("write failed"), 16);
+
+  let (sender, receiver) = mpsc::channel();
+  api::read(&read_end, vec![0u8; 32]).with_lio(&lio).send_with(sender);
+  let (read, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(read.expect("read failed"), 16);
+  assert_eq!(&buf[..16], b"through the pipe");
+}
+
+#[test]
+fn test_pipe_nonblocking() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::pipe(libc::O_NONBLOCK).with_lio(&lio).send_with(sender);
+  let (read_end, write_end) =
+    poll_until_recv(&mut lio, &receiver).expect("pipe failed");
+  for fd in [&read_end, &write_end] {
+    let fl = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+    assert_ne!(fl & libc::O_NONBLOCK, 0);
+  }
+}
+
+#[test]
+fn test_pipe_rejects_bad_flags() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::pipe(libc::O_CREAT).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{
+  Lio,
+  api::{
+    self,
+    ops::Interest,
+    resource::{AsResource, FromResource},
+  },
+  net::Socket,
+};
+use std::{os::fd::AsRawFd, sync::mpsc};
+
+/// Reads whatever is buffered without blocking, like a TLS `read_tls` would.
+fn read_available(socket: &Socket, out: &mut Vec<u8>) -> bool {
+  let fd = socket.as_resource().as_raw_fd();
+  let mut buf = [0u8; 7];
+  loop {
+    let n = unsafe {
+      libc::recv(fd, buf.as_mut_ptr().cast(), buf.len(), libc::MSG_DONTWAIT)
+    };
+    match n {
+      0 => return true,
+      n if n > 0 => out.extend_from_slice(&buf[..n as usize]),
+      _ => {
+        let err = std::io::Error::last_os_error();
+        assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock, "{err}");
+        return false;
+      }
+    }
+  }
+}
+
+#[test]
+fn test_ready_drives_readiness_loop() {
+  let mut lio = Lio::new(64).unwrap();
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd } =
+    setup_tcp_pair(&mut lio);
+  let socket = Socket::from_resource(accepted_fd);
+
+  let payload: Vec<u8> = (0..100u8).collect();
+  let (sender, receiver) = mpsc::channel();
+  api::send(&client_sock, payload.clone(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), payload.len() as i32);
+  unsafe { libc::shutdown(client_sock.as_raw_fd(), libc::SHUT_WR) };
+
+  let mut received = Vec::new();
+  loop {
+    let (sender, receiver) = mpsc::channel();
+    socket.ready(Interest::READABLE).with_lio(&lio).send_with(sender);
+    let readiness = poll_until_recv(&mut lio, &receiver).expect("poll failed");
+    assert!(readiness.is_readable());
+
+    if read_available(&socket, &mut received) {
+      break;
+    }
+  }
+
+  assert_eq!(received, payload);
+}
+
+#[test]
+fn test_ready_writable() {
+  let mut lio = Lio::new(64).unwrap();
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd: _ } =
+    setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::poll(&client_sock, Interest::READ_AND_WRITE)
+    .with_lio(&lio)
+    .send_with(sender);
+  let readiness = poll_until_recv(&mut lio, &receiver).expect("poll failed");
+
+  // Nothing was sent to the client, so only the write side is ready.
+  assert!(readiness.is_writable());
+  assert!(!readiness.is_readable());
+}
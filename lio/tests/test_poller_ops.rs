@@ -0,0 +1,240 @@
+//! Runs the op surface on the readiness-based backend explicitly.
+//!
+//! On Linux `Lio::new` picks io_uring, so without these the blocking/readiness
+//! path would only be exercised on macOS/BSD.
+
+mod common;
+
+use common::{TempFile, poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, api::resource::Resource, backends::pollingv2::Poller};
+use std::{
+  ffi::CString,
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+  time::{Duration, Instant},
+};
+
+fn poller_lio() -> Lio {
+  Lio::new_with_backend(Poller::new(), 64).unwrap()
+}
+
+fn cwd() -> Resource {
+  unsafe { Resource::from_raw_fd(libc::AT_FDCWD) }
+}
+
+fn create(file: &TempFile) {
+  let fd = unsafe {
+    libc::open(file.path.as_ptr(), libc::O_CREAT | libc::O_RDWR, 0o644)
+  };
+  assert!(fd >= 0, "Failed to create test file");
+  unsafe { libc::close(fd) };
+}
+
+#[test]
+fn test_poller_nop_and_timeout() {
+  let mut lio = poller_lio();
+
+  let (sender, receiver) = mpsc::channel();
+  api::nop().with_lio(&mut lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("nop failed");
+
+  let (sender, receiver) = mpsc::channel();
+  let start = Instant::now();
+  api::timeout(Duration::from_millis(20)).with_lio(&mut lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("timeout failed");
+  assert!(start.elapsed() >= Duration::from_millis(20));
+}
+
+#[test]
+fn test_poller_file_ops() {
+  let mut lio = poller_lio();
+  let file = TempFile::new("poller_file_ops");
+  create(&file);
+
+  let (sender, receiver) = mpsc::channel();
+  api::openat(&cwd(), file.path.clone(), libc::O_RDWR)
+    .with_lio(&mut lio)
+    .send_with(sender);
+  let fd = poll_until_recv(&mut lio, &receiver).expect("openat failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::write(&fd, b"hello world".to_vec()).with_lio(&mut lio).send_with(sender);
+  let (written, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("write failed"), 11);
+
+  let (sender, receiver) = mpsc::channel();
+  api::write_at(&fd, b"HELLO".to_vec(), 0).with_lio(&mut lio).send_with(sender);
+  let (written, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("write_at failed"), 5);
+
+  let (sender, receiver) = mpsc::channel();
+  api::fsync(&fd).with_lio(&mut lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("fsync failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::read_at(&fd, vec![0u8; 11], 0).with_lio(&mut lio).send_with(sender);
+  let (read, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(read.expect("read_at failed"), 11);
+  assert_eq!(&buf[..11], b"HELLO world");
+
+  let (sender, receiver) = mpsc::channel();
+  api::truncate(&fd, 5).with_lio(&mut lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("truncate failed");
+
+  unsafe { libc::lseek(fd.as_raw_fd(), 0, libc::SEEK_SET) };
+  let (sender, receiver) = mpsc::channel();
+  api::read(&fd, vec![0u8; 16]).with_lio(&mut lio).send_with(sender);
+  let (read, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(read.expect("read failed"), 5);
+  assert_eq!(&buf[..5], b"HELLO");
+
+  let region_len = 5;
+  let (sender, receiver) = mpsc::channel();
+  api::mmap(&fd, region_len, libc::PROT_READ, libc::MAP_SHARED, 0)
+    .with_lio(&mut lio)
+    .send_with(sender);
+  let region = poll_until_recv(&mut lio, &receiver).expect("mmap failed");
+  assert_eq!(region.as_slice(), Some(&b"HELLO"[..]));
+
+  let (sender, receiver) = mpsc::channel();
+  api::msync(region, libc::MS_SYNC).with_lio(&mut lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).0.expect("msync failed");
+
+  let raw = unsafe { libc::dup(fd.as_raw_fd()) };
+  let (sender, receiver) = mpsc::channel();
+  api::close(raw).with_lio(&mut lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("close failed");
+}
+
+#[test]
+fn test_poller_link_ops() {
+  let mut lio = poller_lio();
+  let file = TempFile::new("poller_link_target");
+  let hard = TempFile::new("poller_hardlink");
+  let soft = TempFile::new("poller_symlink");
+  let cwd = cwd();
+  create(&file);
+
+  let (sender, receiver) = mpsc::channel();
+  api::linkat(&cwd, file.path.clone(), cwd.clone(), hard.path.clone())
+    .with_lio(&mut lio)
+    .send_with(sender.clone());
+  poll_until_recv(&mut lio, &receiver).expect("linkat failed");
+
+  api::symlinkat(&cwd, file.path.clone(), soft.path.clone())
+    .with_lio(&mut lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("symlinkat failed");
+
+  let mut buf = [0u8; 256];
+  let n = unsafe {
+    libc::readlink(soft.path.as_ptr(), buf.as_mut_ptr().cast(), buf.len())
+  };
+  assert_eq!(&buf[..n as usize], file.path.as_bytes());
+  assert_eq!(unsafe { libc::access(hard.path.as_ptr(), libc::F_OK) }, 0);
+}
+
+#[test]
+fn test_poller_socket_ops() {
+  let mut lio = poller_lio();
+
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd } =
+    setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::send(&client_sock, b"ping".to_vec(), None)
+    .with_lio(&mut lio)
+    .send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), 4);
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&accepted_fd, vec![0u8; 16], None)
+    .with_lio(&mut lio)
+    .send_with(sender);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(received.expect("recv failed"), 4);
+  assert_eq!(&buf[..4], b"ping");
+
+  let (sender, receiver) = mpsc::channel();
+  api::shutdown(&client_sock, libc::SHUT_WR)
+    .with_lio(&mut lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("shutdown failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&accepted_fd, vec![0u8; 16], None)
+    .with_lio(&mut lio)
+    .send_with(sender);
+  let (received, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(received.expect("recv after shutdown failed"), 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_poller_tee() {
+  let mut lio = poller_lio();
+
+  let mut in_fds = [0; 2];
+  let mut out_fds = [0; 2];
+  unsafe {
+    assert_eq!(libc::pipe(in_fds.as_mut_ptr()), 0);
+    assert_eq!(libc::pipe(out_fds.as_mut_ptr()), 0);
+    libc::write(in_fds[1], b"tee data".as_ptr().cast(), 8);
+  }
+  let (in_r, in_w, out_r, out_w) = unsafe {
+    (
+      Resource::from_raw_fd(in_fds[0]),
+      Resource::from_raw_fd(in_fds[1]),
+      Resource::from_raw_fd(out_fds[0]),
+      Resource::from_raw_fd(out_fds[1]),
+    )
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  api::tee(&in_r, out_w.clone(), 8).with_lio(&mut lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).expect("tee failed"), 8);
+
+  let mut buf = [0u8; 8];
+  let n = unsafe { libc::read(out_r.as_raw_fd(), buf.as_mut_ptr().cast(), 8) };
+  assert_eq!(n, 8);
+  assert_eq!(&buf, b"tee data");
+  drop((in_w, out_w));
+}
+
+#[test]
+fn test_poller_openat_missing_file() {
+  let mut lio = poller_lio();
+  let (sender, receiver) = mpsc::channel();
+  api::openat(
+    &cwd(),
+    CString::new("lio-definitely-missing-file").unwrap(),
+    libc::O_RDONLY,
+  )
+  .with_lio(&mut lio)
+  .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).expect_err("should fail");
+  assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+}
+
+#[test]
+fn test_poller_sleeps_run_concurrently() {
+  let mut lio = poller_lio();
+
+  let start = Instant::now();
+  let receivers: Vec<_> = (0..3)
+    .map(|_| {
+      let (sender, receiver) = mpsc::channel();
+      api::sleep(Duration::from_millis(60)).with_lio(&lio).send_with(sender);
+      receiver
+    })
+    .collect();
+  for receiver in &receivers {
+    poll_until_recv(&mut lio, receiver).expect("sleep failed");
+  }
+
+  // One after the other would take 180ms.
+  let elapsed = start.elapsed();
+  assert!(elapsed >= Duration::from_millis(60), "woke early: {elapsed:?}");
+  assert!(elapsed < Duration::from_millis(150), "slept in turn: {elapsed:?}");
+}
@@ -0,0 +1,66 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{
+  Lio,
+  api::resource::{FromResource, Resource},
+  net::{Socket, proxy},
+};
+use std::{
+  io::{Read, Write},
+  net::{TcpListener, TcpStream},
+  os::fd::{FromRawFd, IntoRawFd},
+  sync::mpsc,
+  thread,
+};
+
+/// A connected loopback pair, `(client, accepted)`.
+fn loopback_pair() -> (TcpStream, TcpStream) {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+  let (accepted, _) = listener.accept().unwrap();
+  (client, accepted)
+}
+
+fn into_socket(stream: TcpStream) -> Socket {
+  Socket::from_resource(unsafe { Resource::from_raw_fd(stream.into_raw_fd()) })
+}
+
+#[test]
+fn test_proxy_both_ways_until_eof() {
+  let mut lio = Lio::new(64).unwrap();
+  // client <-> [front | proxy | back] <-> server
+  let (mut client, front) = loopback_pair();
+  let (back, mut server) = loopback_pair();
+
+  let (sender, receiver) = mpsc::channel();
+  proxy(into_socket(front), into_socket(back)).with_lio(&lio).send_with(sender);
+  lio.try_run().unwrap();
+
+  let request: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+  let expected = request.clone();
+  let client = thread::spawn(move || {
+    client.write_all(&request).unwrap();
+    let mut response = [0u8; 4];
+    client.read_exact(&mut response).unwrap();
+    assert_eq!(&response, b"pong");
+    // Closing the client ends the proxy.
+  });
+
+  let mut received = vec![0u8; expected.len()];
+  server.read_exact(&mut received).unwrap();
+  assert!(received == expected, "request corrupted in transit");
+  server.write_all(b"pong").unwrap();
+  client.join().unwrap();
+
+  let (up, down) = poll_until_recv(&mut lio, &receiver).expect("proxy failed");
+  assert_eq!(up, expected.len() as u64);
+  assert_eq!(down, 4);
+
+  // The client's EOF was passed on.
+  let mut rest = Vec::new();
+  server.read_to_end(&mut rest).unwrap();
+  assert!(rest.is_empty());
+}
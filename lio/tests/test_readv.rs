@@ -0,0 +1,60 @@
+mod common;
+
+use common::{pipe, poll_until_recv};
+use lio::{
+  Lio,
+  api::{self, resource::Resource},
+};
+use std::{os::fd::AsRawFd, sync::mpsc};
+
+fn write_all(fd: &Resource, data: &[u8]) {
+  let n =
+    unsafe { libc::write(fd.as_raw_fd(), data.as_ptr().cast(), data.len()) };
+  assert_eq!(n as usize, data.len());
+}
+
+#[test]
+fn test_readv_fills_buffers_in_order() {
+  let mut lio = Lio::new(64).unwrap();
+  let (read_end, write_end) = pipe();
+  write_all(&write_end, b"HEADbody bytes");
+
+  let bufs = vec![Vec::with_capacity(4), Vec::with_capacity(64)];
+  let (sender, receiver) = mpsc::channel();
+  api::readv(&read_end, bufs).with_lio(&lio).send_with(sender);
+  let (read, bufs) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(read.expect("readv failed"), 14);
+  assert_eq!(bufs[0], b"HEAD");
+  assert_eq!(bufs[1], b"body bytes");
+}
+
+#[test]
+fn test_readv_short_read_leaves_later_buffers_empty() {
+  let mut lio = Lio::new(64).unwrap();
+  let (read_end, write_end) = pipe();
+  write_all(&write_end, b"ab");
+
+  let bufs = vec![Vec::with_capacity(4), Vec::with_capacity(4)];
+  let (sender, receiver) = mpsc::channel();
+  api::readv(&read_end, bufs).with_lio(&lio).send_with(sender);
+  let (read, bufs) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(read.expect("readv failed"), 2);
+  assert_eq!(bufs[0], b"ab");
+  assert!(bufs[1].is_empty());
+}
+
+#[test]
+fn test_readv_empty_list_completes_with_zero() {
+  let mut lio = Lio::new(64).unwrap();
+  // Nothing is ever written, so this would block if it reached the pipe.
+  let (read_end, _write_end) = pipe();
+
+  let (sender, receiver) = mpsc::channel();
+  api::readv(&read_end, Vec::<Vec<u8>>::new()).with_lio(&lio).send_with(sender);
+  let (read, bufs) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(read.expect("readv failed"), 0);
+  assert!(bufs.is_empty());
+}
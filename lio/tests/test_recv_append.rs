@@ -0,0 +1,64 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, api::resource::FromResource, net::Socket};
+use std::sync::mpsc;
+
+fn send_all(lio: &mut Lio, sock: &lio::api::resource::Resource, data: &[u8]) {
+  let (sender, receiver) = mpsc::channel();
+  api::send(sock, data.to_vec(), None).with_lio(lio).send_with(sender);
+  let (sent, _) = poll_until_recv(lio, &receiver);
+  assert_eq!(sent.expect("send failed") as usize, data.len());
+}
+
+#[test]
+fn test_recv_append_twice() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let server = Socket::from_resource(pair.accepted_fd);
+  let (sender, receiver) = mpsc::channel();
+
+  send_all(&mut lio, &pair.client_sock, b"hello ");
+  server
+    .recv_append(Vec::with_capacity(64), 0)
+    .with_lio(&lio)
+    .send_with(sender.clone());
+  let (filled, buf) = poll_until_recv(&mut lio, &receiver);
+  let filled = filled.expect("first recv failed");
+  assert_eq!(filled, 6);
+  assert_eq!(buf.len(), 6);
+  let cap = buf.capacity();
+
+  send_all(&mut lio, &pair.client_sock, b"world");
+  server.recv_append(buf, filled).with_lio(&lio).send_with(sender);
+  let (filled, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(filled.expect("second recv failed"), 11);
+  assert_eq!(buf, b"hello world");
+  assert_eq!(buf.capacity(), cap, "the buffer should not be reallocated");
+}
+
+#[test]
+fn test_recv_append_overwrites_past_offset() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let server = Socket::from_resource(pair.accepted_fd);
+
+  let mut buf = Vec::with_capacity(32);
+  buf.extend_from_slice(b"abcXXX");
+
+  send_all(&mut lio, &pair.client_sock, b"def");
+  let (sender, receiver) = mpsc::channel();
+  server.recv_append(buf, 3).with_lio(&lio).send_with(sender);
+  let (filled, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(filled.expect("recv failed"), 6);
+  assert_eq!(buf, b"abcdef");
+}
+
+#[test]
+#[should_panic(expected = "past the buffer capacity")]
+fn test_recv_append_offset_past_capacity() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let server = Socket::from_resource(pair.accepted_fd);
+  let _ = server.recv_append(Vec::new(), 1);
+}
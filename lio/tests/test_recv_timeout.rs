@@ -0,0 +1,59 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, api::resource::FromResource, net::Socket};
+use std::{
+  io,
+  sync::mpsc,
+  time::{Duration, Instant},
+};
+
+#[test]
+fn test_recv_timeout_on_idle_socket() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let idle = Socket::from_resource(pair.accepted_fd);
+
+  let start = Instant::now();
+  let (sender, receiver) = mpsc::channel();
+  idle
+    .recv_timeout(vec![0u8; 16], Duration::from_millis(100))
+    .with_lio(&lio)
+    .send_with(sender.clone());
+  let (result, buf) = poll_until_recv(&mut lio, &receiver);
+
+  assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+  assert!(start.elapsed() >= Duration::from_millis(100));
+  assert_eq!(buf.len(), 16, "buffer should come back untouched");
+
+  // The socket is still open: data sent now is received as usual.
+  let (sent_tx, sent_rx) = mpsc::channel();
+  api::send(&pair.client_sock, b"still here".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sent_tx);
+  let (sent, _) = poll_until_recv(&mut lio, &sent_rx);
+  assert_eq!(sent.expect("send failed"), 10);
+
+  idle
+    .recv_timeout(buf, Duration::from_secs(5))
+    .with_lio(&lio)
+    .send_with(sender);
+  let (result, buf) = poll_until_recv(&mut lio, &receiver);
+  let n = result.expect("recv after timeout failed") as usize;
+  assert_eq!(&buf[..n], b"still here");
+}
+
+#[test]
+fn test_send_timeout_completes_when_writable() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let client = Socket::from_resource(pair.client_sock);
+
+  let (sender, receiver) = mpsc::channel();
+  client
+    .send_timeout(b"ping".to_vec(), Duration::from_secs(5))
+    .with_lio(&lio)
+    .send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), 4);
+}
@@ -0,0 +1,59 @@
+//! Scheduling new operations from inside a completion callback.
+
+mod common;
+
+use common::pipe;
+use lio::{Lio, api};
+use std::{
+  os::fd::AsRawFd,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+    mpsc,
+  },
+  time::Duration,
+};
+
+#[test]
+fn test_read_callback_submits_write() {
+  let lio = Lio::new(64).unwrap();
+  let driver = lio.clone();
+  // Callbacks must be Send, so they reach the driver through the global.
+  lio::install_global(lio);
+
+  let (in_r, in_w) = pipe();
+  let (out_r, out_w) = pipe();
+  unsafe { libc::write(in_w.as_raw_fd(), b"ping".as_ptr().cast(), 4) };
+
+  let read_done = Arc::new(AtomicBool::new(false));
+  let (sender, receiver) = mpsc::channel();
+  {
+    let read_done = read_done.clone();
+    api::read(&in_r, vec![0u8; 16]).when_done(move |(res, buf)| {
+      let n = res.expect("read failed") as usize;
+      read_done.store(true, Ordering::SeqCst);
+      api::write(&out_w, buf[..n].to_vec()).send_with(sender);
+    });
+  }
+
+  while !read_done.load(Ordering::SeqCst) {
+    driver.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+  // The write was only submitted during that tick, it completes on a later one.
+  assert!(receiver.try_recv().is_err());
+
+  let (written, _) = loop {
+    driver.run_timeout(Duration::from_millis(5)).unwrap();
+    if let Ok(result) = receiver.try_recv() {
+      break result;
+    }
+  };
+  assert_eq!(written.expect("write failed"), 4);
+
+  let mut buf = [0u8; 4];
+  let n = unsafe { libc::read(out_r.as_raw_fd(), buf.as_mut_ptr().cast(), 4) };
+  assert_eq!(n, 4);
+  assert_eq!(&buf, b"ping");
+
+  lio::uninstall_global();
+}
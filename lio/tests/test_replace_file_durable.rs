@@ -0,0 +1,65 @@
+use lio::{Lio, api};
+use std::ffi::CString;
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+fn block_on<F: Future>(
+  lio: &Lio,
+  fut: F,
+  mut each_poll: impl FnMut(),
+) -> F::Output {
+  let mut fut = std::pin::pin!(fut);
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut attempts = 0;
+  loop {
+    if let Poll::Ready(result) = fut.as_mut().poll(&mut cx) {
+      return result;
+    }
+    each_poll();
+    attempts += 1;
+    assert!(attempts < 1000, "future never finished");
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+}
+
+#[test]
+fn test_replace_file_durable_never_exposes_torn_state() {
+  let lio = Lio::new(64).unwrap();
+
+  let dir = std::env::temp_dir()
+    .join(format!("lio_test_replace_durable_{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let target = dir.join("state");
+
+  let old = b"old contents".repeat(64);
+  let new = b"new, longer contents".repeat(4096);
+  std::fs::write(&target, &old).unwrap();
+
+  let path = CString::new(target.to_str().unwrap()).unwrap();
+  let result =
+    block_on(&lio, api::replace_file_durable(path, new.clone(), &lio), || {
+      // Every step in between must show one version, whole.
+      let seen = std::fs::read(&target).unwrap();
+      assert!(seen == old || seen == new, "saw a torn file");
+    });
+  result.expect("replace_file_durable failed");
+
+  assert_eq!(std::fs::read(&target).unwrap(), new);
+  let leftovers: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+  assert_eq!(leftovers.len(), 1, "temporary file was left behind");
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_replace_file_durable_missing_dir_fails() {
+  let lio = Lio::new(64).unwrap();
+
+  let path =
+    CString::new("/nonexistent/lio_test_replace_durable/state").unwrap();
+  let err =
+    block_on(&lio, api::replace_file_durable(path, b"x".to_vec(), &lio), || {})
+      .unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
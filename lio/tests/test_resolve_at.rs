@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api};
+use std::{
+  ffi::CString,
+  os::unix::ffi::OsStrExt,
+  path::{Path, PathBuf},
+  sync::mpsc,
+};
+
+/// `base/dir`, `base/sibling/file` and `base/dir/link -> ../sibling`.
+fn make_tree(name: &str) -> PathBuf {
+  let base = std::env::temp_dir()
+    .join(format!("lio_resolve_at_{name}_{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&base);
+  std::fs::create_dir_all(base.join("dir")).unwrap();
+  std::fs::create_dir_all(base.join("sibling")).unwrap();
+  std::fs::write(base.join("sibling/file"), b"").unwrap();
+  std::os::unix::fs::symlink("../sibling", base.join("dir/link")).unwrap();
+  base
+}
+
+fn resolve(lio: &mut Lio, dir: &Path, path: &str) -> std::io::Result<CString> {
+  let (sender, receiver) = mpsc::channel();
+  api::open_dir(CString::new(dir.as_os_str().as_bytes()).unwrap())
+    .with_lio(lio)
+    .send_with(sender);
+  let handle = poll_until_recv(lio, &receiver).expect("open_dir failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::resolve_at(&handle, CString::new(path).unwrap())
+    .with_lio(lio)
+    .send_with(sender);
+  poll_until_recv(lio, &receiver)
+}
+
+fn expected(path: PathBuf) -> CString {
+  let canonical = std::fs::canonicalize(path).unwrap();
+  CString::new(canonical.as_os_str().as_bytes()).unwrap()
+}
+
+#[test]
+fn test_resolve_at_parent_then_sibling() {
+  let mut lio = Lio::new(64).unwrap();
+  let base = make_tree("parent");
+
+  let resolved = resolve(&mut lio, &base.join("dir"), "../sibling/file")
+    .expect("resolve_at failed");
+  assert_eq!(resolved, expected(base.join("sibling/file")));
+
+  let resolved = resolve(&mut lio, &base.join("dir"), "./link/../dir/.")
+    .expect("resolve_at failed");
+  assert_eq!(resolved, expected(base.join("dir")));
+
+  std::fs::remove_dir_all(base).unwrap();
+}
+
+#[test]
+fn test_resolve_at_follows_symlinks() {
+  let mut lio = Lio::new(64).unwrap();
+  let base = make_tree("symlink");
+
+  let resolved = resolve(&mut lio, &base.join("dir"), "link/file")
+    .expect("resolve_at failed");
+  assert_eq!(resolved, expected(base.join("sibling/file")));
+
+  let err = resolve(&mut lio, &base.join("dir"), "missing/file").unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+  std::fs::remove_dir_all(base).unwrap();
+}
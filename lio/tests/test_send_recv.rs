@@ -3,7 +3,10 @@
 mod common;
 
 use common::{poll_recv, poll_until_recv, setup_tcp_pair};
-use lio::{Lio, api};
+use lio::{
+  Lio, api,
+  api::flags::{RecvFlags, SendFlags},
+};
 use std::sync::mpsc;
 
 #[test]
@@ -123,12 +126,15 @@ fn test_send_recv_with_flags() {
   let common::TcpPair { server_sock: _, client_sock, accepted_fd } =
     setup_tcp_pair(&mut lio);
 
-  // Send with MSG_NOSIGNAL flag (prevents SIGPIPE)
+  // Send with MSG_NOSIGNAL (prevents SIGPIPE) and MSG_DONTWAIT
   let data = b"Data with flags".to_vec();
   let data_len = data.len();
 
+  let flags = SendFlags::NOSIGNAL | SendFlags::DONTWAIT;
+  assert_eq!(flags.bits(), libc::MSG_NOSIGNAL | libc::MSG_DONTWAIT);
+
   let (sender_send, receiver_send) = mpsc::channel();
-  api::send(&client_sock, data.clone(), Some(libc::MSG_NOSIGNAL))
+  api::send(&client_sock, data.clone(), Some(flags))
     .with_lio(&mut lio)
     .send_with(sender_send);
 
@@ -137,9 +143,9 @@ fn test_send_recv_with_flags() {
 
   assert_eq!(bytes_sent, data_len);
 
-  // Receive with flags (0 is valid)
+  // Receive with no flags set
   let (sender_recv, receiver_recv) = mpsc::channel();
-  api::recv(&accepted_fd, vec![0u8; 64], Some(0))
+  api::recv(&accepted_fd, vec![0u8; 64], Some(RecvFlags::NONE))
     .with_lio(&mut lio)
     .send_with(sender_recv);
 
@@ -152,6 +158,36 @@ fn test_send_recv_with_flags() {
   assert_eq!(&received_buf[..bytes_received], data.as_slice());
 }
 
+#[test]
+fn test_recv_peek_leaves_data_queued() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd } =
+    setup_tcp_pair(&mut lio);
+
+  let data = b"peekaboo".to_vec();
+  let (sender_send, receiver_send) = mpsc::channel();
+  api::send(&client_sock, data.clone(), None)
+    .with_lio(&mut lio)
+    .send_with(sender_send);
+  poll_until_recv(&mut lio, &receiver_send).0.expect("Failed to send");
+
+  let (sender_recv, receiver_recv) = mpsc::channel();
+  api::recv(&accepted_fd, vec![0u8; 64], Some(RecvFlags::PEEK))
+    .with_lio(&mut lio)
+    .send_with(sender_recv.clone());
+  let (peeked, _) = poll_until_recv(&mut lio, &receiver_recv);
+  assert!(peeked.expect("Failed to peek") > 0);
+
+  // The peeked bytes are still there for a regular recv.
+  api::recv(&accepted_fd, vec![0u8; 64], None)
+    .with_lio(&mut lio)
+    .send_with(sender_recv);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver_recv);
+  let received = received.expect("Failed to receive") as usize;
+  assert_eq!(&buf[..received], &data[..received]);
+}
+
 #[test]
 fn test_recv_on_closed_connection() {
   let mut lio = Lio::new(64).unwrap();
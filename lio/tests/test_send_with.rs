@@ -0,0 +1,68 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api, api::io::Sender};
+use std::{
+  sync::{Arc, Mutex, mpsc},
+  time::Duration,
+};
+
+#[test]
+fn test_send_with_std_mpsc_shared_sender() {
+  let mut lio = Lio::new(64).unwrap();
+  let (sender, receiver) = mpsc::channel();
+
+  for _ in 0..3 {
+    api::nop().with_lio(&lio).send_with(sender.clone());
+  }
+  drop(sender);
+
+  for _ in 0..3 {
+    poll_until_recv(&mut lio, &receiver).expect("nop failed");
+  }
+}
+
+#[test]
+fn test_send_with_sync_sender() {
+  let mut lio = Lio::new(64).unwrap();
+  let (sender, receiver) = mpsc::sync_channel(1);
+
+  api::nop().with_lio(&lio).send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("nop failed");
+}
+
+#[test]
+fn test_send_with_crossbeam_sender() {
+  let lio = Lio::new(64).unwrap();
+  let (sender, receiver) = crossbeam_channel::unbounded();
+
+  api::nop().with_lio(&lio).send_with(sender);
+  let result = loop {
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+    if let Ok(result) = receiver.try_recv() {
+      break result;
+    }
+  };
+  result.expect("nop failed");
+}
+
+/// A channel lio knows nothing about, plugged in through the trait.
+struct Collect(Arc<Mutex<Vec<std::io::Result<()>>>>);
+
+impl Sender<std::io::Result<()>> for Collect {
+  fn send(&self, value: std::io::Result<()>) {
+    self.0.lock().unwrap().push(value);
+  }
+}
+
+#[test]
+fn test_send_with_custom_sender() {
+  let lio = Lio::new(64).unwrap();
+  let results = Arc::new(Mutex::new(Vec::new()));
+
+  api::nop().with_lio(&lio).send_with(Collect(results.clone()));
+  while results.lock().unwrap().is_empty() {
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  }
+  assert!(matches!(results.lock().unwrap().as_slice(), [Ok(())]));
+}
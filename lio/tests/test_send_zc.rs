@@ -0,0 +1,69 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, backends::pollingv2::Poller};
+use std::{
+  io::Read,
+  net::TcpStream,
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+  thread,
+};
+
+fn send_zc_large(mut lio: Lio) {
+  let pair = setup_tcp_pair(&mut lio);
+  let payload: Vec<u8> = (0..256 * 1024).map(|i| (i % 251) as u8).collect();
+
+  // Drain the other end so the whole payload fits.
+  let fd = unsafe { libc::dup(pair.accepted_fd.as_raw_fd()) };
+  let reader = thread::spawn(move || {
+    let mut stream = unsafe { TcpStream::from_raw_fd(fd) };
+    let mut got = Vec::new();
+    stream.read_to_end(&mut got).unwrap();
+    got
+  });
+
+  let mut sent_total = 0;
+  while sent_total < payload.len() {
+    let (sender, receiver) = mpsc::channel();
+    api::send_zc(&pair.client_sock, payload[sent_total..].to_vec(), None)
+      .with_lio(&lio)
+      .send_with(sender);
+    let (sent, _) = poll_until_recv(&mut lio, &receiver);
+    let sent = sent.expect("send_zc failed") as usize;
+    assert!(sent > 0);
+    sent_total += sent;
+  }
+  // Every send completed exactly once, nothing is left waiting.
+  assert_eq!(lio.in_flight(), 0);
+
+  let (sender, receiver) = mpsc::channel();
+  api::shutdown(&pair.client_sock, libc::SHUT_WR)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("shutdown failed");
+
+  assert_eq!(reader.join().unwrap(), payload);
+}
+
+#[test]
+fn test_send_zc_large_payload() {
+  send_zc_large(Lio::new(64).unwrap());
+}
+
+#[test]
+fn test_send_zc_large_payload_poller() {
+  send_zc_large(Lio::new_with_backend(Poller::new(), 64).unwrap());
+}
+
+#[test]
+fn test_send_zc_not_a_socket() {
+  let mut lio = Lio::new(64).unwrap();
+  let (sender, receiver) = mpsc::channel();
+  api::send_zc(&api::resource::Resource::stdin(), b"x".to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (sent, buf) = poll_until_recv(&mut lio, &receiver);
+  assert!(sent.is_err());
+  assert_eq!(buf, b"x", "the buffer comes back on errors too");
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::{pipe, poll_until_recv};
+use lio::{
+  Lio,
+  api::{self, ops::ControlMessage, resource::Resource},
+};
+use std::{
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+};
+
+fn socketpair() -> (Resource, Resource) {
+  let mut fds = [0; 2];
+  assert_eq!(
+    unsafe {
+      libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, fds.as_mut_ptr())
+    },
+    0
+  );
+  unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) }
+}
+
+#[test]
+fn test_sendmsg_passes_fd() {
+  let mut lio = Lio::new(64).unwrap();
+  let (left, right) = socketpair();
+  let (read_end, write_end) = pipe();
+
+  let (sender, receiver) = mpsc::channel();
+  let cmsgs = vec![ControlMessage::ScmRights(vec![write_end.as_raw_fd()])];
+  api::sendmsg(&left, vec![b"fd".to_vec()], None, cmsgs)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("sendmsg failed"), 2);
+
+  let (sender, receiver) = mpsc::channel();
+  let bufs = vec![Vec::with_capacity(16)];
+  api::recvmsg(&right, bufs, ControlMessage::rights_space(1))
+    .with_lio(&lio)
+    .send_with(sender);
+  let (received, bufs) = poll_until_recv(&mut lio, &receiver);
+  let received = received.expect("recvmsg failed");
+  assert_eq!(received.len, 2);
+  assert_eq!(bufs[0], b"fd");
+  assert_eq!(received.flags & libc::MSG_CTRUNC, 0);
+
+  let [ControlMessage::ScmRights(fds)] = received.control.as_slice() else {
+    panic!("expected one SCM_RIGHTS message, got {:?}", received.control);
+  };
+  assert_eq!(fds.len(), 1);
+  assert_ne!(fds[0], write_end.as_raw_fd());
+
+  // The received descriptor writes into the same pipe.
+  let passed = unsafe { Resource::from_raw_fd(fds[0]) };
+  drop(write_end);
+  let n = unsafe { libc::write(passed.as_raw_fd(), b"hi".as_ptr().cast(), 2) };
+  assert_eq!(n, 2);
+  let mut out = [0u8; 2];
+  let n =
+    unsafe { libc::read(read_end.as_raw_fd(), out.as_mut_ptr().cast(), 2) };
+  assert_eq!(n, 2);
+  assert_eq!(&out, b"hi");
+}
+
+#[test]
+fn test_sendmsg_without_control_gathers_buffers() {
+  let mut lio = Lio::new(64).unwrap();
+  let (left, right) = socketpair();
+
+  let (sender, receiver) = mpsc::channel();
+  let bufs = vec![b"head".to_vec(), b"body".to_vec()];
+  api::sendmsg(&left, bufs, None, Vec::new()).with_lio(&lio).send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("sendmsg failed"), 8);
+
+  let (sender, receiver) = mpsc::channel();
+  let bufs = vec![Vec::with_capacity(4), Vec::with_capacity(16)];
+  api::recvmsg(&right, bufs, 0).with_lio(&lio).send_with(sender);
+  let (received, bufs) = poll_until_recv(&mut lio, &receiver);
+  let received = received.expect("recvmsg failed");
+  assert_eq!(received.len, 8);
+  assert!(received.control.is_empty());
+  assert_eq!(bufs[0], b"head");
+  assert_eq!(bufs[1], b"body");
+}
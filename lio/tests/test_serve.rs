@@ -0,0 +1,75 @@
+use lio::{
+  Lio,
+  net::{TcpListener, serve},
+};
+use std::{
+  future::Future,
+  io::{ErrorKind, Read, Write},
+  net::TcpStream,
+  pin::pin,
+  task::{Context, Waker},
+  time::{Duration, Instant},
+};
+
+/// Polls `serve` and runs `lio` until `client` receives `expected`.
+fn drive_until_echo<F: Future<Output = std::io::Result<()>>>(
+  lio: &Lio,
+  mut serve: std::pin::Pin<&mut F>,
+  client: &mut TcpStream,
+  expected: &[u8],
+) {
+  let mut cx = Context::from_waker(Waker::noop());
+  let deadline = Instant::now() + Duration::from_secs(5);
+  let mut received = Vec::new();
+  while received.len() < expected.len() {
+    assert!(Instant::now() < deadline, "no echo of {expected:?}");
+    assert!(serve.as_mut().poll(&mut cx).is_pending());
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+    let mut buf = [0u8; 16];
+    match client.read(&mut buf) {
+      Ok(n) => received.extend_from_slice(&buf[..n]),
+      Err(err) if err.kind() == ErrorKind::WouldBlock => {}
+      Err(err) => panic!("client read failed: {err}"),
+    }
+  }
+  assert_eq!(received, expected);
+}
+
+#[test]
+fn test_serve_handles_connections_concurrently() {
+  let lio = Lio::new(64).unwrap();
+  lio::install_global(lio.clone());
+
+  let listener = TcpListener::bind_sync("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  // Echoes the first message of each connection.
+  let server = serve(listener, |socket| async move {
+    let (result, buf) = socket.recv(vec![0u8; 16]).await;
+    let n = result.expect("recv failed") as usize;
+    let (result, _) = socket.send(buf[..n].to_vec()).await;
+    result.expect("send failed");
+  })
+  .with_lio(&lio);
+  let mut server = pin!(server);
+
+  let mut first = TcpStream::connect(addr).unwrap();
+  let mut second = TcpStream::connect(addr).unwrap();
+  first.set_nonblocking(true).unwrap();
+  second.set_nonblocking(true).unwrap();
+
+  // The first client stays silent. Served one after the other, the second
+  // client would wait behind it forever.
+  second.write_all(b"second").unwrap();
+  drive_until_echo(&lio, server.as_mut(), &mut second, b"second");
+  assert_eq!(server.connections(), 1, "only the silent client is left");
+
+  first.write_all(b"first").unwrap();
+  drive_until_echo(&lio, server.as_mut(), &mut first, b"first");
+
+  let mut cx = Context::from_waker(Waker::noop());
+  assert!(server.as_mut().poll(&mut cx).is_pending());
+  assert_eq!(server.connections(), 0);
+
+  lio::uninstall_global();
+}
@@ -0,0 +1,80 @@
+#![cfg(unix)]
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, net::TcpListener};
+use std::sync::mpsc;
+
+fn get_int(
+  lio: &mut Lio,
+  res: &api::resource::Resource,
+  level: i32,
+  name: i32,
+) -> i32 {
+  let (sender, receiver) = mpsc::channel();
+  api::getsockopt(res, level, name).with_lio(&*lio).send_with(sender);
+  let raw = poll_until_recv(lio, &receiver).expect("getsockopt failed");
+  libc::c_int::from_ne_bytes(raw[..4].try_into().unwrap())
+}
+
+#[test]
+fn test_setsockopt_round_trips() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+
+  let (sender, receiver) = mpsc::channel();
+  api::setsockopt(
+    &pair.client_sock,
+    libc::IPPROTO_TCP,
+    libc::TCP_NODELAY,
+    1i32.to_ne_bytes().to_vec(),
+  )
+  .with_lio(&lio)
+  .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("setsockopt failed");
+
+  let nodelay =
+    get_int(&mut lio, &pair.client_sock, libc::IPPROTO_TCP, libc::TCP_NODELAY);
+  assert_ne!(nodelay, 0);
+}
+
+#[test]
+fn test_setsockopt_not_a_socket() {
+  let mut lio = Lio::new(64).unwrap();
+  let stdin = api::resource::Resource::stdin();
+
+  let (sender, receiver) = mpsc::channel();
+  api::setsockopt(
+    &stdin,
+    libc::SOL_SOCKET,
+    libc::SO_REUSEADDR,
+    vec![1, 0, 0, 0],
+  )
+  .with_lio(&lio)
+  .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::ENOTSOCK));
+}
+
+#[test]
+fn test_listener_builder_reuseport() {
+  let first =
+    TcpListener::builder().reuseport(true).bind_sync("127.0.0.1:0").unwrap();
+  let addr = first.local_addr().unwrap();
+
+  // Without SO_REUSEPORT the port is taken. The BSDs set it on every
+  // stream socket already.
+  #[cfg(target_os = "linux")]
+  {
+    let err = TcpListener::bind_sync(addr).err().expect("bound a taken port");
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+  }
+
+  let second = TcpListener::builder()
+    .reuseaddr(true)
+    .reuseport(true)
+    .nodelay(true)
+    .bind_sync(addr)
+    .unwrap();
+  assert_eq!(second.local_addr().unwrap(), addr);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, buf::BufStore};
+use std::{os::fd::AsRawFd, sync::mpsc};
+
+fn recv_exact(fd: &impl AsRawFd, len: usize) -> Vec<u8> {
+  let mut buf = vec![0u8; len];
+  let n = unsafe {
+    libc::recv(fd.as_raw_fd(), buf.as_mut_ptr().cast(), len, libc::MSG_WAITALL)
+  };
+  assert_eq!(n, len as isize);
+  buf
+}
+
+#[test]
+fn test_shared_buf_fans_out_without_copy() {
+  let mut lio = Lio::new(64).unwrap();
+  let pool: &'static BufStore = Box::leak(Box::new(BufStore::with_capacity(2)));
+  let publisher = setup_tcp_pair(&mut lio);
+  let subscribers = [setup_tcp_pair(&mut lio), setup_tcp_pair(&mut lio)];
+
+  let message = b"hello subscribers";
+  let (sender, receiver) = mpsc::channel();
+  api::send(&publisher.client_sock, message.to_vec(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).0.expect("send failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&publisher.accepted_fd, pool.try_get().unwrap(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(received.expect("recv failed"), message.len() as i32);
+
+  let shared = buf.share();
+  let payload = shared.as_ref().as_ptr();
+  assert_eq!(shared.as_ref(), message);
+
+  let (sender, receiver) = mpsc::channel();
+  for sub in &subscribers {
+    api::send(&sub.client_sock, shared.clone(), None)
+      .with_lio(&lio)
+      .send_with(sender.clone());
+  }
+  assert_eq!(shared.ref_count(), 3);
+  for _ in &subscribers {
+    let (sent, returned) = poll_until_recv(&mut lio, &receiver);
+    assert_eq!(sent.expect("send failed"), message.len() as i32);
+    // Every send went out of the same pooled memory.
+    assert_eq!(returned.as_ref().as_ptr(), payload);
+  }
+
+  for sub in &subscribers {
+    assert_eq!(recv_exact(&sub.accepted_fd, message.len()), message);
+  }
+
+  assert_eq!(pool.available(), 1);
+  drop(shared);
+  assert_eq!(pool.available(), 2);
+}
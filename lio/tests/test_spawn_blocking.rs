@@ -0,0 +1,72 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::Lio;
+use std::{
+  future::{Future, IntoFuture},
+  pin::pin,
+  sync::mpsc,
+  task::{Context, Poll, Waker},
+  thread,
+  time::Duration,
+};
+
+fn sum_of_squares(n: u64) -> u64 {
+  (0..n).fold(0u64, |acc, i| acc.wrapping_add(i.wrapping_mul(i)))
+}
+
+#[test]
+fn test_spawn_blocking_await_cpu_loop() {
+  let lio = Lio::new(64).unwrap();
+  let caller = thread::current().id();
+
+  let mut future = pin!(
+    lio::spawn_blocking(move || {
+      assert_ne!(thread::current().id(), caller);
+      sum_of_squares(5_000_000)
+    })
+    .with_lio(&lio)
+    .into_future()
+  );
+
+  let mut cx = Context::from_waker(Waker::noop());
+  let result = loop {
+    if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+      break result;
+    }
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+  };
+  assert_eq!(result.expect("spawn_blocking failed"), sum_of_squares(5_000_000));
+}
+
+#[test]
+fn test_spawn_blocking_callbacks_complete_independently() {
+  let mut lio = Lio::new(64).unwrap();
+  let (sender, receiver) = mpsc::channel();
+
+  for i in 0..8u64 {
+    lio::spawn_blocking(move || i * i).with_lio(&lio).send_with(sender.clone());
+  }
+
+  let mut results: Vec<u64> = (0..8)
+    .map(|_| {
+      poll_until_recv(&mut lio, &receiver).expect("spawn_blocking failed")
+    })
+    .collect();
+  results.sort();
+  assert_eq!(results, [0, 1, 4, 9, 16, 25, 36, 49]);
+}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn test_spawn_blocking_resumes_panic() {
+  let lio = Lio::new(64).unwrap();
+  let mut receiver =
+    lio::spawn_blocking(|| -> u8 { panic!("boom") }).with_lio(&lio).send();
+  loop {
+    lio.run_timeout(Duration::from_millis(5)).unwrap();
+    if receiver.try_recv().is_some() {
+      break;
+    }
+  }
+}
@@ -0,0 +1,68 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::poll_until_recv;
+use lio::api::ops::Interest;
+use lio::api::resource::{AsResource, Resource};
+use lio::{Lio, api};
+use std::ffi::CString;
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::sync::mpsc;
+
+/// Waits for the child behind `pidfd` to exit and returns its exit status.
+fn wait_exit(lio: &mut Lio, pidfd: &impl AsResource) -> i32 {
+  let (sender, receiver) = mpsc::channel();
+  api::poll(pidfd, Interest::READABLE).with_lio(lio).send_with(sender);
+  poll_until_recv(lio, &receiver).expect("poll on pidfd failed");
+
+  let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+  let fd = pidfd.as_resource().as_raw_fd();
+  let ret =
+    unsafe { libc::waitid(libc::P_PIDFD, fd as _, &mut info, libc::WEXITED) };
+  assert_eq!(ret, 0, "waitid failed: {}", std::io::Error::last_os_error());
+  unsafe { info.si_status() }
+}
+
+#[test]
+fn test_spawn_process_true() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::spawn_process(CString::new("/bin/true").unwrap(), vec![], vec![])
+    .with_lio(&lio)
+    .send_with(sender);
+  let (pid, pidfd) =
+    poll_until_recv(&mut lio, &receiver).expect("spawn_process failed");
+
+  assert!(pid > 0);
+  assert_eq!(wait_exit(&mut lio, &pidfd), 0);
+}
+
+#[test]
+fn test_spawn_process_dup2_stdout() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let mut fds = [0; 2];
+  assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+  let (read, write) =
+    unsafe { (Resource::from_raw_fd(fds[0]), Resource::from_raw_fd(fds[1])) };
+
+  let (sender, receiver) = mpsc::channel();
+  api::spawn_process(
+    CString::new("sh").unwrap(),
+    vec![CString::new("-c").unwrap(), CString::new("echo hi; exit 3").unwrap()],
+    vec![(write, libc::STDOUT_FILENO)],
+  )
+  .with_lio(&lio)
+  .send_with(sender);
+  let (_, pidfd) =
+    poll_until_recv(&mut lio, &receiver).expect("spawn_process failed");
+
+  assert_eq!(wait_exit(&mut lio, &pidfd), 3);
+
+  let (sender, receiver) = mpsc::channel();
+  api::read(&read, vec![0u8; 16]).with_lio(&lio).send_with(sender);
+  let (n, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(&buf[..n.unwrap() as usize], b"hi\n");
+}
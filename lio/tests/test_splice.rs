@@ -0,0 +1,73 @@
+#![cfg(target_os = "linux")]
+//! Zero-copy moves between sockets, pipes and files.
+
+mod common;
+
+use common::{TempFile, poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, api::resource::Resource};
+use std::{
+  os::fd::{AsRawFd, FromRawFd},
+  sync::mpsc,
+};
+
+fn open(temp: &TempFile) -> Resource {
+  let fd = unsafe {
+    libc::open(
+      temp.path.as_ptr(),
+      libc::O_CREAT | libc::O_RDWR | libc::O_TRUNC | libc::O_CLOEXEC,
+      0o644,
+    )
+  };
+  assert!(fd >= 0);
+  unsafe { Resource::from_raw_fd(fd) }
+}
+
+#[test]
+fn test_splice_socket_to_file_through_pipe() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let temp = TempFile::new("splice_socket_to_file");
+  let file = open(&temp);
+
+  let (sender, receiver) = mpsc::channel();
+  api::pipe(libc::O_CLOEXEC).with_lio(&lio).send_with(sender);
+  let (pipe_read, pipe_write) =
+    poll_until_recv(&mut lio, &receiver).expect("pipe failed");
+
+  let sent = unsafe {
+    libc::send(pair.client_sock.as_raw_fd(), b"zero copy".as_ptr().cast(), 9, 0)
+  };
+  assert_eq!(sent, 9);
+
+  let (sender, receiver) = mpsc::channel();
+  api::splice(&pair.accepted_fd, -1, &pipe_write, -1, 64, 0)
+    .with_lio(&lio)
+    .send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).expect("splice in"), 9);
+
+  // Written at offset 4 of the file, past a hole.
+  let (sender, receiver) = mpsc::channel();
+  api::splice(&pipe_read, -1, &file, 4, 9, 0).with_lio(&lio).send_with(sender);
+  assert_eq!(poll_until_recv(&mut lio, &receiver).expect("splice out"), 9);
+
+  let contents = std::fs::read(temp.path.to_str().unwrap()).unwrap();
+  assert_eq!(contents, b"\0\0\0\0zero copy");
+  // An explicit offset leaves the file position alone.
+  assert_eq!(unsafe { libc::lseek(file.as_raw_fd(), 0, libc::SEEK_CUR) }, 0);
+}
+
+#[test]
+fn test_splice_without_pipe_is_einval() {
+  let mut lio = Lio::new(64).unwrap();
+  let first = TempFile::new("splice_einval_in");
+  let second = TempFile::new("splice_einval_out");
+  let (file_in, file_out) = (open(&first), open(&second));
+
+  let (sender, receiver) = mpsc::channel();
+  api::splice(&file_in, 0, &file_out, 0, 16, 0)
+    .with_lio(&lio)
+    .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,123 @@
+//! Tests for [`SqFullPolicy`] against a backend with a tiny submission queue.
+
+use lio::{
+  Lio, SqFullPolicy, api,
+  backends::{IoBackend, OpCompleted},
+  op::Op,
+};
+use std::{io, sync::mpsc, time::Duration};
+
+const SQ_SIZE: usize = 2;
+
+/// Completes every op with `0`, but only accepts `SQ_SIZE` ops between flushes.
+#[derive(Default)]
+struct TinyQueue {
+  queued: Vec<u64>,
+  submitted: Vec<u64>,
+  completed: Vec<OpCompleted>,
+}
+
+impl IoBackend for TinyQueue {
+  fn init(&mut self, _cap: usize) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn push(&mut self, id: u64, _op: Op) -> io::Result<()> {
+    if self.is_full() {
+      return Err(io::Error::new(
+        io::ErrorKind::WouldBlock,
+        "submission queue full",
+      ));
+    }
+    self.queued.push(id);
+    Ok(())
+  }
+
+  fn is_full(&self) -> bool {
+    self.queued.len() >= SQ_SIZE
+  }
+
+  fn flush(&mut self) -> io::Result<usize> {
+    let n = self.queued.len();
+    self.submitted.append(&mut self.queued);
+    Ok(n)
+  }
+
+  fn wait_timeout(
+    &mut self,
+    _timeout: Option<Duration>,
+  ) -> io::Result<&[OpCompleted]> {
+    self.completed.clear();
+    for id in self.submitted.drain(..) {
+      self.completed.push(OpCompleted::new(id, 0));
+    }
+    Ok(&self.completed)
+  }
+}
+
+fn run_until<T>(
+  lio: &Lio,
+  receiver: &mpsc::Receiver<T>,
+  count: usize,
+) -> Vec<T> {
+  let mut results = Vec::with_capacity(count);
+  for _ in 0..100 {
+    lio.try_run().unwrap();
+    results.extend(receiver.try_iter());
+    if results.len() == count {
+      return results;
+    }
+  }
+  panic!("only {} of {} ops completed", results.len(), count);
+}
+
+#[test]
+fn test_policy_error_rejects_overflow() {
+  let lio = Lio::new_with_backend(TinyQueue::default(), 64).unwrap();
+  lio.set_sq_full_policy(SqFullPolicy::Error);
+
+  let (sender, receiver) = mpsc::channel();
+  for _ in 0..SQ_SIZE + 1 {
+    api::nop().with_lio(&lio).send_with(sender.clone());
+  }
+
+  let results = run_until(&lio, &receiver, SQ_SIZE + 1);
+  let errors: Vec<_> =
+    results.iter().filter_map(|r| r.as_ref().err()).collect();
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].raw_os_error(), Some(libc::EBUSY));
+  assert_eq!(errors[0].kind(), io::ErrorKind::ResourceBusy);
+}
+
+#[test]
+fn test_policy_block_submits_to_make_room() {
+  let lio = Lio::new_with_backend(TinyQueue::default(), 64).unwrap();
+  lio.set_sq_full_policy(SqFullPolicy::Block);
+
+  let (sender, receiver) = mpsc::channel();
+  for _ in 0..SQ_SIZE * 3 {
+    api::nop().with_lio(&lio).send_with(sender.clone());
+  }
+
+  let results = run_until(&lio, &receiver, SQ_SIZE * 3);
+  assert!(results.iter().all(|r| r.is_ok()));
+}
+
+#[test]
+fn test_policy_grow_buffers_everything() {
+  let lio = Lio::new_with_backend(TinyQueue::default(), 64).unwrap();
+  lio.set_sq_full_policy(SqFullPolicy::Grow);
+
+  let (sender, receiver) = mpsc::channel();
+  for _ in 0..SQ_SIZE * 5 {
+    api::nop().with_lio(&lio).send_with(sender.clone());
+  }
+
+  let results = run_until(&lio, &receiver, SQ_SIZE * 5);
+  assert!(results.iter().all(|r| r.is_ok()));
+}
+
+#[test]
+fn test_default_policy_is_block() {
+  assert_eq!(SqFullPolicy::default(), SqFullPolicy::Block);
+}
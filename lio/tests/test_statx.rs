@@ -0,0 +1,63 @@
+#![cfg(unix)]
+//! File metadata queried through the event loop.
+
+mod common;
+
+use common::{TempFile, poll_until_recv};
+use lio::{Lio, api, api::resource::Resource};
+use std::{
+  ffi::CString,
+  os::fd::FromRawFd,
+  sync::mpsc,
+  time::{Duration, SystemTime},
+};
+
+#[test]
+fn test_statx_reports_size_mtime_and_mode() {
+  let mut lio = Lio::new(64).unwrap();
+  let temp = TempFile::new("statx");
+  let path = temp.path.to_str().unwrap().to_owned();
+  std::fs::write(&path, b"hello statx").unwrap();
+  let expected = std::fs::metadata(&path).unwrap();
+
+  // SAFETY: AT_FDCWD is never closed.
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+  let (sender, receiver) = mpsc::channel();
+  api::statx(&cwd, temp.path.clone(), 0, u32::MAX)
+    .with_lio(&lio)
+    .send_with(sender);
+  let meta = poll_until_recv(&mut lio, &receiver).expect("statx failed");
+
+  assert_eq!(meta.size, 11);
+  assert!(meta.is_file());
+  assert!(!meta.is_dir());
+  assert_eq!(meta.modified, expected.modified().unwrap());
+  let age = SystemTime::now().duration_since(meta.modified).unwrap();
+  assert!(age < Duration::from_secs(60));
+}
+
+#[test]
+fn test_statx_missing_file() {
+  let mut lio = Lio::new(64).unwrap();
+
+  // SAFETY: AT_FDCWD is never closed.
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+  let (sender, receiver) = mpsc::channel();
+  let path = CString::new("/nonexistent/lio_statx").unwrap();
+  api::statx(&cwd, path, 0, u32::MAX).with_lio(&lio).send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).unwrap_err();
+  assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+}
+
+#[test]
+fn test_statx_directory() {
+  let mut lio = Lio::new(64).unwrap();
+
+  // SAFETY: AT_FDCWD is never closed.
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+  let (sender, receiver) = mpsc::channel();
+  let path = CString::new("/tmp").unwrap();
+  api::statx(&cwd, path, 0, u32::MAX).with_lio(&lio).send_with(sender);
+  let meta = poll_until_recv(&mut lio, &receiver).expect("statx failed");
+  assert!(meta.is_dir());
+}
@@ -0,0 +1,57 @@
+#![cfg(unix)]
+mod common;
+
+use common::{TempFile, poll_until_recv};
+use lio::{Lio, api, api::resource::Resource};
+use std::{os::fd::FromRawFd, sync::mpsc};
+
+#[cfg(target_os = "linux")]
+const FLAGS: u32 = libc::SYNC_FILE_RANGE_WAIT_BEFORE
+  | libc::SYNC_FILE_RANGE_WRITE
+  | libc::SYNC_FILE_RANGE_WAIT_AFTER;
+#[cfg(not(target_os = "linux"))]
+const FLAGS: u32 = 0;
+
+#[test]
+fn test_sync_file_range_after_write() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("sync_file_range");
+  let resource = unsafe {
+    let fd =
+      libc::open(file.path.as_ptr(), libc::O_CREAT | libc::O_RDWR, 0o644);
+    assert!(fd >= 0, "Failed to create test file");
+    Resource::from_raw_fd(fd)
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  api::write_at(&resource, b"wal record".to_vec(), 0)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).0.expect("write failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::sync_file_range(&resource, 0, 10, FLAGS)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("sync_file_range failed");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_sync_file_range_invalid_flags() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("sync_file_range_invalid");
+  let resource = unsafe {
+    let fd =
+      libc::open(file.path.as_ptr(), libc::O_CREAT | libc::O_RDWR, 0o644);
+    assert!(fd >= 0, "Failed to create test file");
+    Resource::from_raw_fd(fd)
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  api::sync_file_range(&resource, 0, 0, !FLAGS)
+    .with_lio(&lio)
+    .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).expect_err("bogus flags");
+  assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api};
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn test_tag_delivered_to_callback() {
+  let mut lio = Lio::new(64).unwrap();
+  let (tx, rx) = mpsc::channel();
+
+  for tag in [u64::MAX, 0xDEAD_BEEF, 1] {
+    let tx = tx.clone();
+    api::nop().with_lio(&lio).with_tag(tag).when_done_tagged(
+      move |tag, res| {
+        tx.send((tag, res)).unwrap();
+      },
+    );
+  }
+
+  let mut tags = Vec::new();
+  for _ in 0..3 {
+    let (tag, res) = poll_until_recv(&mut lio, &rx);
+    res.expect("nop failed");
+    tags.push(tag);
+  }
+  tags.sort_unstable();
+  assert_eq!(tags, vec![1, 0xDEAD_BEEF, u64::MAX]);
+}
+
+#[test]
+fn test_untagged_callback_gets_zero() {
+  let mut lio = Lio::new(64).unwrap();
+  let (tx, rx) = mpsc::channel();
+
+  api::nop().with_lio(&lio).when_done_tagged(move |tag, _| {
+    tx.send(tag).unwrap();
+  });
+
+  assert_eq!(poll_until_recv(&mut lio, &rx), 0);
+}
+
+#[test]
+fn test_tag_listed_in_debug_dump() {
+  let lio = Lio::new(64).unwrap();
+
+  let _rx =
+    api::timeout(Duration::from_secs(60)).with_lio(&lio).with_tag(99).send();
+  lio.try_run().unwrap();
+
+  let ops = lio.debug_dump();
+  assert_eq!(ops.len(), 1);
+  assert_eq!(ops[0].tag, Some(99));
+}
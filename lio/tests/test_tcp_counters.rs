@@ -0,0 +1,75 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api::resource::FromResource, net::TcpSocket};
+use std::sync::mpsc;
+
+#[test]
+fn test_tcp_byte_counters() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let client = TcpSocket::from_resource(pair.client_sock);
+  let server = TcpSocket::from_resource(pair.accepted_fd);
+
+  let (sent_tx, sent_rx) = mpsc::channel();
+  client.send(vec![7u8; 100]).with_lio(&lio).send_with(sent_tx);
+  let (sent, _) = poll_until_recv(&mut lio, &sent_rx);
+  assert_eq!(sent.expect("send failed"), 100);
+
+  let (recv_tx, recv_rx) = mpsc::channel();
+  server.recv(vec![0u8; 50]).with_lio(&lio).send_with(recv_tx);
+  let (received, _) = poll_until_recv(&mut lio, &recv_rx);
+  assert_eq!(received.expect("recv failed"), 50);
+
+  assert_eq!(client.bytes_written(), 100);
+  assert_eq!(client.bytes_read(), 0);
+  assert_eq!(server.bytes_read(), 50);
+  assert_eq!(server.bytes_written(), 0);
+}
+
+#[test]
+fn test_tcp_byte_counters_accumulate() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let client = TcpSocket::from_resource(pair.client_sock);
+  let server = TcpSocket::from_resource(pair.accepted_fd);
+
+  let (sent_tx, sent_rx) = mpsc::channel();
+  client.send(b"abc".to_vec()).with_lio(&lio).send_with(sent_tx);
+  poll_until_recv(&mut lio, &sent_rx).0.expect("send failed");
+  let (vec_tx, vec_rx) = mpsc::channel();
+  client
+    .send_vectored(vec![b"de".to_vec(), b"f".to_vec()])
+    .with_lio(&lio)
+    .send_with(vec_tx);
+  poll_until_recv(&mut lio, &vec_rx).0.expect("send_vectored failed");
+  assert_eq!(client.bytes_written(), 6);
+
+  let (recv_tx, recv_rx) = mpsc::channel();
+  let mut buf = Vec::with_capacity(16);
+  while buf.len() < 6 {
+    let at = buf.len();
+    server.recv_append(buf, at).with_lio(&lio).send_with(recv_tx.clone());
+    let (filled, returned) = poll_until_recv(&mut lio, &recv_rx);
+    filled.expect("recv failed");
+    buf = returned;
+  }
+  assert_eq!(buf, b"abcdef");
+  assert_eq!(server.bytes_read(), 6);
+}
+
+#[test]
+fn test_tcp_detached_send_still_counts() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let client = TcpSocket::from_resource(pair.client_sock);
+
+  client.send(vec![7u8; 100]).with_lio(&lio).detach();
+  for _ in 0..100 {
+    if client.bytes_written() == 100 {
+      break;
+    }
+    lio.run_timeout(std::time::Duration::from_millis(5)).unwrap();
+  }
+  assert_eq!(client.bytes_written(), 100);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{
+  Lio,
+  api::resource::{AsResource, FromResource},
+  net::TcpSocket,
+};
+use std::{os::fd::AsRawFd, sync::mpsc};
+
+#[test]
+fn test_split_halves_share_connection() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let client = TcpSocket::from_resource(pair.client_sock);
+  let server = TcpSocket::from_resource(pair.accepted_fd);
+
+  let (sent_tx, sent_rx) = mpsc::channel();
+  client.send(b"hi".to_vec()).with_lio(&lio).send_with(sent_tx.clone());
+  poll_until_recv(&mut lio, &sent_rx).0.expect("send failed");
+
+  let (reader, writer) = client.into_split();
+  assert_eq!(writer.bytes_written(), 2);
+
+  writer.send(b"ping".to_vec()).with_lio(&lio).send_with(sent_tx);
+  poll_until_recv(&mut lio, &sent_rx).0.expect("send failed");
+  assert_eq!(writer.bytes_written(), 6);
+
+  let (recv_tx, recv_rx) = mpsc::channel();
+  let mut buf = Vec::with_capacity(16);
+  while buf.len() < 6 {
+    let at = buf.len();
+    server.recv_append(buf, at).with_lio(&lio).send_with(recv_tx.clone());
+    let (filled, returned) = poll_until_recv(&mut lio, &recv_rx);
+    filled.expect("recv failed");
+    buf = returned;
+  }
+  assert_eq!(buf, b"hiping");
+
+  let (echo_tx, echo_rx) = mpsc::channel();
+  server.send(b"pong".to_vec()).with_lio(&lio).send_with(echo_tx);
+  poll_until_recv(&mut lio, &echo_rx).0.expect("send failed");
+  let (read_tx, read_rx) = mpsc::channel();
+  reader.recv(vec![0u8; 16]).with_lio(&lio).send_with(read_tx);
+  let (received, buf) = poll_until_recv(&mut lio, &read_rx);
+  let received = received.expect("recv failed") as usize;
+  assert_eq!(&buf[..received], b"pong");
+  assert_eq!(reader.bytes_read(), 4);
+}
+
+#[test]
+fn test_split_write_shutdown_and_close() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let client = TcpSocket::from_resource(pair.client_sock);
+  let server = TcpSocket::from_resource(pair.accepted_fd);
+
+  let (reader, writer) = client.into_split();
+  let fd = reader.as_resource().as_raw_fd();
+  assert_eq!(writer.as_resource().as_raw_fd(), fd);
+
+  let (shutdown_tx, shutdown_rx) = mpsc::channel();
+  writer.shutdown().with_lio(&lio).send_with(shutdown_tx);
+  poll_until_recv(&mut lio, &shutdown_rx).expect("shutdown failed");
+
+  // The peer reads EOF, while the read half can still receive.
+  let (recv_tx, recv_rx) = mpsc::channel();
+  server.recv(vec![0u8; 16]).with_lio(&lio).send_with(recv_tx.clone());
+  assert_eq!(poll_until_recv(&mut lio, &recv_rx).0.expect("recv failed"), 0);
+  server.send(b"late".to_vec()).with_lio(&lio).send_with(recv_tx.clone());
+  poll_until_recv(&mut lio, &recv_rx).0.expect("send failed");
+  reader.recv(vec![0u8; 16]).with_lio(&lio).send_with(recv_tx);
+  assert_eq!(poll_until_recv(&mut lio, &recv_rx).0.expect("recv failed"), 4);
+
+  // Dropping one half leaves the fd open for the other.
+  drop(writer);
+  assert_ne!(unsafe { libc::fcntl(fd, libc::F_GETFD) }, -1);
+}
@@ -464,3 +464,72 @@ fn test_tee_concurrent() {
   }
   // pipe1_read and pipe2_write are closed when Resources are dropped
 }
+
+/// A pipe as (read end, write end), both closed on drop.
+fn pipe() -> (std::fs::File, std::fs::File) {
+  let mut fds = [0i32; 2];
+  assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+  unsafe {
+    (std::fs::File::from_raw_fd(fds[0]), std::fs::File::from_raw_fd(fds[1]))
+  }
+}
+
+#[test]
+fn test_tee_multi_fans_out_to_every_pipe() {
+  use std::future::Future;
+  use std::io::{Read, Write};
+  use std::os::fd::{AsRawFd, IntoRawFd};
+  use std::task::{Context, Poll, Waker};
+
+  let lio = Lio::new(64).unwrap();
+
+  let (input_read, mut input_write) = pipe();
+  let (mut first_read, first_write) = pipe();
+  let (mut second_read, second_write) = pipe();
+  let test_data = b"one stream, two consumers";
+  input_write.write_all(test_data).unwrap();
+
+  let input = unsafe { Resource::from_raw_fd(input_read.into_raw_fd()) };
+  let outs = unsafe {
+    [
+      Resource::from_raw_fd(first_write.into_raw_fd()),
+      Resource::from_raw_fd(second_write.into_raw_fd()),
+    ]
+  };
+
+  let teed = {
+    let mut fan_out = std::pin::pin!(api::tee_multi(
+      &input,
+      &outs,
+      test_data.len() as u32,
+      &lio
+    ));
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut attempts = 0;
+    loop {
+      if let Poll::Ready(result) = fan_out.as_mut().poll(&mut cx) {
+        break result.expect("tee_multi failed");
+      }
+      attempts += 1;
+      assert!(attempts < 1000, "tee_multi never finished");
+      lio.run_timeout(Duration::from_millis(5)).unwrap();
+    }
+  };
+  assert_eq!(teed, vec![test_data.len(); 2]);
+  drop(outs);
+
+  let mut first = Vec::new();
+  let mut second = Vec::new();
+  first_read.read_to_end(&mut first).unwrap();
+  second_read.read_to_end(&mut second).unwrap();
+  assert_eq!(first, test_data);
+  assert_eq!(second, first, "both consumers get identical data");
+
+  // tee doesn't consume, the input still holds everything.
+  drop(input_write);
+  let mut rest = Vec::new();
+  unsafe { std::fs::File::from_raw_fd(libc::dup(input.as_raw_fd())) }
+    .read_to_end(&mut rest)
+    .unwrap();
+  assert_eq!(rest, test_data);
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::{pipe, poll_until_recv};
+use lio::{Lio, api, buf::BufStore};
+use std::{os::fd::AsRawFd, sync::mpsc};
+
+#[test]
+fn test_try_read_returns_none_when_pool_is_exhausted() {
+  let mut lio = Lio::new(64).unwrap();
+  let pool: &'static BufStore = Box::leak(Box::new(BufStore::with_capacity(1)));
+  let (read_end, write_end) = pipe();
+
+  let held = pool.try_get().unwrap();
+  assert_eq!(pool.available(), 0);
+  assert!(api::try_read(&read_end, pool).is_none());
+  drop(held);
+
+  let n =
+    unsafe { libc::write(write_end.as_raw_fd(), b"lent".as_ptr().cast(), 4) };
+  assert_eq!(n, 4);
+
+  let (sender, receiver) = mpsc::channel();
+  api::try_read(&read_end, pool)
+    .expect("a buffer was returned to the pool")
+    .with_lio(&lio)
+    .send_with(sender);
+  assert_eq!(pool.available(), 0);
+
+  let (read, buf) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(read.expect("read failed"), 4);
+  assert_eq!(buf.as_ref(), b"lent");
+
+  drop(buf);
+  assert_eq!(pool.available(), 1);
+}
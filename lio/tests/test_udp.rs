@@ -0,0 +1,80 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{
+  Lio,
+  api::resource::{FromResource, Resource},
+  net::UdpSocket,
+};
+use std::os::fd::{FromRawFd, IntoRawFd};
+use std::sync::mpsc;
+use std::time::Duration;
+
+fn bound(addr: &str) -> UdpSocket {
+  let socket = std::net::UdpSocket::bind(addr).unwrap();
+  // SAFETY: into_raw_fd gave up ownership of the socket.
+  UdpSocket::from_resource(unsafe {
+    Resource::from_raw_fd(socket.into_raw_fd())
+  })
+}
+
+fn round_trip(addr: &str) {
+  let mut lio = Lio::new(64).unwrap();
+  let socket = bound(addr);
+  let local = socket.local_addr().unwrap();
+
+  let peer = std::net::UdpSocket::bind(addr).unwrap();
+  peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+  let peer_addr = peer.local_addr().unwrap();
+
+  // Receive from the peer, with its address.
+  let (tx, rx) = mpsc::channel();
+  socket.recv_from(vec![0u8; 64]).with_lio(&lio).send_with(tx);
+  peer.send_to(b"question", local).unwrap();
+
+  let (result, buf) = poll_until_recv(&mut lio, &rx);
+  let (len, from) = result.expect("recv_from failed");
+  assert_eq!(&buf[..len], b"question");
+  assert_eq!(from, peer_addr);
+
+  // Answer back to that address.
+  let (tx, rx) = mpsc::channel();
+  socket.send_to(b"answer".to_vec(), from).with_lio(&lio).send_with(tx);
+  let (sent, _) = poll_until_recv(&mut lio, &rx);
+  assert_eq!(sent.expect("send_to failed"), 6);
+
+  let mut reply = [0u8; 64];
+  let (len, from) = peer.recv_from(&mut reply).unwrap();
+  assert_eq!(&reply[..len], b"answer");
+  assert_eq!(from, local);
+}
+
+#[test]
+fn test_udp_send_to_recv_from_v4() {
+  round_trip("127.0.0.1:0");
+}
+
+#[test]
+fn test_udp_send_to_recv_from_v6() {
+  // Skip where the loopback has no IPv6.
+  if std::net::UdpSocket::bind("[::1]:0").is_err() {
+    return;
+  }
+  round_trip("[::1]:0");
+}
+
+#[test]
+fn test_udp_recv_from_truncates() {
+  let mut lio = Lio::new(64).unwrap();
+  let socket = bound("127.0.0.1:0");
+  let peer = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+  peer.send_to(b"longer than four", socket.local_addr().unwrap()).unwrap();
+
+  let (tx, rx) = mpsc::channel();
+  socket.recv_from(Vec::with_capacity(4)).with_lio(&lio).send_with(tx);
+
+  let (result, buf) = poll_until_recv(&mut lio, &rx);
+  let (len, _) = result.expect("recv_from failed");
+  assert_eq!(len, 4);
+  assert_eq!(buf, b"long");
+}
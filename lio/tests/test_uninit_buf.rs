@@ -0,0 +1,33 @@
+mod common;
+
+use common::{poll_until_recv, setup_tcp_pair};
+use lio::{Lio, api, buf::UninitBuf};
+use std::sync::mpsc;
+
+#[test]
+fn test_recv_into_uninit_buf() {
+  let mut lio = Lio::new(64).unwrap();
+  let common::TcpPair { server_sock: _, client_sock, accepted_fd } =
+    setup_tcp_pair(&mut lio);
+
+  let payload: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+  let (sender, receiver) = mpsc::channel();
+  api::send(&client_sock, payload.clone(), None)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (sent, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(sent.expect("send failed"), 1000);
+
+  let buf = UninitBuf::new(64 * 1024);
+  assert!(buf.filled().is_empty());
+  let (sender, receiver) = mpsc::channel();
+  api::recv(&accepted_fd, buf, None).with_lio(&lio).send_with(sender);
+  let (received, buf) = poll_until_recv(&mut lio, &receiver);
+
+  let n = received.expect("recv failed") as usize;
+  assert!(n > 0);
+  assert_eq!(buf.capacity(), 64 * 1024);
+  assert_eq!(buf.len(), n, "only the received bytes are visible");
+  assert_eq!(buf.filled(), &payload[..n]);
+  assert_eq!(buf.into_vec(), &payload[..n]);
+}
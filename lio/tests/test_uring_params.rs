@@ -0,0 +1,23 @@
+#![cfg(target_os = "linux")]
+
+use lio::{api, lio_uring::Params};
+
+#[test]
+fn test_try_init_with_uring_params_sqpoll() {
+  std::thread::spawn(|| {
+    // Works with or without the privileges SQPOLL needs on older kernels.
+    lio::try_init_with_uring_params(Params::default().sqpoll(100), 64).unwrap();
+    let lio = lio::uninstall_global().expect("no Lio installed");
+
+    let mut receiver = api::nop().with_lio(&lio).send();
+    let result = loop {
+      lio.try_run().unwrap();
+      if let Some(result) = receiver.try_recv() {
+        break result;
+      }
+    };
+    result.expect("nop failed");
+  })
+  .join()
+  .unwrap();
+}
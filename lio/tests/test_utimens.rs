@@ -0,0 +1,113 @@
+mod common;
+
+use common::{TempFile, create, poll_until_recv};
+use lio::{
+  Lio,
+  api::{futimens, ops::FileTime, resource::Resource, utimensat},
+};
+use std::{
+  ffi::CString,
+  os::fd::FromRawFd,
+  sync::mpsc,
+  time::{Duration, UNIX_EPOCH},
+};
+
+/// Returns `(atime, mtime)` of `path` as `(secs, nsecs)`.
+#[cfg(target_os = "linux")]
+fn times(path: &CString) -> ((i64, u32), (i64, u32)) {
+  let mut stx: libc::statx = unsafe { std::mem::zeroed() };
+  let ret = unsafe {
+    libc::statx(
+      libc::AT_FDCWD,
+      path.as_ptr(),
+      0,
+      libc::STATX_ATIME | libc::STATX_MTIME,
+      &mut stx,
+    )
+  };
+  assert_eq!(ret, 0, "statx failed");
+  (
+    (stx.stx_atime.tv_sec, stx.stx_atime.tv_nsec),
+    (stx.stx_mtime.tv_sec, stx.stx_mtime.tv_nsec),
+  )
+}
+
+/// Returns `(atime, mtime)` of `path` as `(secs, nsecs)`.
+#[cfg(not(target_os = "linux"))]
+fn times(path: &CString) -> ((i64, u32), (i64, u32)) {
+  let mut st: libc::stat = unsafe { std::mem::zeroed() };
+  assert_eq!(unsafe { libc::stat(path.as_ptr(), &mut st) }, 0, "stat failed");
+  (
+    (st.st_atime as i64, st.st_atime_nsec as u32),
+    (st.st_mtime as i64, st.st_mtime_nsec as u32),
+  )
+}
+
+#[test]
+fn test_futimens_sets_mtime() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("futimens_mtime");
+  let fd = create(&file);
+  let (atime_before, _) = times(&file.path);
+
+  let mtime = UNIX_EPOCH + Duration::new(1_000_000, 500);
+  let (sender, receiver) = mpsc::channel();
+  futimens(&fd, FileTime::Omit, FileTime::At(mtime))
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("futimens failed");
+
+  let (atime, mtime) = times(&file.path);
+  assert_eq!(mtime, (1_000_000, 500));
+  assert_eq!(atime, atime_before, "UTIME_OMIT must leave atime alone");
+}
+
+#[test]
+fn test_utimensat_by_path() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("utimensat_path");
+  drop(create(&file));
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+
+  let atime = UNIX_EPOCH + Duration::from_secs(42);
+  let (sender, receiver) = mpsc::channel();
+  utimensat(
+    &cwd,
+    file.path.clone(),
+    FileTime::At(atime),
+    FileTime::At(atime),
+    0,
+  )
+  .with_lio(&lio)
+  .send_with(sender.clone());
+  poll_until_recv(&mut lio, &receiver).expect("utimensat failed");
+  assert_eq!(times(&file.path), ((42, 0), (42, 0)));
+
+  // UTIME_NOW moves mtime away from the fixed value.
+  utimensat(&cwd, file.path.clone(), FileTime::Omit, FileTime::Now, 0)
+    .with_lio(&lio)
+    .send_with(sender);
+  poll_until_recv(&mut lio, &receiver).expect("utimensat failed");
+  let (atime, mtime) = times(&file.path);
+  assert_eq!(atime, (42, 0));
+  assert!(mtime.0 > 42);
+}
+
+#[test]
+fn test_utimensat_missing_file() {
+  let mut lio = Lio::new(64).unwrap();
+  let cwd = unsafe { Resource::from_raw_fd(libc::AT_FDCWD) };
+
+  let (sender, receiver) = mpsc::channel();
+  utimensat(
+    &cwd,
+    CString::new("lio-definitely-missing-file").unwrap(),
+    FileTime::Now,
+    FileTime::Now,
+    0,
+  )
+  .with_lio(&lio)
+  .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).expect_err("should fail");
+  assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+}
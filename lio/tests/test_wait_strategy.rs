@@ -0,0 +1,41 @@
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, WaitStrategy, api};
+use std::{sync::mpsc, thread, time::Duration};
+
+const SPIN: Duration = Duration::from_millis(20);
+
+/// Runs `count` nops back to back, each waited for with a blocking run.
+fn steady_stream(lio: &mut Lio, count: usize) {
+  for _ in 0..count {
+    let (sender, receiver) = mpsc::channel();
+    api::nop().with_lio(lio).send_with(sender);
+    poll_until_recv(lio, &receiver).expect("nop failed");
+  }
+}
+
+#[test]
+fn test_block_never_spins() {
+  let mut lio = Lio::new(64).unwrap();
+  steady_stream(&mut lio, 20);
+  assert_eq!(lio.spin_count(), 0);
+}
+
+#[test]
+fn test_adaptive_spins_under_load_and_blocks_when_idle() {
+  let mut lio = Lio::new(64).unwrap();
+  lio.set_wait_strategy(WaitStrategy::Adaptive { spin: SPIN });
+
+  steady_stream(&mut lio, 20);
+  let spins = lio.spin_count();
+  assert!(spins > 0, "a steady stream of completions should spin");
+
+  // Idle for longer than the spin window, the next wait blocks.
+  thread::sleep(SPIN * 3);
+  let (sender, receiver) = mpsc::channel();
+  api::timeout(Duration::from_millis(5)).with_lio(&lio).send_with(sender);
+  lio.run().unwrap();
+  assert_eq!(lio.spin_count(), spins, "an idle driver should block");
+  poll_until_recv(&mut lio, &receiver).expect("timeout failed");
+}
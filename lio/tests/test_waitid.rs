@@ -0,0 +1,53 @@
+#![cfg(unix)]
+// The children are reaped through lio instead of `Child::wait`.
+#![allow(clippy::zombie_processes)]
+mod common;
+
+use common::poll_until_recv;
+use lio::{Lio, api};
+use std::{process::Command, sync::mpsc};
+
+#[test]
+fn test_waitid_reaps_exited_child() {
+  let mut lio = Lio::new(64).unwrap();
+  let child = Command::new("sh").args(["-c", "exit 7"]).spawn().unwrap();
+  let pid = child.id();
+
+  let (sender, receiver) = mpsc::channel();
+  api::waitid(libc::P_PID, pid, libc::WEXITED).with_lio(&lio).send_with(sender);
+  let status = poll_until_recv(&mut lio, &receiver).expect("waitid failed");
+
+  assert_eq!(status.pid, pid as libc::pid_t);
+  assert_eq!(status.code, libc::CLD_EXITED);
+  assert_eq!(status.exit_code(), Some(7));
+}
+
+#[test]
+fn test_waitid_killed_child_has_no_exit_code() {
+  let mut lio = Lio::new(64).unwrap();
+  let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+  let pid = child.id();
+  child.kill().unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  api::waitid(libc::P_PID, pid, libc::WEXITED).with_lio(&lio).send_with(sender);
+  let status = poll_until_recv(&mut lio, &receiver).expect("waitid failed");
+
+  assert_eq!(status.code, libc::CLD_KILLED);
+  assert_eq!(status.status, libc::SIGKILL);
+  assert_eq!(status.exit_code(), None);
+}
+
+#[test]
+fn test_waitid_not_a_child() {
+  let mut lio = Lio::new(64).unwrap();
+
+  let (sender, receiver) = mpsc::channel();
+  // Our parent is never our child.
+  let ppid = unsafe { libc::getppid() } as libc::id_t;
+  api::waitid(libc::P_PID, ppid, libc::WEXITED)
+    .with_lio(&lio)
+    .send_with(sender);
+  let err = poll_until_recv(&mut lio, &receiver).expect_err("not a child");
+  assert_eq!(err.raw_os_error(), Some(libc::ECHILD));
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::{pipe, poll_until_recv, setup_tcp_pair};
+use lio::{
+  Lio,
+  api::{self, resource::Resource},
+};
+use std::{os::fd::AsRawFd, sync::mpsc};
+
+fn read_to_end(fd: &Resource) -> Vec<u8> {
+  let mut out = Vec::new();
+  let mut buf = [0u8; 4096];
+  loop {
+    let n =
+      unsafe { libc::read(fd.as_raw_fd(), buf.as_mut_ptr().cast(), 4096) };
+    assert!(n >= 0, "read failed");
+    if n == 0 {
+      return out;
+    }
+    out.extend_from_slice(&buf[..n as usize]);
+  }
+}
+
+/// 2000 buffers is past IOV_MAX on every supported platform.
+fn numbered_bufs() -> Vec<Vec<u8>> {
+  (0..2000u32).map(|i| format!("{i:04};").into_bytes()).collect()
+}
+
+#[test]
+fn test_writev_more_than_iov_max() {
+  let mut lio = Lio::new(64).unwrap();
+  let (read_end, write_end) = pipe();
+  let bufs = numbered_bufs();
+  let expected = bufs.concat();
+  // Drain concurrently, the payload is larger than some pipe buffers.
+  let reader = std::thread::spawn(move || read_to_end(&read_end));
+
+  let (sender, receiver) = mpsc::channel();
+  api::writev(&write_end, bufs).with_lio(&lio).send_with(sender);
+  let (written, bufs) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("writev failed"), expected.len());
+  assert_eq!(bufs.len(), 2000);
+
+  drop(write_end);
+  assert_eq!(reader.join().unwrap(), expected);
+}
+
+#[test]
+fn test_writev_socket_preserves_order() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let bufs = numbered_bufs();
+  let expected = bufs.concat();
+
+  let (sender, receiver) = mpsc::channel();
+  api::writev(&pair.client_sock, bufs).with_lio(&lio).send_with(sender);
+  let (written, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("writev failed"), expected.len());
+
+  unsafe { libc::shutdown(pair.client_sock.as_raw_fd(), libc::SHUT_WR) };
+  assert_eq!(read_to_end(&pair.accepted_fd), expected);
+}
+
+#[test]
+fn test_writev_full_socket_waits_without_blocking_loop() {
+  let mut lio = Lio::new(64).unwrap();
+  let pair = setup_tcp_pair(&mut lio);
+  let chunk = [7u8; 4096];
+  let mut filled = 0;
+  loop {
+    let n = unsafe {
+      libc::send(
+        pair.client_sock.as_raw_fd(),
+        chunk.as_ptr().cast(),
+        chunk.len(),
+        libc::MSG_DONTWAIT,
+      )
+    };
+    if n < 0 {
+      break;
+    }
+    filled += n as usize;
+  }
+
+  let (sender, receiver) = mpsc::channel();
+  api::writev(&pair.client_sock, vec![b"tail".to_vec()])
+    .with_lio(&lio)
+    .send_with(sender);
+
+  // Other ops keep completing while the writev waits for room.
+  let (nop_tx, nop_rx) = mpsc::channel();
+  api::nop().with_lio(&lio).send_with(nop_tx);
+  poll_until_recv(&mut lio, &nop_rx).expect("nop failed");
+  assert!(receiver.try_recv().is_err());
+
+  let accepted = pair.accepted_fd;
+  let reader = std::thread::spawn(move || read_to_end(&accepted));
+  let (written, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(written.expect("writev failed"), 4);
+
+  unsafe { libc::shutdown(pair.client_sock.as_raw_fd(), libc::SHUT_WR) };
+  let received = reader.join().unwrap();
+  assert_eq!(received.len(), filled + 4);
+  assert!(received.ends_with(b"tail"));
+}
@@ -0,0 +1,86 @@
+#![cfg(target_os = "linux")]
+mod common;
+
+use common::{TempFile, create, poll_until_recv};
+use lio::{BufResult, Lio, api};
+use std::{ffi::CString, sync::mpsc};
+
+/// `/tmp` may not support user xattrs, the tests have nothing to check then.
+fn unsupported(err: &std::io::Error) -> bool {
+  err.raw_os_error() == Some(libc::EOPNOTSUPP)
+}
+
+fn name() -> CString {
+  CString::new("user.lio_test").unwrap()
+}
+
+#[test]
+fn test_setxattr_then_getxattr() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("xattr_path");
+  drop(create(&file));
+
+  let (sender, receiver) = mpsc::channel();
+  api::setxattr(file.path.clone(), name(), b"hello".to_vec(), 0)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (result, value): BufResult<(), Vec<u8>> =
+    poll_until_recv(&mut lio, &receiver);
+  if let Err(err) = &result
+    && unsupported(err)
+  {
+    return;
+  }
+  result.expect("setxattr failed");
+  assert_eq!(value, b"hello");
+
+  let (sender, receiver) = mpsc::channel();
+  api::getxattr(file.path.clone(), name(), vec![0; 64])
+    .with_lio(&lio)
+    .send_with(sender);
+  let (result, value) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.expect("getxattr failed"), 5);
+  assert_eq!(value, b"hello");
+}
+
+#[test]
+fn test_fsetxattr_then_fgetxattr() {
+  let mut lio = Lio::new(64).unwrap();
+  let file = TempFile::new("xattr_fd");
+  let fd = create(&file);
+
+  let (sender, receiver) = mpsc::channel();
+  api::fsetxattr(&fd, name(), b"world".to_vec(), libc::XATTR_CREATE)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (result, _) = poll_until_recv(&mut lio, &receiver);
+  if let Err(err) = &result
+    && unsupported(err)
+  {
+    return;
+  }
+  result.expect("fsetxattr failed");
+
+  let (sender, receiver) = mpsc::channel();
+  api::fsetxattr(&fd, name(), b"again".to_vec(), libc::XATTR_CREATE)
+    .with_lio(&lio)
+    .send_with(sender);
+  let (result, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::EEXIST));
+
+  let (sender, receiver) = mpsc::channel();
+  api::fgetxattr(&fd, name(), Vec::new()).with_lio(&lio).send_with(sender);
+  let (result, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.expect("size query failed"), 5);
+
+  let (sender, receiver) = mpsc::channel();
+  api::fgetxattr(&fd, name(), vec![0; 2]).with_lio(&lio).send_with(sender);
+  let (result, _) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::ERANGE));
+
+  let (sender, receiver) = mpsc::channel();
+  api::fgetxattr(&fd, name(), vec![0; 64]).with_lio(&lio).send_with(sender);
+  let (result, value) = poll_until_recv(&mut lio, &receiver);
+  assert_eq!(result.expect("fgetxattr failed"), 5);
+  assert_eq!(value, b"world");
+}